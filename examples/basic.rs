@@ -76,14 +76,15 @@ fn main() -> Result<()> {
         .singleton_with::<Arc<dyn Logger>>(|_| {
             Ok(Arc::new(ConsoleLogger) as Arc<dyn Logger>)
         })
-        // Database — singleton (depends on Config + Logger)
-        .singleton_with::<Arc<Database>>(|r| {
+        // Database — singleton (depends on Config + Logger), resolved
+        // as Arc<Database>; Database itself needn't be Clone
+        .singleton_arc::<Database>(|r| {
             let config: Config = r.resolve()?;
             let logger: Arc<dyn Logger> = r.resolve()?;
-            Ok(Arc::new(Database {
+            Ok(Database {
                 url: config.database_url,
                 logger,
-            }))
+            })
         })
         // UserRepository — scoped (one per request)
         .scoped_with::<Arc<UserRepository>>(|r| {
@@ -0,0 +1,54 @@
+//! A small actix-web app wired through Makhzan.
+//!
+//! Run with `cargo run -p makhzan-actix --example actix_app`, then
+//! `curl localhost:8080/users/1`.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use actix_web::{App, HttpServer, web};
+use makhzan_actix::{Inject, MakhzanApp};
+use makhzan_container::container::resolve;
+use makhzan_container::prelude::*;
+
+struct UserRepository {
+    names: HashMap<u32, &'static str>,
+}
+
+struct UserService {
+    repo: Arc<UserRepository>,
+}
+
+impl UserService {
+    fn name(&self, id: u32) -> Option<&'static str> {
+        self.repo.names.get(&id).copied()
+    }
+}
+
+async fn show_user(path: web::Path<u32>, Inject(users): Inject<Arc<UserService>>) -> String {
+    let id = path.into_inner();
+    match users.name(id) {
+        Some(name) => format!("user {id}: {name}"),
+        None => format!("user {id} not found"),
+    }
+}
+
+#[actix_web::main]
+async fn main() -> std::io::Result<()> {
+    let container = Container::builder()
+        .singleton_value(Arc::new(UserRepository {
+            names: HashMap::from([(1, "ada"), (2, "grace")]),
+        }))
+        .singleton_with(|r| Ok(Arc::new(UserService { repo: resolve(r)? })))
+        .build()
+        .expect("container should build");
+
+    HttpServer::new(move || {
+        App::new()
+            .app_data(MakhzanApp::new(container.clone()))
+            .route("/users/{id}", web::get().to(show_user))
+    })
+    .bind(("127.0.0.1", 8080))?
+    .run()
+    .await
+}
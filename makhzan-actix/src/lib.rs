@@ -0,0 +1,120 @@
+//! Actix-web integration for Makhzan DI.
+//!
+//! Register the container as app data with [`MakhzanApp`]; the [`Inject`]
+//! extractor then resolves handler arguments from a per-request
+//! [`OwnedScope`], created on first use and kept in the request
+//! extensions. `Scoped` registrations are built once per request, and the
+//! scope with everything it cached is dropped when the request completes.
+//!
+//! ```rust
+//! # use actix_web::{App, web};
+//! # use makhzan_actix::{Inject, MakhzanApp};
+//! # use makhzan_container::prelude::*;
+//! # use std::sync::Arc;
+//! # struct User { name: String }
+//! # struct UserService;
+//! # impl UserService { fn current(&self) -> User { User { name: "ada".into() } } }
+//! async fn me(Inject(users): Inject<Arc<UserService>>) -> String {
+//!     users.current().name
+//! }
+//!
+//! # let container = Container::builder().singleton_arc(|_| Ok(UserService)).build()?;
+//! # let app =
+//! App::new()
+//!     .app_data(MakhzanApp::new(container))
+//!     .route("/me", web::get().to(me))
+//! # ;
+//! # actix_web::rt::System::new().block_on(async {
+//! #     let app = actix_web::test::init_service(app).await;
+//! #     let request = actix_web::test::TestRequest::get().uri("/me").to_request();
+//! #     assert_eq!(actix_web::test::call_and_read_body(&app, request).await, "ada");
+//! # });
+//! # Ok::<(), MakhzanError>(())
+//! ```
+
+use std::future::{Ready, ready};
+
+use actix_web::dev::Payload;
+use actix_web::error::ErrorInternalServerError;
+use actix_web::{Error, FromRequest, HttpMessage, HttpRequest};
+use makhzan_container::container::{Container, OwnedScope};
+
+/// App data holding the container requests are scoped from.
+#[derive(Debug, Clone)]
+pub struct MakhzanApp {
+    container: Container,
+}
+
+impl MakhzanApp {
+    /// Wrap `container` for [`App::app_data`](actix_web::App::app_data).
+    pub fn new(container: Container) -> Self {
+        Self { container }
+    }
+
+    /// The wrapped container.
+    pub fn container(&self) -> &Container {
+        &self.container
+    }
+}
+
+/// Returns the scope of `request`, creating it on first use.
+///
+/// Middleware can call this to seed per-request values before handlers
+/// run:
+///
+/// ```rust
+/// # use actix_web::App;
+/// # use actix_web::dev::Service;
+/// # use makhzan_actix::MakhzanApp;
+/// # use makhzan_container::prelude::*;
+/// # #[derive(Clone)]
+/// # struct RequestId(u64);
+/// # impl RequestId { fn new() -> Self { RequestId(1) } }
+/// # let container = Container::builder().build()?;
+/// # let app = App::new()
+/// #     .app_data(MakhzanApp::new(container))
+/// .wrap_fn(|req, srv| {
+///     if let Ok(scope) = makhzan_actix::request_scope(req.request()) {
+///         scope.insert(RequestId::new());
+///     }
+///     srv.call(req)
+/// })
+/// # ;
+/// # Ok::<(), MakhzanError>(())
+/// ```
+///
+/// # Errors
+/// An internal server error if no [`MakhzanApp`] is registered.
+pub fn request_scope(request: &HttpRequest) -> Result<OwnedScope, Error> {
+    if let Some(scope) = request.extensions().get::<OwnedScope>() {
+        return Ok(scope.clone());
+    }
+
+    let app = request.app_data::<MakhzanApp>().ok_or_else(|| {
+        ErrorInternalServerError("No MakhzanApp registered; add .app_data(MakhzanApp::new(container))")
+    })?;
+    let scope = app.container.create_owned_scope();
+    request.extensions_mut().insert(scope.clone());
+    Ok(scope)
+}
+
+/// Extracts a `T` resolved from the request's scope.
+///
+/// A failed resolve becomes `500 Internal Server Error` with the rendered
+/// [`MakhzanError`](makhzan_container::error::MakhzanError) as the body.
+#[derive(Debug, Clone)]
+pub struct Inject<T>(pub T);
+
+impl<T: Send + Sync + 'static> FromRequest for Inject<T> {
+    type Error = Error;
+    type Future = Ready<Result<Self, Error>>;
+
+    fn from_request(request: &HttpRequest, _payload: &mut Payload) -> Self::Future {
+        ready(request_scope(request).and_then(|scope| {
+            scope
+                .resolve::<T>()
+                .map(Inject)
+                .map_err(|e| ErrorInternalServerError(e.to_string()))
+        }))
+    }
+}
@@ -0,0 +1,110 @@
+//! End-to-end tests through an actix-web service.
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use actix_web::dev::Service;
+use actix_web::http::StatusCode;
+use actix_web::{App, test, web};
+use makhzan_actix::{Inject, MakhzanApp, request_scope};
+use makhzan_container::container::resolve;
+use makhzan_container::prelude::*;
+
+struct UserService {
+    name: &'static str,
+}
+
+#[derive(Clone)]
+struct RequestId(u32);
+
+/// Scoped; counts how many instances are still alive.
+#[derive(Clone)]
+struct Session {
+    request: u32,
+    _alive: Arc<Alive>,
+}
+
+struct Alive(Arc<AtomicUsize>);
+
+impl Drop for Alive {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+fn container(alive: Arc<AtomicUsize>) -> Container {
+    Container::builder()
+        .singleton_value(Arc::new(UserService { name: "ada" }))
+        .scoped_with(move |r| {
+            alive.fetch_add(1, Ordering::SeqCst);
+            Ok(Session {
+                request: resolve::<RequestId>(r)?.0,
+                _alive: Arc::new(Alive(alive.clone())),
+            })
+        })
+        .build()
+        .unwrap()
+}
+
+async fn me(
+    Inject(users): Inject<Arc<UserService>>,
+    Inject(session): Inject<Session>,
+    Inject(again): Inject<Session>,
+) -> String {
+    assert!(Arc::ptr_eq(&session._alive, &again._alive));
+    format!("{} #{}", users.name, session.request)
+}
+
+#[actix_web::test]
+async fn resolves_per_request_and_drops_scope_afterwards() {
+    let alive = Arc::new(AtomicUsize::new(0));
+    let app = test::init_service(
+        App::new()
+            .app_data(MakhzanApp::new(container(alive.clone())))
+            .wrap_fn(|req, srv| {
+                let id = req.headers().get("x-request-id").unwrap().to_str().unwrap().parse().unwrap();
+                request_scope(req.request()).unwrap().insert(RequestId(id));
+                srv.call(req)
+            })
+            .route("/me", web::get().to(me)),
+    )
+    .await;
+
+    for id in [3, 4] {
+        let request = test::TestRequest::get()
+            .uri("/me")
+            .insert_header(("x-request-id", id.to_string()))
+            .to_request();
+        let body = test::call_and_read_body(&app, request).await;
+        assert_eq!(body, format!("ada #{id}").as_bytes());
+        assert_eq!(alive.load(Ordering::SeqCst), 0, "the request's scope outlived it");
+    }
+}
+
+#[actix_web::test]
+async fn resolve_errors_become_500() {
+    let app = test::init_service(
+        App::new()
+            .app_data(MakhzanApp::new(container(Arc::default())))
+            .route("/", web::get().to(|Inject(_): Inject<String>| async { "" })),
+    )
+    .await;
+
+    let response = test::call_service(&app, test::TestRequest::get().uri("/").to_request()).await;
+    assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    let body = test::read_body(response).await;
+    assert!(String::from_utf8_lossy(&body).contains("not registered"));
+}
+
+#[actix_web::test]
+async fn missing_app_data_is_reported() {
+    let app = test::init_service(
+        App::new().route("/", web::get().to(|Inject(_): Inject<u32>| async { "" })),
+    )
+    .await;
+
+    let response = test::call_service(&app, test::TestRequest::get().uri("/").to_request()).await;
+    assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    let body = test::read_body(response).await;
+    assert!(String::from_utf8_lossy(&body).contains("MakhzanApp"));
+}
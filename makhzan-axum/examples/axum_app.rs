@@ -0,0 +1,76 @@
+//! A small axum app wired through Makhzan.
+//!
+//! Run with `cargo run -p makhzan-axum --example axum_app`, then
+//! `curl -H 'x-request-id: 42' localhost:3000/greet/world`.
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use axum::Router;
+use axum::extract::{Path, Request};
+use axum::middleware::{self, Next};
+use axum::response::Response;
+use axum::routing::get;
+use makhzan_axum::{Inject, MakhzanLayer};
+use makhzan_container::container::resolve;
+use makhzan_container::prelude::*;
+
+#[derive(Clone)]
+struct RequestId(String);
+
+struct Greeter {
+    greeting: &'static str,
+    served: AtomicU64,
+}
+
+/// Built once per request.
+#[derive(Clone)]
+struct RequestLog {
+    request: String,
+}
+
+impl RequestLog {
+    fn line(&self, message: &str) -> String {
+        format!("[{}] {message}", self.request)
+    }
+}
+
+async fn greet(
+    Path(name): Path<String>,
+    Inject(greeter): Inject<Arc<Greeter>>,
+    Inject(log): Inject<RequestLog>,
+) -> String {
+    let served = greeter.served.fetch_add(1, Ordering::Relaxed) + 1;
+    log.line(&format!("{}, {name}! (request {served})", greeter.greeting))
+}
+
+async fn seed_request_id(request: Request, next: Next) -> Response {
+    let id = request
+        .headers()
+        .get("x-request-id")
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or("-")
+        .to_string();
+    if let Some(scope) = request.extensions().get::<OwnedScope>() {
+        scope.insert(RequestId(id));
+    }
+    next.run(request).await
+}
+
+#[tokio::main]
+async fn main() -> std::result::Result<(), Box<dyn std::error::Error>> {
+    let container = Container::builder()
+        .singleton_value(Arc::new(Greeter { greeting: "Hello", served: AtomicU64::new(0) }))
+        .scoped_with(|r| Ok(RequestLog { request: resolve::<RequestId>(r)?.0 }))
+        .build()?;
+
+    let app = Router::new()
+        .route("/greet/{name}", get(greet))
+        .layer(middleware::from_fn(seed_request_id))
+        .layer(MakhzanLayer::new(container));
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:3000").await?;
+    println!("listening on {}", listener.local_addr()?);
+    axum::serve(listener, app).await?;
+    Ok(())
+}
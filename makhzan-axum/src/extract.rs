@@ -0,0 +1,64 @@
+//! The `Inject<T>` extractor.
+
+use axum::extract::FromRequestParts;
+use axum::response::{IntoResponse, Response};
+use http::StatusCode;
+use http::request::Parts;
+use makhzan_container::container::OwnedScope;
+use makhzan_container::error::MakhzanError;
+
+/// Extracts a `T` resolved from the request's scope.
+///
+/// Requires [`MakhzanLayer`](crate::MakhzanLayer) on the route. A failed
+/// resolve rejects the request with `500 Internal Server Error` and the
+/// rendered [`MakhzanError`] as the body.
+///
+/// ```rust
+/// # use makhzan_axum::Inject;
+/// # use std::sync::Arc;
+/// # struct Database;
+/// # #[derive(Clone)]
+/// # struct Config;
+/// async fn handler(Inject(db): Inject<Arc<Database>>, Inject(cfg): Inject<Config>) {}
+/// # let _: axum::Router = axum::Router::new().route("/", axum::routing::get(handler));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Inject<T>(pub T);
+
+impl<T, S> FromRequestParts<S> for Inject<T>
+where
+    T: Send + Sync + 'static,
+    S: Send + Sync,
+{
+    type Rejection = InjectRejection;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let scope = parts
+            .extensions
+            .get::<OwnedScope>()
+            .ok_or(InjectRejection::MissingScope)?;
+        scope.resolve::<T>().map(Inject).map_err(InjectRejection::Resolve)
+    }
+}
+
+/// Why [`Inject`] could not extract its value.
+#[derive(Debug)]
+pub enum InjectRejection {
+    /// The request has no scope; [`MakhzanLayer`](crate::MakhzanLayer) is
+    /// missing from the route.
+    MissingScope,
+    /// Resolving the value failed.
+    Resolve(MakhzanError),
+}
+
+impl IntoResponse for InjectRejection {
+    fn into_response(self) -> Response {
+        let message = match self {
+            Self::MissingScope => {
+                "No Makhzan scope on this request; add MakhzanLayer to the router".to_string()
+            }
+            Self::Resolve(error) => error.to_string(),
+        };
+        (StatusCode::INTERNAL_SERVER_ERROR, message).into_response()
+    }
+}
@@ -0,0 +1,38 @@
+//! Axum integration for Makhzan DI.
+//!
+//! [`MakhzanLayer`] gives every request its own
+//! [`OwnedScope`](makhzan_container::container::OwnedScope), stored in the
+//! request extensions; the [`Inject`] extractor resolves handler
+//! arguments from it. `Scoped` registrations are therefore built once per
+//! request, and middleware can seed per-request values into the scope.
+//!
+//! ```rust
+//! # use axum::Router;
+//! # use axum::routing::get;
+//! # use makhzan_axum::{Inject, MakhzanLayer};
+//! # use makhzan_container::prelude::*;
+//! # use std::sync::Arc;
+//! # struct User { name: String }
+//! # struct UserService;
+//! # impl UserService { fn current(&self) -> User { User { name: "ada".into() } } }
+//! async fn show_user(Inject(users): Inject<Arc<UserService>>) -> String {
+//!     users.current().name
+//! }
+//!
+//! # let container = Container::builder().singleton_arc(|_| Ok(UserService)).build()?;
+//! let app = Router::new()
+//!     .route("/me", get(show_user))
+//!     .layer(MakhzanLayer::new(container));
+//! # tokio::runtime::Runtime::new().unwrap().block_on(async {
+//! #     use tower::ServiceExt;
+//! #     let request = http::Request::get("/me").body(axum::body::Body::empty()).unwrap();
+//! #     let response = app.oneshot(request).await.unwrap();
+//! #     assert_eq!(response.status(), http::StatusCode::OK);
+//! # });
+//! # Ok::<(), MakhzanError>(())
+//! ```
+
+mod extract;
+
+pub use extract::{Inject, InjectRejection};
+pub use makhzan_tower::{ScopeLayer as MakhzanLayer, ScopeService as MakhzanService};
@@ -0,0 +1,101 @@
+//! End-to-end tests through an axum router.
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use axum::Router;
+use axum::body::Body;
+use axum::extract::Request;
+use axum::middleware::{self, Next};
+use axum::routing::get;
+use http::StatusCode;
+use http_body_util::BodyExt;
+use makhzan_axum::{Inject, MakhzanLayer};
+use makhzan_container::container::resolve;
+use makhzan_container::prelude::*;
+use tower::ServiceExt;
+
+#[derive(Clone)]
+struct RequestId(u64);
+
+/// Scoped: one per request.
+#[derive(Clone)]
+struct Session {
+    request: u64,
+    serial: u64,
+}
+
+struct Greeting(&'static str);
+
+fn app() -> Router {
+    let serial = Arc::new(AtomicU64::new(0));
+    let container = Container::builder()
+        .singleton_value(Arc::new(Greeting("hello")))
+        .scoped_with(move |r| {
+            Ok(Session {
+                request: resolve::<RequestId>(r)?.0,
+                serial: serial.fetch_add(1, Ordering::SeqCst),
+            })
+        })
+        .build()
+        .unwrap();
+
+    Router::new()
+        .route(
+            "/",
+            get(
+                |Inject(greeting): Inject<Arc<Greeting>>,
+                 Inject(first): Inject<Session>,
+                 Inject(second): Inject<Session>| async move {
+                    assert_eq!(first.serial, second.serial);
+                    format!("{} #{} (session {})", greeting.0, first.request, first.serial)
+                },
+            ),
+        )
+        .route("/missing", get(|Inject(_): Inject<String>| async {}))
+        .layer(middleware::from_fn(|request: Request, next: Next| async move {
+            let id = request.headers()["x-request-id"].to_str().unwrap().parse().unwrap();
+            if let Some(scope) = request.extensions().get::<OwnedScope>() {
+                scope.insert(RequestId(id));
+            }
+            next.run(request).await
+        }))
+        .layer(MakhzanLayer::new(container))
+}
+
+async fn get_body(app: Router, uri: &str, id: u64) -> (StatusCode, String) {
+    let request = Request::builder()
+        .uri(uri)
+        .header("x-request-id", id.to_string())
+        .body(Body::empty())
+        .unwrap();
+    let response = app.oneshot(request).await.unwrap();
+    let status = response.status();
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    (status, String::from_utf8(body.to_vec()).unwrap())
+}
+
+#[tokio::test]
+async fn scoped_values_are_per_request() {
+    let app = app();
+
+    assert_eq!(get_body(app.clone(), "/", 7).await, (StatusCode::OK, "hello #7 (session 0)".into()));
+    assert_eq!(get_body(app, "/", 8).await, (StatusCode::OK, "hello #8 (session 1)".into()));
+}
+
+#[tokio::test]
+async fn resolve_errors_become_500() {
+    let (status, body) = get_body(app(), "/missing", 1).await;
+
+    assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+    assert!(body.contains("alloc::string::String"), "{body}");
+}
+
+#[tokio::test]
+async fn missing_layer_is_reported() {
+    let app = Router::new().route("/", get(|Inject(_): Inject<u32>| async {}));
+    let (status, body) = get_body(app, "/", 1).await;
+
+    assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+    assert!(body.contains("MakhzanLayer"));
+}
@@ -0,0 +1,188 @@
+//! Ambient scope for code that can't take a scope parameter.
+//!
+//! [`with_current`] makes an [`OwnedScope`] the current scope while a
+//! future runs, [`with_current_sync`] while a closure runs on this thread;
+//! [`current`] and [`resolve_current`] read it back from anywhere below.
+//! Nested calls shadow the outer scope and restore it when they return,
+//! also when they unwind.
+//!
+//! The async scope lives in a tokio task-local, so a task spawned inside
+//! `with_current` starts without one. Propagate it explicitly:
+//!
+//! ```rust
+//! # use makhzan_container::prelude::*;
+//! # use makhzan_container::scope::resolve_current;
+//! # #[derive(Clone)]
+//! # struct Mailer;
+//! # struct Order(u32);
+//! # async fn send_receipt(_: Order) -> Result<()> { resolve_current::<Mailer>().map(drop) }
+//! # tokio::runtime::Runtime::new().unwrap().block_on(async {
+//! # let container = Container::builder().scoped_with(|_| Ok(Mailer)).build()?;
+//! # let order = Order(1);
+//! # makhzan_container::scope::with_current(container.create_owned_scope(), async {
+//! let scope = makhzan_container::scope::current().expect("called inside a request");
+//! let receipt = tokio::spawn(makhzan_container::scope::with_current(scope, send_receipt(order)));
+//! # receipt.await.unwrap()
+//! # })
+//! # .await
+//! # })
+//! # .unwrap();
+//! ```
+
+use std::cell::RefCell;
+use std::future::Future;
+
+use crate::container::OwnedScope;
+use crate::error::{MakhzanError, Result};
+
+tokio::task_local! {
+    static TASK_SCOPE: OwnedScope;
+}
+
+thread_local! {
+    static THREAD_SCOPE: RefCell<Option<OwnedScope>> = const { RefCell::new(None) };
+}
+
+/// Run `future` with `scope` as the current scope.
+///
+/// ```rust
+/// # use makhzan_container::prelude::*;
+/// # use makhzan_container::scope::with_current;
+/// # mod audit {
+/// #     #[derive(Clone)]
+/// #     pub struct AuditLog;
+/// #     pub async fn record(_: &str) -> makhzan_container::Result<()> {
+/// #         makhzan_container::scope::resolve_current::<AuditLog>().map(drop)
+/// #     }
+/// # }
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// # let container = Container::builder().scoped_with(|_| Ok(audit::AuditLog)).build()?;
+/// with_current(container.create_owned_scope(), async {
+///     audit::record("login").await // calls resolve_current() inside
+/// })
+/// .await?;
+/// # Ok::<(), MakhzanError>(())
+/// # })
+/// # .unwrap();
+/// ```
+pub fn with_current<F: Future>(scope: OwnedScope, future: F) -> impl Future<Output = F::Output> {
+    TASK_SCOPE.scope(scope, future)
+}
+
+/// Run `f` with `scope` as the current scope of this thread.
+///
+/// For synchronous code; inside `f` it shadows any scope set by
+/// [`with_current`].
+pub fn with_current_sync<R>(scope: OwnedScope, f: impl FnOnce() -> R) -> R {
+    let previous = THREAD_SCOPE.with(|slot| slot.replace(Some(scope)));
+    let _restore = Restore(previous);
+    f()
+}
+
+/// Puts the shadowed thread scope back, also on unwind.
+struct Restore(Option<OwnedScope>);
+
+impl Drop for Restore {
+    fn drop(&mut self) {
+        let previous = self.0.take();
+        let _ = THREAD_SCOPE.try_with(|slot| *slot.borrow_mut() = previous);
+    }
+}
+
+/// The innermost scope set by [`with_current_sync`] or [`with_current`],
+/// if any.
+pub fn current() -> Option<OwnedScope> {
+    THREAD_SCOPE
+        .with(|slot| slot.borrow().clone())
+        .or_else(|| TASK_SCOPE.try_with(OwnedScope::clone).ok())
+}
+
+/// Resolve `T` from the [`current`] scope.
+///
+/// # Errors
+/// [`MakhzanError::NoCurrentScope`] outside `with_current`, otherwise
+/// whatever [`OwnedScope::resolve`] returns.
+pub fn resolve_current<T: Send + Sync + 'static>() -> Result<T> {
+    current().ok_or(MakhzanError::NoCurrentScope)?.resolve()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::container::Container;
+    use std::panic::{AssertUnwindSafe, catch_unwind};
+    use std::pin::pin;
+    use std::task::Poll;
+
+    #[derive(Clone, Debug, PartialEq)]
+    struct Name(&'static str);
+
+    fn scope(name: &'static str) -> OwnedScope {
+        let scope = Container::builder().build().unwrap().create_owned_scope();
+        scope.insert(Name(name));
+        scope
+    }
+
+    fn current_name() -> Option<&'static str> {
+        resolve_current::<Name>().ok().map(|name| name.0)
+    }
+
+    #[test]
+    fn nothing_is_current_outside_a_scope() {
+        assert!(current().is_none());
+        assert!(matches!(resolve_current::<Name>(), Err(MakhzanError::NoCurrentScope)));
+    }
+
+    #[tokio::test]
+    async fn nested_scopes_shadow_and_restore() {
+        with_current(scope("outer"), async {
+            assert_eq!(current_name(), Some("outer"));
+            with_current(scope("inner"), async {
+                assert_eq!(current_name(), Some("inner"));
+                with_current_sync(scope("sync"), || assert_eq!(current_name(), Some("sync")));
+                assert_eq!(current_name(), Some("inner"));
+            })
+            .await;
+            assert_eq!(current_name(), Some("outer"));
+        })
+        .await;
+        assert_eq!(current_name(), None);
+    }
+
+    #[tokio::test]
+    async fn spawned_tasks_do_not_inherit_the_scope() {
+        let inherited = with_current(scope("request"), async {
+            tokio::spawn(async { current().is_some() }).await.unwrap()
+        })
+        .await;
+        assert!(!inherited);
+
+        let propagated = with_current(scope("request"), async {
+            let scope = current().unwrap();
+            tokio::spawn(with_current(scope, async { current_name() })).await.unwrap()
+        })
+        .await;
+        assert_eq!(propagated, Some("request"));
+    }
+
+    #[tokio::test]
+    async fn scope_is_restored_after_a_panic() {
+        with_current(scope("outer"), async {
+            let mut inner = pin!(with_current(scope("inner"), async { panic!("handler failed") }));
+            let panicked = std::future::poll_fn(|cx| {
+                Poll::Ready(catch_unwind(AssertUnwindSafe(|| inner.as_mut().poll(cx))).is_err())
+            })
+            .await;
+            assert!(panicked);
+            assert_eq!(current_name(), Some("outer"));
+        })
+        .await;
+
+        with_current_sync(scope("outer"), || {
+            let result = catch_unwind(|| with_current_sync(scope("inner"), || panic!("job failed")));
+            assert!(result.is_err());
+            assert_eq!(current_name(), Some("outer"));
+        });
+        assert_eq!(current_name(), None);
+    }
+}
@@ -0,0 +1,111 @@
+//! A `OnceCell` that can be emptied again.
+//!
+//! Singleton caches must be clearable for
+//! [`Container::reset_singletons`](crate::container::Container::reset_singletons),
+//! but a `OnceCell` only hands out `&T` and so can't be cleared through a
+//! shared reference. [`ResetCell`] keeps an `Arc` to the current cell and
+//! swaps in a fresh one on [`reset`](ResetCell::reset); anyone still
+//! holding the old generation keeps its value.
+//!
+//! [`AsyncResetCell`] is the same over `tokio::sync::OnceCell`, so an async
+//! initializer runs once even when several tasks await it together.
+
+use std::sync::Arc;
+
+use once_cell::sync::OnceCell;
+use parking_lot::RwLock;
+
+pub(crate) struct ResetCell<T> {
+    current: RwLock<Arc<OnceCell<T>>>,
+}
+
+impl<T> ResetCell<T> {
+    pub fn new() -> Self {
+        Self { current: RwLock::new(Arc::new(OnceCell::new())) }
+    }
+
+    /// The current generation. Initialise and borrow through it; the
+    /// value stays alive as long as the returned `Arc` does.
+    pub fn generation(&self) -> Arc<OnceCell<T>> {
+        self.current.read().clone()
+    }
+
+    /// Clones the current value out, if there is one.
+    #[inline]
+    pub fn get_cloned(&self) -> Option<T>
+    where
+        T: Clone,
+    {
+        self.current.read().get().cloned()
+    }
+
+    pub fn is_set(&self) -> bool {
+        self.current.read().get().is_some()
+    }
+
+    /// Empties the cell; the next initialisation starts a new generation.
+    pub fn reset(&self) {
+        *self.current.write() = Arc::new(OnceCell::new());
+    }
+}
+
+impl<T> Default for ResetCell<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// [`ResetCell`] for values produced by an async initializer.
+#[cfg(feature = "async")]
+pub(crate) struct AsyncResetCell<T> {
+    current: RwLock<Arc<tokio::sync::OnceCell<T>>>,
+}
+
+#[cfg(feature = "async")]
+impl<T> AsyncResetCell<T> {
+    pub fn new() -> Self {
+        Self { current: RwLock::new(Arc::new(tokio::sync::OnceCell::new())) }
+    }
+
+    /// The current generation; see [`ResetCell::generation`].
+    pub fn generation(&self) -> Arc<tokio::sync::OnceCell<T>> {
+        self.current.read().clone()
+    }
+
+    /// Clones the current value out, if there is one.
+    pub fn get_cloned(&self) -> Option<T>
+    where
+        T: Clone,
+    {
+        self.current.read().get().cloned()
+    }
+
+    pub fn is_set(&self) -> bool {
+        self.current.read().initialized()
+    }
+
+    /// Empties the cell; the next initialisation starts a new generation.
+    pub fn reset(&self) {
+        *self.current.write() = Arc::new(tokio::sync::OnceCell::new());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reset_starts_a_new_generation() {
+        let cell = ResetCell::new();
+        let old = cell.generation();
+        old.get_or_init(|| 1);
+        assert_eq!(cell.get_cloned(), Some(1));
+
+        cell.reset();
+        assert!(!cell.is_set());
+        assert_eq!(old.get(), Some(&1));
+
+        cell.generation().get_or_init(|| 2);
+        assert_eq!(cell.get_cloned(), Some(2));
+    }
+}
@@ -0,0 +1,72 @@
+//! Parsed command-line arguments as dependencies, for
+//! [`ContainerBuilder::with_cli_args`](crate::container::ContainerBuilder::with_cli_args).
+
+use std::ffi::OsString;
+use std::sync::Arc;
+
+use clap::{ArgAction, ArgMatches, Command, Parser};
+
+use crate::error::{MakhzanError, Result};
+use crate::key::DependencyKey;
+use crate::provider::{Cacheable, ProviderRegistry};
+use crate::scope::Scope;
+
+/// Parses the arguments and registers the results; run by `build`.
+pub(crate) type ParseArgs = Arc<dyn Fn(&mut dyn ProviderRegistry) -> Result<()> + Send + Sync>;
+
+/// Parse `argv` into `A` once the container is built, registering `A` and
+/// one named `String` per top-level argument that has a value.
+pub(crate) fn deferred<A: Parser + Clone + Send + Sync + 'static>(argv: Vec<OsString>) -> ParseArgs {
+    Arc::new(move |registry| {
+        let matches = A::command()
+            .try_get_matches_from(argv.iter().cloned())
+            .map_err(failed::<A>)?;
+        let args = A::from_arg_matches(&matches).map_err(failed::<A>)?;
+        register_value(registry, DependencyKey::of::<A>(), args);
+        for (name, value) in argument_values(&A::command(), &matches) {
+            register_value(registry, DependencyKey::named_owned::<String>(name), value);
+        }
+        Ok(())
+    })
+}
+
+/// Register a ready value as a cached singleton under `key`.
+pub(crate) fn register_value<T: Clone + Send + Sync + 'static>(
+    registry: &mut dyn ProviderRegistry,
+    key: DependencyKey,
+    value: T,
+) {
+    registry.register_cacheable(
+        key,
+        Scope::Singleton,
+        Arc::new(move |_| Ok(Box::new(value.clone()))),
+        vec![],
+        Cacheable::of::<T>(),
+    );
+}
+
+fn failed<A: 'static>(error: clap::Error) -> MakhzanError {
+    MakhzanError::construction_failed(DependencyKey::of::<A>(), error)
+}
+
+/// `(name, value)` for every argument given or defaulted, named by its
+/// long flag or, for positionals, its id. Repeated values are joined
+/// with commas.
+fn argument_values(command: &Command, matches: &ArgMatches) -> Vec<(String, String)> {
+    command
+        .get_arguments()
+        .filter(|arg| {
+            !matches!(
+                arg.get_action(),
+                ArgAction::Help | ArgAction::HelpShort | ArgAction::HelpLong | ArgAction::Version
+            )
+        })
+        .filter_map(|arg| {
+            let id = arg.get_id().as_str();
+            let raw = matches.try_get_raw(id).ok()??;
+            let value: Vec<String> = raw.map(|value| value.to_string_lossy().into_owned()).collect();
+            let name = arg.get_long().unwrap_or(id);
+            Some((name.to_string(), value.join(",")))
+        })
+        .collect()
+}
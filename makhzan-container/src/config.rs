@@ -0,0 +1,567 @@
+//! Typed configuration from environment variables and files.
+//!
+//! [`ConfigProvider`] deserializes a `serde` struct and registers it as a
+//! singleton; [`ContainerBuilder::config_from_env`] and
+//! [`ContainerBuilder::config_from_file`] are the usual way to add one.
+//!
+//! ```rust
+//! # use makhzan_container::prelude::*;
+//! # use serde::Deserialize;
+//! # #[derive(Clone, Deserialize)]
+//! # struct DatabaseConfig { url: String, pool_size: u32 }
+//! #[derive(Clone, Deserialize)]
+//! struct AppConfig {
+//!     port: u16,
+//!     database: DatabaseConfig, // APP_DATABASE__URL, APP_DATABASE__POOL_SIZE
+//!     sentry_dsn: Option<String>,
+//! }
+//!
+//! # unsafe {
+//! #     std::env::set_var("APP_PORT", "8080");
+//! #     std::env::set_var("APP_DATABASE__URL", "postgres://localhost/app");
+//! #     std::env::set_var("APP_DATABASE__POOL_SIZE", "10");
+//! # }
+//! let container = Container::builder()
+//!     .config_from_env::<AppConfig>("APP_")
+//!     .build()?;
+//! let config: AppConfig = container.resolve()?;
+//! # assert_eq!(config.database.pool_size, 10);
+//! # Ok::<(), MakhzanError>(())
+//! ```
+//!
+//! Environment variables are matched case-insensitively after the prefix,
+//! and `__` separates nested fields. Numbers and booleans are parsed from
+//! the variable's text, sequences are split on commas, and absent `Option`
+//! fields are `None`. A variable that is not valid UTF-8, or two that set
+//! the same field, such as `APP_DATABASE` and `APP_DATABASE__URL`, fail
+//! the read rather than one silently winning.
+//!
+//! Files are read as TOML or JSON by extension.
+//!
+//! Either source is read when the value is first resolved, so a bad value
+//! fails that resolve with [`MakhzanError::ConstructionFailed`] wrapping a
+//! [`ConfigError`] that names the field. Call
+//! [`Container::warm_up_all_singletons`](crate::container::Container::warm_up_all_singletons)
+//! to fail at startup instead.
+//!
+//! [`ContainerBuilder::config_from_env`]: crate::container::ContainerBuilder::config_from_env
+//! [`ContainerBuilder::config_from_file`]: crate::container::ContainerBuilder::config_from_file
+
+use std::collections::BTreeMap;
+use std::ffi::OsString;
+use std::fmt;
+use std::marker::PhantomData;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use serde::de::value::{MapDeserializer, SeqDeserializer};
+use serde::de::{self, DeserializeOwned, IntoDeserializer, Visitor};
+use serde::forward_to_deserialize_any;
+
+use crate::error::MakhzanError;
+use crate::key::DependencyKey;
+use crate::provider::{Cacheable, Provider, ProviderRegistry};
+use crate::scope::Scope;
+
+/// Registers `T`, deserialized from a [configuration source](self), as a
+/// singleton.
+pub struct ConfigProvider<T> {
+    source: Source,
+    _config: PhantomData<fn() -> T>,
+}
+
+#[derive(Clone)]
+enum Source {
+    Env(String),
+    File(PathBuf),
+}
+
+impl<T> ConfigProvider<T> {
+    /// Read `T` from environment variables starting with `prefix`.
+    pub fn from_env(prefix: impl Into<String>) -> Self {
+        Self { source: Source::Env(prefix.into()), _config: PhantomData }
+    }
+
+    /// Read `T` from a `.toml` or `.json` file.
+    pub fn from_file(path: impl Into<PathBuf>) -> Self {
+        Self { source: Source::File(path.into()), _config: PhantomData }
+    }
+}
+
+impl<T: DeserializeOwned + Clone + Send + Sync + 'static> Provider for ConfigProvider<T> {
+    fn register(&self, builder: &mut dyn ProviderRegistry) {
+        let source = self.source.clone();
+        builder.register_cacheable(
+            DependencyKey::of::<T>(),
+            Scope::Singleton,
+            Arc::new(move |_| {
+                let config: T =
+                    source.load().map_err(|e| MakhzanError::construction_failed(DependencyKey::of::<T>(), e))?;
+                Ok(Box::new(config))
+            }),
+            vec![],
+            Cacheable::of::<T>(),
+        );
+    }
+
+    fn provides(&self) -> Vec<DependencyKey> {
+        vec![DependencyKey::of::<T>()]
+    }
+}
+
+impl<T> fmt::Debug for ConfigProvider<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ConfigProvider")
+            .field("config", &std::any::type_name::<T>())
+            .field("source", &self.source)
+            .finish()
+    }
+}
+
+impl fmt::Debug for Source {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+impl fmt::Display for Source {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Source::Env(prefix) => write!(f, "environment variables {prefix}*"),
+            Source::File(path) => write!(f, "{}", path.display()),
+        }
+    }
+}
+
+impl Source {
+    fn load<T: DeserializeOwned>(&self) -> std::result::Result<T, ConfigError> {
+        match self {
+            Source::Env(prefix) => {
+                let vars = EnvValue::from_vars(prefix, std::env::vars_os())
+                    .map_err(|message| ConfigError::new(self, None, message))?;
+                serde_path_to_error::deserialize(vars).map_err(|e| self.error(e))
+            }
+            Source::File(path) => {
+                let format = path.extension().and_then(|ext| ext.to_str());
+                if !matches!(format, Some("toml" | "json")) {
+                    return Err(ConfigError::new(
+                        self,
+                        None,
+                        "unsupported format; expected a .toml or .json file".to_string(),
+                    ));
+                }
+                let text = std::fs::read_to_string(path)
+                    .map_err(|e| ConfigError::new(self, None, e.to_string()))?;
+                if format == Some("toml") {
+                    serde_path_to_error::deserialize(toml::Deserializer::new(&text))
+                        .map_err(|e| self.error(e))
+                } else {
+                    serde_path_to_error::deserialize(&mut serde_json::Deserializer::from_str(&text))
+                        .map_err(|e| self.error(e))
+                }
+            }
+        }
+    }
+
+    fn error<E: fmt::Display>(&self, error: serde_path_to_error::Error<E>) -> ConfigError {
+        let field = match error.path().to_string() {
+            root if root == "." => None,
+            field => Some(field),
+        };
+        ConfigError::new(self, field, error.into_inner().to_string())
+    }
+}
+
+/// A configuration source that could not be read into its type.
+#[derive(Debug)]
+pub struct ConfigError {
+    source: String,
+    field: Option<String>,
+    message: String,
+}
+
+impl ConfigError {
+    fn new(source: &Source, field: Option<String>, message: String) -> Self {
+        Self { source: source.to_string(), field, message }
+    }
+
+    /// Dotted path of the offending field, e.g. `database.pool_size`;
+    /// `None` when the source as a whole is at fault.
+    pub fn field(&self) -> Option<&str> {
+        self.field.as_deref()
+    }
+
+    /// The file path or environment prefix that was read.
+    pub fn source_name(&self) -> &str {
+        &self.source
+    }
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.field {
+            Some(field) => write!(f, "invalid `{field}` in {}: {}", self.source, self.message),
+            None => write!(f, "cannot read {}: {}", self.source, self.message),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+/// Environment variables as a tree: `APP_DATABASE__URL` becomes
+/// `database.url` under the prefix `APP_`.
+enum EnvValue {
+    Text(String),
+    Table(BTreeMap<String, EnvValue>),
+}
+
+impl EnvValue {
+    /// Builds the tree from the variables starting with `prefix`.
+    ///
+    /// Fails on a matching variable that is not valid UTF-8, and on two
+    /// that set the same field or a field and one nested in it. The
+    /// variables are sorted first, so which pair is reported doesn't
+    /// depend on the environment's order.
+    fn from_vars(
+        prefix: &str,
+        vars: impl IntoIterator<Item = (OsString, OsString)>,
+    ) -> std::result::Result<Self, String> {
+        let mut fields = Vec::new();
+        for (name, value) in vars {
+            let lossy = name.to_string_lossy();
+            if strip_prefix_ignore_case(&lossy, prefix).is_none() {
+                continue;
+            }
+            let (Some(name), Ok(value)) = (name.to_str(), value.into_string()) else {
+                return Err(format!("`{lossy}` is not valid UTF-8"));
+            };
+            let path: Vec<String> = name[prefix.len()..].to_lowercase().split("__").map(str::to_string).collect();
+            fields.push((path, name.to_string(), value));
+        }
+        fields.sort_unstable_by(|(a, a_name, _), (b, b_name, _)| a.cmp(b).then_with(|| a_name.cmp(b_name)));
+
+        for pair in fields.windows(2) {
+            let [(outer, outer_name, _), (inner, inner_name, _)] = pair else { unreachable!() };
+            if inner.starts_with(outer) {
+                return Err(format!("`{outer_name}` and `{inner_name}` both set `{}`", outer.join(".")));
+            }
+        }
+
+        let mut root = BTreeMap::new();
+        for (path, _, value) in fields {
+            let (last, parents) = path.split_last().expect("split yields at least one segment");
+            let mut table = &mut root;
+            for segment in parents {
+                let entry = table.entry(segment.clone()).or_insert_with(|| EnvValue::Table(BTreeMap::new()));
+                let EnvValue::Table(next) = entry else { unreachable!("conflicting fields were rejected") };
+                table = next;
+            }
+            table.insert(last.clone(), EnvValue::Text(value));
+        }
+        Ok(EnvValue::Table(root))
+    }
+}
+
+fn strip_prefix_ignore_case<'a>(name: &'a str, prefix: &str) -> Option<&'a str> {
+    let head = name.get(..prefix.len())?;
+    head.eq_ignore_ascii_case(prefix).then(|| &name[prefix.len()..])
+}
+
+macro_rules! parse_text {
+    ($($method:ident => $visit:ident,)*) => {$(
+        fn $method<V: Visitor<'de>>(self, visitor: V) -> std::result::Result<V::Value, Self::Error> {
+            match self {
+                EnvValue::Text(text) => match text.trim().parse() {
+                    Ok(value) => visitor.$visit(value),
+                    Err(e) => Err(de::Error::custom(format_args!("`{text}`: {e}"))),
+                },
+                table => table.deserialize_any(visitor),
+            }
+        }
+    )*};
+}
+
+impl<'de> de::Deserializer<'de> for EnvValue {
+    type Error = de::value::Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> std::result::Result<V::Value, Self::Error> {
+        match self {
+            EnvValue::Text(text) => visitor.visit_string(text),
+            EnvValue::Table(table) => visitor.visit_map(MapDeserializer::new(table.into_iter())),
+        }
+    }
+
+    parse_text! {
+        deserialize_bool => visit_bool,
+        deserialize_i8 => visit_i8,
+        deserialize_i16 => visit_i16,
+        deserialize_i32 => visit_i32,
+        deserialize_i64 => visit_i64,
+        deserialize_i128 => visit_i128,
+        deserialize_u8 => visit_u8,
+        deserialize_u16 => visit_u16,
+        deserialize_u32 => visit_u32,
+        deserialize_u64 => visit_u64,
+        deserialize_u128 => visit_u128,
+        deserialize_f32 => visit_f32,
+        deserialize_f64 => visit_f64,
+        deserialize_char => visit_char,
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> std::result::Result<V::Value, Self::Error> {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> std::result::Result<V::Value, Self::Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> std::result::Result<V::Value, Self::Error> {
+        match self {
+            EnvValue::Text(text) => {
+                let items = text
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|item| !item.is_empty())
+                    .map(|item| EnvValue::Text(item.to_string()));
+                visitor.visit_seq(SeqDeserializer::new(items))
+            }
+            table => table.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> std::result::Result<V::Value, Self::Error> {
+        match self {
+            EnvValue::Text(text) => visitor.visit_enum(text.into_deserializer()),
+            table => table.deserialize_any(visitor),
+        }
+    }
+
+    forward_to_deserialize_any! {
+        str string bytes byte_buf unit unit_struct tuple tuple_struct map struct
+        identifier ignored_any
+    }
+}
+
+impl<'de> IntoDeserializer<'de, de::value::Error> for EnvValue {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::container::Container;
+    use parking_lot::Mutex;
+    use serde::Deserialize;
+
+    /// Tests that touch the process environment take this lock.
+    static ENV: Mutex<()> = Mutex::new(());
+
+    fn with_env<R>(vars: &[(&str, &str)], f: impl FnOnce() -> R) -> R {
+        let _guard = ENV.lock();
+        for (name, value) in vars {
+            // SAFETY: every test that reads or writes these variables
+            // holds `ENV`.
+            unsafe { std::env::set_var(name, value) };
+        }
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(f));
+        for (name, _) in vars {
+            // SAFETY: as above.
+            unsafe { std::env::remove_var(name) };
+        }
+        result.unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+    }
+
+    #[derive(Debug, Clone, PartialEq, Deserialize)]
+    struct AppConfig {
+        port: u16,
+        debug: bool,
+        database: DatabaseConfig,
+        sentry_dsn: Option<String>,
+        #[serde(default)]
+        hosts: Vec<String>,
+    }
+
+    #[derive(Debug, Clone, PartialEq, Deserialize)]
+    struct DatabaseConfig {
+        url: String,
+        pool_size: Option<u32>,
+    }
+
+    #[test]
+    fn reads_nested_config_from_env() {
+        let config = with_env(
+            &[
+                ("CFGTEST_A_PORT", "8080"),
+                ("CFGTEST_A_DEBUG", "true"),
+                ("CFGTEST_A_DATABASE__URL", "postgres://localhost/app"),
+                ("CFGTEST_A_DATABASE__POOL_SIZE", "4"),
+                ("CFGTEST_A_HOSTS", "a.example, b.example"),
+            ],
+            || {
+                Container::builder()
+                    .config_from_env::<AppConfig>("CFGTEST_A_")
+                    .build()
+                    .unwrap()
+                    .resolve::<AppConfig>()
+                    .unwrap()
+            },
+        );
+        assert_eq!(
+            config,
+            AppConfig {
+                port: 8080,
+                debug: true,
+                database: DatabaseConfig {
+                    url: "postgres://localhost/app".into(),
+                    pool_size: Some(4),
+                },
+                sentry_dsn: None,
+                hosts: vec!["a.example".into(), "b.example".into()],
+            }
+        );
+    }
+
+    #[test]
+    fn env_errors_name_the_field() {
+        let err = with_env(
+            &[
+                ("CFGTEST_B_PORT", "8080"),
+                ("CFGTEST_B_DEBUG", "false"),
+                ("CFGTEST_B_DATABASE__URL", "postgres://localhost/app"),
+                ("CFGTEST_B_DATABASE__POOL_SIZE", "lots"),
+            ],
+            || {
+                Container::builder()
+                    .config_from_env::<AppConfig>("CFGTEST_B_")
+                    .build()
+                    .unwrap()
+                    .resolve::<AppConfig>()
+                    .unwrap_err()
+            },
+        );
+        let MakhzanError::ConstructionFailed { key, source, .. } = err else {
+            panic!("expected ConstructionFailed, got {err:?}");
+        };
+        assert_eq!(key, DependencyKey::of::<AppConfig>());
+        let config_error = source.downcast_ref::<ConfigError>().unwrap();
+        assert_eq!(config_error.field(), Some("database.pool_size"));
+        assert!(config_error.to_string().contains("`lots`"), "{config_error}");
+    }
+
+    #[test]
+    fn missing_env_field_is_reported() {
+        let err = with_env(&[("CFGTEST_C_PORT", "1")], || {
+            ConfigProvider::<AppConfig>::from_env("CFGTEST_C_")
+                .source
+                .load::<AppConfig>()
+                .unwrap_err()
+        });
+        assert!(err.to_string().contains("missing field `debug`"), "{err}");
+    }
+
+    fn vars(vars: &[(&str, &str)]) -> Vec<(OsString, OsString)> {
+        vars.iter().map(|(name, value)| (name.into(), value.into())).collect()
+    }
+
+    #[test]
+    fn a_field_set_as_value_and_table_is_an_error_in_any_order() {
+        let set = [("APP_DATABASE", "postgres://localhost/app"), ("APP_DATABASE__URL", "postgres://db/app")];
+        let reversed = [set[1], set[0]];
+
+        for env in [vars(&set), vars(&reversed)] {
+            let Err(message) = EnvValue::from_vars("APP_", env) else {
+                panic!("expected a conflict");
+            };
+            assert_eq!(message, "`APP_DATABASE` and `APP_DATABASE__URL` both set `database`");
+        }
+
+        let Err(message) = EnvValue::from_vars("APP_", vars(&[("app_port", "1"), ("APP_PORT", "2")])) else {
+            panic!("expected a conflict");
+        };
+        assert_eq!(message, "`APP_PORT` and `app_port` both set `port`");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn non_utf8_variables_are_reported_only_under_the_prefix() {
+        use std::os::unix::ffi::OsStringExt;
+
+        let invalid = || OsString::from_vec(vec![0x66, 0x6f, 0x80]);
+        let mut env = vars(&[("APP_PORT", "8080")]);
+        env.push((invalid(), "ignored".into()));
+        env.push(("OTHER".into(), invalid()));
+        assert!(EnvValue::from_vars("APP_", env.clone()).is_ok());
+
+        env.push(("APP_HOST".into(), invalid()));
+        let Err(message) = EnvValue::from_vars("APP_", env) else {
+            panic!("expected a UTF-8 error");
+        };
+        assert_eq!(message, "`APP_HOST` is not valid UTF-8");
+    }
+
+    fn write_temp(name: &str, contents: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("makhzan-{}-{name}", std::process::id()));
+        std::fs::write(&path, contents).unwrap();
+        path
+    }
+
+    #[test]
+    fn reads_toml_and_json_files() {
+        let toml = write_temp(
+            "app.toml",
+            "port = 80\ndebug = false\n\n[database]\nurl = \"postgres://db/app\"\n",
+        );
+        let json = write_temp(
+            "app.json",
+            r#"{"port": 81, "debug": true, "database": {"url": "postgres://db/app", "pool_size": 2}, "sentry_dsn": "dsn"}"#,
+        );
+
+        let from_toml: AppConfig = Container::builder()
+            .config_from_file::<AppConfig>(&toml)
+            .build()
+            .unwrap()
+            .resolve()
+            .unwrap();
+        let from_json: AppConfig = Container::builder()
+            .config_from_file::<AppConfig>(&json)
+            .build()
+            .unwrap()
+            .resolve()
+            .unwrap();
+        std::fs::remove_file(toml).unwrap();
+        std::fs::remove_file(json).unwrap();
+
+        assert_eq!(from_toml.port, 80);
+        assert_eq!(from_toml.database.pool_size, None);
+        assert_eq!(from_json.database.pool_size, Some(2));
+        assert_eq!(from_json.sentry_dsn.as_deref(), Some("dsn"));
+    }
+
+    #[test]
+    fn file_errors_name_the_field() {
+        let path = write_temp("bad.toml", "port = \"eighty\"\n");
+        let err = ConfigProvider::<AppConfig>::from_file(&path).source.load::<AppConfig>().unwrap_err();
+        std::fs::remove_file(path).unwrap();
+        assert_eq!(err.field(), Some("port"));
+    }
+
+    #[test]
+    fn unknown_extension_is_rejected() {
+        let err = ConfigProvider::<AppConfig>::from_file("app.yaml").source.load::<AppConfig>().unwrap_err();
+        assert_eq!(err.field(), None);
+    }
+}
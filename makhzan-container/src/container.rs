@@ -33,11 +33,11 @@
 //!
 //! let container = Container::builder()
 //!     .singleton_with::<Arc<dyn Logger>>(|_| {
-//!         Ok(Box::new(Arc::new(ConsoleLogger) as Arc<dyn Logger>))
+//!         Ok(Arc::new(ConsoleLogger) as Arc<dyn Logger>)
 //!     })
 //!     .transient_with::<UserService>(|resolver| {
-//!         let logger: Arc<dyn Logger> = resolver.resolve()?;
-//!         Ok(Box::new(UserService { logger }))
+//!         let logger: Arc<dyn Logger> = resolve(resolver)?;
+//!         Ok(UserService { logger })
 //!     })
 //!     .build()
 //!     .expect("Failed to build container");
@@ -48,17 +48,41 @@
 use std::any::{Any, type_name};
 use std::collections::HashMap;
 use std::fmt;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex, PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard, Weak};
+use std::time::{Duration, Instant};
 
 use once_cell::sync::OnceCell;
-use tracing::{debug, info, instrument, trace};
 
-use crate::error::{MakhzanError, NotRegisteredError, Result};
-use crate::graph::{DependencyInfo, GraphValidator};
-use crate::key::DependencyKey;
-use crate::provider::{Provider, ProviderRegistry};
-use crate::registry::{FactoryFn, Registration, Registry, Resolver};
-use crate::scope::Scope;
+#[cfg(feature = "async")]
+use crate::cell::AsyncResetCell;
+use crate::cell::ResetCell;
+use crate::error::{
+    AlreadyRegisteredError, MakhzanError, NotRegisteredError, Result, SuggestionConfig,
+    UnknownDynamicNameError, UnknownMapKeyError,
+};
+use crate::graph::{dependency_order, GraphStats, GraphValidator, ValidationReport};
+use crate::init::{initializer, InitFn, Initialize};
+use crate::inject::Injectable;
+use crate::invoke::Invoke;
+use crate::key::{BuildKeyHasher, DependencyKey};
+use crate::logging::{debug, factory_span, info, trace, warn};
+use crate::plan::{CachedFailure, PlanEntry, Plans, ResolutionPlan};
+use crate::provider::{Cacheable, Provider, ProviderRegistry, UnmetRequirement};
+use crate::recording::{RecordingResolver, Sentinels, sentinel_factory};
+use crate::describe::KeyDescription;
+use crate::export::DependencyGraph;
+use crate::generic::{GenericFactory, GenericParams};
+use crate::registry::{
+    Cloner, FactoryFn, FamilyFn, InlineFactory, InstanceProbe, Registration, Registry, Resolved,
+    Resolver, SingletonReset,
+};
+use crate::scope::{RefreshFailurePolicy, Scope, ScopeValidation, ScopedFromRoot, SingletonFailurePolicy};
+use crate::stack::{self, StackGuard};
+use crate::trace::{ResolutionTrace, ResolveOutcome, TraceRecorder};
+use crate::tree;
+use crate::tuple::{ResolveTuple, ResolverExt};
+use crate::upcast::Upcast;
+use makhzan_support::rendering::render_tree;
 
 
 // ============================================================
@@ -72,22 +96,130 @@ use crate::scope::Scope;
 /// thread-safe container.
 ///
 /// # Examples
-/// ```rust,ignore
+/// ```rust
+/// # use makhzan_container::prelude::*;
+/// # #[derive(Clone)]
+/// # struct Config;
+/// # impl Config { fn load() -> Self { Config } }
+/// # #[derive(Clone)]
+/// # struct Database;
+/// # struct UserService { db: Database }
 /// let container = Container::builder()
 ///     .singleton_value(Config::load())
-///     .singleton_with::<Database>(|resolver| { ... })
-///     .transient_with::<UserService>(|resolver| { ... })
+///     .singleton_with::<Database>(|resolver| {
+///         let _config: Config = resolve(resolver)?;
+///         Ok(Database)
+///     })
+///     .transient_with::<UserService>(|resolver| Ok(UserService { db: resolve(resolver)? }))
 ///     .build()?;
+/// # Ok::<(), MakhzanError>(())
 /// ```
+///
+/// # Cloning
+/// A builder can be cloned to derive several containers from a common
+/// base (e.g. A/B wiring in tests). Each clone owns its own registration
+/// map, so registering into one never affects the other.
+///
+/// Factories themselves are shared by `Arc`, but each built container
+/// caches its own [`singleton_with`](ContainerBuilder::singleton_with)
+/// instances: containers built from clones never share one. State a
+/// factory captures itself, such as the instance of a
+/// [`singleton_ref_with`](ContainerBuilder::singleton_ref_with), is
+/// shared unless the binding is re-registered on the clone.
+///
+/// ```rust
+/// # use makhzan_container::prelude::*;
+/// # #[derive(Clone, Default)]
+/// # struct Config;
+/// # struct Mailer(&'static str);
+/// # impl Mailer {
+/// #     fn smtp() -> Self { Mailer("smtp") }
+/// #     fn fake() -> Self { Mailer("fake") }
+/// # }
+/// let base = Container::builder().singleton_value(Config::default());
+///
+/// let a = base.clone().transient_with::<Mailer>(|_| Ok(Mailer::smtp())).build()?;
+/// let b = base.transient_with::<Mailer>(|_| Ok(Mailer::fake())).build()?;
+/// assert_eq!(a.resolve::<Mailer>()?.0, "smtp");
+/// assert_eq!(b.resolve::<Mailer>()?.0, "fake");
+/// # Ok::<(), MakhzanError>(())
+/// ```
+#[derive(Clone)]
 pub struct ContainerBuilder {
     registry: Registry,
+    sentinels: Sentinels,
+    dynamic: HashMap<String, DependencyKey>,
+    /// Fallbacks from [`register_default_impl`](Self::register_default_impl),
+    /// registered at build time for keys still missing.
+    defaults: Vec<Registration>,
+    /// [`register_for`](Self::register_for) bindings with the profiles
+    /// they apply to, registered at build time if one is active.
+    profiled: Vec<(Vec<String>, Registration)>,
+    /// Active profile set with [`profile`](Self::profile).
+    profile: Option<String>,
+    /// [`bind`](Self::bind) and [`also_as`](Self::also_as) keys, which
+    /// take their scope from the implementation at build time.
+    bindings: Vec<(DependencyKey, FactoryFn, Cloner)>,
+    /// [`register_family`](Self::register_family) and
+    /// [`register_generic`](Self::register_generic) families, in
+    /// registration order.
+    families: Vec<FamilyFn>,
+    /// [`on_resolve`](Self::on_resolve) callbacks, in registration order.
+    observers: Vec<Observer>,
+    /// [`add_keyed`](Self::add_keyed) entries added twice without
+    /// `allow_override`, reported by `build`.
+    duplicate_entries: Vec<DependencyKey>,
+    /// [`Provider::requires`] of every added provider, by provider name.
+    requirements: Vec<(String, DependencyKey)>,
+    /// Every provider added by value, in add order, for
+    /// [`Provider::teardown`]. Shared with clones of the builder.
+    providers: Vec<Arc<OwnedProvider>>,
     allow_override: bool,
+    scoped_from_root: ScopedFromRoot,
+    scope_validation: ScopeValidation,
+    max_depth: usize,
+    catch_panics: bool,
+    suggestions: SuggestionConfig,
+    /// Keys marked with [`with_initializer`](Self::with_initializer).
+    initializers: Vec<(DependencyKey, InitFn)>,
+    resolve_timeout: Option<Duration>,
+    /// Async factories from [`singleton_async`](Self::singleton_async).
+    #[cfg(feature = "async")]
+    async_inits: HashMap<DependencyKey, AsyncInit>,
+    /// Live-reference counters of [`singleton_weak`](Self::singleton_weak) keys.
+    weak_counts: HashMap<DependencyKey, StrongCount>,
+    /// Deferred [`with_cli_args`](Self::with_cli_args) parse, run by `build`.
+    #[cfg(feature = "clap")]
+    cli_args: Option<crate::cli::ParseArgs>,
 }
 impl ContainerBuilder {
     fn new() -> Self {
         Self {
             registry: Registry::new(),
+            sentinels: Sentinels::default(),
+            dynamic: HashMap::new(),
+            defaults: Vec::new(),
+            profiled: Vec::new(),
+            profile: None,
+            bindings: Vec::new(),
+            families: Vec::new(),
+            observers: Vec::new(),
+            duplicate_entries: Vec::new(),
+            requirements: Vec::new(),
+            providers: Vec::new(),
             allow_override: false,
+            scoped_from_root: ScopedFromRoot::Allow,
+            scope_validation: ScopeValidation::Deny,
+            max_depth: DEFAULT_MAX_RESOLVE_DEPTH,
+            catch_panics: false,
+            suggestions: SuggestionConfig::default(),
+            initializers: Vec::new(),
+            resolve_timeout: None,
+            #[cfg(feature = "async")]
+            async_inits: HashMap::new(),
+            weak_counts: HashMap::new(),
+            #[cfg(feature = "clap")]
+            cli_args: None,
         }
     }
 
@@ -97,20 +229,310 @@ impl ContainerBuilder {
         self
     }
 
+    /// What the root [`Container`] does when a [`Scope::Scoped`]
+    /// dependency is resolved from it instead of from a
+    /// [`ScopedContainer`]; [`ScopedFromRoot::Allow`] unless set.
+    ///
+    /// ```rust
+    /// # use makhzan_container::prelude::*;
+    /// # #[derive(Clone)]
+    /// # struct Pool;
+    /// # #[derive(Clone)]
+    /// # struct Session;
+    /// # impl Session { fn open(_: Pool) -> Self { Session } }
+    /// let container = Container::builder()
+    /// #   .singleton_value(Pool)
+    ///     .scoped_with(|r| Ok(Session::open(resolve(r)?)))
+    ///     .scoped_from_root(ScopedFromRoot::Deny)
+    ///     .build()?;
+    /// assert!(container.resolve::<Session>().is_err());
+    /// assert!(container.create_scope().resolve::<Session>().is_ok());
+    /// # Ok::<(), MakhzanError>(())
+    /// ```
+    pub fn scoped_from_root(mut self, scoped_from_root: ScopedFromRoot) -> Self {
+        self.scoped_from_root = scoped_from_root;
+        self
+    }
+
+    /// How `build` treats a dependency that lives shorter than its
+    /// consumer; [`ScopeValidation::Deny`] unless set.
+    ///
+    /// ```rust
+    /// # use makhzan_container::prelude::*;
+    /// # #[derive(Clone)]
+    /// # struct Metrics;
+    /// # impl Metrics { fn sample() -> Self { Metrics } }
+    /// # #[derive(Clone)]
+    /// # struct Snapshot;
+    /// # impl Snapshot { fn of(_: Metrics) -> Self { Snapshot } }
+    /// // `Snapshot` is a singleton that copies a transient `Metrics` once, on purpose.
+    /// let container = Container::builder()
+    ///     .transient_with(|_| Ok(Metrics::sample()))
+    ///     .register_factory(Scope::Singleton, vec![DependencyKey::of::<Metrics>()], |r| Ok(Snapshot::of(resolve(r)?)))
+    ///     .scope_validation(ScopeValidation::Warn)
+    ///     .build()?;
+    /// assert_eq!(container.validation_report().warnings.len(), 1);
+    /// # Ok::<(), MakhzanError>(())
+    /// ```
+    pub fn scope_validation(mut self, scope_validation: ScopeValidation) -> Self {
+        self.scope_validation = scope_validation;
+        self
+    }
+
+    /// Limit how deeply factories may nest while resolving one key.
+    ///
+    /// Exceeding it returns [`MakhzanError::MaxDepthExceeded`] with the
+    /// path taken, instead of overflowing the stack. Defaults to
+    /// [`DEFAULT_MAX_RESOLVE_DEPTH`]; cached singletons don't count.
+    pub fn max_resolve_depth(mut self, depth: usize) -> Self {
+        self.max_depth = depth;
+        self
+    }
+
+    /// Turn a panicking factory into [`MakhzanError::ConstructionFailed`]
+    /// carrying the panic message, instead of unwinding through the
+    /// caller.
+    ///
+    /// Keeps one bad `.unwrap()` in a request-scoped factory from taking
+    /// down a server thread. Factories are run under
+    /// [`AssertUnwindSafe`](std::panic::AssertUnwindSafe): state a factory
+    /// mutated before panicking may be left half-updated, and the panic
+    /// hook still prints the message. Has no effect when panics abort.
+    pub fn catch_factory_panics(mut self, catch: bool) -> Self {
+        self.catch_panics = catch;
+        self
+    }
+
+    /// Call `observer` after every resolve with the key and how it ended.
+    ///
+    /// A seam for metrics, auditing or test spies that need nothing from
+    /// the built-in tracing. Each observer added is called in turn; keys
+    /// resolved by a factory are reported too, before the key that needed
+    /// them. Runs on the resolving thread, so keep it cheap.
+    ///
+    /// ```rust
+    /// use makhzan_container::prelude::*;
+    /// use std::sync::atomic::{AtomicUsize, Ordering};
+    /// use std::sync::Arc;
+    ///
+    /// let failures = Arc::new(AtomicUsize::new(0));
+    /// let container = Container::builder()
+    ///     .on_resolve({
+    ///         let failures = failures.clone();
+    ///         move |_, outcome| {
+    ///             if outcome == ResolveOutcome::Failed {
+    ///                 failures.fetch_add(1, Ordering::Relaxed);
+    ///             }
+    ///         }
+    ///     })
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// assert!(container.resolve::<u32>().is_err());
+    /// assert_eq!(failures.load(Ordering::Relaxed), 1);
+    /// ```
+    pub fn on_resolve(
+        mut self,
+        observer: impl Fn(&DependencyKey, ResolveOutcome) + Send + Sync + 'static,
+    ) -> Self {
+        self.observers.push(Arc::new(observer));
+        self
+    }
+
+    /// Fail an async factory that runs longer than `timeout` with
+    /// [`MakhzanError::ConstructionTimeout`], so a hanging `connect().await`
+    /// fails startup instead of stalling it.
+    ///
+    /// Applies to [`singleton_async`](Self::singleton_async) factories run
+    /// by [`Container::resolve_async`]; synchronous factories can't be
+    /// interrupted. A no-op without the `async` feature.
+    pub fn resolve_timeout(mut self, timeout: Duration) -> Self {
+        self.resolve_timeout = Some(timeout);
+        self
+    }
+
+    /// Tune the "did you mean?" suggestions in not-registered errors,
+    /// both from [`build`](Self::build) and from resolves afterwards.
+    ///
+    /// ```rust
+    /// # use makhzan_container::prelude::*;
+    /// use makhzan_container::error::SuggestionConfig;
+    ///
+    /// let container = Container::builder()
+    ///     .suggestions(SuggestionConfig { max: 5, min_score: 80 })
+    ///     .build()?;
+    /// # Ok::<(), MakhzanError>(())
+    /// ```
+    pub fn suggestions(mut self, config: SuggestionConfig) -> Self {
+        self.suggestions = config;
+        self
+    }
+
     // ── Singleton: pre-built value ──
 
     /// Register a pre-built value as a singleton.
     ///
     /// Cloned on every resolve (use `Arc<T>` for cheap sharing).
-    pub fn singleton_value<T: Clone + Send + Sync + 'static>(self, value: T) -> Self {
-        self.register_internal(
-            DependencyKey::of::<T>(),
-            Scope::Singleton,
-            Arc::new(move |_: &dyn Resolver| {
-                Ok(Box::new(value.clone()) as Box<dyn Any + Send + Sync>)
+    ///
+    /// The value also serves as the [`sentinel`](Self::sentinel) for `T`.
+    pub fn singleton_value<T: Clone + Send + Sync + 'static>(mut self, value: T) -> Self {
+        self.sentinels.insert(DependencyKey::of::<T>(), sentinel_factory(value.clone()));
+        self.register_entry(Registration {
+            cloner: Some(Cloner::of::<T>()),
+            instantiated: Some(Arc::new(|| true)),
+            ..Registration::new(
+                DependencyKey::of::<T>(),
+                Scope::Singleton,
+                Arc::new(move |_: &dyn Resolver| {
+                    Ok(Box::new(value.clone()) as Box<dyn Any + Send + Sync>)
+                }),
+                vec![],
+            )
+        })
+    }
+
+    /// Register an instance built outside the container, such as one
+    /// handed over by a host application, as a singleton.
+    ///
+    /// Same as [`singleton_value`](Self::singleton_value); the name says
+    /// the container does not own its construction.
+    ///
+    /// ```rust
+    /// # use makhzan_container::prelude::*;
+    /// # use std::sync::Arc;
+    /// # trait Logger: Send + Sync {}
+    /// # struct HostLogger;
+    /// # impl Logger for HostLogger {}
+    /// fn plugin_container(host_logger: Arc<dyn Logger>) -> Result<Container> {
+    ///     Container::builder().singleton_instance(host_logger).build()
+    /// }
+    ///
+    /// let host_logger: Arc<dyn Logger> = Arc::new(HostLogger);
+    /// let container = plugin_container(Arc::clone(&host_logger))?;
+    /// assert!(Arc::ptr_eq(&container.resolve::<Arc<dyn Logger>>()?, &host_logger));
+    /// # Ok::<(), MakhzanError>(())
+    /// ```
+    pub fn singleton_instance<T: Clone + Send + Sync + 'static>(self, value: T) -> Self {
+        self.singleton_value(value)
+    }
+
+    /// Register an existing `Arc<C>` and its trait-object binding in one
+    /// call.
+    ///
+    /// Registers `instance` as `Arc<C>` and [`bind`](Self::bind)s `Arc<I>`
+    /// to it, so both resolve to `instance` and `Arc<I>` shows up as a
+    /// binding of `Arc<C>` rather than a second singleton.
+    ///
+    /// ```rust
+    /// use makhzan_container::prelude::*;
+    /// use std::sync::Arc;
+    ///
+    /// trait Logger: Send + Sync {}
+    /// makhzan_container::upcast!(Logger);
+    ///
+    /// struct ConsoleLogger;
+    /// impl Logger for ConsoleLogger {}
+    ///
+    /// let host = Arc::new(ConsoleLogger);
+    /// let container = Container::builder().bind_instance::<dyn Logger, _>(Arc::clone(&host)).build().unwrap();
+    /// let logger: Arc<dyn Logger> = container.resolve().unwrap();
+    /// assert!(std::ptr::addr_eq(Arc::as_ptr(&logger), Arc::as_ptr(&host)));
+    /// ```
+    pub fn bind_instance<I: ?Sized + Upcast<C>, C: Send + Sync + 'static>(self, instance: Arc<C>) -> Self {
+        self.singleton_instance(instance).bind::<I, C>()
+    }
+
+    // ── Singleton: async factory ──
+
+    /// Register a singleton built by an async factory.
+    ///
+    /// [`Container::resolve_async`] runs the factory on first use, bounded
+    /// by [`resolve_timeout`](Self::resolve_timeout). Tasks awaiting it
+    /// concurrently share that one run; if it fails, the next resolve runs
+    /// the factory again. Afterwards the value
+    /// also resolves synchronously, so async singletons are typically
+    /// resolved once at startup. A synchronous resolve before that fails
+    /// with [`MakhzanError::ConstructionFailed`].
+    ///
+    /// The factory receives a handle to the container to resolve its own
+    /// dependencies with.
+    ///
+    /// ```rust
+    /// # use makhzan_container::prelude::*;
+    /// # use std::sync::Arc;
+    /// # use std::time::Duration;
+    /// # #[derive(Clone)]
+    /// # struct DbConfig { url: String }
+    /// # impl DbConfig {
+    /// #     fn from_env() -> Result<Self> { Ok(DbConfig { url: "postgres://localhost/app".into() }) }
+    /// # }
+    /// # struct Pool { url: String }
+    /// # impl Pool {
+    /// #     async fn connect(url: &str) -> std::io::Result<Pool> { Ok(Pool { url: url.into() }) }
+    /// # }
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let container = Container::builder()
+    ///     .singleton_value(DbConfig::from_env()?)
+    ///     .singleton_async(|c: Container| async move {
+    ///         let config: DbConfig = c.resolve()?;
+    ///         Pool::connect(&config.url)
+    ///             .await
+    ///             .map(Arc::new)
+    ///             .map_err(|e| MakhzanError::construction_failed(DependencyKey::of::<Arc<Pool>>(), e))
+    ///     })
+    ///     .resolve_timeout(Duration::from_secs(10))
+    ///     .build()?;
+    /// let pool: Arc<Pool> = container.resolve_async().await?;
+    /// assert_eq!(pool.url, "postgres://localhost/app");
+    /// # Ok::<(), MakhzanError>(())
+    /// # }).unwrap();
+    /// ```
+    #[cfg(feature = "async")]
+    pub fn singleton_async<T, F, Fut>(mut self, factory: F) -> Self
+    where
+        T: Clone + Send + Sync + 'static,
+        F: Fn(Container) -> Fut + Send + Sync + 'static,
+        Fut: std::future::Future<Output = Result<T>> + Send + 'static,
+    {
+        let key = DependencyKey::of::<T>();
+        let cell: Arc<AsyncResetCell<T>> = Arc::new(AsyncResetCell::new());
+        let factory = Arc::new(factory);
+
+        let init_cell = Arc::clone(&cell);
+        let init: AsyncInit = Arc::new(move |container| {
+            if init_cell.is_set() {
+                return Box::pin(std::future::ready(Ok(())));
+            }
+            let generation = init_cell.generation();
+            let factory = Arc::clone(&factory);
+            Box::pin(async move {
+                generation.get_or_try_init(|| factory(container)).await?;
+                Ok(())
+            })
+        });
+        self.async_inits.insert(key.clone(), init);
+
+        self.register_entry(Registration {
+            reset: Some({
+                let cell = Arc::clone(&cell);
+                Arc::new(move || cell.reset())
             }),
-            vec![],
-        )
+            ..Registration::new(
+                key,
+                Scope::Singleton,
+                Arc::new(move |_: &dyn Resolver| match cell.get_cloned() {
+                    Some(value) => Ok(Box::new(value) as Box<dyn Any + Send + Sync>),
+                    None => Err(MakhzanError::construction_failed(
+                        DependencyKey::of::<T>(),
+                        format!(
+                            "{} has an async factory; resolve it with resolve_async first",
+                            type_name::<T>()
+                        ),
+                    )),
+                }),
+                vec![],
+            )
+        })
     }
 
     // ── Singleton: factory ──
@@ -120,479 +542,7378 @@ impl ContainerBuilder {
     /// Called ONCE on first resolve (via `OnceCell`).
     /// Result is cloned on subsequent resolves.
     ///
-    /// **`T` must implement `Clone`** — use `Arc<T>` for services.
+    /// Threads racing on the first resolve block until the one running the
+    /// factory finishes; they all receive the same value. A factory that
+    /// resolves its own key fails with [`MakhzanError::CircularDependency`]
+    /// instead of deadlocking.
+    ///
+    /// If the factory fails, nothing is cached and the next resolve calls it
+    /// again, which suits a database that is not reachable yet. Use
+    /// [`singleton_with_memo_errors`](Self::singleton_with_memo_errors) to
+    /// fail fast with the first error instead.
+    ///
+    /// **`T` must implement `Clone`** — use `Arc<T>` for services, or
+    /// [`singleton_arc`](Self::singleton_arc) to have it wrapped for you.
     pub fn singleton_with<T: Clone + Send + Sync + 'static>(
         self,
         factory: impl Fn(&dyn Resolver) -> Result<T> + Send + Sync + 'static,
     ) -> Self {
-        let cell: Arc<OnceCell<T>> = Arc::new(OnceCell::new());
-
-        self.register_internal(
-            DependencyKey::of::<T>(),
-            Scope::Singleton,
-            {
-                let cell = cell.clone();
-                Arc::new(move |resolver: &dyn Resolver| {
-                    let value = cell.get_or_try_init(|| factory(resolver))?;
-                    Ok(Box::new(value.clone()) as Box<dyn Any + Send + Sync>)
-                })
-            },
-            vec![],
-        )
+        self.singleton_with_policy(SingletonFailurePolicy::RetryOnNextResolve, factory)
     }
 
-    // ── Scoped ──
+    /// Register a singleton factory for a service that isn't `Clone`.
+    ///
+    /// The value is wrapped in an `Arc` and registered as **`Arc<T>`**, so
+    /// that is what resolves; resolving a bare `T` fails with
+    /// [`MakhzanError::NotRegistered`]. Otherwise the same as
+    /// `singleton_with::<Arc<T>>(|r| factory(r).map(Arc::new))`.
+    ///
+    /// ```rust
+    /// use makhzan_container::prelude::*;
+    /// use std::sync::Arc;
+    ///
+    /// struct Database {
+    ///     url: String,
+    /// }
+    ///
+    /// let container = Container::builder()
+    ///     .singleton_value(String::from("postgres://localhost/app"))
+    ///     .singleton_arc(|r| Ok(Database { url: resolve(r)? }))
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// let db: Arc<Database> = container.resolve().unwrap();
+    /// assert_eq!(db.url, "postgres://localhost/app");
+    /// assert!(Arc::ptr_eq(&db, &container.resolve::<Arc<Database>>().unwrap()));
+    /// assert!(container.resolve::<Database>().is_err());
+    /// ```
+    ///
+    /// [`bind_shared`](Self::bind_shared) registers the same `Arc<T>` plus a
+    /// trait-object binding to it.
+    #[doc(alias = "singleton_shared")]
+    pub fn singleton_arc<T: Send + Sync + 'static>(
+        self,
+        factory: impl Fn(&dyn Resolver) -> Result<T> + Send + Sync + 'static,
+    ) -> Self {
+        self.singleton_with::<Arc<T>>(move |r| factory(r).map(Arc::new))
+    }
 
-    /// Register a scoped factory.
+    /// Register a singleton factory with an explicit failure policy.
     ///
-    /// Creates a new instance per scope. (Full per-scope caching is Phase 2.)
-    pub fn scoped_with<T: Send + Sync + 'static>(
+    /// Like [`singleton_with`](Self::singleton_with), but with
+    /// [`SingletonFailurePolicy::CacheError`] the first failure is final:
+    /// the factory runs exactly once, even when threads race, and every
+    /// resolve afterwards returns [`MakhzanError::ConstructionFailed`]
+    /// wrapping that first error.
+    ///
+    /// ```rust
+    /// # use makhzan_container::prelude::*;
+    /// # use std::sync::Arc;
+    /// # const URL: &str = "postgres://localhost/app";
+    /// # struct Pool;
+    /// # impl Pool { fn connect(_: &str) -> Result<Arc<Pool>> { Ok(Arc::new(Pool)) } }
+    /// let container = Container::builder()
+    ///     .singleton_with_policy(SingletonFailurePolicy::CacheError, |_| Pool::connect(URL))
+    ///     .build()?;
+    /// # Ok::<(), MakhzanError>(())
+    /// ```
+    pub fn singleton_with_policy<T: Clone + Send + Sync + 'static>(
         self,
+        policy: SingletonFailurePolicy,
         factory: impl Fn(&dyn Resolver) -> Result<T> + Send + Sync + 'static,
     ) -> Self {
-        self.register_internal(
-            DependencyKey::of::<T>(),
-            Scope::Scoped,
-            Arc::new(move |resolver: &dyn Resolver| {
-                Ok(Box::new(factory(resolver)?) as Box<dyn Any + Send + Sync>)
-            }),
-            vec![],
-        )
+        self.singleton_keyed(DependencyKey::of::<T>(), policy, factory)
     }
 
-    // ── Transient ──
+    /// Register a singleton factory whose first error is cached too.
+    ///
+    /// Shorthand for [`singleton_with_policy`](Self::singleton_with_policy)
+    /// with [`SingletonFailurePolicy::CacheError`]: the factory runs at most
+    /// once, and if it fails every later resolve returns the same
+    /// [`MakhzanError::ConstructionFailed`] immediately. Suits failures
+    /// that retrying won't fix, such as invalid configuration, where
+    /// [`singleton_with`](Self::singleton_with) would rerun a doomed
+    /// factory on every resolve.
+    ///
+    /// ```rust
+    /// # use makhzan_container::prelude::*;
+    /// # use std::sync::Arc;
+    /// # #[derive(Clone)]
+    /// # struct Settings { cert_path: String }
+    /// # struct TlsConfig;
+    /// # impl TlsConfig {
+    /// #     fn load(path: String) -> Result<Self> {
+    /// #         let missing = format!("no certificate at {path}");
+    /// #         Err(MakhzanError::construction_failed(DependencyKey::of::<Arc<TlsConfig>>(), missing))
+    /// #     }
+    /// # }
+    /// let container = Container::builder()
+    /// #   .singleton_value(Settings { cert_path: "missing.pem".into() })
+    ///     .singleton_with_memo_errors(|r| Ok(Arc::new(TlsConfig::load(resolve::<Settings>(r)?.cert_path)?)))
+    ///     .build()?;
+    /// assert!(container.resolve::<Arc<TlsConfig>>().is_err());
+    /// assert!(container.resolve::<Arc<TlsConfig>>().is_err()); // the cached error, without loading again
+    /// # Ok::<(), MakhzanError>(())
+    /// ```
+    pub fn singleton_with_memo_errors<T: Clone + Send + Sync + 'static>(
+        self,
+        factory: impl Fn(&dyn Resolver) -> Result<T> + Send + Sync + 'static,
+    ) -> Self {
+        self.singleton_with_policy(SingletonFailurePolicy::CacheError, factory)
+    }
 
-    /// Register a transient factory.
+    /// Register a singleton factory under a [tagged](DependencyKey::tagged) key.
     ///
-    /// Creates a NEW instance on every `resolve()` call.
-    pub fn transient_with<T: Send + Sync + 'static>(
+    /// Lets several values of one type coexist without typo-prone string
+    /// names; resolve them with [`Container::resolve_tagged`].
+    ///
+    /// ```rust
+    /// # use makhzan_container::prelude::*;
+    /// struct Primary;
+    /// struct Replica;
+    ///
+    /// let container = Container::builder()
+    ///     .singleton_tagged::<String, Primary>(|_| Ok("postgres://primary".into()))
+    ///     .singleton_tagged::<String, Replica>(|_| Ok("postgres://replica".into()))
+    ///     .build()?;
+    /// let url = container.resolve_tagged::<String, Replica>()?;
+    /// assert_eq!(url, "postgres://replica");
+    /// # Ok::<(), MakhzanError>(())
+    /// ```
+    pub fn singleton_tagged<T: Clone + Send + Sync + 'static, Tag: ?Sized + 'static>(
         self,
         factory: impl Fn(&dyn Resolver) -> Result<T> + Send + Sync + 'static,
     ) -> Self {
-        self.register_internal(
-            DependencyKey::of::<T>(),
-            Scope::Transient,
-            Arc::new(move |resolver: &dyn Resolver| {
-                Ok(Box::new(factory(resolver)?) as Box<dyn Any + Send + Sync>)
-            }),
-            vec![],
+        self.singleton_keyed(
+            DependencyKey::tagged::<T, Tag>(),
+            SingletonFailurePolicy::RetryOnNextResolve,
+            factory,
         )
     }
 
-    // ── Provider modules ──
+    /// Singleton factory registration shared by the typed and tagged forms.
+    fn singleton_keyed<T: Clone + Send + Sync + 'static>(
+        self,
+        key: DependencyKey,
+        policy: SingletonFailurePolicy,
+        factory: impl Fn(&dyn Resolver) -> Result<T> + Send + Sync + 'static,
+    ) -> Self {
+        self.register_entry(singleton_registration(key, policy, factory))
+    }
 
-    /// Add a [`Provider`] module.
-    pub fn add_provider(mut self, provider: &dyn Provider) -> Self {
-        provider.register(&mut self);
+    /// Register a singleton factory that only applies under some profiles.
+    ///
+    /// At [`build`](Self::build) time, if the active
+    /// [`profile`](Self::profile) is one of `profiles`, the binding replaces
+    /// any profile-agnostic registration of `T`, whatever the call order.
+    /// Otherwise it is dropped and `T` resolves through its other
+    /// registration or [`register_default_impl`](Self::register_default_impl)
+    /// fallback, if any, else fails with [`MakhzanError::NotRegistered`].
+    /// If several bindings of `T` match, the last one wins.
+    ///
+    /// ```rust
+    /// # use makhzan_container::prelude::*;
+    /// # use std::env;
+    /// # use std::sync::Arc;
+    /// # trait Mailer: Send + Sync {}
+    /// # struct LogMailer;
+    /// # impl Mailer for LogMailer {}
+    /// # #[derive(Clone)]
+    /// # struct SmtpConfig;
+    /// # struct SmtpMailer;
+    /// # impl SmtpMailer { fn new(_: SmtpConfig) -> Self { SmtpMailer } }
+    /// # impl Mailer for SmtpMailer {}
+    /// # #[derive(Default)]
+    /// # struct FakeMailer;
+    /// # impl Mailer for FakeMailer {}
+    /// let container = Container::builder()
+    ///     .profile(&env::var("APP_ENV").unwrap_or_else(|_| "dev".into()))
+    /// #   .singleton_value(SmtpConfig)
+    ///     .singleton_with::<Arc<dyn Mailer>>(|_| Ok(Arc::new(LogMailer)))
+    ///     .register_for::<Arc<dyn Mailer>>(&["prod"], |r| Ok(Arc::new(SmtpMailer::new(resolve(r)?))))
+    ///     .register_for::<Arc<dyn Mailer>>(&["test"], |_| Ok(Arc::new(FakeMailer::default())))
+    ///     .build()?;
+    /// # Ok::<(), MakhzanError>(())
+    /// ```
+    pub fn register_for<T: Clone + Send + Sync + 'static>(
+        mut self,
+        profiles: &[&str],
+        factory: impl Fn(&dyn Resolver) -> Result<T> + Send + Sync + 'static,
+    ) -> Self {
+        let registration =
+            singleton_registration(DependencyKey::of::<T>(), SingletonFailurePolicy::RetryOnNextResolve, factory);
+        self.profiled.push((profiles.iter().map(|profile| profile.to_string()).collect(), registration));
         self
     }
 
-    // ── Build ──
-
-    /// Build the container, validating the dependency graph.
+    /// Set the active profile, e.g. `"dev"`, `"test"` or `"prod"`, that
+    /// selects which [`register_for`](Self::register_for) bindings apply.
     ///
-    /// Checks: all deps registered, no cycles, scope compatibility.
-    #[instrument(skip(self), name = "container_build")]
-    pub fn build(self) -> Result<Container> {
-        info!(registered = self.registry.len(), "Building container");
+    /// Without one, no profile binding applies.
+    pub fn profile(mut self, name: &str) -> Self {
+        self.profile = Some(name.to_string());
+        self
+    }
 
-        let dep_infos: HashMap<DependencyKey, DependencyInfo> = self
-            .registry
-            .all_registrations()
+    /// The [`register_for`](Self::register_for) bindings of the active
+    /// profile, in registration order.
+    fn profile_bindings(&self) -> impl Iterator<Item = &Registration> {
+        let active = self.profile.as_deref();
+        self.profiled
             .iter()
-            .map(|(key, reg)| {
-                (
-                    key.clone(),
-                    DependencyInfo {
-                        key: key.clone(),
-                        dependencies: reg.dependencies.clone(),
-                        scope: reg.scope,
-                    },
-                )
-            })
-            .collect();
+            .filter(move |(profiles, _)| active.is_some_and(|active| profiles.iter().any(|p| p == active)))
+            .map(|(_, registration)| registration)
+    }
 
-        let mut validator = GraphValidator::new(dep_infos);
-        validator.validate()?;
+    /// Register an `Arc<T>` singleton that the container holds only weakly.
+    ///
+    /// Resolves share one instance while anything outside the container
+    /// keeps it alive. Once the last `Arc` is dropped the instance is
+    /// freed, and the next resolve runs `factory` again. Use it for large
+    /// resources, such as a loaded model, that should not stay in memory
+    /// between bursts of use.
+    ///
+    /// `T`'s `Drop` runs when the last strong reference goes away, never
+    /// at container shutdown, since the container owns none. Racing
+    /// resolves wait for a single factory run.
+    ///
+    /// ```rust
+    /// # use makhzan_container::prelude::*;
+    /// # use std::sync::Arc;
+    /// # struct Model;
+    /// # impl Model { fn load(_: &str) -> std::io::Result<Model> { Ok(Model) } }
+    /// let container = Container::builder()
+    ///     .singleton_weak(|_| {
+    ///         Model::load("weights.bin")
+    ///             .map(Arc::new)
+    ///             .map_err(|e| MakhzanError::construction_failed(DependencyKey::of::<Arc<Model>>(), e))
+    ///     })
+    ///     .build()?;
+    /// let model: Arc<Model> = container.resolve()?;
+    /// assert!(Arc::ptr_eq(&model, &container.resolve()?));
+    /// # Ok::<(), MakhzanError>(())
+    /// ```
+    pub fn singleton_weak<T: Send + Sync + 'static>(
+        mut self,
+        factory: impl Fn(&dyn Resolver) -> Result<Arc<T>> + Send + Sync + 'static,
+    ) -> Self {
+        let key = DependencyKey::of::<Arc<T>>();
+        let weak: Arc<Mutex<Weak<T>>> = Arc::new(Mutex::new(Weak::new()));
+        let lock = |weak: &Mutex<Weak<T>>| weak.lock().unwrap_or_else(PoisonError::into_inner).clone();
 
-        info!("Container built successfully ✓");
-        Ok(Container {
-            registry: Arc::new(self.registry),
+        self.weak_counts.insert(key.clone(), {
+            let weak = weak.clone();
+            Arc::new(move || lock(&weak).strong_count())
+        });
+        self.register_entry(Registration {
+            instantiated: Some({
+                let weak = weak.clone();
+                Arc::new(move || lock(&weak).strong_count() > 0)
+            }),
+            reset: Some({
+                let weak = weak.clone();
+                Arc::new(move || *weak.lock().unwrap_or_else(PoisonError::into_inner) = Weak::new())
+            }),
+            ..Registration::new(
+                key,
+                Scope::Singleton,
+                Arc::new(move |resolver: &dyn Resolver| {
+                    // Held while the factory runs so racing resolves build
+                    // one instance; self-reentry is caught as a cycle first.
+                    let mut current = weak.lock().unwrap_or_else(PoisonError::into_inner);
+                    let value = match current.upgrade() {
+                        Some(value) => value,
+                        None => {
+                            let value = factory(resolver)?;
+                            *current = Arc::downgrade(&value);
+                            value
+                        }
+                    };
+                    Ok(Box::new(value) as Box<dyn Any + Send + Sync>)
+                }),
+                vec![],
+            )
         })
     }
 
-    // ── Internal ──
+    /// Register a singleton that is rebuilt once it is older than `ttl`.
+    ///
+    /// The first resolve after expiry runs `factory` again while racing
+    /// resolves wait for it; until then the cached value is cloned as
+    /// with [`singleton_with`](Self::singleton_with). If the refresh
+    /// fails, the expired value keeps being served; see
+    /// [`singleton_with_ttl_policy`](Self::singleton_with_ttl_policy).
+    ///
+    /// ```rust
+    /// # use makhzan_container::prelude::*;
+    /// # use std::sync::Arc;
+    /// # use std::time::Duration;
+    /// # struct Discovery;
+    /// # impl Discovery {
+    /// #     fn endpoints(&self, _: &str) -> Result<Vec<String>> { Ok(vec!["10.0.0.7:443".into()]) }
+    /// # }
+    /// let container = Container::builder()
+    /// #   .singleton_value(Arc::new(Discovery))
+    ///     .singleton_with_ttl(Duration::from_secs(30), |r| {
+    ///         let discovery: Arc<Discovery> = resolve(r)?;
+    ///         discovery.endpoints("billing").map(Arc::new)
+    ///     })
+    ///     .build()?;
+    /// # let _: Arc<Vec<String>> = container.resolve()?;
+    /// # Ok::<(), MakhzanError>(())
+    /// ```
+    pub fn singleton_with_ttl<T: Clone + Send + Sync + 'static>(
+        self,
+        ttl: Duration,
+        factory: impl Fn(&dyn Resolver) -> Result<T> + Send + Sync + 'static,
+    ) -> Self {
+        self.singleton_with_ttl_policy(ttl, RefreshFailurePolicy::KeepStale, factory)
+    }
 
-    fn register_internal(
-        mut self,
-        key: DependencyKey,
-        scope: Scope,
-        factory: FactoryFn,
-        dependencies: Vec<DependencyKey>,
+    /// [`singleton_with_ttl`](Self::singleton_with_ttl) with an explicit
+    /// policy for failed refreshes.
+    pub fn singleton_with_ttl_policy<T: Clone + Send + Sync + 'static>(
+        self,
+        ttl: Duration,
+        policy: RefreshFailurePolicy,
+        factory: impl Fn(&dyn Resolver) -> Result<T> + Send + Sync + 'static,
     ) -> Self {
-        let registration = Registration {
-            key,
-            factory,
-            scope,
-            dependencies,
+        let state: Arc<RwLock<Option<(T, Instant)>>> = Arc::new(RwLock::new(None));
+        let fresh = move |state: &Option<(T, Instant)>| match state {
+            Some((value, created)) if created.elapsed() < ttl => Some(value.clone()),
+            _ => None,
         };
-        let _ = self.registry.register(registration, self.allow_override);
-        self
-    }
-}
 
-// ProviderRegistry impl so providers can register into builder
-impl ProviderRegistry for ContainerBuilder {
-    fn register_singleton(
-        &mut self, key: DependencyKey, factory: FactoryFn, deps: Vec<DependencyKey>,
-    ) {
-        let reg = Registration { key, factory, scope: Scope::Singleton, dependencies: deps };
-        let _ = self.registry.register(reg, self.allow_override);
+        self.register_entry(Registration {
+            instantiated: Some({
+                let state = state.clone();
+                Arc::new(move || state.read().unwrap_or_else(PoisonError::into_inner).is_some())
+            }),
+            reset: Some({
+                let state = state.clone();
+                Arc::new(move || *state.write().unwrap_or_else(PoisonError::into_inner) = None)
+            }),
+            ..Registration::new(
+                DependencyKey::of::<T>(),
+                Scope::Singleton,
+                Arc::new(move |resolver: &dyn Resolver| {
+                    let cached = fresh(&state.read().unwrap_or_else(PoisonError::into_inner));
+                    if let Some(value) = cached {
+                        return Ok(Box::new(value) as Box<dyn Any + Send + Sync>);
+                    }
+                    let mut current = state.write().unwrap_or_else(PoisonError::into_inner);
+                    // Another resolve may have refreshed it while we waited.
+                    let value = match fresh(&current) {
+                        Some(value) => value,
+                        None => match (factory(resolver), current.as_ref(), policy) {
+                            (Ok(value), _, _) => {
+                                *current = Some((value.clone(), Instant::now()));
+                                value
+                            }
+                            (Err(_), Some((stale, _)), RefreshFailurePolicy::KeepStale) => stale.clone(),
+                            (Err(e), _, _) => return Err(e),
+                        },
+                    };
+                    Ok(Box::new(value) as Box<dyn Any + Send + Sync>)
+                }),
+                vec![],
+            )
+        })
     }
 
-    fn register_scoped(
-        &mut self, key: DependencyKey, factory: FactoryFn, deps: Vec<DependencyKey>,
-    ) {
-        let reg = Registration { key, factory, scope: Scope::Scoped, dependencies: deps };
-        let _ = self.registry.register(reg, self.allow_override);
+    /// Register `T::default()` as a singleton unless `T` gets another binding.
+    ///
+    /// For libraries shipping sensible defaults: the fallback is only
+    /// added at [`build`](Self::build) time, and only if nothing else was
+    /// registered or aliased under `T` by then, whatever the call order.
+    pub fn register_default_impl<T: Default + Clone + Send + Sync + 'static>(mut self) -> Self {
+        self.defaults.push(Registration {
+            cloner: Some(Cloner::of::<T>()),
+            ..Registration::new(
+                DependencyKey::of::<T>(),
+                Scope::Singleton,
+                Arc::new(|_: &dyn Resolver| Ok(Box::new(T::default()) as Box<dyn Any + Send + Sync>)),
+                vec![],
+            )
+        });
+        self
     }
 
-    fn register_transient(
-        &mut self, key: DependencyKey, factory: FactoryFn, deps: Vec<DependencyKey>,
-    ) {
-        let reg = Registration { key, factory, scope: Scope::Transient, dependencies: deps };
-        let _ = self.registry.register(reg, self.allow_override);
+    /// Change the scope of the already registered `T`.
+    ///
+    /// For tests that want to count constructions, e.g. by turning a
+    /// singleton into a transient, without repeating its factory. A
+    /// [`singleton_with`](Self::singleton_with) factory drops its own
+    /// instance cache; the container caches the value for the new scope
+    /// unless it is `Transient`. [`build`](Self::build) validates the graph
+    /// with the new scope, so a singleton now depending on a shorter-lived
+    /// `T` fails there.
+    ///
+    /// ```rust
+    /// # use makhzan_container::prelude::*;
+    /// # use std::sync::Arc;
+    /// # struct Clock;
+    /// # fn production_wiring() -> ContainerBuilder {
+    /// #     Container::builder().singleton_with(|_| Ok(Arc::new(Clock)))
+    /// # }
+    /// let container = production_wiring()
+    ///     .set_scope::<Arc<Clock>>(Scope::Transient)?
+    ///     .build()?;
+    /// assert!(!Arc::ptr_eq(&container.resolve::<Arc<Clock>>()?, &container.resolve()?));
+    /// # Ok::<(), MakhzanError>(())
+    /// ```
+    ///
+    /// # Errors
+    /// [`MakhzanError::NotRegistered`] if `T` was not registered before
+    /// this call.
+    pub fn set_scope<T: Clone + Send + Sync + 'static>(mut self, scope: Scope) -> Result<Self> {
+        let key = DependencyKey::of::<T>();
+        if !self.registry.set_scope(&key, scope, Cloner::of::<T>()) {
+            return Err(MakhzanError::NotRegistered(Box::new(NotRegisteredError {
+                requested: key,
+                required_by: None,
+                suggestions: Vec::new(),
+            })));
+        }
+        Ok(self)
     }
 
-    fn register_alias(&mut self, from: DependencyKey, to: DependencyKey) {
-        self.registry.register_alias(from, to);
+    /// Register shared mutable state as a singleton.
+    ///
+    /// The value is stored as `Arc<Mutex<T>>`, which is what dependents
+    /// resolve. Use [`Container::with_mut`] to lock and update it in place.
+    ///
+    /// ```rust
+    /// # use makhzan_container::prelude::*;
+    /// #[derive(Default)]
+    /// struct Metrics {
+    ///     requests: u64,
+    /// }
+    ///
+    /// let container = Container::builder().singleton_mutable(Metrics::default()).build()?;
+    /// container.with_mut(|m: &mut Metrics| m.requests += 1)?;
+    /// assert_eq!(container.with_mut(|m: &mut Metrics| m.requests)?, 1);
+    /// # Ok::<(), MakhzanError>(())
+    /// ```
+    pub fn singleton_mutable<T: Send + 'static>(self, value: T) -> Self {
+        self.singleton_value(Arc::new(Mutex::new(value)))
     }
-}
 
-// ═══════════════════════════════════════════
-// Container
-// ═══════════════════════════════════════════
+    /// Register a singleton factory for a type that is only borrowed.
+    ///
+    /// Like [`singleton_with`](Self::singleton_with), the factory is called
+    /// once and the instance is cached, but `T` needs no `Clone` bound: the
+    /// value is never handed out by value. Access it with
+    /// [`Container::with_singleton`]; [`Container::resolve`] on this key
+    /// returns an error.
+    pub fn singleton_ref_with<T: Send + Sync + 'static>(
+        self,
+        factory: impl Fn(&dyn Resolver) -> Result<T> + Send + Sync + 'static,
+    ) -> Self {
+        let cell: Arc<ResetCell<T>> = Arc::new(ResetCell::new());
 
-/// Immutable, thread-safe dependency injection container.
-///
-/// Created by [`ContainerBuilder::build()`].
-pub struct Container {
-    registry: Arc<Registry>,
-}
+        self.register_entry(Registration {
+            instantiated: Some(instance_probe(&cell)),
+            reset: Some(reset_hook(&cell)),
+            ..Registration::new(
+                DependencyKey::of::<T>(),
+                Scope::Singleton,
+                {
+                    let cell = cell.clone();
+                    Arc::new(move |resolver: &dyn Resolver| {
+                        let cell = cell.generation();
+                        cell.get_or_try_init(|| factory(resolver))?;
+                        Ok(Box::new(SingletonRef(cell)) as Box<dyn Any + Send + Sync>)
+                    })
+                },
+                vec![],
+            )
+        })
+    }
 
-impl Container {
-    /// Create a new builder.
-    pub fn builder() -> ContainerBuilder {
-        ContainerBuilder::new()
+    /// Register a shared implementation and its trait-object binding in one call.
+    ///
+    /// Registers `Arc<C>` like [`singleton_arc`](Self::singleton_arc),
+    /// plus `Arc<I>` resolving to the same instance through
+    /// [`bind`](Self::bind), so `I` must have opted in with
+    /// [`upcast!`](crate::upcast!).
+    ///
+    /// ```rust
+    /// use makhzan_container::prelude::*;
+    /// use std::sync::Arc;
+    ///
+    /// trait Logger: Send + Sync {}
+    /// makhzan_container::upcast!(Logger);
+    ///
+    /// struct ConsoleLogger;
+    /// impl Logger for ConsoleLogger {}
+    ///
+    /// let container = Container::builder()
+    ///     .bind_shared::<dyn Logger, ConsoleLogger>(|_| Ok(ConsoleLogger))
+    ///     .build()
+    ///     .unwrap();
+    /// let logger: Arc<dyn Logger> = container.resolve().unwrap();
+    /// let concrete: Arc<ConsoleLogger> = container.resolve().unwrap();
+    /// assert!(std::ptr::addr_eq(Arc::as_ptr(&logger), Arc::as_ptr(&concrete)));
+    /// ```
+    pub fn bind_shared<I: ?Sized + Upcast<C>, C: Send + Sync + 'static>(
+        self,
+        factory: impl Fn(&dyn Resolver) -> Result<C> + Send + Sync + 'static,
+    ) -> Self {
+        self.singleton_arc(factory).bind::<I, C>()
     }
 
-    /// Resolve a dependency by type.
+    /// Resolve `Arc<I>` to the registered `Arc<C>`, for a trait object `I`
+    /// that `C` implements.
     ///
-    /// ```rust,ignore
-    /// let db: Arc<Database> = container.resolve()?;
+    /// Replaces a hand-written `as Arc<dyn Trait>` in every factory. The
+    /// binding has the scope of `Arc<C>`, whenever that is registered, so
+    /// both resolve to the same instance. `I` must have opted in with
+    /// [`upcast!`](crate::upcast!); see [`upcast`](crate::upcast).
+    ///
+    /// ```rust
+    /// # use makhzan_container::prelude::*;
+    /// # use std::sync::Arc;
+    /// # trait Logger: Send + Sync {}
+    /// # struct ConsoleLogger;
+    /// # impl ConsoleLogger { fn new() -> Self { ConsoleLogger } }
+    /// # impl Logger for ConsoleLogger {}
+    /// makhzan_container::upcast!(Logger);
+    ///
+    /// let container = Container::builder()
+    ///     .bind::<dyn Logger, ConsoleLogger>()
+    ///     .singleton_arc(|_| Ok(ConsoleLogger::new()))
+    ///     .build()?;
+    /// let logger: Arc<dyn Logger> = container.resolve()?;
+    /// # Ok::<(), MakhzanError>(())
     /// ```
-    pub fn resolve<T: Send + Sync + 'static>(&self) -> Result<T> {
-        let key = DependencyKey::of::<T>();
-        trace!(key = %key, "Resolving");
+    ///
+    /// [`build`](Self::build) fails with [`MakhzanError::NotRegistered`]
+    /// if `Arc<C>` never gets registered.
+    pub fn bind<I: ?Sized + Upcast<C>, C: Send + Sync + 'static>(mut self) -> Self {
+        let key = DependencyKey::of::<Arc<I>>();
+        let factory: FactoryFn = Arc::new(|resolver: &dyn Resolver| {
+            let concrete: Arc<C> = resolve(resolver)?;
+            Ok(Box::new(I::upcast(concrete)) as Box<dyn Any + Send + Sync>)
+        });
+        self.bindings.push((key.clone(), factory.clone(), Cloner::of::<Arc<I>>()));
+        self.register_internal(key, Scope::Transient, factory, vec![DependencyKey::of::<Arc<C>>()])
+    }
 
-        let boxed = self.resolve_internal(&key)?;
+    /// Register `To` as a conversion of the registered `From`.
+    ///
+    /// Each resolve of `To` resolves `From` and passes it through `f`, so
+    /// sharing follows `From`'s scope. Unlike an alias the value may change
+    /// type, which covers exposing one `Arc<C>` under several trait
+    /// objects.
+    ///
+    /// ```rust
+    /// # use makhzan_container::prelude::*;
+    /// # use std::sync::Arc;
+    /// # trait Repository: Send + Sync {}
+    /// # trait ReadOnlyRepository: Send + Sync {}
+    /// # struct PostgresRepo;
+    /// # impl PostgresRepo { fn new() -> Self { PostgresRepo } }
+    /// # impl Repository for PostgresRepo {}
+    /// # impl ReadOnlyRepository for PostgresRepo {}
+    /// let container = Container::builder()
+    ///     .singleton_arc(|_| Ok(PostgresRepo::new()))
+    ///     .map_binding(|repo: Arc<PostgresRepo>| repo as Arc<dyn Repository>)
+    ///     .map_binding(|repo: Arc<PostgresRepo>| repo as Arc<dyn ReadOnlyRepository>)
+    ///     .build()?;
+    /// let repo: Arc<dyn ReadOnlyRepository> = container.resolve()?;
+    /// # Ok::<(), MakhzanError>(())
+    /// ```
+    pub fn map_binding<From: Send + Sync + 'static, To: Send + Sync + 'static>(
+        self,
+        f: impl Fn(From) -> To + Send + Sync + 'static,
+    ) -> Self {
+        self.register_internal(
+            DependencyKey::of::<To>(),
+            Scope::Transient,
+            Arc::new(move |resolver: &dyn Resolver| {
+                let from: From = resolve(resolver)?;
+                Ok(Box::new(f(from)) as Box<dyn Any + Send + Sync>)
+            }),
+            vec![DependencyKey::of::<From>()],
+        )
+    }
 
-        boxed.downcast::<T>().map(|b| *b).map_err(|_| {
-            MakhzanError::ConstructionFailed {
-                key,
-                source: format!(
-                    "Type mismatch: expected {}",
-                    type_name::<T>()
+    /// Also expose the registered `T` as `U`, projected from it by `f`.
+    ///
+    /// For narrower views of one value, such as the database section of
+    /// an `AppConfig`. The projection shares `T`'s lifetime: for a
+    /// singleton or scoped `T` it runs once per instance of `T` and its
+    /// result is cached alongside it, for a transient `T` it runs on every
+    /// resolve. Unlike [`map_binding`](Self::map_binding), `f` borrows
+    /// `T` instead of taking it, straight from the cached instance when
+    /// there is one, so `T` is never cloned and may be registered with
+    /// [`singleton_ref_with`](Self::singleton_ref_with). `T` may also be
+    /// registered after this call.
+    ///
+    /// ```rust
+    /// # use makhzan_container::prelude::*;
+    /// # #[derive(Clone)]
+    /// # struct DbConfig { url: String }
+    /// # #[derive(Clone)]
+    /// # struct AuthConfig;
+    /// # #[derive(Clone)]
+    /// # struct AppConfig { database: DbConfig, auth: AuthConfig }
+    /// # impl AppConfig {
+    /// #     fn load() -> Result<Self> {
+    /// #         Ok(AppConfig { database: DbConfig { url: "postgres://localhost/app".into() }, auth: AuthConfig })
+    /// #     }
+    /// # }
+    /// let container = Container::builder()
+    ///     .singleton_with(|_| AppConfig::load())
+    ///     .also_as(|config: &AppConfig| config.database.clone())
+    ///     .also_as(|config: &AppConfig| config.auth.clone())
+    ///     .build()?;
+    /// let database: DbConfig = container.resolve()?;
+    /// assert_eq!(database.url, "postgres://localhost/app");
+    /// # Ok::<(), MakhzanError>(())
+    /// ```
+    pub fn also_as<T: Send + Sync + 'static, U: Clone + Send + Sync + 'static>(
+        mut self,
+        f: impl Fn(&T) -> U + Send + Sync + 'static,
+    ) -> Self {
+        let key = DependencyKey::of::<U>();
+        let factory: FactoryFn = Arc::new(move |resolver: &dyn Resolver| {
+            let key = DependencyKey::of::<T>();
+            let source = resolver.resolve_erased(&key)?;
+            let source = source
+                .downcast_ref::<T>()
+                .or_else(|| source.downcast_ref::<SingletonRef<T>>().map(SingletonRef::get))
+                .ok_or_else(|| MakhzanError::TypeMismatch {
+                    key,
+                    expected: type_name::<T>(),
+                    actual: source.type_name(),
+                })?;
+            Ok(Box::new(f(source)) as Box<dyn Any + Send + Sync>)
+        });
+        self.bindings.push((key.clone(), factory.clone(), Cloner::of::<U>()));
+        self.register_internal(key, Scope::Transient, factory, vec![DependencyKey::of::<T>()])
+    }
+
+    // ── Collections ──
+
+    /// Add an element to the collection of `T`s.
+    ///
+    /// Every call adds one element, built by `factory` from the declared
+    /// `dependencies` and cached according to `scope`. The collection
+    /// resolves as a `Vec<T>` in the order elements were added, through
+    /// [`Container::resolve_all`] or as a dependency on `Vec<T>`, which
+    /// takes the scope of its shortest-lived element.
+    ///
+    /// Each element is registered under its own
+    /// [`DependencyKey::element`] key, so [`build`](Self::build) validates
+    /// it like any other registration and errors name it, e.g.
+    /// `element #2 of collection Vec<Arc<dyn Handler>>`.
+    ///
+    /// ```rust
+    /// # use makhzan_container::prelude::*;
+    /// # use std::sync::Arc;
+    /// # trait Handler: Send + Sync {}
+    /// # struct Mailer;
+    /// # struct AuditHandler;
+    /// # impl Handler for AuditHandler {}
+    /// # struct MailHandler(Arc<Mailer>);
+    /// # impl MailHandler { fn new(mailer: Arc<Mailer>) -> Self { MailHandler(mailer) } }
+    /// # impl Handler for MailHandler {}
+    /// let container = Container::builder()
+    /// #   .singleton_value(Arc::new(Mailer))
+    ///     .add_element(Scope::Singleton, vec![], |_| Ok(Arc::new(AuditHandler) as Arc<dyn Handler>))
+    ///     .add_element(Scope::Transient, vec![DependencyKey::of::<Arc<Mailer>>()], |r| {
+    ///         Ok(Arc::new(MailHandler::new(resolve(r)?)) as Arc<dyn Handler>)
+    ///     })
+    ///     .build()?;
+    /// let handlers: Vec<Arc<dyn Handler>> = container.resolve_all()?;
+    /// assert_eq!(handlers.len(), 2);
+    /// # Ok::<(), MakhzanError>(())
+    /// ```
+    pub fn add_element<T: Clone + Send + Sync + 'static>(
+        mut self,
+        scope: Scope,
+        dependencies: Vec<DependencyKey>,
+        factory: impl Fn(&dyn Resolver) -> Result<T> + Send + Sync + 'static,
+    ) -> Self {
+        let mut index = 0;
+        while self.registry.contains(&DependencyKey::element::<T>(index)) {
+            index += 1;
+        }
+        let element = DependencyKey::element::<T>(index);
+        let _ = self.registry.register(
+            Registration {
+                cloner: scope.is_cached().then(Cloner::of::<T>),
+                ..Registration::new(
+                    element,
+                    scope,
+                    Arc::new(move |resolver: &dyn Resolver| {
+                        Ok(Box::new(factory(resolver)?) as Box<dyn Any + Send + Sync>)
+                    }),
+                    dependencies,
                 )
-                .into(),
+            },
+            false,
+        );
+
+        let collection = DependencyKey::of::<Vec<T>>();
+        let scope = match self.registry.all_registrations().get(&collection) {
+            Some(registration) if index > 0 => registration.scope.min(scope),
+            _ => scope,
+        };
+        let elements: Vec<DependencyKey> = (0..=index).map(DependencyKey::element::<T>).collect();
+        let _ = self.registry.register(
+            Registration::new(
+                collection,
+                scope,
+                Arc::new({
+                    let elements = elements.clone();
+                    move |resolver: &dyn Resolver| {
+                        let items = elements
+                            .iter()
+                            .map(|element| resolve_keyed::<T>(resolver, element.clone()))
+                            .collect::<Result<Vec<T>>>()?;
+                        Ok(Box::new(items) as Box<dyn Any + Send + Sync>)
+                    }
+                }),
+                elements,
+            ),
+            true,
+        );
+        self
+    }
+
+    /// Add a singleton entry named `name` to the keyed map of `T`s.
+    ///
+    /// The map resolves as a `HashMap<&'static str, T>` through
+    /// [`Container::resolve_map`] or as a dependency, and a single entry
+    /// through [`Container::resolve_keyed`]. Each entry is registered
+    /// under [`DependencyKey::named::<T>(name)`](DependencyKey::named), so
+    /// [`build`](Self::build) validates it and fails with
+    /// [`MakhzanError::AlreadyRegistered`] if `name` is taken, unless
+    /// [`allow_override`](Self::allow_override) is on.
+    ///
+    /// ```rust
+    /// # use makhzan_container::prelude::*;
+    /// # use std::sync::Arc;
+    /// # trait PaymentStrategy: Send + Sync { fn name(&self) -> &'static str; }
+    /// # #[derive(Clone)]
+    /// # struct StripeKey;
+    /// # struct Stripe;
+    /// # impl Stripe { fn new(_: StripeKey) -> Self { Stripe } }
+    /// # impl PaymentStrategy for Stripe { fn name(&self) -> &'static str { "stripe" } }
+    /// # struct PayPal;
+    /// # impl PaymentStrategy for PayPal { fn name(&self) -> &'static str { "paypal" } }
+    /// # struct Request { provider: String }
+    /// # let request = Request { provider: "paypal".into() };
+    /// let container = Container::builder()
+    /// #   .singleton_value(StripeKey)
+    ///     .add_keyed::<Arc<dyn PaymentStrategy>>("stripe", |r| Ok(Arc::new(Stripe::new(resolve(r)?))))
+    ///     .add_keyed::<Arc<dyn PaymentStrategy>>("paypal", |_| Ok(Arc::new(PayPal)))
+    ///     .build()?;
+    /// let strategy = container.resolve_keyed::<Arc<dyn PaymentStrategy>>(&request.provider)?;
+    /// assert_eq!(strategy.name(), "paypal");
+    /// # Ok::<(), MakhzanError>(())
+    /// ```
+    pub fn add_keyed<T: Clone + Send + Sync + 'static>(
+        mut self,
+        name: &'static str,
+        factory: impl Fn(&dyn Resolver) -> Result<T> + Send + Sync + 'static,
+    ) -> Self {
+        let entry = DependencyKey::named::<T>(name);
+        if !self.allow_override && self.registry.contains(&entry) {
+            self.duplicate_entries.push(entry);
+            return self;
+        }
+        let _ = self.registry.register(
+            singleton_registration(entry.clone(), SingletonFailurePolicy::RetryOnNextResolve, factory),
+            true,
+        );
+
+        let map = DependencyKey::of::<HashMap<&'static str, T>>();
+        let mut entries = self.registry.all_registrations().get(&map).map_or_else(Vec::new, |r| r.dependencies.clone());
+        if !entries.contains(&entry) {
+            entries.push(entry);
+        }
+        let factory = {
+            let entries = entries.clone();
+            move |resolver: &dyn Resolver| {
+                entries
+                    .iter()
+                    .map(|entry| Ok((entry.name().unwrap_or_default(), resolve_keyed::<T>(resolver, entry.clone())?)))
+                    .collect::<Result<HashMap<&'static str, T>>>()
             }
+        };
+        let _ = self.registry.register(
+            Registration {
+                dependencies: entries,
+                ..singleton_registration(map, SingletonFailurePolicy::RetryOnNextResolve, factory)
+            },
+            true,
+        );
+        self
+    }
+
+    // ── Scoped ──
+
+    /// Register a scoped factory.
+    ///
+    /// Called once per scope; the instance is cached in the scope and
+    /// cloned on later resolves from it. Resolving from the root container
+    /// builds a fresh instance every time.
+    ///
+    /// **`T` must implement `Clone`** — use `Arc<T>` for services.
+    pub fn scoped_with<T: Clone + Send + Sync + 'static>(
+        self,
+        factory: impl Fn(&dyn Resolver) -> Result<T> + Send + Sync + 'static,
+    ) -> Self {
+        self.register_entry(Registration {
+            cloner: Some(Cloner::of::<T>()),
+            ..Registration::new(
+                DependencyKey::of::<T>(),
+                Scope::Scoped,
+                Arc::new(move |resolver: &dyn Resolver| {
+                    Ok(Box::new(factory(resolver)?) as Box<dyn Any + Send + Sync>)
+                }),
+                vec![],
+            )
+        })
+    }
+
+    // ── Transient ──
+
+    /// Register a transient factory.
+    ///
+    /// Creates a NEW instance on every `resolve()` call.
+    pub fn transient_with<T: Send + Sync + 'static>(
+        self,
+        factory: impl Fn(&dyn Resolver) -> Result<T> + Send + Sync + 'static,
+    ) -> Self {
+        self.register_internal(
+            DependencyKey::of::<T>(),
+            Scope::Transient,
+            Arc::new(move |resolver: &dyn Resolver| {
+                Ok(Box::new(factory(resolver)?) as Box<dyn Any + Send + Sync>)
+            }),
+            vec![],
+        )
+    }
+
+    /// Register an [`Injectable`] type as transient.
+    ///
+    /// Uses the constructor generated by `#[injectable]`, with its
+    /// parameters declared as dependencies.
+    pub fn register_injectable<T: Injectable>(self) -> Self {
+        self.register_internal(
+            DependencyKey::of::<T>(),
+            Scope::Transient,
+            Arc::new(|resolver: &dyn Resolver| {
+                Ok(Box::new(T::inject(resolver)?) as Box<dyn Any + Send + Sync>)
+            }),
+            T::dependencies(),
+        )
+    }
+
+    /// Register a transient factory for a small `Copy` type.
+    ///
+    /// Behaves like [`transient_with`](Self::transient_with), but
+    /// [`Container::resolve_copy`] builds the value on the stack instead of
+    /// boxing it first — worthwhile for `u32`-sized values resolved in hot
+    /// loops.
+    pub fn transient_copy_with<T: Copy + Send + Sync + 'static>(
+        self,
+        factory: impl Fn(&dyn Resolver) -> Result<T> + Send + Sync + 'static,
+    ) -> Self {
+        let factory = Arc::new(factory);
+        let inline: InlineFactory = {
+            let factory = factory.clone();
+            Arc::new(move |resolver: &dyn Resolver, slot: &mut dyn Any| {
+                if let Some(slot) = slot.downcast_mut::<Option<T>>() {
+                    *slot = Some(factory(resolver)?);
+                }
+                Ok(())
+            })
+        };
+
+        self.register_entry(Registration {
+            inline: Some(inline),
+            ..Registration::new(
+                DependencyKey::of::<T>(),
+                Scope::Transient,
+                Arc::new(move |resolver: &dyn Resolver| {
+                    Ok(Box::new(factory(resolver)?) as Box<dyn Any + Send + Sync>)
+                }),
+                vec![],
+            )
+        })
+    }
+
+    /// Register a transient factory whose dependencies are inferred.
+    ///
+    /// Instead of being declared, the dependency edges are recorded by
+    /// running `factory` once against a
+    /// [`RecordingResolver`](crate::recording::RecordingResolver) during
+    /// [`build`](Self::build), then validated like any other.
+    ///
+    /// Only use this for factories that tolerate stand-in inputs and have no
+    /// side effects: dependencies resolve to their [`sentinel`](Self::sentinel)
+    /// or to an error, and recording stops where the factory gives up.
+    ///
+    /// ```rust
+    /// # use makhzan_container::prelude::*;
+    /// # #[derive(Clone, Default)]
+    /// # struct Config;
+    /// # struct Client;
+    /// # impl Client { fn new(_: Config) -> Self { Client } }
+    /// let container = Container::builder()
+    ///     .singleton_value(Config::default())
+    ///     .transient_with_recorded(|r| Ok(Client::new(resolve::<Config>(r)?)))
+    ///     .build()?; // Client → Config inferred and validated
+    /// assert_eq!(container.plan_for::<Client>().unwrap().dependencies, [DependencyKey::of::<Config>()]);
+    /// # Ok::<(), MakhzanError>(())
+    /// ```
+    pub fn transient_with_recorded<T: Send + Sync + 'static>(
+        self,
+        factory: impl Fn(&dyn Resolver) -> Result<T> + Send + Sync + 'static,
+    ) -> Self {
+        self.register_entry(Registration {
+            recorded: true,
+            ..Registration::new(
+                DependencyKey::of::<T>(),
+                Scope::Transient,
+                Arc::new(move |resolver: &dyn Resolver| {
+                    Ok(Box::new(factory(resolver)?) as Box<dyn Any + Send + Sync>)
+                }),
+                vec![],
+            )
         })
     }
 
-    /// Create a scoped child container.
-    pub fn create_scope(&self) -> ScopedContainer<'_> {
-        debug!("Creating new scope");
-        ScopedContainer { parent: self }
+    /// Register the stand-in value `T` resolves to while dependencies of
+    /// [`transient_with_recorded`](Self::transient_with_recorded) factories
+    /// are being recorded.
+    ///
+    /// Sentinels are never handed out by the built container.
+    pub fn sentinel<T: Clone + Send + Sync + 'static>(mut self, value: T) -> Self {
+        self.sentinels.insert(DependencyKey::of::<T>(), sentinel_factory(value));
+        self
+    }
+
+    // ── Dynamic names ──
+
+    /// Make `key` resolvable by `name` at runtime.
+    ///
+    /// For plugin hosts and config-driven wiring, where the implementation
+    /// to use is only known as a string. `build()` fails if `key` can't be
+    /// resolved. Registering a name again replaces it.
+    ///
+    /// ```rust
+    /// # use makhzan_container::prelude::*;
+    /// # struct Config { handler: String }
+    /// # let config = Config { handler: "my_plugin::CsvExporter".into() };
+    /// # struct CsvExporter;
+    /// let container = Container::builder()
+    ///     .transient_with::<CsvExporter>(|_| Ok(CsvExporter))
+    ///     .register_dynamic("my_plugin::CsvExporter", DependencyKey::of::<CsvExporter>())
+    ///     .build()?;
+    /// let exporter: CsvExporter = container.resolve_dynamic_as(&config.handler)?;
+    /// # Ok::<(), MakhzanError>(())
+    /// ```
+    pub fn register_dynamic(mut self, name: &str, key: DependencyKey) -> Self {
+        debug!(name, key = %key, "Registered dynamic name");
+        self.dynamic.insert(name.to_string(), key);
+        self
+    }
+
+    // ── Configuration ──
+
+    /// Register `T` as a singleton read from environment variables
+    /// starting with `prefix`; see [`config`](crate::config).
+    ///
+    /// ```rust
+    /// # use makhzan_container::prelude::*;
+    /// # use serde::Deserialize;
+    /// #[derive(Clone, Deserialize)]
+    /// struct AppConfig {
+    ///     port: u16,
+    ///     database: DatabaseConfig,
+    /// }
+    ///
+    /// #[derive(Clone, Deserialize)]
+    /// struct DatabaseConfig {
+    ///     url: String,
+    /// }
+    ///
+    /// // APP_PORT=8080 APP_DATABASE__URL=postgres://localhost/app
+    /// # unsafe {
+    /// #     std::env::set_var("APP_PORT", "8080");
+    /// #     std::env::set_var("APP_DATABASE__URL", "postgres://localhost/app");
+    /// # }
+    /// let container = Container::builder()
+    ///     .config_from_env::<AppConfig>("APP_")
+    ///     .build()?;
+    /// let config: AppConfig = container.resolve()?;
+    /// assert_eq!(config.port, 8080);
+    /// assert_eq!(config.database.url, "postgres://localhost/app");
+    /// # Ok::<(), MakhzanError>(())
+    /// ```
+    #[cfg(feature = "config")]
+    pub fn config_from_env<T>(self, prefix: &str) -> Self
+    where
+        T: serde::de::DeserializeOwned + Clone + Send + Sync + 'static,
+    {
+        self.add_provider(&crate::config::ConfigProvider::<T>::from_env(prefix))
+    }
+
+    /// Register `T` as a singleton read from a TOML or JSON file; see
+    /// [`config`](crate::config).
+    #[cfg(feature = "config")]
+    pub fn config_from_file<T>(self, path: impl AsRef<std::path::Path>) -> Self
+    where
+        T: serde::de::DeserializeOwned + Clone + Send + Sync + 'static,
+    {
+        self.add_provider(&crate::config::ConfigProvider::<T>::from_file(path.as_ref()))
+    }
+
+    // ── Command-line arguments ──
+
+    /// Parse the process arguments into `A` when [`build`](Self::build)
+    /// runs, and register it as a singleton.
+    ///
+    /// Each top-level argument with a value, given or defaulted, is also
+    /// registered as a named `String` under its long flag (or, for
+    /// positionals, its id); repeated values are joined with commas.
+    ///
+    /// A parse failure, including `--help` and `--version`, makes `build`
+    /// return [`MakhzanError::ConstructionFailed`] wrapping the
+    /// `clap::Error` instead of exiting the process.
+    ///
+    /// ```rust,no_run
+    /// # use makhzan_container::prelude::*;
+    /// use clap::Parser;
+    ///
+    /// #[derive(Clone, Parser)]
+    /// struct Args {
+    ///     #[arg(long, default_value = "out")]
+    ///     output_dir: String,
+    /// }
+    ///
+    /// let container = Container::builder().with_cli_args::<Args>().build()?;
+    /// let dir: String = container.resolve_named("output-dir")?;
+    /// # Ok::<(), MakhzanError>(())
+    /// ```
+    #[cfg(feature = "clap")]
+    pub fn with_cli_args<A: clap::Parser + Clone + Send + Sync + 'static>(self) -> Self {
+        self.with_cli_args_from_iter::<A>(std::env::args_os())
+    }
+
+    /// [`with_cli_args`](Self::with_cli_args) over a given argv, first
+    /// item being the binary name.
+    #[cfg(feature = "clap")]
+    pub fn with_cli_args_from_iter<A: clap::Parser + Clone + Send + Sync + 'static>(
+        mut self,
+        argv: impl IntoIterator<Item = impl Into<std::ffi::OsString>>,
+    ) -> Self {
+        let argv = argv.into_iter().map(Into::into).collect();
+        self.cli_args = Some(crate::cli::deferred::<A>(argv));
+        self
+    }
+
+    /// Register already parsed arguments as a singleton.
+    ///
+    /// No named values are registered: they come from the parse itself.
+    #[cfg(feature = "clap")]
+    pub fn with_cli_args_from<A: clap::Parser + Clone + Send + Sync + 'static>(mut self, args: A) -> Self {
+        crate::cli::register_value(&mut self, DependencyKey::of::<A>(), args);
+        self
+    }
+
+    // ── Startup ──
+
+    /// Have [`Container::initialize_all`] resolve `T` and call its
+    /// [`Initialize::initialize`], after every marked key `T` depends on.
+    ///
+    /// The order follows declared dependencies (the `deps` given to a
+    /// [`Provider`] or [`Injectable`], or recorded ones), so a factory that
+    /// resolves undeclared keys may be initialized before them. Meant for
+    /// singletons: a transient would be built just to be initialized and
+    /// dropped.
+    pub fn with_initializer<T: Initialize + 'static>(mut self) -> Self {
+        let key = DependencyKey::of::<T>();
+        if !self.initializers.iter().any(|(marked, _)| *marked == key) {
+            self.initializers.push((key, initializer::<T>()));
+        }
+        self
+    }
+
+    // ── Provider modules ──
+
+    /// Add a [`Provider`] module.
+    ///
+    /// The provider is only borrowed to register, so its
+    /// [`teardown`](Provider::teardown) is never called; use
+    /// [`add_provider_owned`](Self::add_provider_owned) for that.
+    pub fn add_provider(mut self, provider: &dyn Provider) -> Self {
+        self.register_provider(provider);
+        self
+    }
+
+    /// Add a [`Provider`] module and keep it, to call its
+    /// [`teardown`](Provider::teardown) once the containers built with it
+    /// are dropped.
+    ///
+    /// ```rust
+    /// # use makhzan_container::prelude::*;
+    /// # use makhzan_container::provider::ProviderRegistry;
+    /// # struct DatabaseProvider;
+    /// # impl DatabaseProvider { fn new(_: &str) -> Self { DatabaseProvider } }
+    /// # impl Provider for DatabaseProvider {
+    /// #     fn register(&self, _: &mut dyn ProviderRegistry) {}
+    /// # }
+    /// # let url = "postgres://localhost/app";
+    /// let container = Container::builder().add_provider_owned(DatabaseProvider::new(url)).build()?;
+    /// drop(container); // DatabaseProvider::teardown closes the pool
+    /// # Ok::<(), MakhzanError>(())
+    /// ```
+    pub fn add_provider_owned(mut self, provider: impl Provider + 'static) -> Self {
+        self.register_provider(&provider);
+        self.providers.push(Arc::new(OwnedProvider {
+            provider: Arc::new(provider),
+            teardown: Mutex::new(Weak::new()),
+        }));
+        self
+    }
+
+    fn register_provider(&mut self, provider: &dyn Provider) {
+        let revision = self.registry.next_revision();
+        provider.register(self);
+        self.registry.attribute_since(revision, &Arc::from(provider.name()));
+        for key in provider.requires() {
+            self.requirements.push((provider.name().to_string(), key));
+        }
+    }
+
+    /// [`Provider::requires`] keys that nothing has registered yet.
+    ///
+    /// Call it once every provider is added to report what a plugin set is
+    /// missing before [`build`](Self::build) fails on the first gap.
+    ///
+    /// ```rust
+    /// # use makhzan_container::prelude::*;
+    /// # use makhzan_container::provider::ProviderRegistry;
+    /// # use std::sync::Arc;
+    /// # struct Pool;
+    /// # struct Repositories;
+    /// # impl Provider for Repositories {
+    /// #     fn register(&self, _: &mut dyn ProviderRegistry) {}
+    /// #     fn requires(&self) -> Vec<DependencyKey> { vec![DependencyKey::of::<Arc<Pool>>()] }
+    /// # }
+    /// let builder = Container::builder().add_provider(&Repositories);
+    /// for unmet in builder.unmet_requirements() {
+    ///     eprintln!("{unmet}");
+    /// }
+    /// # assert_eq!(builder.unmet_requirements().len(), 1);
+    /// ```
+    pub fn unmet_requirements(&self) -> Vec<UnmetRequirement> {
+        self.requirements
+            .iter()
+            .filter(|(_, key)| {
+                !self.registry.contains(key)
+                    && !self.defaults.iter().any(|default| &default.key == key)
+                    && !self.profile_bindings().any(|binding| &binding.key == key)
+            })
+            .map(|(provider, key)| UnmetRequirement { provider: provider.clone(), key: key.clone() })
+            .collect()
+    }
+
+    // ── Build ──
+
+    /// Build the container, validating the dependency graph.
+    ///
+    /// Checks: all deps registered, no cycles, scope compatibility.
+    ///
+    /// The container keeps a clone of this builder so that
+    /// [`Container::rebuild_with`] can start from it. Factories are shared
+    /// through `Arc`, so the copy costs the registration maps, in memory
+    /// proportional to the number of registrations; clones of the
+    /// container share it.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self), name = "container_build"))]
+    pub fn build(self) -> Result<Container> {
+        self.build_validating(Validation::Full)
+    }
+
+    /// Build the container **without validating** the dependency graph.
+    ///
+    /// For unit tests that wire a few registrations whose declared
+    /// dependencies are never resolved. A missing dependency, cycle or
+    /// scope mismatch is not reported here but by the resolve that hits
+    /// it. Never use it for a production container; see
+    /// [`build_partial`](Self::build_partial) for a middle ground.
+    ///
+    /// ```rust
+    /// # use makhzan_container::prelude::*;
+    /// # use makhzan_container::registry::Resolver;
+    /// # use std::sync::Arc;
+    /// # const NOON: u32 = 12;
+    /// # #[derive(Clone)]
+    /// # struct FakeClock(u32);
+    /// # impl FakeClock { fn at(hour: u32) -> Self { FakeClock(hour) } }
+    /// # struct Database;
+    /// # struct Scheduler { clock: FakeClock }
+    /// # impl Injectable for Scheduler {
+    /// #     fn dependencies() -> Vec<DependencyKey> {
+    /// #         vec![DependencyKey::of::<FakeClock>(), DependencyKey::of::<Arc<Database>>()]
+    /// #     }
+    /// #     fn inject(r: &dyn Resolver) -> Result<Self> { Ok(Scheduler { clock: resolve(r)? }) }
+    /// # }
+    /// let container = Container::builder()
+    ///     .singleton_value(FakeClock::at(NOON))
+    ///     .register_injectable::<Scheduler>() // also declares Arc<Database>
+    ///     .build_unchecked()?;
+    /// assert_eq!(container.resolve::<Scheduler>()?.clock.0, NOON);
+    /// # Ok::<(), MakhzanError>(())
+    /// ```
+    ///
+    /// # Errors
+    /// Only what building reports besides validation, e.g. a failed
+    /// [`register_dynamic`](Self::register_dynamic) lookup.
+    pub fn build_unchecked(self) -> Result<Container> {
+        self.build_validating(Validation::Skip)
+    }
+
+    /// Build the container, validating only what `roots` transitively
+    /// depend on.
+    ///
+    /// Registrations no root reaches are kept but not checked, so a test
+    /// can exercise one service out of a production builder whose other
+    /// services need stubs it doesn't provide.
+    ///
+    /// ```rust
+    /// # use makhzan_container::prelude::*;
+    /// # #[derive(Clone)]
+    /// # struct Ledger;
+    /// # #[derive(Clone)]
+    /// # struct InvoiceService;
+    /// # struct SmtpConfig;
+    /// # #[derive(Clone)]
+    /// # struct Mailer;
+    /// # fn production_wiring() -> ContainerBuilder {
+    /// #     Container::builder()
+    /// #         .singleton_value(Ledger)
+    /// #         .register_factory(Scope::Transient, vec![DependencyKey::of::<Ledger>()], |_| Ok(InvoiceService))
+    /// #         .register_factory(Scope::Transient, vec![DependencyKey::of::<SmtpConfig>()], |_| Ok(Mailer))
+    /// # }
+    /// assert!(production_wiring().build().is_err()); // nothing provides SmtpConfig
+    /// let container = production_wiring()
+    ///     .build_partial(&[DependencyKey::of::<InvoiceService>()])?;
+    /// container.resolve::<InvoiceService>()?;
+    /// # Ok::<(), MakhzanError>(())
+    /// ```
+    ///
+    /// # Errors
+    /// The errors of [`build`](Self::build), for the reachable part of the
+    /// graph; [`MakhzanError::NotRegistered`] if a root is not registered.
+    pub fn build_partial(self, roots: &[DependencyKey]) -> Result<Container> {
+        self.build_validating(Validation::From(roots.into()))
+    }
+
+    /// Validate the wiring as [`build`](Self::build) would, without
+    /// building, and return the report.
+    ///
+    /// Borrows the builder, so a test can check the graph and then build
+    /// or drop it, or validate again after adding registrations. No
+    /// factory runs except the dry runs of
+    /// [recorded](Self::transient_with_recorded) registrations, which
+    /// `build` does as well.
+    ///
+    /// ```rust
+    /// # use makhzan_container::prelude::*;
+    /// # fn production_wiring() -> ContainerBuilder {
+    /// #     Container::builder()
+    /// #         .singleton_value(1u8)
+    /// #         .register_factory(Scope::Transient, vec![DependencyKey::of::<u8>()], |r| {
+    /// #             Ok(u16::from(resolve::<u8>(r)?))
+    /// #         })
+    /// # }
+    /// let report = production_wiring().validate().unwrap();
+    /// assert!(report.warnings.is_empty(), "{:?}", report.warnings);
+    /// ```
+    ///
+    /// # Errors
+    /// Exactly the error `build` would return.
+    pub fn validate(&self) -> Result<ValidationReport> {
+        let mut builder = self.clone();
+        builder.prepare()?;
+        builder.validated(&Validation::Full).map(|(report, _, _)| report)
+    }
+
+    fn build_validating(mut self, validation: Validation) -> Result<Container> {
+        info!(registered = self.registry.len(), "Building container");
+        let source = Arc::new((self.clone(), validation.clone()));
+        self.prepare()?;
+        let (report, stats, plans) = self.validated(&validation)?;
+
+        let initializers = self.ordered_initializers();
+
+        let warnings = self.registry.alias_warnings();
+        #[cfg(feature = "tracing")]
+        for warning in &warnings {
+            warn!("{warning}");
+        }
+
+        let teardown = self.providers.iter().map(|owned| owned.teardown()).collect();
+        let container = Container {
+            plans: Arc::new(plans),
+            registry: Arc::new(self.registry),
+            dynamic: Arc::new(self.dynamic),
+            scoped_from_root: self.scoped_from_root,
+            scope_validation: self.scope_validation,
+            max_depth: self.max_depth,
+            catch_panics: self.catch_panics,
+            suggestions: self.suggestions,
+            initializers: Arc::new(initializers),
+            resolve_timeout: self.resolve_timeout,
+            #[cfg(feature = "async")]
+            async_inits: Arc::new(std::mem::take(&mut self.async_inits)),
+            weak_counts: Arc::new(std::mem::take(&mut self.weak_counts)),
+            families: Arc::new(std::mem::take(&mut self.families)),
+            observers: Arc::new(std::mem::take(&mut self.observers)),
+            warnings: Arc::new(warnings),
+            stats: Arc::new(stats),
+            report: Arc::new(report),
+            source,
+            teardown: Arc::new(teardown),
+        };
+
+        info!("Container built successfully ✓");
+        Ok(container)
+    }
+
+    /// Settles the registrations `build` starts from: profile bindings,
+    /// defaults, inherited scopes, recorded dependencies and family
+    /// members.
+    fn prepare(&mut self) -> Result<()> {
+        if let Some(key) = self.duplicate_entries.first() {
+            return Err(MakhzanError::AlreadyRegistered(AlreadyRegisteredError { key: key.clone() }));
+        }
+
+        #[cfg(feature = "clap")]
+        if let Some(parse) = self.cli_args.take() {
+            parse(self)?;
+        }
+
+        let profiled: Vec<Registration> = self.profile_bindings().cloned().collect();
+        self.profiled.clear();
+        for registration in profiled {
+            debug!(key = %registration.key, profile = ?self.profile, "Using profile binding");
+            let _ = self.registry.register(registration, true);
+        }
+        for registration in std::mem::take(&mut self.defaults) {
+            if !self.registry.contains(&registration.key) {
+                debug!(key = %registration.key, "Using default implementation");
+                let _ = self.registry.register(registration, false);
+            }
+        }
+        for (key, factory, cloner) in &self.bindings {
+            self.registry.inherit_scope(key, factory, *cloner);
+        }
+
+        let recorded: Vec<(DependencyKey, Vec<DependencyKey>)> = self
+            .registry
+            .all_registrations()
+            .iter()
+            .filter(|(_, reg)| reg.recorded)
+            .map(|(key, reg)| (key.clone(), RecordingResolver::record(&self.sentinels, &reg.factory)))
+            .collect();
+        for (key, dependencies) in recorded {
+            debug!(key = %key, dependencies = ?dependencies, "Inferred dependencies");
+            self.registry.set_dependencies(&key, dependencies);
+        }
+        self.register_family_members();
+        Ok(())
+    }
+
+    /// Validates the [prepared](Self::prepare) registrations and compiles
+    /// their plans.
+    fn validated(&self, validation: &Validation) -> Result<(ValidationReport, GraphStats, Plans)> {
+        let mut validator = GraphValidator::new(self.registry.dependency_infos())
+            .with_aliases(self.registry.all_aliases())
+            .with_suggestions(self.suggestions)
+            .with_scope_validation(self.scope_validation);
+        let report = match validation {
+            Validation::Full => validator.validate_report()?,
+            Validation::From(roots) => {
+                for root in roots.iter() {
+                    validator.validate_from(root)?;
+                }
+                validator.report()
+            }
+            Validation::Skip => {
+                warn!("Building container without validating the dependency graph");
+                ValidationReport::default()
+            }
+        };
+        #[cfg(feature = "tracing")]
+        for warning in &report.warnings {
+            debug!("{warning}");
+        }
+        let stats = validator.stats();
+        info!(
+            registrations = stats.registrations,
+            aliases = stats.aliases,
+            max_depth = stats.max_depth(),
+            deepest = ?stats.longest_chain.first(),
+            average_fan_out = stats.average_fan_out,
+            "Dependency graph"
+        );
+
+        let plans = Plans::compile(&self.registry);
+        if let Some(key) = self.dynamic.values().find(|key| plans.get(key).is_none()) {
+            return Err(not_registered(&self.registry, self.suggestions, key));
+        }
+        Ok((report, stats, plans))
+    }
+
+    // ── Extensions ──
+
+    /// Register `factory` for `T` under `scope`, declaring the keys it
+    /// resolves as `dependencies`.
+    ///
+    /// The typed counterpart of [`ProviderRegistry`]'s methods: the key is
+    /// `T` and `factory` returns a plain `T`. A singleton is cached like
+    /// [`singleton_with`](Self::singleton_with), a scoped value like
+    /// [`scoped_with`](Self::scoped_with), and a transient is built on
+    /// every resolve. `dependencies` are validated by
+    /// [`build`](Self::build).
+    ///
+    /// It is the building block for extension traits that give the
+    /// builder helpers from other crates:
+    ///
+    /// ```rust
+    /// use makhzan_container::prelude::*;
+    /// use std::sync::Arc;
+    ///
+    /// pub struct KafkaConfig {
+    ///     pub brokers: String,
+    /// }
+    ///
+    /// pub struct KafkaClient {
+    ///     pub brokers: String,
+    /// }
+    ///
+    /// #[derive(Clone)]
+    /// pub struct Producer(pub Arc<KafkaClient>);
+    ///
+    /// pub trait KafkaExt {
+    ///     fn add_kafka(self, config: KafkaConfig) -> Self;
+    /// }
+    ///
+    /// impl KafkaExt for ContainerBuilder {
+    ///     fn add_kafka(self, config: KafkaConfig) -> Self {
+    ///         let client = Arc::new(KafkaClient { brokers: config.brokers });
+    ///         self.register_factory(Scope::Singleton, vec![], move |_| Ok(client.clone()))
+    ///             .register_factory(Scope::Transient, vec![DependencyKey::of::<Arc<KafkaClient>>()], |r| {
+    ///                 Ok(Producer(resolve(r)?))
+    ///             })
+    ///     }
+    /// }
+    ///
+    /// let container = Container::builder()
+    ///     .add_kafka(KafkaConfig { brokers: "localhost:9092".into() })
+    ///     .build()
+    ///     .unwrap();
+    /// let producer: Producer = container.resolve().unwrap();
+    /// assert_eq!(producer.0.brokers, "localhost:9092");
+    /// ```
+    pub fn register_factory<T: Clone + Send + Sync + 'static>(
+        self,
+        scope: Scope,
+        dependencies: Vec<DependencyKey>,
+        factory: impl Fn(&dyn Resolver) -> Result<T> + Send + Sync + 'static,
+    ) -> Self {
+        let key = DependencyKey::of::<T>();
+        let registration = match scope {
+            Scope::Singleton => Registration {
+                dependencies,
+                ..singleton_registration(key, SingletonFailurePolicy::RetryOnNextResolve, factory)
+            },
+            Scope::Scoped | Scope::Transient => Registration {
+                cloner: scope.is_cached().then(Cloner::of::<T>),
+                ..Registration::new(
+                    key,
+                    scope,
+                    Arc::new(move |resolver: &dyn Resolver| {
+                        Ok(Box::new(factory(resolver)?) as Box<dyn Any + Send + Sync>)
+                    }),
+                    dependencies,
+                )
+            },
+        };
+        self.register_entry(registration)
+    }
+
+    /// Register a family of keys built on demand.
+    ///
+    /// When a key has no registration, `family` is asked for a factory
+    /// for it; `None` means the key is not a member. Members are
+    /// transient. A member something depends on is registered by
+    /// [`build`](Self::build), so validation sees it, and one resolved
+    /// directly is looked up on the miss. Families are asked in
+    /// registration order.
+    ///
+    /// Members declare no dependencies, so the graph check stops at them;
+    /// [`register_generic`](Self::register_generic) declares them.
+    ///
+    /// ```rust
+    /// # use makhzan_container::prelude::*;
+    /// # use makhzan_container::registry::{FactoryFn, Resolver};
+    /// # use std::any::{Any, TypeId};
+    /// # use std::collections::HashMap;
+    /// # use std::sync::Arc;
+    /// # struct CsvExporter;
+    /// # fn load_plugins() -> HashMap<TypeId, FactoryFn> {
+    /// #     let csv: FactoryFn = Arc::new(|_: &dyn Resolver| Ok(Box::new(CsvExporter) as Box<dyn Any + Send + Sync>));
+    /// #     HashMap::from([(TypeId::of::<CsvExporter>(), csv)])
+    /// # }
+    /// let plugins: HashMap<TypeId, FactoryFn> = load_plugins();
+    /// let container = Container::builder()
+    ///     .register_family(move |key| plugins.get(&key.type_id()).cloned())
+    ///     .build()?;
+    /// let exporter: CsvExporter = container.resolve()?;
+    /// # Ok::<(), MakhzanError>(())
+    /// ```
+    pub fn register_family(
+        mut self,
+        family: impl Fn(&DependencyKey) -> Option<FactoryFn> + Send + Sync + 'static,
+    ) -> Self {
+        self.families.push(Arc::new(move |key: &DependencyKey| {
+            family(key).map(|factory| Registration::new(key.clone(), Scope::Transient, factory, vec![]))
+        }));
+        self
+    }
+
+    /// Register the members of the generic type named by `F` for each
+    /// parameter in the tuple `P`.
+    ///
+    /// A [`register_family`](Self::register_family) whose members are
+    /// `F::Member<T>` for every `T` in `P`, all built by `F::create` with
+    /// `F::dependencies()` declared and validated. See
+    /// [`GenericFactory`] for an example.
+    ///
+    /// ```rust
+    /// # use makhzan_container::generic::GenericFactory;
+    /// # use makhzan_container::prelude::*;
+    /// # use makhzan_container::registry::Resolver;
+    /// # use std::marker::PhantomData;
+    /// # use std::sync::Arc;
+    /// # struct Database;
+    /// # impl Database { fn connect() -> Result<Self> { Ok(Database) } }
+    /// # struct Repository<T> { database: Arc<Database>, entity: PhantomData<T> }
+    /// # struct Repositories;
+    /// # impl GenericFactory for Repositories {
+    /// #     type Member<T: Send + Sync + 'static> = Repository<T>;
+    /// #     fn dependencies() -> Vec<DependencyKey> { vec![DependencyKey::of::<Arc<Database>>()] }
+    /// #     fn create<T: Send + Sync + 'static>(r: &dyn Resolver) -> Result<Repository<T>> {
+    /// #         Ok(Repository { database: resolve(r)?, entity: PhantomData })
+    /// #     }
+    /// # }
+    /// # struct User;
+    /// # struct Order;
+    /// # struct Invoice;
+    /// let container = Container::builder()
+    ///     .singleton_value(Arc::new(Database::connect()?))
+    ///     .register_generic::<Repositories, (User, Order, Invoice)>()
+    ///     .build()?;
+    /// let users: Repository<User> = container.resolve()?;
+    /// # Ok::<(), MakhzanError>(())
+    /// ```
+    pub fn register_generic<F: GenericFactory, P: GenericParams>(mut self) -> Self {
+        let members: HashMap<DependencyKey, FactoryFn, BuildKeyHasher> = P::members::<F>().into_iter().collect();
+        let dependencies = F::dependencies();
+        self.families.push(Arc::new(move |key: &DependencyKey| {
+            let factory = members.get(key)?.clone();
+            Some(Registration::new(key.clone(), Scope::Transient, factory, dependencies.clone()))
+        }));
+        self
+    }
+
+    // ── Internal ──
+
+    /// Registers the family member of every dependency, alias target and
+    /// dynamic name that is missing, and of the members' dependencies in
+    /// turn.
+    fn register_family_members(&mut self) {
+        if self.families.is_empty() {
+            return;
+        }
+        let mut pending: Vec<DependencyKey> = self
+            .registry
+            .all_registrations()
+            .values()
+            .flat_map(|registration| registration.dependencies.iter().cloned())
+            .chain(self.registry.all_aliases().values().cloned())
+            .chain(self.dynamic.values().cloned())
+            .collect();
+        while let Some(key) = pending.pop() {
+            if self.registry.contains(&key) {
+                continue;
+            }
+            if let Some(registration) = family_member(&self.families, &key) {
+                debug!(key = %key, "Registering family member");
+                pending.extend(registration.dependencies.iter().cloned());
+                let _ = self.registry.register(registration, false);
+            }
+        }
+    }
+
+    fn register_internal(
+        self,
+        key: DependencyKey,
+        scope: Scope,
+        factory: FactoryFn,
+        dependencies: Vec<DependencyKey>,
+    ) -> Self {
+        self.register_entry(Registration::new(key, scope, factory, dependencies))
+    }
+
+    fn register_entry(mut self, registration: Registration) -> Self {
+        let _ = self.registry.register(registration, self.allow_override);
+        self
+    }
+}
+
+/// The registration the first family covering `key` gives it.
+fn family_member(families: &[FamilyFn], key: &DependencyKey) -> Option<Registration> {
+    families.iter().find_map(|family| family(key))
+}
+
+/// A singleton registration for `factory` under `key`. The instance is
+/// cached by each container built from it, in its [`PlanEntry`].
+fn singleton_registration<T: Clone + Send + Sync + 'static>(
+    key: DependencyKey,
+    policy: SingletonFailurePolicy,
+    factory: impl Fn(&dyn Resolver) -> Result<T> + Send + Sync + 'static,
+) -> Registration {
+    Registration {
+        cloner: Some(Cloner::of::<T>()),
+        failure_policy: policy,
+        ..Registration::new(
+            key,
+            Scope::Singleton,
+            Arc::new(move |resolver: &dyn Resolver| {
+                factory(resolver).map(|value| Box::new(value) as Box<dyn Any + Send + Sync>)
+            }),
+            vec![],
+        )
+    }
+}
+
+/// How much of the graph `build` validates.
+#[derive(Clone)]
+enum Validation {
+    Full,
+    From(Arc<[DependencyKey]>),
+    Skip,
+}
+
+// ProviderRegistry impl so providers can register into builder
+impl ContainerBuilder {
+    /// The marked initializers, each after the marked keys it depends on.
+    fn ordered_initializers(&mut self) -> Vec<(DependencyKey, InitFn)> {
+        let infos = self.registry.dependency_infos();
+        let aliases = self.registry.all_aliases();
+        let mut roots = self.registry.ordered_keys().to_vec();
+        roots.extend(self.initializers.iter().map(|(key, _)| key.clone()));
+        let order = dependency_order(&infos, aliases, &roots);
+        let position = |key: &DependencyKey| {
+            let target = dependency_order(&infos, aliases, std::slice::from_ref(key));
+            target.last().and_then(|target| order.iter().position(|k| k == target))
+        };
+
+        let mut initializers = std::mem::take(&mut self.initializers);
+        initializers.sort_by_cached_key(|(key, _)| position(key));
+        initializers
+    }
+}
+
+impl ProviderRegistry for ContainerBuilder {
+    fn register_singleton(
+        &mut self, key: DependencyKey, factory: FactoryFn, deps: Vec<DependencyKey>,
+    ) {
+        let reg = Registration::new(key, Scope::Singleton, factory, deps);
+        let _ = self.registry.register(reg, self.allow_override);
+    }
+
+    fn register_scoped(
+        &mut self, key: DependencyKey, factory: FactoryFn, deps: Vec<DependencyKey>,
+    ) {
+        let reg = Registration::new(key, Scope::Scoped, factory, deps);
+        let _ = self.registry.register(reg, self.allow_override);
+    }
+
+    fn register_transient(
+        &mut self, key: DependencyKey, factory: FactoryFn, deps: Vec<DependencyKey>,
+    ) {
+        let reg = Registration::new(key, Scope::Transient, factory, deps);
+        let _ = self.registry.register(reg, self.allow_override);
+    }
+
+    fn register_alias(&mut self, from: DependencyKey, to: DependencyKey) {
+        self.registry.register_alias(from, to);
+    }
+
+    fn register_cacheable(
+        &mut self,
+        key: DependencyKey,
+        scope: Scope,
+        factory: FactoryFn,
+        deps: Vec<DependencyKey>,
+        cacheable: Cacheable,
+    ) {
+        let reg = Registration {
+            cloner: Some(cacheable.0),
+            ..Registration::new(key, scope, factory, deps)
+        };
+        let _ = self.registry.register(reg, self.allow_override);
+    }
+}
+
+// ═══════════════════════════════════════════
+// Container
+// ═══════════════════════════════════════════
+
+/// Immutable, thread-safe dependency injection container.
+///
+/// Created by [`ContainerBuilder::build()`].
+///
+/// # Thread safety
+/// `Container` is `Send + Sync`; share it by reference or in an `Arc` and
+/// resolve from any number of threads. Every registered type must be
+/// `Send + Sync` too, which the builder enforces at compile time. A
+/// [`singleton_with`](ContainerBuilder::singleton_with) factory runs at
+/// most once even when threads race on its first resolve; the losers wait
+/// and receive the same instance.
+///
+/// Cloning is cheap and yields a handle to the *same* container: clones
+/// share every singleton, so a clone can be moved into a spawned thread
+/// instead of wrapping the container in an `Arc`.
+#[derive(Clone)]
+pub struct Container {
+    registry: Arc<Registry>,
+    /// Alias-flattened lookup table compiled at build time. Also holds
+    /// the singleton instance cache, which skips the factory, the `Box`
+    /// and the downcast once a value has been produced.
+    plans: Arc<Plans>,
+    /// Names registered with [`ContainerBuilder::register_dynamic`].
+    dynamic: Arc<HashMap<String, DependencyKey>>,
+    scoped_from_root: ScopedFromRoot,
+    scope_validation: ScopeValidation,
+    max_depth: usize,
+    catch_panics: bool,
+    suggestions: SuggestionConfig,
+    /// [`with_initializer`](ContainerBuilder::with_initializer) steps,
+    /// dependencies first.
+    initializers: Arc<Vec<(DependencyKey, InitFn)>>,
+    #[cfg_attr(not(feature = "async"), allow(dead_code))]
+    resolve_timeout: Option<Duration>,
+    #[cfg(feature = "async")]
+    async_inits: Arc<HashMap<DependencyKey, AsyncInit>>,
+    weak_counts: Arc<HashMap<DependencyKey, StrongCount>>,
+    /// Families for keys looked up but not registered at build time.
+    families: Arc<Vec<FamilyFn>>,
+    observers: Arc<Vec<Observer>>,
+    warnings: Arc<Vec<String>>,
+    /// Measured by `build` while validating.
+    stats: Arc<GraphStats>,
+    report: Arc<ValidationReport>,
+    /// The builder this container was built from and how it was
+    /// validated, for [`rebuild_with`](Self::rebuild_with).
+    source: Arc<(ContainerBuilder, Validation)>,
+    /// Owned providers in add order, each torn down by the last
+    /// container holding it.
+    teardown: Arc<Vec<Arc<ProviderTeardown>>>,
+}
+
+/// A provider added with [`ContainerBuilder::add_provider_owned`].
+struct OwnedProvider {
+    provider: Arc<dyn Provider>,
+    /// Shared by every container built with the provider while any of
+    /// them is alive, so its teardown runs once for all of them.
+    teardown: Mutex<Weak<ProviderTeardown>>,
+}
+
+impl OwnedProvider {
+    /// The teardown of the live containers built with this provider, or
+    /// a new one if there are none.
+    fn teardown(&self) -> Arc<ProviderTeardown> {
+        let mut shared = self.teardown.lock().unwrap_or_else(PoisonError::into_inner);
+        shared.upgrade().unwrap_or_else(|| {
+            let teardown = Arc::new(ProviderTeardown(Arc::clone(&self.provider)));
+            *shared = Arc::downgrade(&teardown);
+            teardown
+        })
+    }
+}
+
+/// A provider awaiting [`Provider::teardown`], held by every container
+/// built with it.
+struct ProviderTeardown(Arc<dyn Provider>);
+
+impl Drop for Container {
+    fn drop(&mut self) {
+        let Some(teardown) = Arc::into_inner(std::mem::take(&mut self.teardown)) else { return };
+        for provider in teardown.into_iter().rev() {
+            // Still held by a container of another build, which tears it down.
+            let Some(ProviderTeardown(provider)) = Arc::into_inner(provider) else { continue };
+            debug!(provider = provider.name(), "Tearing down provider");
+            provider.teardown(self);
+        }
+    }
+}
+
+/// An empty builder, the same as [`Container::builder`].
+impl Default for ContainerBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Container {
+    /// Create a new builder.
+    pub fn builder() -> ContainerBuilder {
+        ContainerBuilder::new()
+    }
+
+    /// A `'static` [`Resolver`] handle that can be stored and used later.
+    ///
+    /// See [`OwnedResolver`] for why this should stay rare.
+    pub fn resolver(self: &Arc<Self>) -> OwnedResolver {
+        OwnedResolver { container: Arc::clone(self) }
+    }
+
+    /// Resolve a dependency by type.
+    ///
+    /// ```rust
+    /// # use makhzan_container::prelude::*;
+    /// # use std::sync::Arc;
+    /// # struct Database;
+    /// # let container = Container::builder().singleton_arc(|_| Ok(Database)).build()?;
+    /// let db: Arc<Database> = container.resolve()?;
+    /// # Ok::<(), MakhzanError>(())
+    /// ```
+    pub fn resolve<T: Send + Sync + 'static>(&self) -> Result<T> {
+        self.resolve_from::<T>(None)
+    }
+
+    /// Resolve `T`, or fall back to `T::default()` if it was never registered.
+    ///
+    /// Only a missing registration for `T` itself is replaced by the
+    /// default. Every other failure, such as a
+    /// [`ConstructionFailed`](MakhzanError::ConstructionFailed) factory or
+    /// a missing dependency of `T`, is still returned.
+    pub fn resolve_or_default<T: Default + Send + Sync + 'static>(&self) -> Result<T> {
+        match self.resolve::<T>() {
+            Err(MakhzanError::NotRegistered(e)) if e.requested == DependencyKey::of::<T>() => {
+                Ok(T::default())
+            }
+            other => other,
+        }
+    }
+
+    /// [`resolve`](Self::resolve) that also returns how `T` was produced:
+    /// every key resolved on the way, whether it came from a cache, and
+    /// how long it took.
+    ///
+    /// ```rust
+    /// # use makhzan_container::prelude::*;
+    /// # #[derive(Clone)]
+    /// # struct Cart;
+    /// # struct CheckoutHandler;
+    /// # let container = Container::builder()
+    /// #     .singleton_value(Cart)
+    /// #     .transient_with(|r| {
+    /// #         resolve::<Cart>(r)?;
+    /// #         Ok(CheckoutHandler)
+    /// #     })
+    /// #     .build()?;
+    /// let (handler, trace) = container.resolve_traced::<CheckoutHandler>()?;
+    /// eprintln!("{}", trace.render());
+    /// # Ok::<(), MakhzanError>(())
+    /// ```
+    pub fn resolve_traced<T: Send + Sync + 'static>(&self) -> Result<(T, ResolutionTrace)> {
+        let recorder = TraceRecorder::default();
+        let value = resolve::<T>(&ContainerResolver { container: self, scope: None, trace: Some(&recorder) })?;
+        let trace = recorder.finish().expect("a successful resolve records its root");
+        Ok((value, trace))
+    }
+
+    /// Resolve a dependency, first running its
+    /// [async factory](ContainerBuilder::singleton_async) if it has one
+    /// that hasn't run yet.
+    ///
+    /// Keys without an async factory resolve as with
+    /// [`resolve`](Self::resolve).
+    ///
+    /// # Errors
+    /// [`MakhzanError::ConstructionTimeout`] if the factory runs longer
+    /// than [`resolve_timeout`](ContainerBuilder::resolve_timeout), plus
+    /// everything `resolve` returns.
+    #[cfg(feature = "async")]
+    pub async fn resolve_async<T: Send + Sync + 'static>(&self) -> Result<T> {
+        let key = DependencyKey::of::<T>();
+        if let Some(init) = self.async_inits.get(&key) {
+            let init = init(self.clone());
+            match self.resolve_timeout {
+                Some(limit) => tokio::time::timeout(limit, init)
+                    .await
+                    .map_err(|_| MakhzanError::ConstructionTimeout { key, elapsed: limit })??,
+                None => init.await?,
+            }
+        }
+        self.resolve()
+    }
+
+    /// Resolve a dependency registered under a [named](DependencyKey::named) key.
+    ///
+    /// `name` may be built at runtime; it matches a key registered with
+    /// either [`DependencyKey::named`] or [`DependencyKey::named_owned`].
+    /// Like the latter it interns `name`, so don't feed it unbounded
+    /// untrusted input.
+    ///
+    /// ```rust
+    /// # use makhzan_container::prelude::*;
+    /// # use std::sync::Arc;
+    /// # struct Pool;
+    /// # let id = 7;
+    /// # let container = Container::builder().add_keyed("tenant-7-db", |_| Ok(Arc::new(Pool))).build()?;
+    /// let db: Arc<Pool> = container.resolve_named(&format!("tenant-{id}-db"))?;
+    /// # Ok::<(), MakhzanError>(())
+    /// ```
+    pub fn resolve_named<T: Send + Sync + 'static>(&self, name: &str) -> Result<T> {
+        self.resolve_keyed_from::<T>(DependencyKey::named_owned::<T>(name.to_string()), None)
+    }
+
+    /// Resolve a dependency registered under a [tagged](DependencyKey::tagged) key.
+    ///
+    /// ```rust
+    /// # use makhzan_container::prelude::*;
+    /// # struct Replica;
+    /// # let container = Container::builder()
+    /// #     .singleton_tagged::<String, Replica>(|_| Ok("postgres://replica".into()))
+    /// #     .build()?;
+    /// let url: String = container.resolve_tagged::<String, Replica>()?;
+    /// # Ok::<(), MakhzanError>(())
+    /// ```
+    pub fn resolve_tagged<T: Send + Sync + 'static, Tag: ?Sized + 'static>(&self) -> Result<T> {
+        self.resolve_keyed_from::<T>(DependencyKey::tagged::<T, Tag>(), None)
+    }
+
+    /// Typed resolve shared by [`Container`] and [`ScopedContainer`].
+    fn resolve_from<T: Send + Sync + 'static>(&self, scope: Option<&ScopeCache<'_>>) -> Result<T> {
+        self.resolve_keyed_from::<T>(DependencyKey::of::<T>(), scope)
+    }
+
+    /// [`resolve_from`](Self::resolve_from) for an explicit key of type `T`.
+    fn resolve_keyed_from<T: Send + Sync + 'static>(
+        &self,
+        key: DependencyKey,
+        scope: Option<&ScopeCache<'_>>,
+    ) -> Result<T> {
+        trace!(key = %key, "Resolving");
+        self.observed(&key, || self.resolve_keyed_unobserved(&key, scope))
+    }
+
+    /// [`resolve_keyed_from`](Self::resolve_keyed_from) without reporting
+    /// to the [`on_resolve`](ContainerBuilder::on_resolve) observers.
+    fn resolve_keyed_unobserved<T: Send + Sync + 'static>(
+        &self,
+        key: &DependencyKey,
+        scope: Option<&ScopeCache<'_>>,
+    ) -> Result<T> {
+        let resolved = match scope.and_then(|scope| scope.get(key)) {
+            Some(resolved) => resolved,
+            None => match self.plans.get(key) {
+                Some(entry) => {
+                    // Fast path: clone a cached singleton straight out of the cache.
+                    if let Some(cloner) = Self::cloner(&entry.registration)
+                        && let Some(cached) = entry.cached()
+                        && let Some(value) = cloner.get::<T>(&*cached)
+                    {
+                        return Ok(value);
+                    }
+
+                    self.resolve_entry(key, entry, scope, None)?
+                }
+                None => self.resolve_unregistered(key, scope, None)?,
+            },
+        };
+
+        if resolved.is::<SingletonRef<T>>() {
+            return Err(MakhzanError::construction_failed(
+                key.clone(),
+                format!(
+                    "{} is registered with singleton_ref_with and cannot be resolved by value; \
+                     use Container::with_singleton to borrow it",
+                    type_name::<T>()
+                ),
+            ));
+        }
+
+        resolved.downcast::<T>().map_err(|resolved| MakhzanError::TypeMismatch {
+            key: key.clone(),
+            expected: type_name::<T>(),
+            actual: resolved.type_name(),
+        })
+    }
+
+    /// Resolve several dependencies at once.
+    ///
+    /// ```rust
+    /// # use makhzan_container::prelude::*;
+    /// # use std::sync::Arc;
+    /// # trait Logger: Send + Sync {}
+    /// # struct ConsoleLogger;
+    /// # impl Logger for ConsoleLogger {}
+    /// # struct Database;
+    /// # #[derive(Clone)]
+    /// # struct Config;
+    /// # let container = Container::builder()
+    /// #     .singleton_value(Arc::new(Database))
+    /// #     .singleton_value(Arc::new(ConsoleLogger) as Arc<dyn Logger>)
+    /// #     .singleton_value(Config)
+    /// #     .build()?;
+    /// let (db, logger, cfg): (Arc<Database>, Arc<dyn Logger>, Config) =
+    ///     container.resolve_tuple()?;
+    /// # Ok::<(), MakhzanError>(())
+    /// ```
+    ///
+    /// # Errors
+    /// [`MakhzanError::TupleElementFailed`] for the first element that
+    /// can't be resolved.
+    pub fn resolve_tuple<T: ResolveTuple>(&self) -> Result<T> {
+        T::resolve_tuple(&ContainerResolver { container: self, scope: None, trace: None })
+    }
+
+    /// Resolve `Arc<T>` if it is registered, otherwise `T` wrapped in a
+    /// new `Arc`.
+    ///
+    /// For a consumer wanting shared ownership of a value registered
+    /// plain. A singleton `Arc<T>` gives the same `Arc` on every call; a
+    /// wrapped `T` gives a new one each time.
+    pub fn resolve_arc<T: Send + Sync + 'static>(&self) -> Result<Arc<T>> {
+        let resolver: &dyn Resolver = &ContainerResolver { container: self, scope: None, trace: None };
+        resolver.resolve_arc()
+    }
+
+    /// Resolve every element added with [`ContainerBuilder::add_element`],
+    /// in the order they were added; empty if there are none.
+    pub fn resolve_all<T: Send + Sync + 'static>(&self) -> Result<Vec<T>> {
+        let resolver: &dyn Resolver = &ContainerResolver { container: self, scope: None, trace: None };
+        resolver.resolve_all()
+    }
+
+    /// Resolve the elements added with [`ContainerBuilder::add_element`]
+    /// one at a time, in the order they were added.
+    ///
+    /// Each element's factory runs when the iterator reaches it, so
+    /// stopping early skips the rest; empty if there are none.
+    ///
+    /// ```rust
+    /// # use makhzan_container::prelude::*;
+    /// # use std::sync::Arc;
+    /// # trait Backend: Send + Sync { fn is_healthy(&self) -> bool; }
+    /// # struct Node(bool);
+    /// # impl Backend for Node { fn is_healthy(&self) -> bool { self.0 } }
+    /// # let container = Container::builder()
+    /// #     .add_element(Scope::Singleton, vec![], |_| Ok(Arc::new(Node(false)) as Arc<dyn Backend>))
+    /// #     .add_element(Scope::Singleton, vec![], |_| Ok(Arc::new(Node(true)) as Arc<dyn Backend>))
+    /// #     .build()?;
+    /// let healthy = container
+    ///     .resolve_iter::<Arc<dyn Backend>>()
+    ///     .find(|backend| backend.as_ref().is_ok_and(|backend| backend.is_healthy()))
+    ///     .transpose()?;
+    /// # assert!(healthy.is_some());
+    /// # Ok::<(), MakhzanError>(())
+    /// ```
+    pub fn resolve_iter<T: Send + Sync + 'static>(&self) -> impl Iterator<Item = Result<T>> + '_ {
+        self.elements::<T>().iter().map(|element| self.resolve_keyed_from::<T>(element.clone(), None))
+    }
+
+    /// Resolve every entry added with [`ContainerBuilder::add_keyed`], by
+    /// name; empty if there are none.
+    pub fn resolve_map<T: Send + Sync + 'static>(&self) -> Result<HashMap<&'static str, T>> {
+        let resolver: &dyn Resolver = &ContainerResolver { container: self, scope: None, trace: None };
+        resolver.resolve_map()
+    }
+
+    /// Resolve the entry added with [`ContainerBuilder::add_keyed`] under
+    /// `name`.
+    ///
+    /// # Errors
+    /// [`MakhzanError::UnknownMapKey`] with similar names if the map of
+    /// `T`s has no entry `name`.
+    pub fn resolve_keyed<T: Send + Sync + 'static>(&self, name: &str) -> Result<T> {
+        self.resolve_keyed_from::<T>(self.map_entry::<T>(name)?, None)
+    }
+
+    /// Call `f` with each of its parameters resolved by type.
+    ///
+    /// Works with closures and functions of up to eight parameters.
+    ///
+    /// ```rust
+    /// # use makhzan_container::prelude::*;
+    /// # use std::sync::Arc;
+    /// # struct Database;
+    /// # #[derive(Clone)]
+    /// # struct Config;
+    /// # fn build_report(_: &Database, _: &Config) -> String { String::from("ok") }
+    /// # let container = Container::builder().singleton_value(Arc::new(Database)).singleton_value(Config).build()?;
+    /// let report = container.invoke(|db: Arc<Database>, cfg: Config| build_report(&db, &cfg))?;
+    /// # Ok::<(), MakhzanError>(())
+    /// ```
+    ///
+    /// # Errors
+    /// [`MakhzanError::TupleElementFailed`] for the first parameter that
+    /// can't be resolved; `f` is not called.
+    pub fn invoke<F: Invoke<Args, R>, Args, R>(&self, f: F) -> Result<R> {
+        f.invoke(&ContainerResolver { container: self, scope: None, trace: None })
+    }
+
+    /// Resolve a small `Copy` value without boxing it.
+    ///
+    /// Registrations made with
+    /// [`ContainerBuilder::transient_copy_with`] are built straight into a
+    /// stack slot; everything else falls back to [`resolve`](Self::resolve).
+    pub fn resolve_copy<T: Copy + Send + Sync + 'static>(&self) -> Result<T> {
+        self.resolve_copy_from::<T>(None)
+    }
+
+    /// Unboxed resolve shared by [`Container`] and [`ScopedContainer`].
+    fn resolve_copy_from<T: Copy + Send + Sync + 'static>(&self, scope: Option<&ScopeCache<'_>>) -> Result<T> {
+        let key = DependencyKey::of::<T>();
+        if scope.is_some_and(|scope| scope.contains(&key)) {
+            return self.resolve_from::<T>(scope);
+        }
+        let Some(entry) = self.plans.get(&key) else {
+            return self.resolve_from::<T>(scope);
+        };
+        let Some(inline) = &entry.registration.inline else {
+            return self.resolve_from::<T>(scope);
+        };
+
+        self.observed(&key, || {
+            let _guard = self.enter(&key, &entry.registration, scope)?;
+            let mut slot: Option<T> = None;
+            let resolver = ContainerResolver { container: self, scope, trace: None };
+            self.call_factory(&key, || inline(&resolver, &mut slot))?;
+            slot.ok_or_else(|| MakhzanError::TypeMismatch { key: key.clone(), expected: type_name::<T>(), actual: None })
+        })
+    }
+
+    /// Resolve a runtime `key` to its type-erased value.
+    ///
+    /// For plugin hosts and generated code that hold a [`DependencyKey`],
+    /// e.g. read from a manifest, rather than a static `T`. The caller
+    /// downcasts the value, which is of the key's type:
+    ///
+    /// ```rust
+    /// use makhzan_container::prelude::*;
+    ///
+    /// let container = Container::builder().singleton_value(8080u16).build().unwrap();
+    /// let key = DependencyKey::of::<u16>();
+    ///
+    /// let port = container.resolve_boxed(&key).unwrap();
+    /// assert_eq!(*port.downcast::<u16>().unwrap(), 8080);
+    /// ```
+    ///
+    /// A cached singleton is cloned into a fresh `Box`; prefer
+    /// [`resolve`](Self::resolve) whenever `T` is known.
+    pub fn resolve_boxed(&self, key: &DependencyKey) -> Result<Box<dyn Any + Send + Sync>> {
+        self.resolve_internal(key, None, None).map(Resolved::into_box)
+    }
+
+    /// Resolve the dependency registered under a dynamic `name`.
+    ///
+    /// # Errors
+    /// [`MakhzanError::UnknownDynamicName`] with similar names if `name`
+    /// was never passed to [`ContainerBuilder::register_dynamic`].
+    pub fn resolve_dynamic(&self, name: &str) -> Result<Box<dyn Any + Send + Sync>> {
+        self.resolve_boxed(self.dynamic_key(name)?)
+    }
+
+    /// Typed [`resolve_dynamic`](Self::resolve_dynamic).
+    ///
+    /// Fails with [`MakhzanError::TypeMismatch`] if the value registered
+    /// under `name` is not a `T`.
+    pub fn resolve_dynamic_as<T: Send + Sync + 'static>(&self, name: &str) -> Result<T> {
+        let key = self.dynamic_key(name)?;
+        self.resolve_internal(key, None, None)?.downcast::<T>().map_err(|_| MakhzanError::TypeMismatch {
+            key: key.clone(),
+            expected: type_name::<T>(),
+            actual: Some(key.type_name()),
+        })
+    }
+
+    /// The element keys of the collection of `T`s, in order.
+    fn elements<T: 'static>(&self) -> &[DependencyKey] {
+        self.registry
+            .all_registrations()
+            .get(&DependencyKey::of::<Vec<T>>())
+            .map_or(&[], |collection| &collection.dependencies)
+    }
+
+    /// The key of entry `name` of the keyed map of `T`s.
+    fn map_entry<T: 'static>(&self, name: &str) -> Result<DependencyKey> {
+        let map = DependencyKey::of::<HashMap<&'static str, T>>();
+        let entries = self.registry.all_registrations().get(&map).map_or(&[][..], |r| &r.dependencies[..]);
+        if let Some(entry) = entries.iter().find(|entry| entry.name() == Some(name)) {
+            return Ok(entry.clone());
+        }
+        let mut available: Vec<&str> = entries.iter().filter_map(DependencyKey::name).collect();
+        available.sort_unstable();
+        Err(MakhzanError::UnknownMapKey(Box::new(UnknownMapKeyError {
+            key: DependencyKey::of::<T>(),
+            name: name.to_string(),
+            suggestions: self
+                .suggestions
+                .pick(name, available.iter().copied(), |name| name)
+                .into_iter()
+                .map(str::to_string)
+                .collect(),
+            available: available.into_iter().map(str::to_string).collect(),
+        })))
+    }
+
+    fn dynamic_key(&self, name: &str) -> Result<&DependencyKey> {
+        self.dynamic.get(name).ok_or_else(|| {
+            let mut available: Vec<&str> = self.dynamic.keys().map(String::as_str).collect();
+            available.sort_unstable();
+            MakhzanError::UnknownDynamicName(UnknownDynamicNameError {
+                name: name.to_string(),
+                suggestions: self
+                    .suggestions
+                    .pick(name, available.iter().copied(), |name| name)
+                    .into_iter()
+                    .map(str::to_string)
+                    .collect(),
+                available: available.into_iter().map(str::to_string).collect(),
+            })
+        })
+    }
+
+    /// Borrow a dependency without cloning it.
+    ///
+    /// Intended for singletons registered with
+    /// [`ContainerBuilder::singleton_ref_with`]: `f` receives a reference to
+    /// the cached instance. Other registrations are constructed as usual,
+    /// borrowed for the duration of `f`, then dropped — so a transient is
+    /// built fresh on every call.
+    ///
+    /// ```rust
+    /// # use makhzan_container::prelude::*;
+    /// # struct Pool;
+    /// # impl Pool { fn query(&self, sql: &str) -> Vec<String> { vec![sql.to_string()] } }
+    /// # let container = Container::builder().singleton_ref_with(|_| Ok(Pool)).build()?;
+    /// let rows = container.with_singleton(|pool: &Pool| pool.query("SELECT 1"))?;
+    /// # Ok::<(), MakhzanError>(())
+    /// ```
+    pub fn with_singleton<T: Send + Sync + 'static, R>(
+        &self,
+        f: impl FnOnce(&T) -> R,
+    ) -> Result<R> {
+        let key = DependencyKey::of::<T>();
+        trace!(key = %key, "Borrowing");
+
+        let resolved = match self.resolve_internal(&key, None, None)?.downcast::<SingletonRef<T>>() {
+            Ok(shared) => return Ok(f(shared.get())),
+            Err(resolved) => resolved,
+        };
+
+        resolved.downcast::<T>().map(|value| f(&value)).map_err(|resolved| MakhzanError::TypeMismatch {
+            key,
+            expected: type_name::<T>(),
+            actual: resolved.type_name(),
+        })
+    }
+
+    /// Lock a singleton registered with
+    /// [`ContainerBuilder::singleton_mutable`] and call `f` with it.
+    ///
+    /// Returns [`MakhzanError::LockPoisoned`] instead of panicking if an
+    /// earlier `f` panicked while holding the lock.
+    pub fn with_mut<T: Send + 'static, R>(&self, f: impl FnOnce(&mut T) -> R) -> Result<R> {
+        let shared: Arc<Mutex<T>> = self.resolve()?;
+        let mut guard = shared.lock().map_err(|_| MakhzanError::LockPoisoned {
+            key: DependencyKey::of::<Arc<Mutex<T>>>(),
+        })?;
+        Ok(f(&mut guard))
+    }
+
+    /// Describe everything the container knows about `key`.
+    ///
+    /// Returns `None` if the key is neither registered nor an alias.
+    ///
+    /// ```rust
+    /// # use makhzan_container::prelude::*;
+    /// # use std::sync::Arc;
+    /// # struct Database;
+    /// # let container = Container::builder().singleton_arc(|_| Ok(Database)).build()?;
+    /// if let Some(description) = container.describe(&DependencyKey::of::<Arc<Database>>()) {
+    ///     eprintln!("{description}");
+    /// }
+    /// # Ok::<(), MakhzanError>(())
+    /// ```
+    pub fn describe(&self, key: &DependencyKey) -> Option<KeyDescription> {
+        let entry = self.plans.get(key)?;
+        let registration = &entry.registration;
+
+        let instantiated = Self::instantiated(entry);
+
+        Some(KeyDescription {
+            key: key.clone(),
+            alias_for: self.registry.alias_target(key).cloned(),
+            scope: registration.scope,
+            dependencies: registration.dependencies.clone(),
+            instantiated,
+        })
+    }
+
+    /// Renders the declared dependency graph as an indented tree.
+    ///
+    /// Roots are the registrations nothing depends on; each node shows its
+    /// scope, and aliases show the key they point at. A subtree reached a
+    /// second time (a diamond) is marked `(see above)` instead of being
+    /// repeated, and an edge back into the current path is marked
+    /// `⟲ cycle`. Only declared dependencies appear, so factories
+    /// registered without them show up as leaves.
+    ///
+    /// ```rust
+    /// # use makhzan_container::prelude::*;
+    /// # use std::sync::Arc;
+    /// # trait Logger: Send + Sync {}
+    /// # makhzan_container::upcast!(Logger);
+    /// # struct ConsoleLogger;
+    /// # impl Logger for ConsoleLogger {}
+    /// # struct Database;
+    /// # #[derive(Clone)]
+    /// # struct UserRepository;
+    /// # #[derive(Clone)]
+    /// # struct UserService;
+    /// # let logger = DependencyKey::of::<Arc<dyn Logger>>();
+    /// # let database = DependencyKey::of::<Arc<Database>>();
+    /// # let repository = DependencyKey::of::<UserRepository>();
+    /// let container = Container::builder()
+    ///     .singleton_arc(|_| Ok(Database))
+    ///     .bind_shared::<dyn Logger, ConsoleLogger>(|_| Ok(ConsoleLogger))
+    ///     .register_factory(Scope::Transient, vec![database], |_| Ok(UserRepository))
+    ///     .register_factory(Scope::Transient, vec![logger, repository], |_| Ok(UserService))
+    ///     .build()?;
+    ///
+    /// println!("{}", container.tree());
+    /// // [Transient] UserService
+    /// // ├── [Singleton] Arc<dyn Logger>
+    /// // │   └── [Singleton] Arc<ConsoleLogger>
+    /// // └── [Transient] UserRepository
+    /// //     └── [Singleton] Arc<Database>
+    /// # Ok::<(), MakhzanError>(())
+    /// ```
+    pub fn tree(&self) -> String {
+        let forest = tree::forest(&self.registry.dependency_infos(), self.registry.all_aliases());
+        render_tree(&forest)
+    }
+
+    /// Renders every registration grouped by scope, for debugging.
+    ///
+    /// Shows short type names with binding names and tags, each
+    /// registration's declared dependencies, which singletons are already
+    /// initialized, and the aliases with their targets. Entries are sorted
+    /// by name, so the output can be snapshot-tested. `{container:#?}`
+    /// prints the same view.
+    ///
+    /// ```rust
+    /// # use makhzan_container::prelude::*;
+    /// # use std::sync::Arc;
+    /// # trait Logger: Send + Sync {}
+    /// # makhzan_container::upcast!(Logger);
+    /// # struct ConsoleLogger;
+    /// # impl Logger for ConsoleLogger {}
+    /// # struct Database;
+    /// # #[derive(Clone)]
+    /// # struct UserRepository;
+    /// # #[derive(Clone)]
+    /// # struct UserService;
+    /// # let logger = DependencyKey::of::<Arc<dyn Logger>>();
+    /// # let database = DependencyKey::of::<Arc<Database>>();
+    /// # let repository = DependencyKey::of::<UserRepository>();
+    /// let container = Container::builder()
+    ///     .singleton_arc(|_| Ok(Database))
+    ///     .bind_shared::<dyn Logger, ConsoleLogger>(|_| Ok(ConsoleLogger))
+    ///     .register_factory(Scope::Transient, vec![database], |_| Ok(UserRepository))
+    ///     .register_factory(Scope::Transient, vec![logger, repository], |_| Ok(UserService))
+    ///     .build()?;
+    ///
+    /// println!("{}", container.debug_tree());
+    /// // Singleton (3)
+    /// // ├── Arc<ConsoleLogger>
+    /// // ├── Arc<Database>
+    /// // └── Arc<dyn Logger>
+    /// //     └── Arc<ConsoleLogger>
+    /// // Transient (2)
+    /// // ├── UserRepository
+    /// // │   └── Arc<Database>
+    /// // └── UserService
+    /// //     ├── Arc<dyn Logger>
+    /// //     └── UserRepository
+    /// # Ok::<(), MakhzanError>(())
+    /// ```
+    pub fn debug_tree(&self) -> String {
+        let groups = tree::by_scope(
+            &self.registry.dependency_infos(),
+            self.registry.all_aliases(),
+            |key| self.is_instantiated(key),
+        );
+        render_tree(&groups)
+    }
+
+    /// Every registration and alias with its scope, and the dependency
+    /// and alias edges between them. See [`export`](crate::export) for
+    /// the layout.
+    pub fn dependency_graph(&self) -> DependencyGraph {
+        DependencyGraph::new(
+            self.registry.ordered_keys(),
+            &self.registry.dependency_infos(),
+            self.registry.all_aliases(),
+            |key| self.is_instantiated(key),
+        )
+    }
+
+    /// The [`dependency_graph`](Self::dependency_graph) in Graphviz DOT.
+    ///
+    /// ```rust,no_run
+    /// # use makhzan_container::prelude::*;
+    /// # let container = Container::builder().singleton_value(1u8).build().unwrap();
+    /// std::fs::write("wiring.dot", container.to_dot())?; // dot -Tsvg wiring.dot
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    pub fn to_dot(&self) -> String {
+        self.dependency_graph().to_dot()
+    }
+
+    /// The [`dependency_graph`](Self::dependency_graph) as JSON, for
+    /// external viewers. The schema is documented in [`export`](crate::export).
+    #[cfg(feature = "serde")]
+    pub fn graph_json(&self) -> serde_json::Value {
+        serde_json::to_value(self.dependency_graph()).expect("the dependency graph serializes to JSON")
+    }
+
+    /// Whether the singleton registered under `key` has been constructed.
+    fn is_instantiated(&self, key: &DependencyKey) -> bool {
+        self.plans
+            .get(key)
+            .is_some_and(|entry| Self::instantiated(entry).unwrap_or_else(|| entry.is_instantiated()))
+    }
+
+    /// Whether a singleton has a cached instance; `None` for other scopes
+    /// and for singletons that can't tell.
+    fn instantiated(entry: &PlanEntry) -> Option<bool> {
+        let registration = &entry.registration;
+        match registration.scope {
+            Scope::Singleton => match &registration.instantiated {
+                Some(probe) => Some(probe() || entry.is_instantiated()),
+                None => registration.cloner.map(|_| entry.is_instantiated()),
+            },
+            _ => None,
+        }
+    }
+
+    /// Registered keys in the order they were first registered, aliases
+    /// excluded.
+    ///
+    /// Stable across runs, unlike iteration over the hash maps inside;
+    /// overriding a registration keeps its original position, and
+    /// [`register_default_impl`](ContainerBuilder::register_default_impl)
+    /// fallbacks come last.
+    pub fn registration_order(&self) -> Vec<DependencyKey> {
+        self.registry.ordered_keys().to_vec()
+    }
+
+    /// How many registrations each scope has, for diagnostics and metrics.
+    ///
+    /// Aliases are not counted: they add a key but no registration, so an
+    /// alias doesn't inflate its target's scope. Scopes without
+    /// registrations are absent from the map.
+    pub fn count_by_scope(&self) -> HashMap<Scope, usize> {
+        let mut counts = HashMap::new();
+        for registration in self.registry.all_registrations().values() {
+            *counts.entry(registration.scope).or_insert(0) += 1;
+        }
+        counts
+    }
+
+    /// The registered keys with the given scope, in
+    /// [registration order](Self::registration_order). Like
+    /// [`count_by_scope`](Self::count_by_scope), aliases are excluded.
+    pub fn registrations_with_scope(&self, scope: Scope) -> Vec<DependencyKey> {
+        let registrations = self.registry.all_registrations();
+        self.registry
+            .ordered_keys()
+            .iter()
+            .filter(|key| registrations.get(*key).is_some_and(|registration| registration.scope == scope))
+            .cloned()
+            .collect()
+    }
+
+    /// A serializable snapshot of the wiring, for checking it against an
+    /// approved copy; see [`manifest`](crate::manifest).
+    #[cfg(feature = "serde")]
+    pub fn manifest(&self) -> crate::manifest::ContainerManifest {
+        use crate::manifest::{ContainerManifest, ManifestEntry};
+
+        let registrations = self.registry.all_registrations().values().map(|registration| ManifestEntry {
+            scope: Some(registration.scope),
+            dependencies: registration.dependencies.iter().map(DependencyKey::to_string).collect(),
+            provider: registration.provider.as_deref().map(str::to_string),
+            ..ManifestEntry::new(&registration.key)
+        });
+        let aliases = self.registry.all_aliases().iter().map(|(from, to)| ManifestEntry {
+            alias_for: Some(to.to_string()),
+            ..ManifestEntry::new(from)
+        });
+        ContainerManifest::new(registrations.chain(aliases).collect())
+    }
+
+    /// Non-fatal problems found by [`ContainerBuilder::build`].
+    ///
+    /// Currently these are aliases that likely bind the wrong thing: their
+    /// target was re-registered after the alias was recorded, was never
+    /// registered, or the alias hides a registration with the same key.
+    /// Each one is also logged as a warning during the build.
+    pub fn warnings(&self) -> Vec<String> {
+        self.warnings.to_vec()
+    }
+
+    /// Registration counts, the longest dependency chain and fan-out of
+    /// this container's graph, as logged by [`ContainerBuilder::build`].
+    pub fn graph_stats(&self) -> &GraphStats {
+        &self.stats
+    }
+
+    /// What validating the graph found during [`ContainerBuilder::build`]:
+    /// how many keys were checked, warnings such as unused registrations
+    /// or deep chains, and the keys in dependency order.
+    ///
+    /// Empty after [`build_unchecked`](ContainerBuilder::build_unchecked).
+    ///
+    /// ```rust
+    /// # use makhzan_container::prelude::*;
+    /// # let container = Container::builder().build()?;
+    /// for warning in &container.validation_report().warnings {
+    ///     eprintln!("warning: {warning}");
+    /// }
+    /// # Ok::<(), MakhzanError>(())
+    /// ```
+    pub fn validation_report(&self) -> &ValidationReport {
+        &self.report
+    }
+
+    /// Check that `T` could be resolved, without constructing anything.
+    ///
+    /// Walks the declared dependencies of `T` the way
+    /// [`ContainerBuilder::build`] validates the whole graph: every key
+    /// reached must be registered, acyclic and scope-compatible. No
+    /// factory runs, so it is safe for services whose constructors open
+    /// sockets or files. Only declared dependencies are followed.
+    ///
+    /// ```rust
+    /// # use makhzan_container::prelude::*;
+    /// # #[derive(Clone)]
+    /// # struct UserService;
+    /// # fn app_container() -> Container { Container::builder().singleton_value(UserService).build().unwrap() }
+    /// app_container().assert_resolvable::<UserService>().unwrap();
+    /// ```
+    ///
+    /// # Errors
+    /// The errors `build` reports: [`MakhzanError::NotRegistered`],
+    /// [`MakhzanError::CircularDependency`],
+    /// [`MakhzanError::ScopeMismatch`] or
+    /// [`MakhzanError::AliasScopeMismatch`].
+    pub fn assert_resolvable<T: ?Sized + 'static>(&self) -> Result<()> {
+        GraphValidator::new(self.registry.dependency_infos())
+            .with_aliases(self.registry.all_aliases())
+            .with_suggestions(self.suggestions)
+            .with_scope_validation(self.scope_validation)
+            .validate_from(&DependencyKey::of::<T>())
+    }
+
+    /// Returns the compiled resolution plan for `T`, for inspection.
+    ///
+    /// Shows which registration serves `T` and the alias hops taken to
+    /// reach it. Returns `None` if `T` cannot be resolved.
+    pub fn plan_for<T: ?Sized + 'static>(&self) -> Option<ResolutionPlan> {
+        self.plans.describe(&DependencyKey::of::<T>())
+    }
+
+    /// Construct the listed singletons now instead of on first resolve.
+    ///
+    /// Lets you choose when the expensive ones (connection pools, caches)
+    /// are built, e.g. on a background thread after startup. Keys already
+    /// constructed are skipped.
+    ///
+    /// # Errors
+    /// Stops at the first failing key: [`MakhzanError::NotSingleton`] if it
+    /// isn't a singleton, [`MakhzanError::WarmUpFailed`] naming the key if
+    /// construction fails, or [`MakhzanError::NotRegistered`].
+    ///
+    /// ```rust
+    /// # use makhzan_container::prelude::*;
+    /// # use std::sync::Arc;
+    /// # struct DbPool;
+    /// # struct Cache;
+    /// # let container = Container::builder().singleton_arc(|_| Ok(DbPool)).singleton_arc(|_| Ok(Cache)).build()?;
+    /// container.warm_up(&[
+    ///     DependencyKey::of::<Arc<DbPool>>(),
+    ///     DependencyKey::of::<Arc<Cache>>(),
+    /// ])?;
+    /// # Ok::<(), MakhzanError>(())
+    /// ```
+    pub fn warm_up(&self, keys: &[DependencyKey]) -> Result<()> {
+        for key in keys {
+            let entry = self.lookup(key)?;
+            let scope = entry.registration.scope;
+            if scope != Scope::Singleton {
+                return Err(MakhzanError::NotSingleton { key: key.clone(), scope });
+            }
+
+            debug!(key = %key, "Warming up");
+            self.resolve_entry(key, entry, None, None)
+                .map_err(|e| MakhzanError::WarmUpFailed {
+                    key: key.clone(),
+                    source: Box::new(e),
+                })?;
+        }
+        Ok(())
+    }
+
+    /// Run every [`with_initializer`](ContainerBuilder::with_initializer)
+    /// step, each after the marked keys it depends on.
+    ///
+    /// Independent services run in registration order. Together with
+    /// [`warm_up_all_singletons`](Self::warm_up_all_singletons) this gives
+    /// a deterministic startup sequence. Call it once: it initializes again
+    /// if called again.
+    ///
+    /// # Errors
+    /// Stops at the first failure. A failing `initialize` is reported as
+    /// [`MakhzanError::ConstructionFailed`] for the marked key; a failing
+    /// resolve is returned as is.
+    pub fn initialize_all(&self) -> Result<()> {
+        for (_, initialize) in self.initializers.iter() {
+            initialize(self)?;
+        }
+        Ok(())
+    }
+
+    /// [`warm_up`](Self::warm_up) every registered singleton, in key order.
+    pub fn warm_up_all_singletons(&self) -> Result<()> {
+        let mut keys: Vec<DependencyKey> = self
+            .registry
+            .all_registrations()
+            .values()
+            .filter(|reg| reg.scope == Scope::Singleton)
+            .map(|reg| reg.key.clone())
+            .collect();
+        keys.sort_by(|a, b| a.type_name().cmp(b.type_name()));
+
+        info!(count = keys.len(), "Warming up all singletons");
+        self.warm_up(&keys)
+    }
+
+    /// Build a new container from this one's registrations as changed by
+    /// `f`, keeping the singletons already constructed for registrations
+    /// `f` left alone.
+    ///
+    /// A registration is kept when `f` did not replace it and its scope is
+    /// unchanged; replaced ones start with an empty cache. Overriding is
+    /// allowed inside `f`. Kept singletons are not rebuilt even if they
+    /// depend on a replaced key, which is the point: a config reload must
+    /// not tear down the connection pool. Singletons not constructed yet
+    /// are constructed separately by each container. Factories that keep
+    /// their own instance, like [`singleton_ref_with`](ContainerBuilder::singleton_ref_with),
+    /// [`singleton_weak`](ContainerBuilder::singleton_weak) or
+    /// [`singleton_with_ttl`](ContainerBuilder::singleton_with_ttl), share
+    /// it between the two.
+    ///
+    /// The new container is validated the way this one was: by
+    /// [`build`](ContainerBuilder::build),
+    /// [`build_unchecked`](ContainerBuilder::build_unchecked), or
+    /// [`build_partial`](ContainerBuilder::build_partial) with the same roots.
+    ///
+    /// `f` gets the builder by reference; the builder's own methods take
+    /// it by value, so swap it out with [`std::mem::take`]:
+    ///
+    /// ```rust
+    /// # use std::sync::Arc;
+    /// # use makhzan_container::prelude::*;
+    /// # #[derive(Clone)]
+    /// # struct Config(u32);
+    /// # struct Database;
+    /// # fn load_config() -> Result<Config> { Ok(Config(2)) }
+    /// # fn main() -> Result<()> {
+    /// let container = Container::builder()
+    ///     .singleton_value(Config(1))
+    ///     .singleton_arc(|_| Ok(Database))
+    ///     .build()?;
+    /// let pool: Arc<Database> = container.resolve()?;
+    ///
+    /// let reloaded = container.rebuild_with(|b| {
+    ///     *b = std::mem::take(b).singleton_value(load_config()?);
+    ///     Ok(())
+    /// })?;
+    /// assert_eq!(reloaded.resolve::<Config>()?.0, 2);
+    /// assert!(Arc::ptr_eq(&pool, &reloaded.resolve()?)); // same pool as before
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    /// The error `f` returned, or whatever building this container
+    /// reported, for the new set of registrations.
+    pub fn rebuild_with(&self, f: impl FnOnce(&mut ContainerBuilder) -> Result<()>) -> Result<Container> {
+        let (source, validation) = &*self.source;
+        let mut builder = source.clone().allow_override(true);
+        f(&mut builder)?;
+        builder.allow_override = source.allow_override;
+        let rebuilt = builder.build_validating(validation.clone())?;
+
+        let fresh: HashMap<&DependencyKey, &PlanEntry> = rebuilt
+            .plans
+            .entries()
+            .map(|entry| (&entry.registration.key, entry))
+            .collect();
+        for old in self.plans.entries() {
+            let Some(value) = old.cached() else { continue };
+            let Some(new) = fresh.get(&old.registration.key) else { continue };
+            if new.registration.scope == old.registration.scope
+                && Arc::ptr_eq(&new.registration.factory, &old.registration.factory)
+            {
+                debug!(key = %old.registration.key, "Keeping singleton across rebuild");
+                new.instance.generation().get_or_init(|| Ok(value));
+            }
+        }
+        Ok(rebuilt)
+    }
+
+    /// Drop every cached singleton so the next resolve constructs it again.
+    ///
+    /// Meant for test harnesses that share one container but want each
+    /// test to start from fresh singleton state. Values handed out before
+    /// the reset — an `Arc` held by a caller, a singleton captured inside
+    /// another singleton — keep the old instance; only later resolves see
+    /// the new one. `singleton_value` registrations are unaffected.
+    ///
+    /// Clones of this container share its singletons and see the reset;
+    /// other containers built from the same builder keep theirs. A
+    /// construction still running when the reset happens hands its value
+    /// to its own caller only and doesn't repopulate the cache.
+    ///
+    /// ```rust
+    /// # use makhzan_container::prelude::*;
+    /// # use std::sync::LazyLock;
+    /// # static SHARED: LazyLock<Container> = LazyLock::new(|| Container::builder().build().unwrap());
+    /// fn setup() -> Container {
+    ///     let container = SHARED.clone();
+    ///     container.reset_singletons();
+    ///     container
+    /// }
+    /// # setup();
+    /// ```
+    pub fn reset_singletons(&self) {
+        debug!("Resetting all singletons");
+        for entry in self.plans.entries() {
+            Self::reset_entry(entry);
+        }
+    }
+
+    /// [`reset_singletons`](Self::reset_singletons) for `T` alone, e.g. to
+    /// rotate an API client after its credentials were refreshed.
+    ///
+    /// The next resolve of `T` runs its factory again, and dependents
+    /// built afterwards receive the new instance; values already handed
+    /// out are unaffected. Cached [`bind`](ContainerBuilder::bind) and
+    /// [`also_as`](ContainerBuilder::also_as) views of `T` are reset with
+    /// it. Returns whether an instance was cached.
+    ///
+    /// ```rust
+    /// # use makhzan_container::prelude::*;
+    /// # use std::sync::Arc;
+    /// # struct Credentials;
+    /// # impl Credentials { fn refresh(&self) -> Result<()> { Ok(()) } }
+    /// # struct ApiClient;
+    /// # let credentials = Credentials;
+    /// # let container = Container::builder().singleton_arc(|_| Ok(ApiClient)).build()?;
+    /// credentials.refresh()?;
+    /// container.reset_singleton::<Arc<ApiClient>>()?;
+    /// # Ok::<(), MakhzanError>(())
+    /// ```
+    ///
+    /// # Errors
+    /// [`MakhzanError::NotRegistered`] if `T` is not registered,
+    /// [`MakhzanError::NotSingleton`] if it is not a singleton.
+    pub fn reset_singleton<T: ?Sized + 'static>(&self) -> Result<bool> {
+        let key = DependencyKey::of::<T>();
+        let entry = self.lookup(&key)?;
+        let scope = entry.registration.scope;
+        if scope != Scope::Singleton {
+            return Err(MakhzanError::NotSingleton { key, scope });
+        }
+        debug!(key = %key, "Resetting singleton");
+        let existed = entry.registration.instantiated.as_ref().is_some_and(|probe| probe())
+            || entry.is_instantiated();
+        Self::reset_entry(entry);
+        for derived in self.plans.derived(entry) {
+            Self::reset_entry(derived);
+        }
+        Ok(existed)
+    }
+
+    fn reset_entry(entry: &PlanEntry) {
+        if entry.registration.scope != Scope::Singleton {
+            return;
+        }
+        if let Some(reset) = &entry.registration.reset {
+            reset();
+        }
+        entry.instance.reset();
+    }
+
+    /// How many `Arc<T>`s to a [`singleton_weak`](ContainerBuilder::singleton_weak)
+    /// instance are alive, or `None` if `Arc<T>` is not a weak singleton.
+    ///
+    /// The container's own reference is weak and not counted, so `0`
+    /// means the next resolve runs the factory again.
+    pub fn strong_count<T: ?Sized + 'static>(&self) -> Option<usize> {
+        self.weak_counts.get(&DependencyKey::of::<Arc<T>>()).map(|count| count())
+    }
+
+    /// Create a scoped child container.
+    pub fn create_scope(&self) -> ScopedContainer<'_> {
+        debug!("Creating new scope");
+        ScopedContainer { root: self, parent: None, cache: ScopeCache::default() }
+    }
+
+    /// Create a scope that owns a handle to this container.
+    ///
+    /// Unlike [`create_scope`](Self::create_scope) the result is `'static`
+    /// and cheap to clone (clones share the scope), so it can be stored in
+    /// request extensions or moved into tasks.
+    pub fn create_owned_scope(&self) -> OwnedScope {
+        debug!("Creating new owned scope");
+        OwnedScope { container: self.clone(), cache: Arc::default() }
+    }
+
+    /// Internal resolve — returns type-erased value.
+    ///
+    /// `scope` is the cache of the [`ScopedContainer`] or [`OwnedScope`]
+    /// the request originates in, `None` for the root container. Scoped
+    /// requests see seeded values, cache `Scoped` instances and are exempt
+    /// from the scoped-from-root check.
+    ///
+    /// Singletons that know how to clone their value (`singleton_value`,
+    /// `singleton_with`) are served from the fast cache as shared values;
+    /// everything else runs its factory and comes back boxed.
+    ///
+    /// `trace` is set only under [`resolve_traced`](Self::resolve_traced).
+    fn resolve_internal(
+        &self,
+        key: &DependencyKey,
+        scope: Option<&ScopeCache<'_>>,
+        trace: Option<&TraceRecorder>,
+    ) -> Result<Resolved> {
+        self.observed(key, || self.resolve_recorded(key, scope, trace))
+    }
+
+    /// Reports the outcome of `resolve`, the resolve of `key`, to every
+    /// [`on_resolve`](ContainerBuilder::on_resolve) observer.
+    ///
+    /// It counts as constructed when any factory was entered on the way:
+    /// a cached value is returned without running one.
+    fn observed<R>(&self, key: &DependencyKey, resolve: impl FnOnce() -> Result<R>) -> Result<R> {
+        if self.observers.is_empty() {
+            return resolve();
+        }
+        let entered = stack::entered();
+        let result = resolve();
+        let outcome = match &result {
+            Err(_) => ResolveOutcome::Failed,
+            Ok(_) if stack::entered() != entered => ResolveOutcome::Constructed,
+            Ok(_) => ResolveOutcome::CacheHit,
+        };
+        for observer in self.observers.iter() {
+            observer(key, outcome);
+        }
+        result
+    }
+
+    /// [`resolve_internal`](Self::resolve_internal) without the observers.
+    fn resolve_recorded(
+        &self,
+        key: &DependencyKey,
+        scope: Option<&ScopeCache<'_>>,
+        trace: Option<&TraceRecorder>,
+    ) -> Result<Resolved> {
+        if let Some(trace) = trace {
+            let (registered, cached) = match self.plans.get(key) {
+                Some(entry) => (entry.registration.scope, Self::instantiated(entry) == Some(true)),
+                None => (Scope::Scoped, false),
+            };
+            return trace.record(key, registered, cached, || self.resolve_step(key, scope, Some(trace)));
+        }
+        self.resolve_step(key, scope, None)
+    }
+
+    /// [`resolve_recorded`](Self::resolve_recorded) without the tracing.
+    fn resolve_step(
+        &self,
+        key: &DependencyKey,
+        scope: Option<&ScopeCache<'_>>,
+        trace: Option<&TraceRecorder>,
+    ) -> Result<Resolved> {
+        if let Some(resolved) = scope.and_then(|scope| scope.get(key)) {
+            return Ok(resolved);
+        }
+        match self.plans.get(key) {
+            Some(entry) => self.resolve_entry(key, entry, scope, trace),
+            None => self.resolve_unregistered(key, scope, trace),
+        }
+    }
+
+    /// Resolves a key missing from the plans through the first
+    /// [family](ContainerBuilder::register_family) covering it.
+    ///
+    /// # Errors
+    /// [`MakhzanError::NotRegistered`] if no family does.
+    fn resolve_unregistered(
+        &self,
+        key: &DependencyKey,
+        scope: Option<&ScopeCache<'_>>,
+        trace: Option<&TraceRecorder>,
+    ) -> Result<Resolved> {
+        match family_member(&self.families, key) {
+            Some(registration) => self.run_factory(key, &registration, scope, trace).map(Resolved::owned),
+            None => Err(self.not_registered(key)),
+        }
+    }
+
+    /// [`resolve_internal`](Self::resolve_internal) for an already
+    /// looked-up entry.
+    fn resolve_entry(
+        &self,
+        key: &DependencyKey,
+        entry: &PlanEntry,
+        scope: Option<&ScopeCache<'_>>,
+        trace: Option<&TraceRecorder>,
+    ) -> Result<Resolved> {
+        let registration = &entry.registration;
+
+        if registration.scope == Scope::Scoped
+            && let Some(scope) = scope
+            && let Some(cloner) = registration.cloner
+        {
+            if let Some(cached) = scope.get(&registration.key) {
+                return Ok(cached);
+            }
+            // Entered before waiting on the key's slot, so a factory that
+            // re-enters its own key fails as a cycle instead of blocking.
+            let _guard = self.enter(key, registration, Some(scope))?;
+            let resolver = ContainerResolver { container: self, scope: Some(scope), trace };
+            return scope.get_or_try_init(registration, cloner, || {
+                if let Some(trace) = trace {
+                    trace.constructed();
+                }
+                self.call_factory(&registration.key, || (registration.factory)(&resolver)).map(Arc::from)
+            });
+        }
+
+        let Some(cloner) = Self::cloner(registration) else {
+            return self.run_factory(key, registration, scope, trace).map(Resolved::owned);
+        };
+
+        if let Some(cached) = entry.cached() {
+            return Ok(Resolved::shared(cached, cloner));
+        }
+
+        // Entered before waiting on the instance cell, so a factory that
+        // re-enters its own key fails as a cycle instead of blocking. The
+        // generation is taken before the factory runs, so a value built
+        // across a reset is stored in the generation it was built for.
+        let _guard = self.enter(key, registration, scope)?;
+        let generation = entry.instance.generation();
+        let resolver = ContainerResolver { container: self, scope: None, trace };
+        let construct = || {
+            if let Some(trace) = trace {
+                trace.constructed();
+            }
+            (registration.factory)(&resolver).map(Arc::from)
+        };
+        let value = self.call_factory(&registration.key, || {
+            let outcome = match registration.failure_policy {
+                SingletonFailurePolicy::RetryOnNextResolve => generation.get_or_try_init(|| construct().map(Ok))?,
+                SingletonFailurePolicy::CacheError => {
+                    generation.get_or_init(|| construct().map_err(|e| CachedFailure(Arc::new(e))))
+                }
+            };
+            outcome.clone().map_err(|failure| MakhzanError::construction_failed(registration.key.clone(), failure))
+        })?;
+        Ok(Resolved::shared(value, cloner))
+    }
+
+    /// Returns the cloner of a registration eligible for the singleton cache.
+    fn cloner(registration: &Registration) -> Option<Cloner> {
+        match registration.scope {
+            Scope::Singleton => registration.cloner,
+            _ => None,
+        }
+    }
+
+    /// Finds the plan entry for `key`.
+    fn lookup(&self, key: &DependencyKey) -> Result<&PlanEntry> {
+        self.plans.get(key).ok_or_else(|| self.not_registered(key))
+    }
+
+    fn not_registered(&self, key: &DependencyKey) -> MakhzanError {
+        not_registered(&self.registry, self.suggestions, key)
+    }
+
+    /// Runs the factory of an already looked-up registration.
+    ///
+    /// A singleton's factory runs outside `scope`: its instance outlives
+    /// the scope and must not capture the scope's seeded or scoped values.
+    fn run_factory(
+        &self,
+        key: &DependencyKey,
+        registration: &Registration,
+        scope: Option<&ScopeCache<'_>>,
+        trace: Option<&TraceRecorder>,
+    ) -> Result<Box<dyn Any + Send + Sync>> {
+        let _guard = self.enter(key, registration, scope)?;
+        let scope = scope.filter(|_| registration.scope != Scope::Singleton);
+        if let Some(trace) = trace {
+            trace.constructed();
+        }
+        let resolver = ContainerResolver { container: self, scope, trace };
+        self.call_factory(&registration.key, || (registration.factory)(&resolver))
+    }
+
+    /// Runs the factory call `factory` for `key`, which must already be on
+    /// the resolution stack, inside a `resolve` span for `key`.
+    ///
+    /// A panic is reported as a construction failure of `key` if
+    /// [`catch_factory_panics`](ContainerBuilder::catch_factory_panics) is
+    /// on. The innermost [`MakhzanError::ConstructionFailed`] gets the
+    /// keys whose factories were resolving it as `required_by`.
+    fn call_factory<R>(&self, key: &DependencyKey, factory: impl FnOnce() -> Result<R>) -> Result<R> {
+        let _span = factory_span(key);
+        self.catch_factory_panic(key, factory).map_err(|error| match error {
+            MakhzanError::ConstructionFailed { key, source, required_by } if required_by.is_empty() => {
+                let mut required_by = crate::stack::current_path(Arc::as_ptr(&self.plans) as usize);
+                if required_by.last() == Some(&key) {
+                    required_by.pop();
+                }
+                MakhzanError::ConstructionFailed { key, source, required_by }
+            }
+            other => other,
+        })
+    }
+
+    fn catch_factory_panic<R>(&self, key: &DependencyKey, factory: impl FnOnce() -> Result<R>) -> Result<R> {
+        if !self.catch_panics {
+            return factory();
+        }
+        std::panic::catch_unwind(std::panic::AssertUnwindSafe(factory)).unwrap_or_else(|payload| {
+            let message = payload
+                .downcast_ref::<&str>()
+                .copied()
+                .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+                .unwrap_or("non-string panic payload");
+            Err(MakhzanError::construction_failed(key.clone(), format!("factory panicked: {message}")))
+        })
+    }
+
+    /// Checks that `registration` may run its factory now and records it
+    /// on the resolution stack until the returned guard drops.
+    fn enter(
+        &self,
+        key: &DependencyKey,
+        registration: &Registration,
+        scope: Option<&ScopeCache<'_>>,
+    ) -> Result<StackGuard> {
+        if scope.is_none() && registration.scope == Scope::Scoped {
+            match self.scoped_from_root {
+                ScopedFromRoot::Allow => {}
+                ScopedFromRoot::Warn => {
+                    warn!(key = %key, "Scoped dependency resolved from the root container");
+                }
+                ScopedFromRoot::Deny => {
+                    return Err(MakhzanError::ScopedFromRoot { key: key.clone() });
+                }
+            }
+        }
+
+        // Clones share `plans`, so they count as one container here. The
+        // registration's own key goes on the stack, so reaching it again
+        // through an alias is still a cycle.
+        StackGuard::enter(
+            Arc::as_ptr(&self.plans) as usize,
+            &registration.key,
+            registration.scope,
+            self.max_depth,
+        )
+    }
+}
+
+/// [`MakhzanError::NotRegistered`] for a lookup of `key` outside any
+/// factory, suggesting similar keys of `registry`.
+fn not_registered(registry: &Registry, suggestions: SuggestionConfig, key: &DependencyKey) -> MakhzanError {
+    let candidates = registry.registered_keys().into_iter().filter(|k| k != key);
+    MakhzanError::NotRegistered(Box::new(NotRegisteredError {
+        requested: key.clone(),
+        required_by: None,
+        suggestions: suggestions.pick(key.type_name(), candidates, |k| k.type_name()),
+    }))
+}
+
+impl fmt::Debug for Container {
+    /// `{:#?}` prints [`debug_tree`](Container::debug_tree).
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if f.alternate() {
+            return f.write_str(&self.debug_tree());
+        }
+        f.debug_struct("Container")
+            .field("registered", &self.registry.len())
+            .finish()
+    }
+}
+
+/// Runs a [`singleton_async`](ContainerBuilder::singleton_async) factory
+/// unless its value is already cached.
+#[cfg(feature = "async")]
+type AsyncInit = Arc<
+    dyn Fn(Container) -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<()>> + Send>>
+        + Send
+        + Sync,
+>;
+
+/// An [`on_resolve`](ContainerBuilder::on_resolve) callback.
+type Observer = Arc<dyn Fn(&DependencyKey, ResolveOutcome) + Send + Sync>;
+
+/// Counts the live `Arc`s of a [`singleton_weak`](ContainerBuilder::singleton_weak)
+/// instance.
+type StrongCount = Arc<dyn Fn() -> usize + Send + Sync>;
+
+/// Default for [`ContainerBuilder::max_resolve_depth`].
+pub const DEFAULT_MAX_RESOLVE_DEPTH: usize = 128;
+
+/// Builds an [`InstanceProbe`] reporting whether `cell` is initialised.
+fn instance_probe<T: Send + Sync + 'static>(cell: &Arc<ResetCell<T>>) -> InstanceProbe {
+    let cell = cell.clone();
+    Arc::new(move || cell.is_set())
+}
+
+/// Builds a [`SingletonReset`] that empties `cell`.
+fn reset_hook<T: Send + Sync + 'static>(cell: &Arc<ResetCell<T>>) -> SingletonReset {
+    let cell = cell.clone();
+    Arc::new(move || cell.reset())
+}
+
+/// Type-erased handle produced by [`ContainerBuilder::singleton_ref_with`].
+///
+/// Shares the factory's current `OnceCell` generation so the instance can
+/// be borrowed instead of cloned.
+struct SingletonRef<T>(Arc<OnceCell<T>>);
+
+impl<T> SingletonRef<T> {
+    fn get(&self) -> &T {
+        self.0.get().expect("singleton cell is initialised before the handle is created")
+    }
+}
+
+// ═══════════════════════════════════════════
+// ScopedContainer
+// ═══════════════════════════════════════════
+
+/// Values owned by one scope: `Scoped` instances built in it and values
+/// seeded with `insert`.
+///
+/// Singletons never live here. Whichever scope first resolves one, it is
+/// stored once in the container's plans, so the root and every scope see
+/// the same instance and it outlives the scope that built it.
+///
+/// Lookups fall back along `parent`, so a child scope sees what its
+/// ancestors hold; new instances are always stored in the innermost
+/// cache.
+///
+/// Each key gets its own slot, and the map lock is only held to find or
+/// add a slot. Threads sharing an [`OwnedScope`] that race on the same key
+/// wait for one factory run, while different keys, including those a
+/// factory resolves, build independently.
+#[derive(Default)]
+struct ScopeCache<'a> {
+    instances: RwLock<ScopeInstances>,
+    parent: Option<&'a ScopeCache<'a>>,
+}
+
+type ScopeInstances = HashMap<DependencyKey, Arc<ScopeSlot>, BuildKeyHasher>;
+
+type ScopeSlot = OnceCell<(Arc<dyn Any + Send + Sync>, Cloner)>;
+
+impl<'a> ScopeCache<'a> {
+    fn child(parent: &'a ScopeCache<'a>) -> Self {
+        Self { instances: RwLock::default(), parent: Some(parent) }
+    }
+
+    fn read(&self) -> RwLockReadGuard<'_, ScopeInstances> {
+        self.instances.read().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn write(&self) -> RwLockWriteGuard<'_, ScopeInstances> {
+        self.instances.write().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// The caches from this one up to the outermost.
+    fn chain(&self) -> impl Iterator<Item = &ScopeCache<'a>> {
+        std::iter::successors(Some(self), |cache| cache.parent)
+    }
+
+    fn contains(&self, key: &DependencyKey) -> bool {
+        self.chain()
+            .any(|cache| cache.read().get(key).is_some_and(|slot| slot.get().is_some()))
+    }
+
+    fn get(&self, key: &DependencyKey) -> Option<Resolved> {
+        self.chain().find_map(|cache| {
+            let slot = cache.read().get(key)?.clone();
+            slot.get().map(|(value, cloner)| Resolved::shared(value.clone(), *cloner))
+        })
+    }
+
+    /// Returns the value stored for the `Scoped` `registration` in this
+    /// cache, running `init` to create it if there is none yet.
+    ///
+    /// Concurrent callers for the same key block until the first `init`
+    /// finishes; if it fails, the next waiter runs its own.
+    fn get_or_try_init(
+        &self,
+        registration: &Registration,
+        cloner: Cloner,
+        init: impl FnOnce() -> Result<Arc<dyn Any + Send + Sync>>,
+    ) -> Result<Resolved> {
+        debug_assert_eq!(registration.scope, Scope::Scoped, "only Scoped instances are cached per scope");
+        let existing = self.read().get(&registration.key).cloned();
+        let slot = existing.unwrap_or_else(|| self.write().entry(registration.key.clone()).or_default().clone());
+        let (value, cloner) = slot.get_or_try_init(|| init().map(|value| (value, cloner)))?;
+        Ok(Resolved::shared(value.clone(), *cloner))
+    }
+
+    fn seed<T: Clone + Send + Sync + 'static>(&self, value: T) {
+        let slot = OnceCell::with_value((Arc::new(value) as Arc<dyn Any + Send + Sync>, Cloner::of::<T>()));
+        self.write().insert(DependencyKey::of::<T>(), Arc::new(slot));
+    }
+
+    /// Drops every stored value. The values are dropped after the lock is
+    /// released, so their `Drop` may use the scope again.
+    fn clear(&self) {
+        let instances = std::mem::take(&mut *self.write());
+        drop(instances);
+    }
+}
+
+/// Clears a scope's cache when dropped, including during unwinding.
+struct DisposeGuard<'a>(&'a ScopeCache<'static>);
+
+impl Drop for DisposeGuard<'_> {
+    fn drop(&mut self) {
+        self.0.clear();
+    }
+}
+
+/// A scoped child container.
+///
+/// `Scoped` registrations are built once per scope and cloned on later
+/// resolves. Singletons are shared with the root container, even when a
+/// scope is the first to resolve one: it is built once and never cached
+/// in the scope. Values known
+/// only at scope creation, such as a request id, can be seeded with
+/// [`insert`](Self::insert).
+///
+/// Scopes nest with [`create_child_scope`](Self::create_child_scope). A
+/// child sees the `Scoped` instances and seeded values of its ancestors
+/// and builds whatever they lack in its own cache, where its siblings
+/// and ancestors can't see it.
+pub struct ScopedContainer<'a> {
+    root: &'a Container,
+    parent: Option<&'a ScopedContainer<'a>>,
+    cache: ScopeCache<'a>,
+}
+
+impl ScopedContainer<'_> {
+    /// Create a scope nested in this one.
+    ///
+    /// ```rust
+    /// # use makhzan_container::prelude::*;
+    /// # use std::sync::Arc;
+    /// # #[derive(Clone)]
+    /// # struct RequestId(u64);
+    /// # impl RequestId { fn new() -> Self { RequestId(7) } }
+    /// # struct Operation;
+    /// # struct Handler;
+    /// # impl Handler { fn run(&self, _: Operation) -> Result<()> { Ok(()) } }
+    /// # let container = Container::builder().scoped_with(|_| Ok(Arc::new(Handler))).build()?;
+    /// # let batch = vec![Operation, Operation];
+    /// let request = container.create_scope();
+    /// request.insert(RequestId::new());
+    /// for operation in batch {
+    ///     let operation_scope = request.create_child_scope();
+    ///     // Sees the request's RequestId and Scoped instances,
+    ///     // but builds its own for anything the request hasn't.
+    ///     operation_scope.resolve::<Arc<Handler>>()?.run(operation)?;
+    /// }
+    /// # Ok::<(), MakhzanError>(())
+    /// ```
+    pub fn create_child_scope(&self) -> ScopedContainer<'_> {
+        ScopedContainer { root: self.root, parent: Some(self), cache: ScopeCache::child(&self.cache) }
+    }
+
+    /// The scope this one was created from, or `None` for a scope created
+    /// directly from the container.
+    pub fn parent(&self) -> Option<&ScopedContainer<'_>> {
+        self.parent
+    }
+
+    /// Resolve a dependency within this scope.
+    pub fn resolve<T: Send + Sync + 'static>(&self) -> Result<T> {
+        self.root.resolve_from::<T>(Some(&self.cache))
+    }
+
+    /// Seed `value` into this scope.
+    ///
+    /// Resolving `T` from the scope, including from factories running in
+    /// it, returns a clone of `value` instead of consulting the container.
+    ///
+    /// If `T` is registered as a singleton, the seed shadows it for this
+    /// scope only. Singleton factories run outside any scope, so they
+    /// never see seeded values: a singleton resolving `T` gets the
+    /// registered one, or [`MakhzanError::NotRegistered`] if there is none.
+    pub fn insert<T: Clone + Send + Sync + 'static>(&self, value: T) {
+        self.cache.seed(value);
+    }
+
+    /// Resolve a [named](DependencyKey::named) dependency within this scope.
+    pub fn resolve_named<T: Send + Sync + 'static>(&self, name: &str) -> Result<T> {
+        self.root
+            .resolve_keyed_from::<T>(DependencyKey::named_owned::<T>(name.to_string()), Some(&self.cache))
+    }
+
+    /// Resolve a [tagged](DependencyKey::tagged) dependency within this scope.
+    pub fn resolve_tagged<T: Send + Sync + 'static, Tag: ?Sized + 'static>(&self) -> Result<T> {
+        self.root.resolve_keyed_from::<T>(DependencyKey::tagged::<T, Tag>(), Some(&self.cache))
+    }
+
+    /// Resolve several dependencies within this scope.
+    ///
+    /// See [`Container::resolve_tuple`].
+    pub fn resolve_tuple<T: ResolveTuple>(&self) -> Result<T> {
+        T::resolve_tuple(&ContainerResolver { container: self.root, scope: Some(&self.cache), trace: None })
+    }
+
+    /// Resolve `Arc<T>`, or `T` wrapped in a new `Arc`, within this scope.
+    ///
+    /// See [`Container::resolve_arc`].
+    pub fn resolve_arc<T: Send + Sync + 'static>(&self) -> Result<Arc<T>> {
+        let resolver: &dyn Resolver = &ContainerResolver { container: self.root, scope: Some(&self.cache), trace: None };
+        resolver.resolve_arc()
+    }
+
+    /// Resolve every element of a collection within this scope.
+    ///
+    /// See [`Container::resolve_all`].
+    pub fn resolve_all<T: Send + Sync + 'static>(&self) -> Result<Vec<T>> {
+        let resolver: &dyn Resolver = &ContainerResolver { container: self.root, scope: Some(&self.cache), trace: None };
+        resolver.resolve_all()
+    }
+
+    /// Resolve the elements of a collection one at a time within this
+    /// scope.
+    ///
+    /// See [`Container::resolve_iter`].
+    pub fn resolve_iter<T: Send + Sync + 'static>(&self) -> impl Iterator<Item = Result<T>> + '_ {
+        self.root
+            .elements::<T>()
+            .iter()
+            .map(|element| self.root.resolve_keyed_from::<T>(element.clone(), Some(&self.cache)))
+    }
+
+    /// Resolve every entry of a keyed map within this scope.
+    ///
+    /// See [`Container::resolve_map`].
+    pub fn resolve_map<T: Send + Sync + 'static>(&self) -> Result<HashMap<&'static str, T>> {
+        let resolver: &dyn Resolver = &ContainerResolver { container: self.root, scope: Some(&self.cache), trace: None };
+        resolver.resolve_map()
+    }
+
+    /// Resolve one entry of a keyed map within this scope.
+    ///
+    /// See [`Container::resolve_keyed`].
+    pub fn resolve_keyed<T: Send + Sync + 'static>(&self, name: &str) -> Result<T> {
+        self.root.resolve_keyed_from::<T>(self.root.map_entry::<T>(name)?, Some(&self.cache))
+    }
+
+    /// Call `f` with its parameters resolved within this scope.
+    ///
+    /// See [`Container::invoke`].
+    pub fn invoke<F: Invoke<Args, R>, Args, R>(&self, f: F) -> Result<R> {
+        f.invoke(&ContainerResolver { container: self.root, scope: Some(&self.cache), trace: None })
+    }
+
+    /// Resolve a small `Copy` value within this scope without boxing it.
+    ///
+    /// See [`Container::resolve_copy`].
+    pub fn resolve_copy<T: Copy + Send + Sync + 'static>(&self) -> Result<T> {
+        self.root.resolve_copy_from::<T>(Some(&self.cache))
+    }
+
+    /// Resolve a runtime `key` within this scope to its type-erased value.
+    ///
+    /// See [`Container::resolve_boxed`].
+    pub fn resolve_boxed(&self, key: &DependencyKey) -> Result<Box<dyn Any + Send + Sync>> {
+        self.root.resolve_internal(key, Some(&self.cache), None).map(Resolved::into_box)
+    }
+}
+
+impl fmt::Debug for ScopedContainer<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ScopedContainer").finish()
+    }
+}
+
+/// A scope that owns a handle to its container.
+///
+/// Created by [`Container::create_owned_scope`]. Behaves like
+/// [`ScopedContainer`] but is `'static`, and clones share the same scope.
+///
+/// Besides per-request scopes it suits any unit of work, such as a queue
+/// job: create a scope per job, seed the job's context, resolve the
+/// handler and let [`run`](Self::run) dispose of the scope afterwards.
+///
+/// ```rust
+/// # use makhzan_container::prelude::*;
+/// # use std::sync::Arc;
+/// # #[derive(Clone)]
+/// # struct JobContext { id: u64, attempt: u32 }
+/// # struct Job { id: u64, attempt: u32 }
+/// # struct EmailHandler;
+/// # impl EmailHandler { fn handle(&self, _: &Job) -> Result<()> { Ok(()) } }
+/// # let container = Container::builder().scoped_with(|_| Ok(Arc::new(EmailHandler))).build()?;
+/// # let job = Job { id: 1, attempt: 1 };
+/// let scope = container.create_owned_scope();
+/// scope.insert(JobContext { id: job.id, attempt: job.attempt });
+/// scope.run(|scope| scope.resolve::<Arc<EmailHandler>>()?.handle(&job))?;
+/// # Ok::<(), MakhzanError>(())
+/// ```
+#[derive(Clone)]
+pub struct OwnedScope {
+    container: Container,
+    cache: Arc<ScopeCache<'static>>,
+}
+
+impl OwnedScope {
+    /// Create a scope nested in this one; see
+    /// [`ScopedContainer::create_child_scope`].
+    ///
+    /// The child borrows this scope, so it has no
+    /// [`parent`](ScopedContainer::parent) to return.
+    pub fn create_child_scope(&self) -> ScopedContainer<'_> {
+        ScopedContainer { root: &self.container, parent: None, cache: ScopeCache::child(&self.cache) }
+    }
+
+    /// Resolve a dependency within this scope.
+    pub fn resolve<T: Send + Sync + 'static>(&self) -> Result<T> {
+        self.container.resolve_from::<T>(Some(&self.cache))
+    }
+
+    /// Seed `value` into this scope; see [`ScopedContainer::insert`].
+    pub fn insert<T: Clone + Send + Sync + 'static>(&self, value: T) {
+        self.cache.seed(value);
+    }
+
+    /// Resolve a [named](DependencyKey::named) dependency within this scope.
+    pub fn resolve_named<T: Send + Sync + 'static>(&self, name: &str) -> Result<T> {
+        self.container
+            .resolve_keyed_from::<T>(DependencyKey::named_owned::<T>(name.to_string()), Some(&self.cache))
+    }
+
+    /// Resolve a [tagged](DependencyKey::tagged) dependency within this scope.
+    pub fn resolve_tagged<T: Send + Sync + 'static, Tag: ?Sized + 'static>(&self) -> Result<T> {
+        self.container.resolve_keyed_from::<T>(DependencyKey::tagged::<T, Tag>(), Some(&self.cache))
+    }
+
+    /// Resolve several dependencies within this scope.
+    pub fn resolve_tuple<T: ResolveTuple>(&self) -> Result<T> {
+        T::resolve_tuple(&ContainerResolver { container: &self.container, scope: Some(&self.cache), trace: None })
+    }
+
+    /// Resolve `Arc<T>`, or `T` wrapped in a new `Arc`, within this scope.
+    ///
+    /// See [`Container::resolve_arc`].
+    pub fn resolve_arc<T: Send + Sync + 'static>(&self) -> Result<Arc<T>> {
+        let resolver: &dyn Resolver =
+            &ContainerResolver { container: &self.container, scope: Some(&self.cache), trace: None };
+        resolver.resolve_arc()
+    }
+
+    /// Resolve every element of a collection within this scope.
+    ///
+    /// See [`Container::resolve_all`].
+    pub fn resolve_all<T: Send + Sync + 'static>(&self) -> Result<Vec<T>> {
+        let resolver: &dyn Resolver =
+            &ContainerResolver { container: &self.container, scope: Some(&self.cache), trace: None };
+        resolver.resolve_all()
+    }
+
+    /// Resolve the elements of a collection one at a time within this
+    /// scope.
+    ///
+    /// See [`Container::resolve_iter`].
+    pub fn resolve_iter<T: Send + Sync + 'static>(&self) -> impl Iterator<Item = Result<T>> + '_ {
+        self.container
+            .elements::<T>()
+            .iter()
+            .map(|element| self.container.resolve_keyed_from::<T>(element.clone(), Some(&self.cache)))
+    }
+
+    /// Resolve every entry of a keyed map within this scope.
+    ///
+    /// See [`Container::resolve_map`].
+    pub fn resolve_map<T: Send + Sync + 'static>(&self) -> Result<HashMap<&'static str, T>> {
+        let resolver: &dyn Resolver =
+            &ContainerResolver { container: &self.container, scope: Some(&self.cache), trace: None };
+        resolver.resolve_map()
+    }
+
+    /// Resolve one entry of a keyed map within this scope.
+    ///
+    /// See [`Container::resolve_keyed`].
+    pub fn resolve_keyed<T: Send + Sync + 'static>(&self, name: &str) -> Result<T> {
+        self.container.resolve_keyed_from::<T>(self.container.map_entry::<T>(name)?, Some(&self.cache))
+    }
+
+    /// Resolve a runtime `key` within this scope to its type-erased value.
+    ///
+    /// See [`Container::resolve_boxed`].
+    pub fn resolve_boxed(&self, key: &DependencyKey) -> Result<Box<dyn Any + Send + Sync>> {
+        self.container.resolve_internal(key, Some(&self.cache), None).map(Resolved::into_box)
+    }
+
+    /// Call `f` with its parameters resolved within this scope.
+    pub fn invoke<F: Invoke<Args, R>, Args, R>(&self, f: F) -> Result<R> {
+        f.invoke(&ContainerResolver { container: &self.container, scope: Some(&self.cache), trace: None })
+    }
+
+    /// Resolve a small `Copy` value within this scope without boxing it.
+    pub fn resolve_copy<T: Copy + Send + Sync + 'static>(&self) -> Result<T> {
+        self.container.resolve_copy_from::<T>(Some(&self.cache))
+    }
+
+    /// The container this scope resolves from.
+    pub fn container(&self) -> &Container {
+        &self.container
+    }
+
+    /// Call `f` with this scope, then dispose of it.
+    ///
+    /// Disposal happens whether `f` succeeds, fails or panics, and also
+    /// if clones of the scope are still alive: see
+    /// [`dispose`](Self::dispose).
+    pub fn run<R>(self, f: impl FnOnce(&OwnedScope) -> Result<R>) -> Result<R> {
+        let _dispose = DisposeGuard(&self.cache);
+        f(&self)
+    }
+
+    /// Drop every instance cached in this scope now.
+    ///
+    /// `Scoped` instances and seeded values are released even if clones
+    /// of this scope are still held elsewhere; resolving through such a
+    /// clone afterwards builds fresh instances.
+    pub fn dispose(self) {
+        self.cache.clear();
+    }
+}
+
+impl fmt::Debug for OwnedScope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OwnedScope").finish()
+    }
+}
+
+// ═══════════════════════════════════════════
+// ContainerResolver (internal bridge)
+// ═══════════════════════════════════════════
+
+/// Internal resolver passed to factory functions.
+struct ContainerResolver<'a> {
+    container: &'a Container,
+    scope: Option<&'a ScopeCache<'a>>,
+    trace: Option<&'a TraceRecorder>,
+}
+
+impl Resolver for ContainerResolver<'_> {
+    fn resolve_key(
+        &self,
+        key: &DependencyKey,
+    ) -> Result<Box<dyn Any + Send + Sync>> {
+        self.container.resolve_internal(key, self.scope, self.trace).map(Resolved::into_box)
+    }
+
+    fn resolve_erased(&self, key: &DependencyKey) -> Result<Resolved> {
+        self.container.resolve_internal(key, self.scope, self.trace)
+    }
+}
+
+/// A `'static`, cloneable [`Resolver`] holding an `Arc<Container>`.
+///
+/// Created by [`Container::resolver`]. This is the escape hatch for a
+/// service locator: a service that stores it can resolve anything later,
+/// which hides its real dependencies from validation and from readers.
+/// Prefer injecting dependencies directly (or a factory closure for lazy
+/// construction). Every handle also keeps the whole container, and with it
+/// every singleton, alive.
+///
+/// ```rust
+/// # use makhzan_container::prelude::*;
+/// # use std::sync::Arc;
+/// # trait Plugin: Send + Sync {}
+/// # struct Csv;
+/// # impl Plugin for Csv {}
+/// struct PluginHost { resolver: OwnedResolver }
+///
+/// impl PluginHost {
+///     fn load(&self, name: &str) -> Result<Box<dyn Plugin>> {
+///         self.resolver.resolve_dynamic_as(name)
+///     }
+/// }
+/// # let container = Arc::new(
+/// #     Container::builder()
+/// #         .transient_with::<Box<dyn Plugin>>(|_| Ok(Box::new(Csv)))
+/// #         .register_dynamic("csv", DependencyKey::of::<Box<dyn Plugin>>())
+/// #         .build()?,
+/// # );
+/// # let host = PluginHost { resolver: container.resolver() };
+/// # host.load("csv")?;
+/// # Ok::<(), MakhzanError>(())
+/// ```
+#[derive(Clone)]
+pub struct OwnedResolver {
+    container: Arc<Container>,
+}
+
+impl OwnedResolver {
+    /// Resolve a dependency by type; see [`Container::resolve`].
+    pub fn resolve<T: Send + Sync + 'static>(&self) -> Result<T> {
+        self.container.resolve()
+    }
+
+    /// Resolve a [named](DependencyKey::named) dependency.
+    pub fn resolve_named<T: Send + Sync + 'static>(&self, name: &str) -> Result<T> {
+        self.container.resolve_named(name)
+    }
+
+    /// Resolve by a name registered with
+    /// [`ContainerBuilder::register_dynamic`].
+    pub fn resolve_dynamic_as<T: Send + Sync + 'static>(&self, name: &str) -> Result<T> {
+        self.container.resolve_dynamic_as(name)
+    }
+
+    /// The container this handle keeps alive.
+    pub fn container(&self) -> &Arc<Container> {
+        &self.container
+    }
+}
+
+impl Resolver for OwnedResolver {
+    fn resolve_key(&self, key: &DependencyKey) -> Result<Box<dyn Any + Send + Sync>> {
+        self.container.resolve_internal(key, None, None).map(Resolved::into_box)
+    }
+
+    fn resolve_erased(&self, key: &DependencyKey) -> Result<Resolved> {
+        self.container.resolve_internal(key, None, None)
+    }
+}
+
+impl fmt::Debug for OwnedResolver {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("OwnedResolver").field(&self.container).finish()
+    }
+}
+
+// ═══════════════════════════════════════════
+// Free function for use inside factories
+// ═══════════════════════════════════════════
+
+/// Resolve a typed dependency from a [`Resolver`].
+///
+/// Use this inside factory closures:
+///
+/// ```rust
+/// # use makhzan_container::prelude::*;
+/// # use std::sync::Arc;
+/// # struct Database;
+/// # #[derive(Clone)]
+/// # struct MyService { db: Arc<Database> }
+/// # let builder = Container::builder().singleton_value(Arc::new(Database));
+/// let builder = builder.singleton_with::<MyService>(|r| {
+///     let db: Arc<Database> = makhzan_container::container::resolve(r)?;
+///     Ok(MyService { db })
+/// });
+/// # builder.build()?.resolve::<MyService>()?;
+/// # Ok::<(), MakhzanError>(())
+/// ```
+pub fn resolve<T: Send + Sync + 'static>(resolver: &dyn Resolver) -> Result<T> {
+    resolve_keyed(resolver, DependencyKey::of::<T>())
+}
+
+/// Resolve a [tagged](DependencyKey::tagged) dependency from a [`Resolver`].
+///
+/// ```rust
+/// # use makhzan_container::container::resolve_tagged;
+/// # use makhzan_container::prelude::*;
+/// # struct Primary;
+/// # struct Replica;
+/// # #[derive(Clone)]
+/// # struct Replicator;
+/// # impl Replicator { fn new(_: String, _: String) -> Self { Replicator } }
+/// # let builder = Container::builder()
+/// #     .singleton_tagged::<String, Primary>(|_| Ok("postgres://primary".into()))
+/// #     .singleton_tagged::<String, Replica>(|_| Ok("postgres://replica".into()));
+/// let builder = builder.singleton_with::<Replicator>(|r| {
+///     let primary = resolve_tagged::<String, Primary>(r)?;
+///     let replica = resolve_tagged::<String, Replica>(r)?;
+///     Ok(Replicator::new(primary, replica))
+/// });
+/// # builder.build()?.resolve::<Replicator>()?;
+/// # Ok::<(), MakhzanError>(())
+/// ```
+pub fn resolve_tagged<T: Send + Sync + 'static, Tag: ?Sized + 'static>(
+    resolver: &dyn Resolver,
+) -> Result<T> {
+    resolve_keyed(resolver, DependencyKey::tagged::<T, Tag>())
+}
+
+pub(crate) fn resolve_keyed<T: Send + Sync + 'static>(resolver: &dyn Resolver, key: DependencyKey) -> Result<T> {
+    let resolved = resolver.resolve_erased(&key)?;
+    resolved.downcast::<T>().map_err(|resolved| MakhzanError::TypeMismatch {
+        key,
+        expected: type_name::<T>(),
+        actual: resolved.type_name(),
+    })
+}
+
+// ═══════════════════════════════════════════
+// Prelude
+// ═══════════════════════════════════════════
+
+pub mod prelude {
+    pub use super::{resolve, Container, ContainerBuilder, OwnedResolver, OwnedScope, ScopedContainer};
+    pub use crate::error::{MakhzanError, Result};
+    pub use crate::inject::Injectable;
+    pub use crate::key::DependencyKey;
+    pub use crate::local::{LocalContainer, LocalContainerBuilder};
+    pub use crate::provider::Provider;
+    pub use crate::scope::{RefreshFailurePolicy, Scope, ScopeValidation, ScopedFromRoot, SingletonFailurePolicy};
+    pub use crate::trace::ResolveOutcome;
+    pub use crate::tuple::ResolverExt;
+}
+
+// ═══════════════════════════════════════════
+// Tests
+// ═══════════════════════════════════════════
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tuple::ResolverExt;
+
+    #[test]
+    fn resolve_singleton_value() {
+        let container = Container::builder()
+            .singleton_value(42i32)
+            .build()
+            .unwrap();
+
+        let value: i32 = container.resolve().unwrap();
+        assert_eq!(value, 42);
+
+        // Resolve again — same value
+        let value2: i32 = container.resolve().unwrap();
+        assert_eq!(value2, 42);
+    }
+
+    #[test]
+    fn resolve_singleton_string() {
+        let container = Container::builder()
+            .singleton_value(String::from("hello"))
+            .build()
+            .unwrap();
+
+        let s: String = container.resolve().unwrap();
+        assert_eq!(s, "hello");
+    }
+
+    #[test]
+    fn resolve_transient_creates_new_each_time() {
+        use std::sync::atomic::{AtomicU32, Ordering};
+
+        let counter = Arc::new(AtomicU32::new(0));
+
+        let container = Container::builder()
+            .transient_with::<u32>({
+                let counter = counter.clone();
+                move |_| {
+                    Ok(counter.fetch_add(1, Ordering::SeqCst))
+                }
+            })
+            .build()
+            .unwrap();
+
+        let a: u32 = container.resolve().unwrap();
+        let b: u32 = container.resolve().unwrap();
+        let c: u32 = container.resolve().unwrap();
+
+        assert_eq!(a, 0);
+        assert_eq!(b, 1);
+        assert_eq!(c, 2);
+    }
+
+    #[test]
+    fn singleton_factory_called_once() {
+        use std::sync::atomic::{AtomicU32, Ordering};
+
+        let counter = Arc::new(AtomicU32::new(0));
+
+        let container = Container::builder()
+            .singleton_with::<i32>({
+                let counter = counter.clone();
+                move |_| {
+                    counter.fetch_add(1, Ordering::SeqCst);
+                    Ok(42)
+                }
+            })
+            .build()
+            .unwrap();
+
+        let _a: i32 = container.resolve().unwrap();
+        let _b: i32 = container.resolve().unwrap();
+        let _c: i32 = container.resolve().unwrap();
+
+        // Factory called only once
+        assert_eq!(counter.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn resolve_with_dependency() {
+        let container = Container::builder()
+            .singleton_value(String::from("postgres://localhost"))
+            .transient_with::<Vec<u8>>(|r| {
+                let url: String = resolve(r)?;
+                Ok(url.into_bytes())
+            })
+            .build()
+            .unwrap();
+
+        let bytes: Vec<u8> = container.resolve().unwrap();
+        assert_eq!(bytes, b"postgres://localhost");
+    }
+
+    #[test]
+    fn resolve_not_registered() {
+        let container = Container::builder().build().unwrap();
+
+        let result = container.resolve::<i32>();
+        assert!(result.is_err());
+
+        match result.unwrap_err() {
+            MakhzanError::NotRegistered(e) => {
+                assert!(e.requested.type_name().contains("i32"));
+            }
+            other => panic!("Expected NotRegistered, got: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn scoped_container_resolves() {
+        let container = Container::builder()
+            .singleton_value(42i32)
+            .build()
+            .unwrap();
+
+        let scope = container.create_scope();
+        let value: i32 = scope.resolve().unwrap();
+        assert_eq!(value, 42);
+    }
+
+    #[test]
+    fn arc_singleton_pattern() {
+        // The idiomatic way: wrap services in Arc
+        #[derive(Clone)]
+        struct Database {
+            url: String,
+        }
+
+        struct UserService {
+            db: Arc<Database>,
+        }
+
+        let container = Container::builder()
+            .singleton_with::<Arc<Database>>(|_| {
+                Ok(Arc::new(Database {
+                    url: "postgres://localhost".into(),
+                }))
+            })
+            .transient_with::<UserService>(|r| {
+                let db: Arc<Database> = resolve(r)?;
+                Ok(UserService { db })
+            })
+            .build()
+            .unwrap();
+
+        let svc: UserService = container.resolve().unwrap();
+        assert_eq!(svc.db.url, "postgres://localhost");
+    }
+
+    #[test]
+    fn with_singleton_borrows_non_clone_value() {
+        use std::sync::atomic::{AtomicU32, Ordering};
+
+        struct Pool {
+            size: usize,
+        }
+
+        let counter = Arc::new(AtomicU32::new(0));
+        let container = Container::builder()
+            .singleton_ref_with::<Pool>({
+                let counter = counter.clone();
+                move |_| {
+                    counter.fetch_add(1, Ordering::SeqCst);
+                    Ok(Pool { size: 8 })
+                }
+            })
+            .build()
+            .unwrap();
+
+        let a = container.with_singleton(|pool: &Pool| pool.size).unwrap();
+        let b = container.with_singleton(|pool: &Pool| pool as *const Pool).unwrap();
+        let c = container.with_singleton(|pool: &Pool| pool as *const Pool).unwrap();
+
+        assert_eq!(a, 8);
+        assert_eq!(b, c);
+        assert_eq!(counter.load(Ordering::SeqCst), 1);
+        assert!(container.resolve::<Pool>().is_err());
+    }
+
+    #[test]
+    fn with_singleton_on_transient_constructs_each_call() {
+        use std::sync::atomic::{AtomicU32, Ordering};
+
+        let counter = Arc::new(AtomicU32::new(0));
+        let container = Container::builder()
+            .transient_with::<u32>({
+                let counter = counter.clone();
+                move |_| Ok(counter.fetch_add(1, Ordering::SeqCst))
+            })
+            .build()
+            .unwrap();
+
+        assert_eq!(container.with_singleton(|v: &u32| *v).unwrap(), 0);
+        assert_eq!(container.with_singleton(|v: &u32| *v).unwrap(), 1);
+    }
+
+    #[test]
+    fn describe_reports_singleton_state() {
+        let container = Container::builder()
+            .singleton_with::<Arc<String>>(|_| Ok(Arc::new(String::from("db"))))
+            .singleton_value(1i32)
+            .transient_with::<u8>(|_| Ok(0))
+            .build()
+            .unwrap();
+
+        let key = DependencyKey::of::<Arc<String>>();
+        let before = container.describe(&key).unwrap();
+        assert_eq!(before.scope, Scope::Singleton);
+        assert_eq!(before.instantiated, Some(false));
+        assert_eq!(before.alias_for, None);
+
+        let _: Arc<String> = container.resolve().unwrap();
+        let after = container.describe(&key).unwrap();
+        assert_eq!(after.instantiated, Some(true));
+        assert!(after.to_string().contains("Instantiated: yes"));
+
+        assert_eq!(
+            container.describe(&DependencyKey::of::<i32>()).unwrap().instantiated,
+            Some(true)
+        );
+        assert_eq!(container.describe(&DependencyKey::of::<u8>()).unwrap().instantiated, None);
+        assert!(container.describe(&DependencyKey::of::<u64>()).is_none());
+    }
+
+    #[test]
+    fn singleton_using_alias_to_scoped_fails_build() {
+        trait Cache: Send + Sync {}
+        #[derive(Clone)]
+        struct RedisCache;
+        impl Cache for RedisCache {}
+        struct Sessions;
+
+        struct Bindings;
+        impl Provider for Bindings {
+            fn register(&self, builder: &mut dyn ProviderRegistry) {
+                builder.register_scoped(
+                    DependencyKey::of::<Arc<RedisCache>>(),
+                    Arc::new(|_| Ok(Box::new(Arc::new(RedisCache)))),
+                    vec![],
+                );
+                builder.register_alias(
+                    DependencyKey::of::<Arc<dyn Cache>>(),
+                    DependencyKey::of::<Arc<RedisCache>>(),
+                );
+                builder.register_singleton(
+                    DependencyKey::of::<Sessions>(),
+                    Arc::new(|_| Ok(Box::new(Sessions))),
+                    vec![DependencyKey::of::<Arc<dyn Cache>>()],
+                );
+            }
+        }
+
+        match Container::builder().add_provider(&Bindings).build() {
+            Err(MakhzanError::AliasScopeMismatch(e)) => {
+                assert_eq!(e.alias_from, DependencyKey::of::<Arc<dyn Cache>>());
+                assert_eq!(e.alias_to, DependencyKey::of::<Arc<RedisCache>>());
+                assert_eq!(e.consumer, DependencyKey::of::<Sessions>());
+                assert_eq!((e.consumer_scope, e.target_scope), (Scope::Singleton, Scope::Scoped));
+                assert!(e
+                    .to_string()
+                    .contains("A Singleton cannot resolve a Scoped binding via alias `Arc<dyn Cache>`"));
+            }
+            other => panic!("expected AliasScopeMismatch, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn describe_follows_alias() {
+        struct Aliases;
+        impl Provider for Aliases {
+            fn register(&self, builder: &mut dyn ProviderRegistry) {
+                builder.register_transient(
+                    DependencyKey::of::<u32>(),
+                    Arc::new(|_| Ok(Box::new(1u32))),
+                    vec![DependencyKey::of::<i32>()],
+                );
+                builder.register_alias(DependencyKey::of::<u64>(), DependencyKey::of::<u32>());
+            }
+        }
+
+        let container = Container::builder()
+            .singleton_value(1i32)
+            .add_provider(&Aliases)
+            .build()
+            .unwrap();
+
+        let description = container.describe(&DependencyKey::of::<u64>()).unwrap();
+        assert_eq!(description.alias_for, Some(DependencyKey::of::<u32>()));
+        assert_eq!(description.scope, Scope::Transient);
+        assert_eq!(description.dependencies, vec![DependencyKey::of::<i32>()]);
+        assert!(description.to_string().contains("Alias for"));
+    }
+
+    #[test]
+    fn plan_for_reflects_aliases() {
+        struct Aliases;
+        impl Provider for Aliases {
+            fn register(&self, builder: &mut dyn ProviderRegistry) {
+                builder.register_alias(DependencyKey::of::<u64>(), DependencyKey::of::<i64>());
+                builder.register_alias(DependencyKey::of::<i64>(), DependencyKey::of::<u32>());
+            }
+        }
+
+        let container = Container::builder()
+            .transient_with::<u32>(|_| Ok(5))
+            .add_provider(&Aliases)
+            .build()
+            .unwrap();
+
+        let plan = container.plan_for::<u64>().unwrap();
+        assert_eq!(plan.target, DependencyKey::of::<u32>());
+        assert_eq!(plan.path.len(), 3);
+        assert_eq!(plan.scope, Scope::Transient);
+
+        assert!(!container.plan_for::<u32>().unwrap().is_alias());
+        assert!(container.plan_for::<u8>().is_none());
+    }
+
+    #[test]
+    fn plan_for_reflects_overrides() {
+        let container = Container::builder()
+            .allow_override(true)
+            .transient_with::<u32>(|_| Ok(1))
+            .singleton_value(2u32)
+            .build()
+            .unwrap();
+
+        assert_eq!(container.plan_for::<u32>().unwrap().scope, Scope::Singleton);
+        assert_eq!(container.resolve::<u32>().unwrap(), 2);
+    }
+
+    #[test]
+    fn singleton_fast_cache_populated_on_resolve() {
+        let container = Container::builder()
+            .singleton_with::<Arc<String>>(|_| Ok(Arc::new(String::from("db"))))
+            .build()
+            .unwrap();
+
+        let key = DependencyKey::of::<Arc<String>>();
+        assert!(!container.plans.get(&key).unwrap().instance.is_set());
+
+        let a: Arc<String> = container.resolve().unwrap();
+        let b: Arc<String> = container.resolve().unwrap();
+
+        assert!(Arc::ptr_eq(&a, &b));
+        assert!(container.plans.get(&key).unwrap().instance.is_set());
+    }
+
+    #[test]
+    fn transients_never_hit_fast_cache() {
+        use std::sync::atomic::{AtomicU32, Ordering};
+
+        let counter = Arc::new(AtomicU32::new(0));
+        let container = Container::builder()
+            .transient_with::<u32>({
+                let counter = counter.clone();
+                move |_| Ok(counter.fetch_add(1, Ordering::SeqCst))
+            })
+            .scoped_with::<u64>(|_| Ok(7))
+            .build()
+            .unwrap();
+
+        for expected in 0..3 {
+            assert_eq!(container.resolve::<u32>().unwrap(), expected);
+        }
+        container.create_scope().resolve::<u64>().unwrap();
+
+        assert_eq!(container.plans.cached_instances(), 0);
+    }
+
+    #[test]
+    fn scoped_from_root_allowed_by_default() {
+        let container = Container::builder()
+            .scoped_with::<u32>(|_| Ok(1))
+            .build()
+            .unwrap();
+
+        assert_eq!(container.resolve::<u32>().unwrap(), 1);
+    }
+
+    #[test]
+    fn scoped_from_root_denied_when_strict() {
+        let container = Container::builder()
+            .scoped_with::<u32>(|_| Ok(1))
+            .transient_with::<u64>(|r| Ok(resolve::<u32>(r)? as u64))
+            .scoped_from_root(ScopedFromRoot::Deny)
+            .build()
+            .unwrap();
+
+        match container.resolve::<u32>() {
+            Err(MakhzanError::ScopedFromRoot { key }) => {
+                assert_eq!(key, DependencyKey::of::<u32>());
+            }
+            other => panic!("Expected ScopedFromRoot, got: {other:?}"),
+        }
+        assert!(container.resolve::<u64>().is_err());
+
+        let scope = container.create_scope();
+        assert_eq!(scope.resolve::<u32>().unwrap(), 1);
+        assert_eq!(scope.resolve::<u64>().unwrap(), 1);
+    }
+
+    #[test]
+    fn singleton_arc_registers_arc() {
+        struct Database {
+            url: &'static str,
+        }
+
+        let container = Container::builder()
+            .singleton_arc(|_| Ok(Database { url: "postgres://localhost" }))
+            .build()
+            .unwrap();
+
+        let a: Arc<Database> = container.resolve().unwrap();
+        let b: Arc<Database> = container.resolve().unwrap();
+        assert_eq!(a.url, "postgres://localhost");
+        assert!(Arc::ptr_eq(&a, &b));
+    }
+
+    #[test]
+    fn bind_shared_exposes_trait_object_and_impl() {
+        trait Logger: Send + Sync {
+            fn name(&self) -> &'static str;
+        }
+        crate::upcast!(Logger);
+
+        struct ConsoleLogger;
+        impl Logger for ConsoleLogger {
+            fn name(&self) -> &'static str {
+                "console"
+            }
+        }
+
+        let container = Container::builder()
+            .bind_shared::<dyn Logger, ConsoleLogger>(|_| Ok(ConsoleLogger))
+            .build()
+            .unwrap();
+
+        let logger: Arc<dyn Logger> = container.resolve().unwrap();
+        let concrete: Arc<ConsoleLogger> = container.resolve().unwrap();
+        assert_eq!(logger.name(), "console");
+        assert!(std::ptr::eq(
+            Arc::as_ptr(&logger) as *const u8,
+            Arc::as_ptr(&concrete) as *const u8,
+        ));
+        assert_eq!(
+            container.plan_for::<Arc<dyn Logger>>().unwrap().dependencies,
+            vec![DependencyKey::of::<Arc<ConsoleLogger>>()]
+        );
+    }
+
+    #[test]
+    fn cloned_builders_are_independent() {
+        let base = Container::builder().singleton_value(1i32);
+
+        let a = base.clone().singleton_value(String::from("a")).build().unwrap();
+        let b = base.transient_with::<u8>(|_| Ok(7)).build().unwrap();
+
+        assert_eq!(a.resolve::<i32>().unwrap(), 1);
+        assert_eq!(b.resolve::<i32>().unwrap(), 1);
+        assert_eq!(a.resolve::<String>().unwrap(), "a");
+        assert!(a.resolve::<u8>().is_err());
+        assert!(b.resolve::<String>().is_err());
+    }
+
+    #[test]
+    fn cloned_builders_cache_singletons_per_container() {
+        use std::sync::atomic::{AtomicU32, Ordering};
+
+        let counter = Arc::new(AtomicU32::new(0));
+        let base = Container::builder().singleton_with::<u32>({
+            let counter = counter.clone();
+            move |_| Ok(counter.fetch_add(1, Ordering::SeqCst))
+        });
+
+        let a = base.clone().build().unwrap();
+        let b = base.build().unwrap();
+
+        assert_eq!(a.resolve::<u32>().unwrap(), 0);
+        assert_eq!(b.resolve::<u32>().unwrap(), 1);
+        assert_eq!(a.resolve::<u32>().unwrap(), 0);
+        assert_eq!(counter.load(Ordering::SeqCst), 2);
+
+        a.reset_singletons();
+        assert_eq!(b.resolve::<u32>().unwrap(), 1);
+    }
+
+    #[test]
+    fn reentrant_singleton_is_circular_dependency() {
+        #[derive(Clone)]
+        struct Looping;
+
+        let container = Container::builder()
+            .singleton_with::<Arc<Looping>>(|r| resolve::<Arc<Looping>>(r))
+            .build()
+            .unwrap();
+
+        match container.resolve::<Arc<Looping>>() {
+            Err(MakhzanError::CircularDependency(e)) => assert_eq!(
+                e.chain,
+                vec![DependencyKey::of::<Arc<Looping>>(), DependencyKey::of::<Arc<Looping>>()]
+            ),
+            other => panic!("expected CircularDependency, got {:?}", other.err()),
+        }
+    }
+
+    #[test]
+    fn singleton_reentered_through_an_alias_is_circular_dependency() {
+        #[derive(Clone)]
+        struct Looping;
+
+        let mut builder = Container::builder().singleton_with::<Arc<Looping>>(|r| {
+            resolve::<Arc<u8>>(r)?;
+            Ok(Arc::new(Looping))
+        });
+        builder.register_alias(DependencyKey::of::<Arc<u8>>(), DependencyKey::of::<Arc<Looping>>());
+        let container = builder.build_unchecked().unwrap();
+
+        match container.resolve::<Arc<Looping>>() {
+            Err(MakhzanError::CircularDependency(e)) => assert_eq!(
+                e.chain,
+                vec![DependencyKey::of::<Arc<Looping>>(), DependencyKey::of::<Arc<Looping>>()]
+            ),
+            other => panic!("expected CircularDependency, got {:?}", other.err()),
+        }
+    }
+
+    #[test]
+    fn scoped_reentered_through_an_alias_is_circular_dependency() {
+        #[derive(Clone)]
+        struct Looping;
+
+        let mut builder = Container::builder().scoped_with::<Arc<Looping>>(|r| {
+            resolve::<Arc<u8>>(r)?;
+            Ok(Arc::new(Looping))
+        });
+        builder.register_alias(DependencyKey::of::<Arc<u8>>(), DependencyKey::of::<Arc<Looping>>());
+        let container = builder.build_unchecked().unwrap();
+        let scope = container.create_scope();
+
+        assert!(matches!(
+            scope.resolve::<Arc<Looping>>(),
+            Err(MakhzanError::CircularDependency(_))
+        ));
+        assert!(matches!(scope.resolve::<Arc<u8>>(), Err(MakhzanError::CircularDependency(_))));
+    }
+
+    #[test]
+    fn transitive_reentrant_singleton_reports_chain() {
+        let container = Container::builder()
+            .singleton_with::<String>(|r| Ok(resolve::<u8>(r)?.to_string()))
+            .transient_with::<u8>(|r| Ok(resolve::<String>(r)?.len() as u8))
+            .build()
+            .unwrap();
+
+        match container.resolve::<String>() {
+            Err(MakhzanError::CircularDependency(e)) => assert_eq!(
+                e.chain,
+                vec![
+                    DependencyKey::of::<String>(),
+                    DependencyKey::of::<u8>(),
+                    DependencyKey::of::<String>(),
+                ]
+            ),
+            other => panic!("expected CircularDependency, got {other:?}"),
+        }
+
+        // The failed attempt leaves nothing behind on the stack.
+        assert!(matches!(
+            container.resolve::<u8>(),
+            Err(MakhzanError::CircularDependency(_))
+        ));
+    }
+
+    #[test]
+    fn racing_threads_share_one_slow_singleton() {
+        use std::sync::Barrier;
+        use std::sync::atomic::{AtomicU32, Ordering};
+        use std::time::Duration;
+
+        let calls = Arc::new(AtomicU32::new(0));
+        let container = Container::builder()
+            .singleton_with::<Arc<String>>({
+                let calls = calls.clone();
+                move |_| {
+                    calls.fetch_add(1, Ordering::SeqCst);
+                    std::thread::sleep(Duration::from_millis(50));
+                    Ok(Arc::new(String::from("slow")))
+                }
+            })
+            .build()
+            .unwrap();
+
+        let barrier = Barrier::new(2);
+        let values: Vec<Arc<String>> = std::thread::scope(|s| {
+            let handles: Vec<_> = (0..2)
+                .map(|_| {
+                    s.spawn(|| {
+                        barrier.wait();
+                        container.resolve::<Arc<String>>().unwrap()
+                    })
+                })
+                .collect();
+            handles.into_iter().map(|h| h.join().unwrap()).collect()
+        });
+
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        assert!(Arc::ptr_eq(&values[0], &values[1]));
+    }
+
+    #[test]
+    fn singleton_mutable_updates_in_place() {
+        let container = Container::builder()
+            .singleton_mutable(Vec::<u32>::new())
+            .build()
+            .unwrap();
+
+        container.with_mut(|v: &mut Vec<u32>| v.push(1)).unwrap();
+        let len = container.with_mut(|v: &mut Vec<u32>| {
+            v.push(2);
+            v.len()
+        });
+        assert_eq!(len.unwrap(), 2);
+
+        let shared: Arc<Mutex<Vec<u32>>> = container.resolve().unwrap();
+        assert_eq!(*shared.lock().unwrap(), vec![1, 2]);
+        assert_eq!(
+            container.plan_for::<Arc<Mutex<Vec<u32>>>>().unwrap().scope,
+            Scope::Singleton
+        );
+    }
+
+    #[test]
+    fn poisoned_with_mut_returns_error() {
+        let container = Container::builder().singleton_mutable(0u32).build().unwrap();
+
+        let panicked = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            container.with_mut(|_: &mut u32| panic!("boom")).unwrap();
+        }));
+        assert!(panicked.is_err());
+
+        let err = container.with_mut(|n: &mut u32| *n += 1).unwrap_err();
+        assert!(matches!(err, MakhzanError::LockPoisoned { .. }));
+        assert!(err.to_string().contains("poisoned"));
+    }
+
+    #[test]
+    fn with_mut_on_unregistered_type_fails() {
+        let container = Container::builder().build().unwrap();
+        assert!(matches!(
+            container.with_mut(|_: &mut u32| ()),
+            Err(MakhzanError::NotRegistered(_))
+        ));
+    }
+
+    #[test]
+    fn recorded_dependencies_are_validated() {
+        #[derive(Clone)]
+        struct Config(u32);
+        struct Client(u32);
+
+        let container = Container::builder()
+            .singleton_value(Config(3))
+            .transient_with_recorded(|r| Ok(Client(resolve::<Config>(r)?.0)))
+            .build()
+            .unwrap();
+
+        assert_eq!(container.resolve::<Client>().unwrap().0, 3);
+        assert_eq!(
+            container.plan_for::<Client>().unwrap().dependencies,
+            vec![DependencyKey::of::<Config>()]
+        );
+
+        let missing = Container::builder()
+            .transient_with_recorded(|r| Ok(Client(resolve::<u32>(r)?)))
+            .build();
+        assert!(matches!(missing, Err(MakhzanError::NotRegistered(_))));
+    }
+
+    #[test]
+    fn sentinels_let_recording_continue() {
+        struct Pair(u32, String);
+
+        let builder = Container::builder()
+            .transient_with::<u32>(|_| Ok(7))
+            .transient_with::<String>(|_| Ok(String::from("x")))
+            .transient_with_recorded(|r| Ok(Pair(resolve(r)?, resolve(r)?)));
+
+        let without = builder.clone().build().unwrap();
+        assert_eq!(
+            without.plan_for::<Pair>().unwrap().dependencies,
+            vec![DependencyKey::of::<u32>()]
+        );
+
+        let with = builder.sentinel(0u32).build().unwrap();
+        assert_eq!(
+            with.plan_for::<Pair>().unwrap().dependencies,
+            vec![DependencyKey::of::<u32>(), DependencyKey::of::<String>()]
+        );
+        let pair = with.resolve::<Pair>().unwrap();
+        assert_eq!((pair.0, pair.1.as_str()), (7, "x"));
+    }
+
+    #[test]
+    fn recorded_cycle_is_detected() {
+        let result = Container::builder()
+            .sentinel(0u8)
+            .sentinel(0u16)
+            .transient_with_recorded(|r| Ok(resolve::<u16>(r)? as u8))
+            .transient_with_recorded(|r| Ok(resolve::<u8>(r)? as u16))
+            .build();
+        assert!(matches!(result, Err(MakhzanError::CircularDependency(_))));
+    }
+
+    #[test]
+    fn retry_policy_reruns_failed_factory() {
+        use std::sync::atomic::{AtomicU32, Ordering};
+
+        let calls = Arc::new(AtomicU32::new(0));
+        let container = Container::builder()
+            .singleton_with_policy::<u32>(SingletonFailurePolicy::RetryOnNextResolve, {
+                let calls = calls.clone();
+                move |_| match calls.fetch_add(1, Ordering::SeqCst) {
+                    0 => Err(MakhzanError::ContainerFrozen),
+                    n => Ok(n),
+                }
+            })
+            .build()
+            .unwrap();
+
+        assert!(container.resolve::<u32>().is_err());
+        assert_eq!(container.resolve::<u32>().unwrap(), 1);
+        assert_eq!(container.resolve::<u32>().unwrap(), 1);
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn cache_error_policy_runs_factory_once() {
+        use std::sync::atomic::{AtomicU32, Ordering};
+
+        let calls = Arc::new(AtomicU32::new(0));
+        let container = Container::builder()
+            .singleton_with_policy::<u32>(SingletonFailurePolicy::CacheError, {
+                let calls = calls.clone();
+                move |_| match calls.fetch_add(1, Ordering::SeqCst) {
+                    0 => Err(MakhzanError::ContainerFrozen),
+                    n => Ok(n),
+                }
+            })
+            .build()
+            .unwrap();
+
+        let first = container.resolve::<u32>().unwrap_err().to_string();
+        for _ in 0..3 {
+            let err = container.resolve::<u32>().unwrap_err();
+            assert!(matches!(err, MakhzanError::ConstructionFailed { .. }));
+            assert_eq!(err.to_string(), first);
+        }
+        assert!(first.contains("already built"));
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        let description = container.describe(&DependencyKey::of::<u32>()).unwrap();
+        assert_eq!(description.instantiated, Some(false));
+    }
+
+    #[test]
+    fn memo_errors_fails_fast_where_singleton_with_retries() {
+        use std::sync::atomic::{AtomicU32, Ordering};
+
+        fn flaky(calls: &Arc<AtomicU32>) -> impl Fn(&dyn Resolver) -> Result<u32> + Send + Sync + 'static {
+            let calls = calls.clone();
+            move |_| match calls.fetch_add(1, Ordering::SeqCst) {
+                0 => Err(MakhzanError::ContainerFrozen),
+                n => Ok(n),
+            }
+        }
+
+        let retries = Arc::new(AtomicU32::new(0));
+        let container = Container::builder().singleton_with(flaky(&retries)).build().unwrap();
+        container.resolve::<u32>().unwrap_err();
+        assert_eq!(container.resolve::<u32>().unwrap(), 1);
+        assert_eq!(retries.load(Ordering::SeqCst), 2);
+
+        let memoized = Arc::new(AtomicU32::new(0));
+        let container = Container::builder().singleton_with_memo_errors(flaky(&memoized)).build().unwrap();
+        let first = container.resolve::<u32>().unwrap_err().to_string();
+        assert_eq!(container.resolve::<u32>().unwrap_err().to_string(), first);
+        assert_eq!(container.create_scope().resolve::<u32>().unwrap_err().to_string(), first);
+        assert_eq!(memoized.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn warm_up_constructs_selected_singletons() {
+        let container = Container::builder()
+            .singleton_with::<Arc<String>>(|_| Ok(Arc::new(String::from("pool"))))
+            .singleton_with::<u32>(|_| Ok(1))
+            .transient_with::<u8>(|_| Ok(2))
+            .build()
+            .unwrap();
+        let pool = DependencyKey::of::<Arc<String>>();
+        let number = DependencyKey::of::<u32>();
+
+        container.warm_up(std::slice::from_ref(&pool)).unwrap();
+        assert_eq!(container.describe(&pool).unwrap().instantiated, Some(true));
+        assert_eq!(container.describe(&number).unwrap().instantiated, Some(false));
+
+        container.warm_up_all_singletons().unwrap();
+        assert_eq!(container.describe(&number).unwrap().instantiated, Some(true));
+
+        assert!(matches!(
+            container.warm_up(&[DependencyKey::of::<u8>()]),
+            Err(MakhzanError::NotSingleton { scope: Scope::Transient, .. })
+        ));
+    }
+
+    #[test]
+    fn warm_up_reports_failing_key() {
+        let container = Container::builder()
+            .singleton_with::<u32>(|_| Err(MakhzanError::ContainerFrozen))
+            .build()
+            .unwrap();
+
+        match container.warm_up_all_singletons() {
+            Err(MakhzanError::WarmUpFailed { key, .. }) => {
+                assert_eq!(key, DependencyKey::of::<u32>())
+            }
+            other => panic!("expected WarmUpFailed, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn runaway_chain_hits_depth_limit() {
+        let mut builder = Container::builder();
+        let keys: Vec<DependencyKey> = (0..200)
+            .map(|i| DependencyKey::named::<u32>(Box::leak(format!("link_{i}").into_boxed_str())))
+            .collect();
+        for (i, key) in keys.iter().enumerate() {
+            let next = keys.get(i + 1).cloned();
+            let dependencies = next.iter().cloned().collect();
+            builder.register_transient(
+                key.clone(),
+                Arc::new(move |r: &dyn Resolver| match &next {
+                    Some(next) => r.resolve_key(next),
+                    None => Ok(Box::new(0u32)),
+                }),
+                dependencies,
+            );
+        }
+
+        let container = builder.clone().build().unwrap();
+        match container.resolve_internal(&keys[0], None, None) {
+            Err(MakhzanError::MaxDepthExceeded { key, depth, path }) => {
+                assert_eq!(depth, DEFAULT_MAX_RESOLVE_DEPTH);
+                assert_eq!(key, keys[DEFAULT_MAX_RESOLVE_DEPTH]);
+                assert_eq!(path, keys[..DEFAULT_MAX_RESOLVE_DEPTH]);
+            }
+            other => panic!("expected MaxDepthExceeded, got {:?}", other.err()),
+        }
+
+        let deep = builder.max_resolve_depth(256).build().unwrap();
+        assert!(deep.resolve_internal(&keys[0], None, None).is_ok());
+    }
+
+    #[test]
+    fn undeclared_transient_cycle_hits_depth_limit() {
+        #[derive(Debug)]
+        struct Ping;
+        #[derive(Debug)]
+        struct Pong;
+
+        // Nothing is declared, so the validator sees no cycle, and
+        // transients are never cached, so only the depth limit stops this.
+        let container = Container::builder()
+            .transient_with(|r| resolve::<Pong>(r).map(|_| Ping))
+            .transient_with(|r| resolve::<Ping>(r).map(|_| Pong))
+            .max_resolve_depth(16)
+            .build()
+            .unwrap();
+
+        match container.create_scope().resolve::<Ping>() {
+            Err(MakhzanError::MaxDepthExceeded { depth, path, .. }) => {
+                assert_eq!(depth, 16);
+                assert_eq!(path.len(), 16);
+                assert_eq!(path[0], DependencyKey::of::<Ping>());
+                assert_eq!(path[1], DependencyKey::of::<Pong>());
+            }
+            other => panic!("expected MaxDepthExceeded, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn register_injectable_declares_dependencies() {
+        struct Greeter(String);
+
+        impl Injectable for Greeter {
+            fn dependencies() -> Vec<DependencyKey> {
+                vec![DependencyKey::of::<String>()]
+            }
+
+            fn inject(resolver: &dyn Resolver) -> Result<Self> {
+                Ok(Greeter(resolve(resolver)?))
+            }
+        }
+
+        let container = Container::builder()
+            .singleton_value(String::from("hi"))
+            .register_injectable::<Greeter>()
+            .build()
+            .unwrap();
+        assert_eq!(container.resolve::<Greeter>().unwrap().0, "hi");
+        assert_eq!(
+            container.plan_for::<Greeter>().unwrap().dependencies,
+            vec![DependencyKey::of::<String>()]
+        );
+
+        let missing = Container::builder().register_injectable::<Greeter>().build();
+        assert!(matches!(missing, Err(MakhzanError::NotRegistered(_))));
+    }
+
+    #[test]
+    fn build_collects_alias_warnings() {
+        let mut builder = Container::builder().allow_override(true).singleton_value(1i32);
+        builder.register_alias(DependencyKey::of::<u32>(), DependencyKey::of::<i32>());
+        let builder = builder.transient_with::<i32>(|_| Ok(2));
+
+        let container = builder.build().unwrap();
+        let warnings = container.warnings();
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].contains("scope changed from Singleton to Transient"));
+
+        let clean = Container::builder().singleton_value(1i32).build().unwrap();
+        assert!(clean.warnings().is_empty());
+    }
+
+    #[test]
+    fn tree_shows_forest_with_scopes() {
+        let container = Container::builder()
+            .singleton_value(Arc::new(String::from("db")))
+            .transient_with_recorded::<u32>(|r| Ok(resolve::<Arc<String>>(r)?.len() as u32))
+            .transient_with_recorded::<u8>(|r| Ok(resolve::<u32>(r)? as u8))
+            .transient_with_recorded::<u16>(|r| Ok(resolve::<u32>(r)? as u16))
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            container.tree(),
+            "[Transient] u16\n\
+             └── [Transient] u32\n\
+             \u{20}   └── [Singleton] Arc<String>\n\
+             [Transient] u8\n\
+             └── [Transient] u32  (see above)\n"
+        );
+    }
+
+    #[test]
+    fn resolve_copy_builds_inline_and_falls_back() {
+        #[derive(Clone, Copy, Debug, PartialEq)]
+        struct Point(i32, i32);
+
+        let container = Container::builder()
+            .singleton_value(3i32)
+            .transient_copy_with(|r| Ok(Point(resolve(r)?, 4)))
+            .transient_with::<u8>(|_| Ok(9))
+            .build()
+            .unwrap();
+
+        assert_eq!(container.resolve_copy::<Point>().unwrap(), Point(3, 4));
+        assert_eq!(container.resolve::<Point>().unwrap(), Point(3, 4));
+        assert_eq!(container.create_scope().resolve_copy::<Point>().unwrap(), Point(3, 4));
+        assert_eq!(container.resolve_copy::<u8>().unwrap(), 9);
+        assert_eq!(container.resolve_copy::<i32>().unwrap(), 3);
+        assert!(matches!(
+            container.resolve_copy::<u64>(),
+            Err(MakhzanError::NotRegistered(_))
+        ));
+    }
+
+    #[test]
+    fn resolve_dynamic_by_name() {
+        #[derive(Debug, PartialEq)]
+        struct CsvExporter;
+
+        let container = Container::builder()
+            .transient_with::<CsvExporter>(|_| Ok(CsvExporter))
+            .singleton_value(5u32)
+            .register_dynamic("my_plugin::CsvExporter", DependencyKey::of::<CsvExporter>())
+            .register_dynamic("limits::max", DependencyKey::of::<u32>())
+            .build()
+            .unwrap();
+
+        let exporter: CsvExporter = container.resolve_dynamic_as("my_plugin::CsvExporter").unwrap();
+        assert_eq!(exporter, CsvExporter);
+        let max = container.resolve_dynamic("limits::max").unwrap();
+        assert_eq!(*max.downcast::<u32>().unwrap(), 5);
+
+        let mismatch = container.resolve_dynamic_as::<String>("limits::max").unwrap_err();
+        match &mismatch {
+            MakhzanError::TypeMismatch { expected, actual, .. } => {
+                assert_eq!(*expected, type_name::<String>());
+                assert_eq!(*actual, Some("u32"));
+            }
+            other => panic!("expected TypeMismatch, got {other:?}"),
+        }
+        assert!(mismatch.to_string().contains("Type mismatch"));
+
+        match container.resolve_dynamic("my_plugin::CsvExportr") {
+            Err(MakhzanError::UnknownDynamicName(e)) => {
+                assert_eq!(e.suggestions, vec!["my_plugin::CsvExporter".to_string()]);
+                assert_eq!(e.available, vec!["limits::max", "my_plugin::CsvExporter"]);
+            }
+            other => panic!("expected UnknownDynamicName, got {:?}", other.err()),
+        }
+    }
+
+    #[test]
+    fn dynamic_name_for_unregistered_key_fails_build() {
+        let result = Container::builder()
+            .register_dynamic("missing", DependencyKey::of::<u32>())
+            .build();
+        assert!(matches!(result, Err(MakhzanError::NotRegistered(_))));
+    }
+
+    #[test]
+    fn tagged_singletons_resolve_independently() {
+        struct Primary;
+        struct Replica;
+        #[derive(Clone)]
+        struct Replicator(String, String);
+
+        let container = Container::builder()
+            .singleton_tagged::<String, Primary>(|_| Ok("postgres://primary".to_string()))
+            .singleton_tagged::<String, Replica>(|_| Ok("postgres://replica".to_string()))
+            .singleton_with(|r| {
+                Ok(Replicator(
+                    resolve_tagged::<String, Primary>(r)?,
+                    resolve_tagged::<String, Replica>(r)?,
+                ))
+            })
+            .build()
+            .unwrap();
+
+        assert_eq!(container.resolve_tagged::<String, Primary>().unwrap(), "postgres://primary");
+        assert_eq!(container.resolve_tagged::<String, Replica>().unwrap(), "postgres://replica");
+        let replicator: Replicator = container.resolve().unwrap();
+        assert_eq!(replicator.0, "postgres://primary");
+        assert_eq!(replicator.1, "postgres://replica");
+
+        match container.resolve::<String>() {
+            Err(MakhzanError::NotRegistered(e)) => {
+                assert_eq!(e.requested, DependencyKey::of::<String>());
+            }
+            other => panic!("expected NotRegistered, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn map_binding_exposes_one_instance_as_several_traits() {
+        trait Repository: Send + Sync {
+            fn save(&self) -> &'static str;
+        }
+        trait ReadOnlyRepository: Send + Sync {
+            fn load(&self) -> &'static str;
+        }
+        struct PostgresRepo;
+        impl Repository for PostgresRepo {
+            fn save(&self) -> &'static str {
+                "saved"
+            }
+        }
+        impl ReadOnlyRepository for PostgresRepo {
+            fn load(&self) -> &'static str {
+                "loaded"
+            }
+        }
+
+        let container = Container::builder()
+            .singleton_arc(|_| Ok(PostgresRepo))
+            .map_binding(|repo: Arc<PostgresRepo>| repo as Arc<dyn Repository>)
+            .map_binding(|repo: Arc<PostgresRepo>| repo as Arc<dyn ReadOnlyRepository>)
+            .build()
+            .unwrap();
+
+        let write: Arc<dyn Repository> = container.resolve().unwrap();
+        let read: Arc<dyn ReadOnlyRepository> = container.resolve().unwrap();
+        assert_eq!(write.save(), "saved");
+        assert_eq!(read.load(), "loaded");
+
+        let concrete: Arc<PostgresRepo> = container.resolve().unwrap();
+        assert_eq!(Arc::as_ptr(&write) as *const u8, Arc::as_ptr(&concrete) as *const u8);
+        assert_eq!(Arc::as_ptr(&read) as *const u8, Arc::as_ptr(&concrete) as *const u8);
+        assert_eq!(
+            container.describe(&DependencyKey::of::<Arc<dyn Repository>>()).unwrap().dependencies,
+            vec![DependencyKey::of::<Arc<PostgresRepo>>()]
+        );
+    }
+
+    #[test]
+    fn map_binding_requires_its_source() {
+        let result = Container::builder().map_binding(|n: u32| n as u64).build();
+        assert!(matches!(result, Err(MakhzanError::NotRegistered(_))));
+    }
+
+    #[test]
+    fn resolve_named_with_runtime_names() {
+        let mut builder = Container::builder();
+        for tenant in [7, 42] {
+            builder.register_transient(
+                DependencyKey::named_owned::<String>(format!("tenant-{tenant}-db")),
+                Arc::new(move |_: &dyn Resolver| {
+                    Ok(Box::new(format!("postgres://tenant-{tenant}")) as Box<dyn Any + Send + Sync>)
+                }),
+                vec![],
+            );
+        }
+        let container = builder.build().unwrap();
+
+        let id = 42;
+        assert_eq!(
+            container.resolve_named::<String>(&format!("tenant-{id}-db")).unwrap(),
+            "postgres://tenant-42"
+        );
+        assert_eq!(
+            container.create_scope().resolve_named::<String>("tenant-7-db").unwrap(),
+            "postgres://tenant-7"
+        );
+        assert!(matches!(
+            container.resolve_named::<String>("tenant-1-db"),
+            Err(MakhzanError::NotRegistered(_))
+        ));
+    }
+
+    #[test]
+    fn resolve_tuple_resolves_each_element() {
+        #[derive(Clone)]
+        struct Config(u32);
+        struct Handler(Arc<String>, Config, u8);
+
+        let container = Container::builder()
+            .singleton_value(Arc::new("db".to_string()))
+            .singleton_value(Config(3))
+            .transient_with(|_| Ok(7u8))
+            .transient_with(|r| {
+                let (db, cfg, n) = r.resolve_tuple::<(Arc<String>, Config, u8)>()?;
+                Ok(Handler(db, cfg, n))
+            })
+            .build()
+            .unwrap();
+
+        let (db, cfg, n): (Arc<String>, Config, u8) = container.resolve_tuple().unwrap();
+        assert_eq!((db.as_str(), cfg.0, n), ("db", 3, 7));
+
+        let handler: Handler = container.create_scope().resolve().unwrap();
+        assert_eq!((handler.0.as_str(), handler.1.0, handler.2), ("db", 3, 7));
+    }
+
+    #[test]
+    fn resolve_tuple_names_the_missing_element() {
+        let container = Container::builder().singleton_value(1u32).build().unwrap();
+
+        match container.create_scope().resolve_tuple::<(u32, String, u32)>() {
+            Err(MakhzanError::TupleElementFailed { index, key, source }) => {
+                assert_eq!(index, 1);
+                assert_eq!(key, DependencyKey::of::<String>());
+                assert!(matches!(*source, MakhzanError::NotRegistered(_)));
+            }
+            other => panic!("expected TupleElementFailed, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn invoke_resolves_parameters() {
+        #[derive(Clone)]
+        struct Config(&'static str);
+
+        let container = Container::builder()
+            .singleton_value(Arc::new(5u32))
+            .singleton_value(Config("prod"))
+            .transient_with(|_| Ok(2u8))
+            .build()
+            .unwrap();
+
+        assert_eq!(container.invoke(|| 1).unwrap(), 1);
+
+        fn describe(limit: Arc<u32>, cfg: Config, retries: u8) -> String {
+            format!("{}:{limit}:{retries}", cfg.0)
+        }
+        assert_eq!(container.invoke(describe).unwrap(), "prod:5:2");
+        assert_eq!(
+            container.create_scope().invoke(|cfg: Config, n: u8| (cfg.0, n)).unwrap(),
+            ("prod", 2)
+        );
+    }
+
+    #[test]
+    fn invoke_reports_missing_parameter() {
+        let container = Container::builder().singleton_value(1u32).build().unwrap();
+        let mut called = false;
+
+        match container.invoke(|_: u32, _: u32, _: String| called = true) {
+            Err(MakhzanError::TupleElementFailed { index, key, source }) => {
+                assert_eq!(index, 2);
+                assert_eq!(key, DependencyKey::of::<String>());
+                assert!(matches!(*source, MakhzanError::NotRegistered(_)));
+            }
+            other => panic!("expected TupleElementFailed, got {other:?}"),
+        }
+        assert!(!called);
+    }
+
+    #[test]
+    fn resolve_or_default_falls_back_only_when_unregistered() {
+        #[derive(Default, Debug, PartialEq)]
+        struct Retries(u32);
+        #[derive(Default, Debug, PartialEq)]
+        struct Timeout(u32);
+        #[derive(Default, Debug)]
+        struct Broken;
+
+        let container = Container::builder()
+            .transient_with(|_| Ok(Retries(5)))
+            .transient_with::<Broken>(|_| Err(MakhzanError::construction_failed(DependencyKey::of::<Broken>(), "down")))
+            .build()
+            .unwrap();
+
+        assert_eq!(container.resolve_or_default::<Retries>().unwrap(), Retries(5));
+        assert_eq!(container.resolve_or_default::<Timeout>().unwrap(), Timeout(0));
+        assert!(matches!(
+            container.resolve_or_default::<Broken>(),
+            Err(MakhzanError::ConstructionFailed { .. })
+        ));
+    }
+
+    #[test]
+    fn register_default_impl_yields_to_explicit_bindings() {
+        #[derive(Default, Clone, Debug, PartialEq)]
+        struct Theme(&'static str);
+        #[derive(Default, Clone, Debug, PartialEq)]
+        struct Locale(&'static str);
+
+        let container = Container::builder()
+            .register_default_impl::<Theme>()
+            .register_default_impl::<Locale>()
+            .singleton_value(Theme("dark"))
+            .build()
+            .unwrap();
+
+        assert_eq!(container.resolve::<Theme>().unwrap(), Theme("dark"));
+        assert_eq!(container.resolve::<Locale>().unwrap(), Locale(""));
+    }
+
+    #[test]
+    fn scoped_instances_are_cached_per_scope() {
+        use std::sync::atomic::{AtomicU32, Ordering};
+
+        let built = Arc::new(AtomicU32::new(0));
+        let container = Container::builder()
+            .scoped_with::<u32>({
+                let built = built.clone();
+                move |_| Ok(built.fetch_add(1, Ordering::SeqCst))
+            })
+            .transient_with::<u64>(|r| Ok(resolve::<u32>(r)? as u64))
+            .build()
+            .unwrap();
+
+        let first = container.create_scope();
+        assert_eq!(first.resolve::<u32>().unwrap(), 0);
+        assert_eq!(first.resolve::<u64>().unwrap(), 0);
+
+        let second = container.create_owned_scope();
+        assert_eq!(second.resolve::<u32>().unwrap(), 1);
+        assert_eq!(second.clone().resolve::<u64>().unwrap(), 1);
+
+        assert_eq!(built.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn scoped_factories_share_the_scopes_dependency() {
+        struct UnitOfWork;
+        struct Orders(Arc<UnitOfWork>);
+        struct Invoices(Arc<UnitOfWork>);
+
+        let container = Container::builder()
+            .scoped_with(|_| Ok(Arc::new(UnitOfWork)))
+            .scoped_with(|r| Ok(Arc::new(Orders(resolve(r)?))))
+            .scoped_with(|r| Ok(Arc::new(Invoices(resolve(r)?))))
+            .build()
+            .unwrap();
+
+        let scope = container.create_scope();
+        let orders: Arc<Orders> = scope.resolve().unwrap();
+        let invoices: Arc<Invoices> = scope.resolve().unwrap();
+        let unit: Arc<UnitOfWork> = scope.resolve().unwrap();
+        assert!(Arc::ptr_eq(&orders.0, &invoices.0));
+        assert!(Arc::ptr_eq(&orders.0, &unit));
+
+        let other = container.create_scope();
+        let (orders2, invoices2) = other.resolve_tuple::<(Arc<Orders>, Arc<Invoices>)>().unwrap();
+        assert!(Arc::ptr_eq(&orders2.0, &invoices2.0));
+        assert!(!Arc::ptr_eq(&orders2.0, &unit));
+    }
+
+    #[test]
+    fn seeded_values_are_visible_to_factories_in_the_scope() {
+        #[derive(Clone, Debug, PartialEq)]
+        struct RequestId(u64);
+        struct Handler(RequestId);
+
+        let container = Container::builder()
+            .transient_with(|r| Ok(Handler(resolve::<RequestId>(r)?)))
+            .build()
+            .unwrap();
+
+        let scope = container.create_owned_scope();
+        scope.insert(RequestId(42));
+        assert_eq!(scope.resolve::<Handler>().unwrap().0, RequestId(42));
+
+        let other = container.create_scope();
+        other.insert(RequestId(7));
+        assert_eq!(other.resolve::<Handler>().unwrap().0, RequestId(7));
+
+        assert!(matches!(container.resolve::<Handler>(), Err(MakhzanError::NotRegistered(_))));
+    }
+
+    #[test]
+    fn reset_singletons_rebuilds_on_next_resolve() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counter = calls.clone();
+        let container = Container::builder()
+            .singleton_with(move |_| Ok(Arc::new(counter.fetch_add(1, Ordering::SeqCst))))
+            .singleton_ref_with(|_| Ok(String::from("borrowed")))
+            .singleton_value(7u8)
+            .build()
+            .unwrap();
+
+        let before: Arc<usize> = container.resolve().unwrap();
+        container.with_singleton(|_: &String| ()).unwrap();
+        container.reset_singletons();
+
+        let after: Arc<usize> = container.resolve().unwrap();
+        assert_eq!((*before, *after), (0, 1));
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+        assert_eq!(container.with_singleton(|s: &String| s.clone()).unwrap(), "borrowed");
+        assert_eq!(container.resolve::<u8>().unwrap(), 7);
+    }
+
+    #[test]
+    fn reset_during_construction_does_not_store_the_stale_value() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::mpsc;
+
+        let (started_tx, started) = mpsc::channel();
+        let (go, go_rx) = mpsc::channel::<()>();
+        let go_rx = Mutex::new(go_rx);
+        let calls = AtomicUsize::new(0);
+        let container = Container::builder()
+            .singleton_with(move |_| {
+                let call = calls.fetch_add(1, Ordering::SeqCst);
+                if call == 0 {
+                    started_tx.send(()).unwrap();
+                    go_rx.lock().unwrap().recv().unwrap();
+                }
+                Ok(Arc::new(call))
+            })
+            .build()
+            .unwrap();
+
+        let racing = container.clone();
+        let first = std::thread::spawn(move || racing.resolve::<Arc<usize>>().unwrap());
+        started.recv().unwrap();
+        container.reset_singletons();
+        go.send(()).unwrap();
+
+        assert_eq!(*first.join().unwrap(), 0);
+        assert_eq!(*container.resolve::<Arc<usize>>().unwrap(), 1);
+        assert_eq!(*container.resolve::<Arc<usize>>().unwrap(), 1);
+    }
+
+    #[test]
+    fn reset_singleton_only_touches_one_key() {
+        let container = Container::builder()
+            .singleton_with(|_| Ok(Arc::new(String::from("kept"))))
+            .singleton_with(|_| Ok(Arc::new(1u32)))
+            .build()
+            .unwrap();
+
+        let string: Arc<String> = container.resolve().unwrap();
+        let number: Arc<u32> = container.resolve().unwrap();
+        assert!(container.reset_singleton::<Arc<u32>>().unwrap());
+        assert!(!container.reset_singleton::<Arc<u32>>().unwrap());
+
+        assert!(Arc::ptr_eq(&string, &container.resolve::<Arc<String>>().unwrap()));
+        assert!(!Arc::ptr_eq(&number, &container.resolve::<Arc<u32>>().unwrap()));
+        assert!(matches!(
+            container.reset_singleton::<f64>(),
+            Err(MakhzanError::NotRegistered(_))
+        ));
+    }
+
+    #[test]
+    fn reset_singleton_reruns_the_factory_for_dependents() {
+        use std::sync::atomic::{AtomicU32, Ordering};
+
+        #[derive(Clone)]
+        struct Client(Arc<u32>);
+
+        let generation = Arc::new(AtomicU32::new(0));
+        let counter = Arc::clone(&generation);
+        let container = Container::builder()
+            .singleton_with(move |_| Ok(Arc::new(counter.fetch_add(1, Ordering::SeqCst))))
+            .transient_with(|r| Ok(Client(resolve(r)?)))
+            .build()
+            .unwrap();
+
+        assert_eq!(*container.resolve::<Client>().unwrap().0, 0);
+        assert_eq!(*container.resolve::<Client>().unwrap().0, 0);
+        assert!(container.reset_singleton::<Arc<u32>>().unwrap());
+        assert_eq!(*container.resolve::<Client>().unwrap().0, 1);
+        assert_eq!(generation.load(Ordering::SeqCst), 2);
+
+        assert!(matches!(
+            container.reset_singleton::<Client>(),
+            Err(MakhzanError::NotSingleton { scope: Scope::Transient, .. })
+        ));
+    }
+
+    #[test]
+    fn unmet_requirements_name_the_provider() {
+        struct Mailer;
+        impl Provider for Mailer {
+            fn register(&self, builder: &mut dyn ProviderRegistry) {
+                builder.register_transient(
+                    DependencyKey::of::<u8>(),
+                    Arc::new(|_| Ok(Box::new(1u8))),
+                    vec![],
+                );
+            }
+
+            fn name(&self) -> &str {
+                "Mailer"
+            }
+
+            fn requires(&self) -> Vec<DependencyKey> {
+                vec![DependencyKey::of::<String>(), DependencyKey::of::<u32>()]
+            }
+        }
+
+        let builder = Container::builder()
+            .singleton_value(String::from("smtp://localhost"))
+            .add_provider(&Mailer);
+
+        let unmet = builder.unmet_requirements();
+        assert_eq!(unmet.len(), 1);
+        assert_eq!(unmet[0].key, DependencyKey::of::<u32>());
+        assert_eq!(unmet[0].to_string(), "Mailer requires u32, which is not registered");
+
+        assert!(builder.singleton_value(3u32).unmet_requirements().is_empty());
+    }
+
+    #[test]
+    fn owned_scope_per_job_is_independent_and_disposed() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        #[derive(Clone)]
+        struct JobContext(u32);
+        struct UnitOfWork {
+            job: u32,
+            alive: Arc<AtomicUsize>,
+        }
+        impl Drop for UnitOfWork {
+            fn drop(&mut self) {
+                self.alive.fetch_sub(1, Ordering::SeqCst);
+            }
+        }
+
+        let alive = Arc::new(AtomicUsize::new(0));
+        let created = Arc::new(AtomicUsize::new(0));
+        let (counter, builds) = (alive.clone(), created.clone());
+        let container = Container::builder()
+            .scoped_with(move |resolver| {
+                counter.fetch_add(1, Ordering::SeqCst);
+                builds.fetch_add(1, Ordering::SeqCst);
+                let context: JobContext = resolve(resolver)?;
+                Ok(Arc::new(UnitOfWork { job: context.0, alive: counter.clone() }))
+            })
+            .build()
+            .unwrap();
+
+        let mut jobs = Vec::new();
+        for job in 1..=3 {
+            let scope = container.create_owned_scope();
+            scope.insert(JobContext(job));
+            let leaked = scope.clone();
+            let seen = scope
+                .run(|scope| {
+                    let unit: Arc<UnitOfWork> = scope.resolve()?;
+                    assert!(Arc::ptr_eq(&unit, &scope.resolve()?));
+                    Ok(unit.job)
+                })
+                .unwrap();
+            jobs.push(seen);
+            assert_eq!(alive.load(Ordering::SeqCst), 0, "job {job} was not disposed");
+            drop(leaked);
+        }
+        assert_eq!(jobs, [1, 2, 3]);
+        assert_eq!(created.load(Ordering::SeqCst), 3);
+
+        let scope = container.create_owned_scope();
+        scope.insert(JobContext(4));
+        let panicked = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            scope.run(|scope| -> Result<()> {
+                let _unit: Arc<UnitOfWork> = scope.resolve()?;
+                panic!("job failed")
+            })
+        }));
+        assert!(panicked.is_err());
+        assert_eq!(alive.load(Ordering::SeqCst), 0);
+    }
+
+    #[test]
+    fn child_scopes_see_ancestors_but_not_siblings() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        #[derive(Clone)]
+        struct RequestId(u32);
+        struct Operation;
+
+        let built = Arc::new(AtomicUsize::new(0));
+        let counter = built.clone();
+        let container = Container::builder()
+            .singleton_with(|_| Ok(Arc::new(String::from("pool"))))
+            .scoped_with(move |_| {
+                counter.fetch_add(1, Ordering::SeqCst);
+                Ok(Arc::new(Operation))
+            })
+            .build()
+            .unwrap();
+
+        let request = container.create_scope();
+        request.insert(RequestId(7));
+        let in_request: Arc<Operation> = request.resolve().unwrap();
+
+        let child = request.create_child_scope();
+        let grandchild = child.create_child_scope();
+        assert!(child.parent().is_some());
+        assert_eq!(grandchild.resolve::<RequestId>().unwrap().0, 7);
+        assert!(Arc::ptr_eq(&in_request, &child.resolve().unwrap()));
+        assert!(Arc::ptr_eq(&in_request, &grandchild.resolve().unwrap()));
+
+        // Built in a child with nothing cached above it: private to that
+        // child and its descendants.
+        let other_request = container.create_scope();
+        let left = other_request.create_child_scope();
+        let right = other_request.create_child_scope();
+        let in_left: Arc<Operation> = left.resolve().unwrap();
+        let in_right: Arc<Operation> = right.resolve().unwrap();
+        assert!(!Arc::ptr_eq(&in_left, &in_right));
+        assert!(Arc::ptr_eq(&in_left, &left.create_child_scope().resolve().unwrap()));
+        assert!(!Arc::ptr_eq(&in_left, &other_request.resolve().unwrap()));
+        assert_eq!(built.load(Ordering::SeqCst), 4);
+
+        let singleton: Arc<String> = grandchild.resolve().unwrap();
+        assert!(Arc::ptr_eq(&singleton, &container.resolve().unwrap()));
+    }
+
+    #[test]
+    fn assert_resolvable_runs_no_factory() {
+        use std::sync::atomic::{AtomicBool, Ordering};
+
+        struct Service;
+        struct Orphan;
+
+        static CONSTRUCTED: AtomicBool = AtomicBool::new(false);
+        let container = Container::builder()
+            .add_provider(&{
+                struct Wiring;
+                impl Provider for Wiring {
+                    fn register(&self, builder: &mut dyn ProviderRegistry) {
+                        builder.register_transient(
+                            DependencyKey::of::<Service>(),
+                            Arc::new(|_| {
+                                CONSTRUCTED.store(true, Ordering::SeqCst);
+                                Ok(Box::new(Service))
+                            }),
+                            vec![DependencyKey::of::<u8>()],
+                        );
+                    }
+                }
+                Wiring
+            })
+            .transient_with::<u8>(|_| Ok(0))
+            .build()
+            .unwrap();
+
+        assert!(container.assert_resolvable::<Service>().is_ok());
+        match container.assert_resolvable::<Orphan>() {
+            Err(MakhzanError::NotRegistered(e)) => {
+                assert_eq!(e.requested, DependencyKey::of::<Orphan>());
+            }
+            other => panic!("expected NotRegistered, got {other:?}"),
+        }
+        assert!(!CONSTRUCTED.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn provider_registrations_marked_cacheable_are_cached() {
+        use crate::provider::Cacheable;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        static BUILT: AtomicUsize = AtomicUsize::new(0);
+
+        struct Cached;
+        impl Provider for Cached {
+            fn register(&self, builder: &mut dyn ProviderRegistry) {
+                builder.register_cacheable(
+                    DependencyKey::of::<Arc<String>>(),
+                    Scope::Singleton,
+                    Arc::new(|_| {
+                        BUILT.fetch_add(1, Ordering::SeqCst);
+                        Ok(Box::new(Arc::new(String::from("pool"))))
+                    }),
+                    vec![],
+                    Cacheable::of::<Arc<String>>(),
+                );
+                builder.register_cacheable(
+                    DependencyKey::of::<Arc<u32>>(),
+                    Scope::Scoped,
+                    Arc::new(|_| {
+                        BUILT.fetch_add(1, Ordering::SeqCst);
+                        Ok(Box::new(Arc::new(1u32)))
+                    }),
+                    vec![],
+                    Cacheable::of::<Arc<u32>>(),
+                );
+            }
+        }
+
+        let container = Container::builder().add_provider(&Cached).build().unwrap();
+        let scope = container.create_scope();
+        let pool: Arc<String> = container.resolve().unwrap();
+        assert!(Arc::ptr_eq(&pool, &scope.resolve().unwrap()));
+        let scoped: Arc<u32> = scope.resolve().unwrap();
+        assert!(Arc::ptr_eq(&scoped, &scope.resolve().unwrap()));
+        assert_eq!(BUILT.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn untyped_provider_singletons_and_scoped_values_are_not_cached() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        static BUILT: AtomicUsize = AtomicUsize::new(0);
+
+        struct Untyped;
+        impl Provider for Untyped {
+            fn register(&self, builder: &mut dyn ProviderRegistry) {
+                builder.register_singleton(
+                    DependencyKey::of::<Arc<String>>(),
+                    Arc::new(|_| {
+                        BUILT.fetch_add(1, Ordering::SeqCst);
+                        Ok(Box::new(Arc::new(String::from("pool"))))
+                    }),
+                    vec![],
+                );
+                builder.register_scoped(
+                    DependencyKey::of::<Arc<u32>>(),
+                    Arc::new(|_| {
+                        BUILT.fetch_add(1, Ordering::SeqCst);
+                        Ok(Box::new(Arc::new(1u32)))
+                    }),
+                    vec![],
+                );
+            }
+        }
+
+        let container = Container::builder().add_provider(&Untyped).build().unwrap();
+        let scope = container.create_scope();
+        let pool: Arc<String> = container.resolve().unwrap();
+        assert!(!Arc::ptr_eq(&pool, &container.resolve().unwrap()));
+        let scoped: Arc<u32> = scope.resolve().unwrap();
+        assert!(!Arc::ptr_eq(&scoped, &scope.resolve().unwrap()));
+        assert_eq!(BUILT.load(Ordering::SeqCst), 4);
+    }
+
+    #[test]
+    fn suggestions_follow_the_configured_limits() {
+        #[derive(Clone)]
+        struct UserRepo;
+        #[derive(Clone)]
+        struct UserRepoCache;
+        #[derive(Clone)]
+        struct UserRepoAudit;
+        #[derive(Clone)]
+        struct UserRepoMetrics;
+        #[derive(Debug)]
+        struct UserRepository;
+        #[derive(Clone)]
+        struct Profile;
+
+        fn builder() -> ContainerBuilder {
+            Container::builder()
+                .singleton_value(UserRepo)
+                .singleton_value(UserRepoCache)
+                .singleton_value(UserRepoAudit)
+                .singleton_value(UserRepoMetrics)
+        }
+        fn suggestions(result: Result<impl fmt::Debug>) -> Vec<DependencyKey> {
+            match result {
+                Err(MakhzanError::NotRegistered(e)) => e.suggestions,
+                other => panic!("expected NotRegistered, got {other:?}"),
+            }
+        }
+
+        let container = builder().build().unwrap();
+        let found = suggestions(container.resolve::<UserRepository>());
+        assert_eq!(found.len(), 3);
+
+        let container = builder()
+            .suggestions(SuggestionConfig { max: 10, min_score: 0 })
+            .build()
+            .unwrap();
+        assert_eq!(suggestions(container.resolve::<UserRepository>()).len(), 4);
+
+        let container = builder().suggestions(SuggestionConfig::DISABLED).build().unwrap();
+        assert!(suggestions(container.resolve::<UserRepository>()).is_empty());
+
+        let failed = builder()
+            .suggestions(SuggestionConfig { max: 0, min_score: 0 })
+            .transient_with_recorded::<Profile>(|r| {
+                resolve::<UserRepository>(r)?;
+                Ok(Profile)
+            })
+            .build();
+        assert!(suggestions(failed).is_empty());
+    }
+
+    #[cfg(feature = "clap")]
+    #[test]
+    fn cli_args_are_parsed_at_build() {
+        use clap::Parser;
+
+        #[derive(Debug, Clone, PartialEq, Parser)]
+        struct Args {
+            input: String,
+            #[arg(long, default_value = "out")]
+            output_dir: String,
+            #[arg(long)]
+            verbose: bool,
+            #[arg(long)]
+            tag: Vec<String>,
+            #[arg(long)]
+            limit: Option<u32>,
+        }
+
+        let argv = ["tool", "data.csv", "--verbose", "--tag", "a", "--tag", "b"];
+        let container = Container::builder()
+            .with_cli_args_from_iter::<Args>(argv)
+            .build()
+            .unwrap();
+
+        let args: Args = container.resolve().unwrap();
+        assert_eq!(args, Args::try_parse_from(argv).unwrap());
+        assert_eq!(container.resolve_named::<String>("input").unwrap(), "data.csv");
+        assert_eq!(container.resolve_named::<String>("output-dir").unwrap(), "out");
+        assert_eq!(container.resolve_named::<String>("verbose").unwrap(), "true");
+        assert_eq!(container.resolve_named::<String>("tag").unwrap(), "a,b");
+        assert!(container.resolve_named::<String>("limit").is_err());
+
+        let parsed = Args::try_parse_from(["tool", "in.csv"]).unwrap();
+        let container = Container::builder().with_cli_args_from(parsed.clone()).build().unwrap();
+        assert_eq!(container.resolve::<Args>().unwrap(), parsed);
+
+        match Container::builder().with_cli_args_from_iter::<Args>(["tool", "--limit", "x"]).build() {
+            Err(MakhzanError::ConstructionFailed { key, source, .. }) => {
+                assert_eq!(key, DependencyKey::of::<Args>());
+                assert!(source.downcast_ref::<clap::Error>().is_some());
+            }
+            other => panic!("expected ConstructionFailed, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn owned_resolver_outlives_the_builder() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        struct ServiceLocator {
+            resolver: OwnedResolver,
+        }
+
+        let counter = Arc::new(AtomicUsize::new(0));
+        let locator = {
+            let calls = Arc::clone(&counter);
+            let container = Arc::new(
+                Container::builder()
+                    .singleton_with::<Arc<String>>(move |_| {
+                        calls.fetch_add(1, Ordering::SeqCst);
+                        Ok(Arc::new("smtp://localhost".to_string()))
+                    })
+                    .build()
+                    .unwrap(),
+            );
+            ServiceLocator { resolver: container.resolver() }
+        };
+
+        assert_eq!(counter.load(Ordering::SeqCst), 0);
+        let url: Arc<String> = locator.resolver.resolve().unwrap();
+        assert_eq!(*url, "smtp://localhost");
+        let again: Arc<String> = resolve(&locator.resolver.clone()).unwrap();
+        assert!(Arc::ptr_eq(&url, &again));
+        assert_eq!(counter.load(Ordering::SeqCst), 1);
+        assert!(locator.resolver.resolve::<u8>().is_err());
+    }
+
+    #[test]
+    fn registration_order_is_insertion_order() {
+        macro_rules! services {
+            ($($name:ident),*) => {
+                $(#[derive(Clone)] struct $name;)*
+                let container = Container::builder()
+                    $(.singleton_value($name))*
+                    .allow_override(true)
+                    .singleton_value(Zeta)
+                    .register_default_impl::<u8>()
+                    .build()
+                    .unwrap();
+                let expected = vec![$(DependencyKey::of::<$name>(),)* DependencyKey::of::<u8>()];
+                assert_eq!(container.registration_order(), expected);
+            };
+        }
+
+        services!(Zeta, Alpha, Mu, Beta, Omega, Gamma, Kappa, Delta, Sigma, Epsilon);
+    }
+
+    #[test]
+    fn initialize_all_runs_dependencies_first() {
+        use crate::init::Initialize;
+        use parking_lot::Mutex;
+        use std::error::Error;
+
+        type Log = Arc<Mutex<Vec<&'static str>>>;
+        struct Config(Log);
+        struct Database(Log, #[allow(dead_code)] Arc<Config>, bool);
+        struct Cache(Log, #[allow(dead_code)] Arc<Database>);
+
+        impl Initialize for Config {
+            fn initialize(&self) -> std::result::Result<(), Box<dyn Error + Send + Sync>> {
+                self.0.lock().push("config");
+                Ok(())
+            }
+        }
+        impl Initialize for Database {
+            fn initialize(&self) -> std::result::Result<(), Box<dyn Error + Send + Sync>> {
+                self.0.lock().push("database");
+                if self.2 { Err("migration failed".into()) } else { Ok(()) }
+            }
+        }
+        impl Initialize for Cache {
+            fn initialize(&self) -> std::result::Result<(), Box<dyn Error + Send + Sync>> {
+                self.0.lock().push("cache");
+                Ok(())
+            }
+        }
+
+        fn container(log: &Log, migration_fails: bool) -> Container {
+            let (config_log, db_log, cache_log) = (log.clone(), log.clone(), log.clone());
+            let mut builder = Container::builder();
+            // Registered and marked leaf-last, to show the order comes
+            // from the declared dependencies.
+            builder.register_cacheable(
+                DependencyKey::of::<Arc<Cache>>(),
+                Scope::Singleton,
+                Arc::new(move |r: &dyn Resolver| {
+                    Ok(Box::new(Arc::new(Cache(cache_log.clone(), resolve(r)?))))
+                }),
+                vec![DependencyKey::of::<Arc<Database>>()],
+                Cacheable::of::<Arc<Cache>>(),
+            );
+            builder.register_cacheable(
+                DependencyKey::of::<Arc<Database>>(),
+                Scope::Singleton,
+                Arc::new(move |r: &dyn Resolver| {
+                    Ok(Box::new(Arc::new(Database(db_log.clone(), resolve(r)?, migration_fails))))
+                }),
+                vec![DependencyKey::of::<Arc<Config>>()],
+                Cacheable::of::<Arc<Database>>(),
+            );
+            builder
+                .singleton_with::<Arc<Config>>(move |_| Ok(Arc::new(Config(config_log.clone()))))
+                .with_initializer::<Arc<Cache>>()
+                .with_initializer::<Arc<Database>>()
+                .with_initializer::<Arc<Config>>()
+                .build()
+                .unwrap()
+        }
+
+        let log = Log::default();
+        container(&log, false).initialize_all().unwrap();
+        assert_eq!(*log.lock(), ["config", "database", "cache"]);
+
+        let log = Log::default();
+        let container = container(&log, true);
+        match container.initialize_all() {
+            Err(MakhzanError::ConstructionFailed { key, source, .. }) => {
+                assert_eq!(key, DependencyKey::of::<Arc<Database>>());
+                assert_eq!(source.to_string(), "migration failed");
+            }
+            other => panic!("expected ConstructionFailed, got {other:?}"),
+        }
+        assert_eq!(*log.lock(), ["config", "database"]);
+    }
+
+    #[test]
+    fn bind_instance_shares_the_given_instance() {
+        trait Logger: Send + Sync {
+            fn name(&self) -> &str;
+        }
+        crate::upcast!(Logger);
+        struct HostLogger(String);
+        impl Logger for HostLogger {
+            fn name(&self) -> &str {
+                &self.0
+            }
+        }
+
+        let host = Arc::new(HostLogger("host".into()));
+        let existing: Arc<dyn Logger> = Arc::new(HostLogger("existing".into()));
+        let container = Container::builder()
+            .bind_instance::<dyn Logger, _>(Arc::clone(&host))
+            .build()
+            .unwrap();
+        assert_eq!(
+            container.plan_for::<Arc<dyn Logger>>().unwrap().dependencies,
+            vec![DependencyKey::of::<Arc<HostLogger>>()]
+        );
+
+        let logger: Arc<dyn Logger> = container.resolve().unwrap();
+        assert_eq!(logger.name(), "host");
+        let concrete: Arc<HostLogger> = container.resolve().unwrap();
+        assert!(Arc::ptr_eq(&concrete, &host));
+        assert!(std::ptr::addr_eq(Arc::as_ptr(&logger), Arc::as_ptr(&host)));
+
+        let container = Container::builder().singleton_instance(Arc::clone(&existing)).build().unwrap();
+        let logger: Arc<dyn Logger> = container.resolve().unwrap();
+        assert!(Arc::ptr_eq(&logger, &existing));
+    }
+
+    #[test]
+    fn rebuild_with_keeps_untouched_singletons() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        #[derive(Clone, Debug, PartialEq)]
+        struct Config(u32);
+        struct Database(#[allow(dead_code)] Config);
+        #[derive(Clone)]
+        struct Cache;
+
+        let pools = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&pools);
+        let mut builder = Container::builder()
+            .singleton_value(Config(1))
+            .singleton_with::<Arc<Database>>(move |r| {
+                counter.fetch_add(1, Ordering::SeqCst);
+                Ok(Arc::new(Database(resolve(r)?)))
+            });
+        builder.register_cacheable(
+            DependencyKey::of::<Arc<Cache>>(),
+            Scope::Singleton,
+            Arc::new(|_: &dyn Resolver| Ok(Box::new(Arc::new(Cache)))),
+            vec![],
+            Cacheable::of::<Arc<Cache>>(),
+        );
+        let container = builder.build().unwrap();
+        let db: Arc<Database> = container.resolve().unwrap();
+        let cache: Arc<Cache> = container.resolve().unwrap();
+
+        let reloaded = container
+            .rebuild_with(|b| {
+                *b = std::mem::take(b).singleton_value(Config(2));
+                Ok(())
+            })
+            .unwrap();
+
+        assert_eq!(reloaded.resolve::<Config>().unwrap(), Config(2));
+        assert!(Arc::ptr_eq(&reloaded.resolve::<Arc<Database>>().unwrap(), &db));
+        assert!(Arc::ptr_eq(&reloaded.resolve::<Arc<Cache>>().unwrap(), &cache));
+        assert_eq!(pools.load(Ordering::SeqCst), 1);
+        assert_eq!(container.resolve::<Config>().unwrap(), Config(1));
+
+        let replaced = container
+            .rebuild_with(|b| {
+                *b = std::mem::take(b).singleton_with::<Arc<Database>>(|_| Ok(Arc::new(Database(Config(3)))));
+                Ok(())
+            })
+            .unwrap();
+        assert!(!Arc::ptr_eq(&replaced.resolve::<Arc<Database>>().unwrap(), &db));
+    }
+
+    #[test]
+    fn rebuild_with_does_not_share_singletons_constructed_later() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&calls);
+        let container = Container::builder()
+            .singleton_with(move |_| Ok(Arc::new(counter.fetch_add(1, Ordering::SeqCst))))
+            .build()
+            .unwrap();
+
+        let reloaded = container
+            .rebuild_with(|b| {
+                *b = std::mem::take(b).singleton_value(1u8);
+                Ok(())
+            })
+            .unwrap();
+        assert_eq!(*reloaded.resolve::<Arc<usize>>().unwrap(), 0);
+        assert_eq!(*container.resolve::<Arc<usize>>().unwrap(), 1);
+
+        reloaded.reset_singletons();
+        assert_eq!(*container.resolve::<Arc<usize>>().unwrap(), 1);
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn rebuild_with_validates_like_the_original_build() {
+        struct Service;
+
+        let builder = Container::builder()
+            .singleton_value(1u8)
+            .register_factory::<Arc<Service>>(Scope::Transient, vec![DependencyKey::of::<String>()], |_| {
+                Ok(Arc::new(Service))
+            });
+
+        let replace_u8 = |b: &mut ContainerBuilder| {
+            *b = std::mem::take(b).singleton_value(2u8);
+            Ok(())
+        };
+        let unchecked = builder.clone().build_unchecked().unwrap();
+        assert!(unchecked.rebuild_with(replace_u8).is_ok());
+
+        let partial = builder.clone().build_partial(&[DependencyKey::of::<u8>()]).unwrap();
+        assert!(partial.rebuild_with(replace_u8).is_ok());
+        let missing_root_dependency = partial.rebuild_with(|b| {
+            *b = std::mem::take(b).register_factory::<u8>(Scope::Singleton, vec![DependencyKey::of::<u16>()], |_| {
+                Ok(3)
+            });
+            Ok(())
+        });
+        let failed = partial.rebuild_with(|_| Err(MakhzanError::construction_failed(DependencyKey::of::<u8>(), "bad")));
+        assert!(matches!(failed, Err(MakhzanError::ConstructionFailed { .. })));
+        assert!(matches!(missing_root_dependency, Err(MakhzanError::NotRegistered(_))));
+
+        assert!(matches!(builder.build(), Err(MakhzanError::NotRegistered(_))));
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn async_singleton_resolves_once_awaited() {
+        let container = Container::builder()
+            .singleton_value(String::from("postgres://localhost"))
+            .singleton_async(|c: Container| async move {
+                let url: String = c.resolve()?;
+                tokio::task::yield_now().await;
+                Ok(Arc::new(url.len()))
+            })
+            .build()
+            .unwrap();
+
+        assert!(matches!(
+            container.resolve::<Arc<usize>>(),
+            Err(MakhzanError::ConstructionFailed { .. })
+        ));
+        let first: Arc<usize> = container.resolve_async().await.unwrap();
+        assert_eq!(*first, 20);
+        assert!(Arc::ptr_eq(&first, &container.resolve().unwrap()));
+        assert!(Arc::ptr_eq(&first, &container.resolve_async().await.unwrap()));
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn async_singleton_factory_called_once_under_contention() {
+        use std::sync::atomic::{AtomicU32, Ordering};
+
+        let calls = Arc::new(AtomicU32::new(0));
+        let container = Container::builder()
+            .singleton_async({
+                let calls = calls.clone();
+                move |_: Container| {
+                    let calls = calls.clone();
+                    async move {
+                        calls.fetch_add(1, Ordering::SeqCst);
+                        tokio::time::sleep(Duration::from_millis(10)).await;
+                        Ok(Arc::new(String::from("pool")))
+                    }
+                }
+            })
+            .build()
+            .unwrap();
+
+        let tasks: Vec<_> = (0..32)
+            .map(|_| {
+                let container = container.clone();
+                tokio::spawn(async move { container.resolve_async::<Arc<String>>().await.unwrap() })
+            })
+            .collect();
+        let mut pools = Vec::new();
+        for task in tasks {
+            pools.push(task.await.unwrap());
+        }
+
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        assert!(pools.iter().all(|pool| Arc::ptr_eq(pool, &pools[0])));
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn resolve_timeout_fails_hanging_async_factories() {
+        let timeout = Duration::from_millis(20);
+        let container = Container::builder()
+            .singleton_async(|_| async {
+                std::future::pending::<()>().await;
+                Ok(Arc::new(0u8))
+            })
+            .resolve_timeout(timeout)
+            .build()
+            .unwrap();
+
+        match container.resolve_async::<Arc<u8>>().await {
+            Err(MakhzanError::ConstructionTimeout { key, elapsed }) => {
+                assert_eq!(key, DependencyKey::of::<Arc<u8>>());
+                assert_eq!(elapsed, timeout);
+            }
+            other => panic!("expected ConstructionTimeout, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn scoped_factory_resolving_itself_fails_instead_of_blocking() {
+        #[derive(Clone)]
+        struct Node;
+
+        let container = Container::builder()
+            .scoped_with(|r| {
+                resolve::<Node>(r)?;
+                Ok(Node)
+            })
+            .build()
+            .unwrap();
+        let scope = container.create_scope();
+
+        assert!(matches!(scope.resolve::<Node>(), Err(MakhzanError::CircularDependency(_))));
+    }
+
+    #[test]
+    fn weak_singleton_is_rebuilt_once_every_reference_drops() {
+        use std::sync::atomic::{AtomicU32, Ordering};
+
+        struct Model {
+            dropped: Arc<AtomicU32>,
+        }
+
+        impl Drop for Model {
+            fn drop(&mut self) {
+                self.dropped.fetch_add(1, Ordering::SeqCst);
+            }
+        }
+
+        let built = Arc::new(AtomicU32::new(0));
+        let dropped = Arc::new(AtomicU32::new(0));
+        let container = Container::builder()
+            .singleton_weak({
+                let built = built.clone();
+                let dropped = dropped.clone();
+                move |_| {
+                    built.fetch_add(1, Ordering::SeqCst);
+                    Ok(Arc::new(Model { dropped: dropped.clone() }))
+                }
+            })
+            .build()
+            .unwrap();
+
+        let first: Arc<Model> = container.resolve().unwrap();
+        let second: Arc<Model> = container.resolve().unwrap();
+        assert!(Arc::ptr_eq(&first, &second));
+        assert_eq!(container.strong_count::<Model>(), Some(2));
+        assert_eq!(built.load(Ordering::SeqCst), 1);
+
+        drop((first, second));
+        assert_eq!(dropped.load(Ordering::SeqCst), 1);
+        assert_eq!(container.strong_count::<Model>(), Some(0));
+        assert!(!container.describe(&DependencyKey::of::<Arc<Model>>()).unwrap().to_string().contains("Instantiated: yes"));
+
+        let _third: Arc<Model> = container.resolve().unwrap();
+        assert_eq!(built.load(Ordering::SeqCst), 2);
+        assert_eq!(container.strong_count::<Model>(), Some(1));
+        assert_eq!(container.strong_count::<String>(), None);
+    }
+
+    #[test]
+    fn caught_factory_panic_becomes_construction_failed() {
+        #[derive(Debug)]
+        struct Settings;
+
+        let builder = Container::builder()
+            .transient_with::<Settings>(|_| panic!("PORT must be set"));
+        let container = builder.clone().catch_factory_panics(true).build().unwrap();
+
+        match container.resolve::<Settings>() {
+            Err(MakhzanError::ConstructionFailed { key, source, .. }) => {
+                assert_eq!(key, DependencyKey::of::<Settings>());
+                assert_eq!(source.to_string(), "factory panicked: PORT must be set");
+            }
+            other => panic!("expected ConstructionFailed, got {other:?}"),
+        }
+        // The container stays usable after a caught panic.
+        assert!(container.resolve::<Settings>().is_err());
+
+        let uncaught = builder.build().unwrap();
+        let resolve = std::panic::AssertUnwindSafe(|| uncaught.resolve::<Settings>());
+        assert!(std::panic::catch_unwind(resolve).is_err());
+    }
+
+    #[test]
+    fn ttl_singleton_is_rebuilt_once_after_expiry() {
+        use std::sync::atomic::{AtomicU32, Ordering};
+
+        let ttl = Duration::from_millis(200);
+        let builds = Arc::new(AtomicU32::new(0));
+        let container = Container::builder()
+            .singleton_with_ttl(ttl, {
+                let builds = builds.clone();
+                move |_| Ok(Arc::new(builds.fetch_add(1, Ordering::SeqCst)))
+            })
+            .build()
+            .unwrap();
+
+        let first: Arc<u32> = container.resolve().unwrap();
+        assert!(Arc::ptr_eq(&first, &container.resolve::<Arc<u32>>().unwrap()));
+        assert_eq!(builds.load(Ordering::SeqCst), 1);
+
+        std::thread::sleep(ttl * 2);
+        let refreshed: Arc<u32> = container.resolve().unwrap();
+        assert_eq!(*refreshed, 1);
+        assert!(Arc::ptr_eq(&refreshed, &container.resolve::<Arc<u32>>().unwrap()));
+        assert_eq!(builds.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn failed_ttl_refresh_follows_the_policy() {
+        use std::sync::atomic::{AtomicBool, Ordering};
+
+        let ttl = Duration::from_millis(20);
+        let failing = Arc::new(AtomicBool::new(false));
+        let factory = {
+            let failing = failing.clone();
+            move |_: &dyn Resolver| match failing.load(Ordering::SeqCst) {
+                true => Err(MakhzanError::construction_failed(DependencyKey::of::<String>(), "discovery unreachable")),
+                false => Ok("10.0.0.1".to_string()),
+            }
+        };
+        let stale = Container::builder().singleton_with_ttl(ttl, factory.clone()).build().unwrap();
+        let strict = Container::builder()
+            .singleton_with_ttl_policy(ttl, RefreshFailurePolicy::Fail, factory)
+            .build()
+            .unwrap();
+
+        stale.resolve::<String>().unwrap();
+        strict.resolve::<String>().unwrap();
+        failing.store(true, Ordering::SeqCst);
+        std::thread::sleep(ttl * 2);
+
+        assert_eq!(stale.resolve::<String>().unwrap(), "10.0.0.1");
+        assert!(strict.resolve::<String>().is_err());
+    }
+
+    #[test]
+    fn debug_tree_groups_registrations_by_scope() {
+        trait Logger: Send + Sync {}
+        struct ConsoleLogger;
+        impl Logger for ConsoleLogger {}
+        #[derive(Clone)]
+        struct Config;
+        struct Database;
+        #[derive(Clone)]
+        struct Session;
+        struct UserService;
+
+        let mut builder = Container::builder()
+            .singleton_value(Config)
+            .singleton_arc(|_| Ok(ConsoleLogger));
+        builder.register_alias(
+            DependencyKey::of::<Arc<dyn Logger>>(),
+            DependencyKey::of::<Arc<ConsoleLogger>>(),
+        );
+        builder.register_cacheable(
+            DependencyKey::of::<Arc<Database>>(),
+            Scope::Singleton,
+            Arc::new(|_: &dyn Resolver| Ok(Box::new(Arc::new(Database)))),
+            vec![DependencyKey::of::<Config>()],
+            Cacheable::of::<Arc<Database>>(),
+        );
+        builder.register_cacheable(
+            DependencyKey::named::<Session>("web"),
+            Scope::Scoped,
+            Arc::new(|_: &dyn Resolver| Ok(Box::new(Session))),
+            vec![],
+            Cacheable::of::<Session>(),
+        );
+        builder.register_transient(
+            DependencyKey::of::<UserService>(),
+            Arc::new(|_: &dyn Resolver| Ok(Box::new(UserService))),
+            vec![
+                DependencyKey::of::<Arc<Database>>(),
+                DependencyKey::of::<Arc<dyn Logger>>(),
+                DependencyKey::named::<Session>("web"),
+            ],
+        );
+        let container = builder.build().unwrap();
+        container.resolve::<Arc<Database>>().unwrap();
+
+        let expected = "Singleton (3)\n\
+                        ├── Arc<ConsoleLogger>\n\
+                        ├── Arc<Database>  (initialized)\n\
+                        │   └── Config\n\
+                        └── Config  (initialized)\n\
+                        Scoped (1)\n\
+                        └── Session(\"web\")\n\
+                        Transient (1)\n\
+                        └── UserService\n\
+                        \u{20}   ├── Arc<Database>\n\
+                        \u{20}   ├── Arc<dyn Logger> → Arc<ConsoleLogger>\n\
+                        \u{20}   └── Session(\"web\")\n\
+                        Aliases (1)\n\
+                        └── Arc<dyn Logger> → Arc<ConsoleLogger>\n";
+        assert_eq!(container.debug_tree(), expected);
+        assert_eq!(format!("{container:#?}"), expected);
+    }
+
+    #[test]
+    fn counts_registrations_by_scope() {
+        let mut builder = Container::builder()
+            .singleton_value(1u8)
+            .singleton_with(|_| Ok(2u16))
+            .scoped_with(|_| Ok(3u32))
+            .transient_with(|_| Ok(4u64))
+            .transient_with(|_| Ok(5i8));
+        builder.register_alias(DependencyKey::of::<i16>(), DependencyKey::of::<u8>());
+        let container = builder.build().unwrap();
+
+        let counts = container.count_by_scope();
+        assert_eq!(counts[&Scope::Singleton], 2);
+        assert_eq!(counts[&Scope::Scoped], 1);
+        assert_eq!(counts[&Scope::Transient], 2);
+        assert_eq!(
+            container.registrations_with_scope(Scope::Transient),
+            vec![DependencyKey::of::<u64>(), DependencyKey::of::<i8>()]
+        );
+        assert!(!container.registrations_with_scope(Scope::Singleton).contains(&DependencyKey::of::<i16>()));
+
+        let empty = Container::builder().build().unwrap();
+        assert!(empty.count_by_scope().is_empty());
+    }
+
+    #[test]
+    fn nested_construction_failure_names_who_required_it() {
+        struct Handler;
+        struct Service;
+        struct Repository;
+
+        let container = Container::builder()
+            .transient_with(|r| {
+                let _: Service = resolve(r)?;
+                Ok(Handler)
+            })
+            .transient_with(|r| {
+                let _: Repository = resolve(r)?;
+                Ok(Service)
+            })
+            .transient_with::<Repository>(|_| {
+                Err(MakhzanError::construction_failed(DependencyKey::of::<Repository>(), "connection refused"))
+            })
+            .build()
+            .unwrap();
+
+        let err = container.resolve::<Handler>().err().unwrap();
+        let MakhzanError::ConstructionFailed { key, required_by, .. } = &err else {
+            panic!("expected ConstructionFailed, got {err:?}");
+        };
+        assert_eq!(*key, DependencyKey::of::<Repository>());
+        assert_eq!(required_by, &[DependencyKey::of::<Handler>(), DependencyKey::of::<Service>()]);
+        assert!(err.to_string().ends_with("\n  Required by: Handler → Service → Repository"), "{err}");
+
+        // Resolved directly, nothing required it.
+        match container.resolve::<Repository>() {
+            Err(MakhzanError::ConstructionFailed { required_by, .. }) => assert!(required_by.is_empty()),
+            other => panic!("expected ConstructionFailed, got {:?}", other.err()),
+        }
+    }
+
+    #[test]
+    fn providers_are_torn_down_once_in_reverse_order() {
+        type Log = Arc<Mutex<Vec<&'static str>>>;
+        struct Module(&'static str, Log);
+        impl Provider for Module {
+            fn register(&self, _: &mut dyn ProviderRegistry) {}
+
+            fn teardown(&self, container: &Container) {
+                assert_eq!(container.resolve::<u32>().unwrap(), 7);
+                self.1.lock().unwrap().push(self.0);
+            }
+        }
+
+        let log = Log::default();
+        let container = Container::builder()
+            .singleton_value(7u32)
+            .add_provider_owned(Module("database", Arc::clone(&log)))
+            .add_provider_owned(Module("cache", Arc::clone(&log)))
+            .add_provider_owned(Module("http", Arc::clone(&log)))
+            .build()
+            .unwrap();
+        let clone = container.clone();
+        let rebuilt = container
+            .rebuild_with(|b| {
+                *b = std::mem::take(b).add_provider_owned(Module("metrics", Arc::clone(&log)));
+                Ok(())
+            })
+            .unwrap();
+
+        drop(container);
+        assert!(log.lock().unwrap().is_empty());
+        drop(rebuilt);
+        assert_eq!(*log.lock().unwrap(), ["metrics"]);
+        drop(clone);
+        assert_eq!(*log.lock().unwrap(), ["metrics", "http", "cache", "database"]);
+
+        let failed = Container::builder()
+            .add_provider_owned(Module("orphan", Arc::clone(&log)))
+            .transient_with_recorded(|r| Ok(resolve::<u64>(r)? as u16))
+            .build();
+        assert!(failed.is_err());
+        assert_eq!(log.lock().unwrap().len(), 4);
+    }
+
+    #[test]
+    fn provider_shared_by_two_builds_is_torn_down_once() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        struct Pool(Arc<AtomicUsize>);
+        impl Provider for Pool {
+            fn register(&self, _: &mut dyn ProviderRegistry) {}
+
+            fn teardown(&self, _: &Container) {
+                self.0.fetch_add(1, Ordering::SeqCst);
+            }
+        }
+
+        let closed = Arc::new(AtomicUsize::new(0));
+        let builder = Container::builder().add_provider_owned(Pool(Arc::clone(&closed)));
+        let first = builder.clone().build().unwrap();
+        let second = builder.build().unwrap();
+
+        drop(first);
+        assert_eq!(closed.load(Ordering::SeqCst), 0);
+        drop(second);
+        assert_eq!(closed.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn graph_stats_describe_the_built_graph() {
+        struct Handler;
+        let mut builder = Container::builder().singleton_value(1u8).transient_with_recorded(|r| {
+            let _: u16 = resolve(r)?;
+            Ok(Handler)
+        });
+        builder.register_singleton(
+            DependencyKey::of::<u16>(),
+            Arc::new(|r| Ok(Box::new(u16::from(resolve::<u8>(r)?)))),
+            vec![DependencyKey::of::<u8>()],
+        );
+        builder.register_alias(DependencyKey::of::<u32>(), DependencyKey::of::<u16>());
+        let container = builder.build().unwrap();
+
+        let stats = container.graph_stats();
+        assert_eq!(stats.registrations, 3);
+        assert_eq!(stats.by_scope, container.count_by_scope());
+        assert_eq!(
+            stats.longest_chain,
+            [DependencyKey::of::<Handler>(), DependencyKey::of::<u16>(), DependencyKey::of::<u8>()]
+        );
+        assert_eq!(stats.average_fan_out, 2.0 / 3.0);
+        assert_eq!(stats.aliases, 1);
+
+        let report = container.validation_report();
+        assert_eq!(report.validated, 3);
+        assert!(report.warnings.is_empty());
+        assert_eq!(report.topological_order.last(), Some(&DependencyKey::of::<Handler>()));
+    }
+
+    #[test]
+    fn unchecked_and_partial_builds_skip_what_they_should() {
+        struct Missing;
+        struct Service;
+        struct Clock;
+        let wiring = || {
+            let mut builder = Container::builder().singleton_value(7u8);
+            builder.register_transient(
+                DependencyKey::of::<Service>(),
+                Arc::new(|_| Ok(Box::new(Service))),
+                vec![DependencyKey::of::<Missing>()],
+            );
+            builder.register_transient(
+                DependencyKey::of::<Clock>(),
+                Arc::new(|r| Ok(Box::new(resolve::<u8>(r).map(|_| Clock)?))),
+                vec![DependencyKey::of::<u8>()],
+            );
+            builder
+        };
+
+        assert!(matches!(wiring().build(), Err(MakhzanError::NotRegistered(_))));
+
+        let unchecked = wiring().build_unchecked().unwrap();
+        assert!(unchecked.resolve::<Service>().is_ok());
+        assert!(unchecked.graph_stats().longest_chain.is_empty());
+
+        let partial = wiring().build_partial(&[DependencyKey::of::<Clock>()]).unwrap();
+        assert!(partial.resolve::<Clock>().is_ok());
+        assert_eq!(partial.graph_stats().max_depth(), 2);
+        match wiring().build_partial(&[DependencyKey::of::<Clock>(), DependencyKey::of::<Service>()]) {
+            Err(MakhzanError::NotRegistered(e)) => {
+                assert_eq!(e.requested, DependencyKey::of::<Missing>());
+                assert_eq!(e.required_by, Some(DependencyKey::of::<Service>()));
+            }
+            other => panic!("expected NotRegistered, got {:?}", other.map(|_| ())),
+        }
+        assert!(matches!(
+            wiring().build_partial(&[DependencyKey::of::<Missing>()]),
+            Err(MakhzanError::NotRegistered(_))
+        ));
+    }
+
+    #[test]
+    fn scope_validation_modes_on_a_captive_transient() {
+        use std::sync::atomic::{AtomicU32, Ordering};
+        use crate::error::ScopeMismatchError;
+        use crate::graph::ValidationWarning;
+
+        #[derive(Clone)]
+        struct Snapshot(u32);
+        let wiring = |mode: ScopeValidation| {
+            let counter = Arc::new(AtomicU32::new(0));
+            let mut builder = Container::builder()
+                .transient_with(move |_| Ok(counter.fetch_add(1, Ordering::SeqCst)))
+                .scope_validation(mode);
+            builder.register_cacheable(
+                DependencyKey::of::<Snapshot>(),
+                Scope::Singleton,
+                Arc::new(|r| Ok(Box::new(Snapshot(resolve(r)?)))),
+                vec![DependencyKey::of::<u32>()],
+                Cacheable::of::<Snapshot>(),
+            );
+            builder.build()
+        };
+
+        match wiring(ScopeValidation::Deny) {
+            Err(MakhzanError::ScopeMismatch(e)) => {
+                assert_eq!(e.consumer, DependencyKey::of::<Snapshot>());
+                assert_eq!(e.dependency_scope, Scope::Transient);
+            }
+            other => panic!("expected ScopeMismatch, got {:?}", other.map(|_| ())),
+        }
+
+        let warned = wiring(ScopeValidation::Warn).unwrap();
+        let mismatch = ScopeMismatchError {
+            dependency: DependencyKey::of::<u32>(),
+            dependency_scope: Scope::Transient,
+            consumer: DependencyKey::of::<Snapshot>(),
+            consumer_scope: Scope::Singleton,
+        };
+        assert_eq!(warned.validation_report().warnings, [ValidationWarning::ScopeMismatch(mismatch.clone())]);
+        assert_eq!(warned.validation_report().warnings[0].to_string(), mismatch.to_string());
+        assert_eq!(warned.resolve::<Snapshot>().unwrap().0, 0);
+        assert_eq!(warned.resolve::<Snapshot>().unwrap().0, 0);
+        assert!(warned.assert_resolvable::<Snapshot>().is_ok());
+
+        let off = wiring(ScopeValidation::Off).unwrap();
+        assert!(off.validation_report().warnings.is_empty());
+        assert_eq!(off.resolve::<Snapshot>().unwrap().0, 0);
+    }
+
+    #[test]
+    fn bind_upcasts_to_the_trait_object_in_the_implementations_scope() {
+        trait Logger: Send + Sync {
+            fn id(&self) -> usize;
+        }
+        crate::upcast!(Logger);
+        struct Console;
+        impl Logger for Console {
+            fn id(&self) -> usize {
+                self as *const Self as usize
+            }
+        }
+        #[derive(Clone)]
+        struct Audit(Arc<dyn Logger>);
+
+        let container = Container::builder()
+            .bind::<dyn Logger, Console>()
+            .singleton_arc(|_| Ok(Console))
+            .singleton_with(|r| Ok(Audit(resolve(r)?)))
+            .transient_with_recorded(|r| resolve::<Audit>(r).map(|audit| audit.0.id()))
+            .build()
+            .unwrap();
+        let concrete: Arc<Console> = container.resolve().unwrap();
+        let logger: Arc<dyn Logger> = container.resolve().unwrap();
+        assert_eq!(logger.id(), concrete.id());
+        assert_eq!(container.resolve::<usize>().unwrap(), concrete.id());
+        assert_eq!(container.describe(&DependencyKey::of::<Arc<dyn Logger>>()).unwrap().scope, Scope::Singleton);
+
+        let scoped = Container::builder()
+            .scoped_with(|_| Ok(Arc::new(Console)))
+            .bind::<dyn Logger, Console>()
+            .build()
+            .unwrap();
+        let scope = scoped.create_scope();
+        let logger: Arc<dyn Logger> = scope.resolve().unwrap();
+        assert_eq!(logger.id(), scope.resolve::<Arc<Console>>().unwrap().id());
+        assert_ne!(logger.id(), scoped.create_scope().resolve::<Arc<dyn Logger>>().unwrap().id());
+
+        match Container::builder().bind::<dyn Logger, Console>().build() {
+            Err(MakhzanError::NotRegistered(e)) => {
+                assert_eq!(e.requested, DependencyKey::of::<Arc<Console>>());
+                assert_eq!(e.required_by, Some(DependencyKey::of::<Arc<dyn Logger>>()));
+            }
+            other => panic!("expected NotRegistered, got {:?}", other.map(|_| ())),
+        }
+    }
+
+    #[test]
+    fn resolving_t_for_an_arc_t_registration_is_a_type_mismatch() {
+        struct Database;
+
+        let mut builder = Container::builder();
+        builder.register_cacheable(
+            DependencyKey::of::<Database>(),
+            Scope::Singleton,
+            Arc::new(|_| Ok(Box::new(Arc::new(Database)))),
+            vec![],
+            Cacheable::of::<Arc<Database>>(),
+        );
+        let container = builder.build().unwrap();
+
+        let err = container.resolve::<Database>().map(|_| ()).unwrap_err();
+        match &err {
+            MakhzanError::TypeMismatch { key, expected, actual } => {
+                assert_eq!(*key, DependencyKey::of::<Database>());
+                assert_eq!(*expected, type_name::<Database>());
+                assert_eq!(*actual, Some(type_name::<Arc<Database>>()));
+            }
+            other => panic!("expected TypeMismatch, got {other:?}"),
+        }
+        let message = err.to_string();
+        assert!(message.contains(&format!("found {}", type_name::<Arc<Database>>())), "{message}");
+        assert!(message.contains("Hint: The usual cause is registering T but resolving Arc<T>"), "{message}");
+        assert!(matches!(
+            container.with_singleton::<Database, _>(|_| ()),
+            Err(MakhzanError::TypeMismatch { actual: Some(_), .. })
+        ));
+    }
+
+    #[test]
+    fn uncached_type_mismatch_has_no_actual_type() {
+        let mut builder = Container::builder();
+        builder.register_transient(DependencyKey::named::<u8>("level"), Arc::new(|_| Ok(Box::new(3u32))), vec![]);
+        let container = builder.build().unwrap();
+
+        let err = container.resolve_named::<u8>("level").unwrap_err();
+        assert!(matches!(err, MakhzanError::TypeMismatch { expected: "u8", actual: None, .. }), "{err:?}");
+        assert!(!err.to_string().contains("found"));
+    }
+
+    #[test]
+    fn profile_selects_the_matching_binding() {
+        trait Mailer: Send + Sync {
+            fn kind(&self) -> &'static str;
+        }
+        struct LogMailer;
+        struct SmtpMailer;
+        struct FakeMailer;
+        impl Mailer for LogMailer {
+            fn kind(&self) -> &'static str {
+                "log"
+            }
+        }
+        impl Mailer for SmtpMailer {
+            fn kind(&self) -> &'static str {
+                "smtp"
+            }
+        }
+        impl Mailer for FakeMailer {
+            fn kind(&self) -> &'static str {
+                "fake"
+            }
+        }
+
+        let wiring = |profile: Option<&str>| {
+            let builder = Container::builder()
+                .register_for::<Arc<dyn Mailer>>(&["prod"], |_| Ok(Arc::new(SmtpMailer)))
+                .register_for::<Arc<dyn Mailer>>(&["test", "ci"], |_| Ok(Arc::new(FakeMailer)))
+                .singleton_with::<Arc<dyn Mailer>>(|_| Ok(Arc::new(LogMailer)));
+            let builder = match profile {
+                Some(profile) => builder.profile(profile),
+                None => builder,
+            };
+            let mailer: Arc<dyn Mailer> = builder.build().unwrap().resolve().unwrap();
+            mailer.kind()
+        };
+
+        assert_eq!(wiring(Some("prod")), "smtp");
+        assert_eq!(wiring(Some("test")), "fake");
+        assert_eq!(wiring(Some("ci")), "fake");
+        assert_eq!(wiring(Some("dev")), "log");
+        assert_eq!(wiring(None), "log");
+    }
+
+    #[test]
+    fn unmatched_profile_falls_back_to_default_or_not_registered() {
+        #[derive(Clone, Default, Debug, PartialEq)]
+        struct Retries(u32);
+
+        let container = Container::builder()
+            .profile("dev")
+            .register_for(&["prod"], |_| Ok(Retries(5)))
+            .register_default_impl::<Retries>()
+            .build()
+            .unwrap();
+        assert_eq!(container.resolve::<Retries>().unwrap(), Retries(0));
+
+        let container = Container::builder()
+            .register_default_impl::<Retries>()
+            .register_for(&["prod"], |_| Ok(Retries(5)))
+            .profile("prod")
+            .build()
+            .unwrap();
+        assert_eq!(container.resolve::<Retries>().unwrap(), Retries(5));
+
+        let container = Container::builder()
+            .profile("dev")
+            .register_for(&["prod"], |_| Ok(Retries(5)))
+            .build()
+            .unwrap();
+        assert!(matches!(container.resolve::<Retries>(), Err(MakhzanError::NotRegistered(_))));
+    }
+
+    #[test]
+    fn resolving_the_pointee_of_a_registered_arc_hints_at_the_arc() {
+        struct Database;
+        #[derive(Clone)]
+        struct DatabaseConfig;
+
+        let container = Container::builder()
+            .singleton_arc(|_| Ok(Database))
+            .singleton_value(DatabaseConfig)
+            .build()
+            .unwrap();
+
+        match container.resolve::<Database>().map(|_| ()) {
+            Err(MakhzanError::NotRegistered(e)) => {
+                assert_eq!(e.suggestions[0], DependencyKey::of::<Arc<Database>>());
+                let msg = e.to_string();
+                assert!(msg.contains("Hint: Arc<Database> is registered — did you mean resolve::<Arc<Database>>()?"), "{msg}");
+                assert!(!msg.contains("Did you forget"), "{msg}");
+            }
+            other => panic!("expected NotRegistered, got {:?}", other.err()),
+        }
+    }
+
+    #[test]
+    fn resolving_an_arc_of_a_registered_value_hints_at_the_value() {
+        #[derive(Clone)]
+        struct Settings;
+
+        let err = Container::builder()
+            .singleton_value(Settings)
+            .build()
+            .unwrap()
+            .resolve::<Arc<Settings>>()
+            .map(|_| ())
+            .unwrap_err();
+        let msg = err.to_string();
+        assert!(msg.contains("Hint: Settings is registered — did you mean resolve::<Settings>()?"), "{msg}");
+    }
+
+    #[test]
+    fn validation_hints_at_a_pointer_mismatch() {
+        #[derive(Clone)]
+        struct Database;
+        struct Repository;
+
+        let mut builder = Container::builder()
+            .singleton_value(Database)
+            .suggestions(SuggestionConfig { max: 1, min_score: u32::MAX });
+        builder.register_transient(
+            DependencyKey::of::<Repository>(),
+            Arc::new(|_| Ok(Box::new(Repository))),
+            vec![DependencyKey::of::<Arc<Database>>()],
+        );
+
+        let msg = builder.build().map(|_| ()).unwrap_err().to_string();
+        assert!(msg.contains("Hint: Database is registered — did you mean resolve::<Database>()?"), "{msg}");
+    }
+
+    #[test]
+    fn resolve_boxed_by_runtime_key() {
+        use std::sync::atomic::{AtomicU32, Ordering};
+
+        #[derive(Clone)]
+        struct RequestId(u32);
+
+        let counter = Arc::new(AtomicU32::new(0));
+        let container = Container::builder()
+            .singleton_value(String::from("postgres://localhost"))
+            .scoped_with({
+                let counter = counter.clone();
+                move |_| Ok(RequestId(counter.fetch_add(1, Ordering::SeqCst)))
+            })
+            .build()
+            .unwrap();
+
+        let key = DependencyKey::of::<String>();
+        let url = container.resolve_boxed(&key).unwrap();
+        assert_eq!(*url.downcast::<String>().unwrap(), "postgres://localhost");
+
+        let scope = container.create_scope();
+        let key = DependencyKey::of::<RequestId>();
+        let first = scope.resolve_boxed(&key).unwrap().downcast::<RequestId>().unwrap();
+        let second = scope.resolve_boxed(&key).unwrap().downcast::<RequestId>().unwrap();
+        assert_eq!((first.0, second.0), (0, 0));
+
+        assert!(matches!(
+            container.resolve_boxed(&DependencyKey::of::<u8>()),
+            Err(MakhzanError::NotRegistered(_))
+        ));
+    }
+
+    #[test]
+    fn factory_errors_stay_downcastable() {
+        use std::error::Error;
+
+        #[derive(Debug, PartialEq)]
+        struct ConnectError {
+            host: &'static str,
+        }
+        impl fmt::Display for ConnectError {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, "cannot reach {}", self.host)
+            }
+        }
+        impl Error for ConnectError {}
+
+        struct Pool;
+        struct Repository;
+        let connect = |_: &dyn Resolver| -> Result<Arc<Pool>> {
+            Err(MakhzanError::construction_failed(
+                DependencyKey::of::<Arc<Pool>>(),
+                ConnectError { host: "db:5432" },
+            ))
+        };
+
+        let container = Container::builder()
+            .singleton_with(connect)
+            .transient_with_recorded(|r| {
+                let _: Arc<Pool> = resolve(r)?;
+                Ok(Arc::new(Repository))
+            })
+            .build()
+            .unwrap();
+        let err = container.resolve::<Arc<Repository>>().map(|_| ()).unwrap_err();
+        assert_eq!(err.source().unwrap().downcast_ref(), Some(&ConnectError { host: "db:5432" }));
+        assert_eq!(err.root_cause().downcast_ref(), Some(&ConnectError { host: "db:5432" }));
+
+        let container = Container::builder()
+            .singleton_with_policy(SingletonFailurePolicy::CacheError, connect)
+            .build()
+            .unwrap();
+        container.resolve::<Arc<Pool>>().map(|_| ()).unwrap_err();
+        let warm_up = container.warm_up(&[DependencyKey::of::<Arc<Pool>>()]).unwrap_err();
+        assert!(matches!(warm_up, MakhzanError::WarmUpFailed { .. }));
+        assert_eq!(warm_up.root_cause().downcast_ref(), Some(&ConnectError { host: "db:5432" }));
+        assert!(MakhzanError::NoCurrentScope.root_cause().is::<MakhzanError>());
+    }
+
+    #[test]
+    fn resolver_ext_resolves_named_and_optional_dependencies() {
+        #[derive(Clone)]
+        struct Metrics;
+        struct Repository {
+            primary: Arc<String>,
+            replica: Arc<String>,
+            metrics: Option<Metrics>,
+        }
+
+        let mut builder = Container::builder();
+        builder.register_singleton(
+            DependencyKey::named::<Arc<String>>("primary"),
+            Arc::new(|_| Ok(Box::new(Arc::new(String::from("db-1"))))),
+            vec![],
+        );
+        builder.register_singleton(
+            DependencyKey::named::<Arc<String>>("replica"),
+            Arc::new(|_| Ok(Box::new(Arc::new(String::from("db-2"))))),
+            vec![],
+        );
+        let repository = |r: &dyn Resolver| {
+            Ok(Repository {
+                primary: r.resolve_named("primary")?,
+                replica: r.resolve_named(&format!("{}ca", "repli"))?,
+                metrics: r.resolve_opt()?,
+            })
+        };
+
+        let without = builder.clone().transient_with(repository).build().unwrap();
+        let repo: Repository = without.resolve().unwrap();
+        assert_eq!((repo.primary.as_str(), repo.replica.as_str()), ("db-1", "db-2"));
+        assert!(repo.metrics.is_none());
+
+        let with = builder.singleton_value(Metrics).transient_with(repository).build().unwrap();
+        let scope = with.create_scope();
+        assert!(scope.resolve::<Repository>().unwrap().metrics.is_some());
+
+        let failing = Container::builder()
+            .transient_with::<Metrics>(|_| Err(MakhzanError::NoCurrentScope))
+            .transient_with(|r| r.resolve_opt::<Metrics>())
+            .build()
+            .unwrap();
+        assert!(matches!(failing.resolve::<Option<Metrics>>(), Err(MakhzanError::NoCurrentScope)));
+        let missing = Container::builder()
+            .transient_with(|r| r.resolve_named::<u8>("level"))
+            .build()
+            .unwrap();
+        assert!(matches!(missing.resolve::<u8>(), Err(MakhzanError::NotRegistered(_))));
+    }
+
+    #[test]
+    fn set_scope_changes_how_often_the_factory_runs() {
+        use std::sync::atomic::{AtomicU32, Ordering};
+
+        struct Clock;
+        let calls = Arc::new(AtomicU32::new(0));
+        let builder = Container::builder().singleton_with({
+            let calls = calls.clone();
+            move |_| {
+                calls.fetch_add(1, Ordering::SeqCst);
+                Ok(Arc::new(Clock))
+            }
+        });
+
+        let transient = builder.clone().set_scope::<Arc<Clock>>(Scope::Transient).unwrap().build().unwrap();
+        let a: Arc<Clock> = transient.resolve().unwrap();
+        let b: Arc<Clock> = transient.resolve().unwrap();
+        assert!(!Arc::ptr_eq(&a, &b));
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+        assert_eq!(transient.graph_stats().by_scope.get(&Scope::Transient), Some(&1));
+
+        let singleton = Container::builder()
+            .transient_with({
+                let calls = calls.clone();
+                move |_| {
+                    calls.fetch_add(1, Ordering::SeqCst);
+                    Ok(Arc::new(Clock))
+                }
+            })
+            .set_scope::<Arc<Clock>>(Scope::Singleton)
+            .unwrap()
+            .build()
+            .unwrap();
+        let a: Arc<Clock> = singleton.resolve().unwrap();
+        let b: Arc<Clock> = singleton.resolve().unwrap();
+        assert!(Arc::ptr_eq(&a, &b));
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+
+        let original = builder.build().unwrap();
+        assert!(Arc::ptr_eq(&original.resolve::<Arc<Clock>>().unwrap(), &original.resolve().unwrap()));
+    }
+
+    #[test]
+    fn set_scope_is_validated_at_build() {
+        struct Session;
+        struct Service;
+
+        let mut builder = Container::builder().singleton_with(|_| Ok(Arc::new(Session)));
+        builder.register_singleton(
+            DependencyKey::of::<Service>(),
+            Arc::new(|_| Ok(Box::new(Service))),
+            vec![DependencyKey::of::<Arc<Session>>()],
+        );
+        assert!(builder.clone().build().is_ok());
+        let scoped = builder.set_scope::<Arc<Session>>(Scope::Scoped).unwrap();
+        assert!(matches!(scoped.build(), Err(MakhzanError::ScopeMismatch(_))));
+
+        assert!(matches!(
+            Container::builder().set_scope::<u8>(Scope::Singleton).map(|_| ()),
+            Err(MakhzanError::NotRegistered(e)) if e.requested == DependencyKey::of::<u8>()
+        ));
+    }
+
+    #[test]
+    fn collection_elements_resolve_in_order_with_their_own_scope() {
+        trait Handler: Send + Sync {
+            fn name(&self) -> String;
+        }
+        struct Audit;
+        struct Mail(Arc<String>);
+        impl Handler for Audit {
+            fn name(&self) -> String {
+                "audit".into()
+            }
+        }
+        impl Handler for Mail {
+            fn name(&self) -> String {
+                format!("mail via {}", self.0)
+            }
+        }
+
+        let container = Container::builder()
+            .singleton_value(Arc::new(String::from("smtp")))
+            .add_element(Scope::Singleton, vec![], |_| Ok(Arc::new(Audit) as Arc<dyn Handler>))
+            .add_element(Scope::Transient, vec![DependencyKey::of::<Arc<String>>()], |r| {
+                Ok(Arc::new(Mail(resolve(r)?)) as Arc<dyn Handler>)
+            })
+            .build()
+            .unwrap();
+
+        let first: Vec<Arc<dyn Handler>> = container.resolve_all().unwrap();
+        let names: Vec<String> = first.iter().map(|handler| handler.name()).collect();
+        assert_eq!(names, ["audit", "mail via smtp"]);
+        let second: Vec<Arc<dyn Handler>> = container.create_scope().resolve_all().unwrap();
+        assert!(Arc::ptr_eq(&first[0], &second[0]));
+        assert!(!Arc::ptr_eq(&first[1], &second[1]));
+
+        let collection = DependencyKey::of::<Vec<Arc<dyn Handler>>>();
+        assert_eq!(container.graph_stats().by_scope.get(&Scope::Transient), Some(&2));
+        assert!(container.dependency_graph().nodes.iter().any(|node| node.id == collection.to_string()));
+        assert!(container.resolve_all::<Arc<String>>().unwrap().is_empty());
+    }
+
+    #[test]
+    fn collection_element_with_a_missing_dependency_fails_build() {
+        struct Mailer;
+
+        let err = Container::builder()
+            .add_element(Scope::Transient, vec![], |_| Ok(1u8))
+            .add_element(Scope::Transient, vec![], |_| Ok(2u8))
+            .add_element(Scope::Transient, vec![DependencyKey::of::<Arc<Mailer>>()], |_| Ok(3u8))
+            .build()
+            .map(|_| ())
+            .unwrap_err();
+
+        match &err {
+            MakhzanError::NotRegistered(e) => {
+                assert_eq!(e.required_by, Some(DependencyKey::element::<u8>(2)));
+            }
+            other => panic!("expected NotRegistered, got {other:?}"),
+        }
+        assert!(
+            err.to_string().contains("Required while constructing element #2 of collection alloc::vec::Vec<u8>"),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn collection_elements_are_scope_checked() {
+        #[derive(Clone)]
+        struct Session;
+
+        let err = Container::builder()
+            .scoped_with(|_| Ok(Session))
+            .add_element(Scope::Singleton, vec![DependencyKey::of::<Session>()], |_| Ok(0u8))
+            .build()
+            .map(|_| ())
+            .unwrap_err();
+        match err {
+            MakhzanError::ScopeMismatch(e) => assert_eq!(e.consumer, DependencyKey::element::<u8>(0)),
+            other => panic!("expected ScopeMismatch, got {other:?}"),
+        }
+
+        let container = Container::builder()
+            .scoped_with(|_| Ok(Session))
+            .add_element(Scope::Singleton, vec![], |_| Ok(0u8))
+            .add_element(Scope::Scoped, vec![DependencyKey::of::<Session>()], |_| Ok(1u8))
+            .build()
+            .unwrap();
+        assert_eq!(container.create_scope().resolve::<Vec<u8>>().unwrap(), [0, 1]);
+    }
+
+    trait PaymentStrategy: Send + Sync {
+        fn fee(&self) -> u32;
     }
 
-    /// Internal resolve — returns type-erased value.
-    fn resolve_internal(
-        &self,
-        key: &DependencyKey,
-    ) -> Result<Box<dyn Any + Send + Sync>> {
-        let registration = self.registry.get(key).ok_or_else(|| {
-            MakhzanError::NotRegistered(NotRegisteredError {
-                requested: key.clone(),
-                required_by: None,
-                suggestions: self.find_suggestions(key),
-            })
-        })?;
+    struct Stripe(u32);
+    struct PayPal;
 
-        let resolver = ContainerResolver { container: self };
-        (registration.factory)(&resolver)
+    impl PaymentStrategy for Stripe {
+        fn fee(&self) -> u32 {
+            self.0
+        }
     }
 
-    fn find_suggestions(&self, key: &DependencyKey) -> Vec<DependencyKey> {
-        let target = key.type_name().to_lowercase();
-        self.registry
-            .registered_keys()
-            .into_iter()
-            .filter(|k| {
-                if k == key {
-                    return false;
-                }
-                let name = k.type_name().to_lowercase();
-                name.contains(&target) || target.contains(&name)
-            })
-            .collect()
+    impl PaymentStrategy for PayPal {
+        fn fee(&self) -> u32 {
+            5
+        }
     }
-}
 
-impl fmt::Debug for Container {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        f.debug_struct("Container")
-            .field("registered", &self.registry.len())
-            .finish()
+    fn payments() -> ContainerBuilder {
+        Container::builder()
+            .singleton_value(3u32)
+            .add_keyed::<Arc<dyn PaymentStrategy>>("stripe", |r| Ok(Arc::new(Stripe(resolve(r)?))))
+            .add_keyed::<Arc<dyn PaymentStrategy>>("paypal", |_| Ok(Arc::new(PayPal)))
     }
-}
 
-// ═══════════════════════════════════════════
-// ScopedContainer
-// ═══════════════════════════════════════════
+    #[test]
+    fn keyed_map_resolves_whole_and_by_name() {
+        struct Router(HashMap<&'static str, Arc<dyn PaymentStrategy>>);
 
-/// A scoped child container.
-///
-/// Currently delegates to parent. Per-scope caching is Phase 2.
-pub struct ScopedContainer<'a> {
-    parent: &'a Container,
-}
+        let container = payments()
+            .singleton_with(|r| Ok(Arc::new(Router(resolve(r)?))))
+            .build()
+            .unwrap();
 
-impl ScopedContainer<'_> {
-    /// Resolve a dependency within this scope.
-    pub fn resolve<T: Send + Sync + 'static>(&self) -> Result<T> {
-        // Phase 2: per-scope caching for Scope::Scoped
-        self.parent.resolve::<T>()
+        let map = container.resolve_map::<Arc<dyn PaymentStrategy>>().unwrap();
+        let mut fees: Vec<(&str, u32)> = map.iter().map(|(name, strategy)| (*name, strategy.fee())).collect();
+        fees.sort();
+        assert_eq!(fees, [("paypal", 5), ("stripe", 3)]);
+
+        let stripe = container.resolve_keyed::<Arc<dyn PaymentStrategy>>("stripe").unwrap();
+        assert!(Arc::ptr_eq(&stripe, &map["stripe"]));
+        assert!(Arc::ptr_eq(&stripe, &container.resolve::<Arc<Router>>().unwrap().0["stripe"]));
+        assert!(Arc::ptr_eq(&stripe, &container.create_scope().resolve_keyed("stripe").unwrap()));
+        assert!(container.resolve_map::<Arc<String>>().unwrap().is_empty());
     }
-}
 
-impl fmt::Debug for ScopedContainer<'_> {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        f.debug_struct("ScopedContainer").finish()
+    #[test]
+    fn keyed_map_rejects_duplicate_names_unless_overriding() {
+        let err = payments()
+            .add_keyed::<Arc<dyn PaymentStrategy>>("stripe", |_| Ok(Arc::new(Stripe(9))))
+            .build()
+            .map(|_| ())
+            .unwrap_err();
+        match err {
+            MakhzanError::AlreadyRegistered(e) => {
+                assert_eq!(e.key, DependencyKey::named::<Arc<dyn PaymentStrategy>>("stripe"));
+            }
+            other => panic!("expected AlreadyRegistered, got {other:?}"),
+        }
+
+        let container = payments()
+            .allow_override(true)
+            .add_keyed::<Arc<dyn PaymentStrategy>>("stripe", |_| Ok(Arc::new(Stripe(9))))
+            .build()
+            .unwrap();
+        assert_eq!(container.resolve_keyed::<Arc<dyn PaymentStrategy>>("stripe").unwrap().fee(), 9);
+        assert_eq!(container.resolve_map::<Arc<dyn PaymentStrategy>>().unwrap().len(), 2);
     }
-}
 
-// ═══════════════════════════════════════════
-// ContainerResolver (internal bridge)
-// ═══════════════════════════════════════════
+    #[test]
+    fn unknown_map_key_suggests_similar_names() {
+        let container = payments().build().unwrap();
 
-/// Internal resolver passed to factory functions.
-struct ContainerResolver<'a> {
-    container: &'a Container,
-}
+        let err = container.resolve_keyed::<Arc<dyn PaymentStrategy>>("strype").map(|_| ()).unwrap_err();
+        match &err {
+            MakhzanError::UnknownMapKey(e) => {
+                assert_eq!(e.suggestions, ["stripe"]);
+                assert_eq!(e.available, ["paypal", "stripe"]);
+            }
+            other => panic!("expected UnknownMapKey, got {other:?}"),
+        }
+        assert_eq!(
+            err.to_string(),
+            "No Arc<dyn PaymentStrategy> entry named \"strype\" in the keyed map\n  Did you mean one of:\n    - stripe\n  Available: paypal, stripe"
+        );
 
-impl Resolver for ContainerResolver<'_> {
-    fn resolve_key(
-        &self,
-        key: &DependencyKey,
-    ) -> Result<Box<dyn Any + Send + Sync>> {
-        self.container.resolve_internal(key)
+        let err = container.resolve_keyed::<Arc<String>>("stripe").map(|_| ()).unwrap_err();
+        assert!(err.to_string().ends_with("Hint: The map has no entries; add them with .add_keyed()"), "{err}");
     }
-}
 
-// ═══════════════════════════════════════════
-// Free function for use inside factories
-// ═══════════════════════════════════════════
+    #[test]
+    fn resolve_iter_builds_elements_only_as_far_as_it_is_driven() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
 
-/// Resolve a typed dependency from a [`Resolver`].
-///
-/// Use this inside factory closures:
-///
-/// ```rust,ignore
-/// builder.singleton_with::<MyService>(|r| {
-///     let db: Arc<Database> = makhzan_container::container::resolve(r)?;
-///     Ok(MyService { db })
-/// })
-/// ```
-pub fn resolve<T: Send + Sync + 'static>(resolver: &dyn Resolver) -> Result<T> {
-    let key = DependencyKey::of::<T>();
-    let boxed = resolver.resolve_key(&key)?;
-    boxed.downcast::<T>().map(|b| *b).map_err(|_| {
-        MakhzanError::ConstructionFailed {
-            key,
-            source: format!(
-                "Type mismatch: expected {}",
-                type_name::<T>()
-            )
-            .into(),
-        }
-    })
-}
+        let built = Arc::new(AtomicUsize::new(0));
+        let backend = |healthy: bool| {
+            let built = built.clone();
+            move |_: &dyn Resolver| {
+                built.fetch_add(1, Ordering::SeqCst);
+                Ok(healthy)
+            }
+        };
+        let container = Container::builder()
+            .add_element(Scope::Transient, vec![], backend(false))
+            .add_element(Scope::Transient, vec![], backend(true))
+            .add_element(Scope::Transient, vec![], backend(true))
+            .build()
+            .unwrap();
 
-// ═══════════════════════════════════════════
-// Prelude
-// ═══════════════════════════════════════════
+        let first_healthy = container.resolve_iter::<bool>().position(|healthy| healthy.unwrap());
+        assert_eq!(first_healthy, Some(1));
+        assert_eq!(built.load(Ordering::SeqCst), 2);
 
-pub mod prelude {
-    pub use super::{resolve, Container, ContainerBuilder, ScopedContainer};
-    pub use crate::error::{MakhzanError, Result};
-    pub use crate::key::DependencyKey;
-    pub use crate::provider::Provider;
-    pub use crate::scope::Scope;
-}
+        let scope = container.create_scope();
+        assert_eq!(scope.resolve_iter::<bool>().collect::<Result<Vec<_>>>().unwrap(), [false, true, true]);
+        assert_eq!(built.load(Ordering::SeqCst), 5);
+        assert_eq!(container.resolve_iter::<u8>().count(), 0);
+    }
 
-// ═══════════════════════════════════════════
-// Tests
-// ═══════════════════════════════════════════
+    #[test]
+    fn also_as_projects_views_that_share_the_singletons_lifetime() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+        #[derive(Clone, Debug, PartialEq)]
+        struct DbConfig(String);
+        #[derive(Clone, Debug, PartialEq)]
+        struct AuthConfig(u64);
+        #[derive(Clone)]
+        struct AppConfig {
+            database: DbConfig,
+            auth: AuthConfig,
+        }
+
+        let loads = Arc::new(AtomicUsize::new(0));
+        let projections = Arc::new(AtomicUsize::new(0));
+        let container = Container::builder()
+            .also_as({
+                let projections = projections.clone();
+                move |config: &AppConfig| {
+                    projections.fetch_add(1, Ordering::SeqCst);
+                    config.database.clone()
+                }
+            })
+            .singleton_with({
+                let loads = loads.clone();
+                move |_| {
+                    loads.fetch_add(1, Ordering::SeqCst);
+                    Ok(AppConfig { database: DbConfig("postgres://db".into()), auth: AuthConfig(3600) })
+                }
+            })
+            .also_as(|config: &AppConfig| config.auth.clone())
+            .build()
+            .unwrap();
+
+        assert_eq!(container.resolve::<DbConfig>().unwrap(), DbConfig("postgres://db".into()));
+        assert_eq!(container.resolve::<DbConfig>().unwrap(), DbConfig("postgres://db".into()));
+        assert_eq!(container.resolve::<AuthConfig>().unwrap(), AuthConfig(3600));
+        assert_eq!(container.resolve::<AppConfig>().unwrap().auth, AuthConfig(3600));
+        assert_eq!(loads.load(Ordering::SeqCst), 1);
+        assert_eq!(projections.load(Ordering::SeqCst), 1);
+        assert_eq!(container.describe(&DependencyKey::of::<DbConfig>()).unwrap().scope, Scope::Singleton);
+
+        let transient = Container::builder()
+            .transient_with(|_| Ok(7u32))
+            .also_as(|n: &u32| u64::from(*n) * 6)
+            .build()
+            .unwrap();
+        assert_eq!(transient.resolve::<u64>().unwrap(), 42);
+        assert_eq!(transient.describe(&DependencyKey::of::<u64>()).unwrap().scope, Scope::Transient);
+    }
 
     #[test]
-    fn resolve_singleton_value() {
+    fn also_as_borrows_the_source_instead_of_cloning_it() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        struct Tracked(Arc<AtomicUsize>);
+        impl Clone for Tracked {
+            fn clone(&self) -> Self {
+                self.0.fetch_add(1, Ordering::SeqCst);
+                Tracked(self.0.clone())
+            }
+        }
+        struct Settings {
+            name: String,
+        }
+        #[derive(Clone, Debug, PartialEq)]
+        struct Name(String);
+
+        let clones = Arc::new(AtomicUsize::new(0));
         let container = Container::builder()
-            .singleton_value(42i32)
+            .singleton_with({
+                let clones = clones.clone();
+                move |_| Ok(Tracked(clones.clone()))
+            })
+            .also_as(|tracked: &Tracked| tracked.0.load(Ordering::SeqCst))
+            .singleton_ref_with(|_| Ok(Settings { name: "makhzan".into() }))
+            .also_as(|settings: &Settings| Name(settings.name.clone()))
             .build()
             .unwrap();
 
-        let value: i32 = container.resolve().unwrap();
-        assert_eq!(value, 42);
+        assert_eq!(container.resolve::<usize>().unwrap(), 0);
+        assert_eq!(clones.load(Ordering::SeqCst), 0);
+        assert_eq!(container.resolve::<Name>().unwrap(), Name("makhzan".into()));
+    }
 
-        // Resolve again — same value
-        let value2: i32 = container.resolve().unwrap();
-        assert_eq!(value2, 42);
+    #[test]
+    fn resetting_a_singleton_resets_its_bind_and_also_as_views() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        trait Counter: Send + Sync {
+            fn value(&self) -> usize;
+        }
+        crate::upcast!(Counter);
+        struct Loaded(usize);
+        impl Counter for Loaded {
+            fn value(&self) -> usize {
+                self.0
+            }
+        }
+        #[derive(Clone, Debug, PartialEq)]
+        struct View(usize);
+
+        let loads = AtomicUsize::new(0);
+        let container = Container::builder()
+            .singleton_arc(move |_| Ok(Loaded(loads.fetch_add(1, Ordering::SeqCst))))
+            .bind::<dyn Counter, Loaded>()
+            .also_as(|counter: &Arc<dyn Counter>| View(counter.value()))
+            .build()
+            .unwrap();
+
+        assert_eq!(container.resolve::<View>().unwrap(), View(0));
+        assert!(container.reset_singleton::<Arc<Loaded>>().unwrap());
+        assert_eq!(container.resolve::<Arc<dyn Counter>>().unwrap().value(), 1);
+        assert_eq!(container.resolve::<View>().unwrap(), View(1));
     }
 
     #[test]
-    fn resolve_singleton_string() {
+    fn resolve_arc_prefers_a_registered_arc_and_wraps_otherwise() {
+        struct Pool;
+        struct Request;
+
         let container = Container::builder()
-            .singleton_value(String::from("hello"))
+            .singleton_with(|_| Ok(Arc::new(Pool)))
+            .transient_with(|_| Ok(Request))
             .build()
             .unwrap();
 
-        let s: String = container.resolve().unwrap();
-        assert_eq!(s, "hello");
+        let pool = container.resolve_arc::<Pool>().unwrap();
+        assert!(Arc::ptr_eq(&pool, &container.resolve_arc::<Pool>().unwrap()));
+        assert!(Arc::ptr_eq(&pool, &container.create_scope().resolve_arc::<Pool>().unwrap()));
+        let request = container.resolve_arc::<Request>().unwrap();
+        assert!(!Arc::ptr_eq(&request, &container.resolve_arc::<Request>().unwrap()));
+
+        match container.resolve_arc::<String>().map(|_| ()).unwrap_err() {
+            MakhzanError::NotRegistered(e) => assert_eq!(e.requested, DependencyKey::of::<String>()),
+            other => panic!("expected NotRegistered, got {other:?}"),
+        }
     }
 
     #[test]
-    fn resolve_transient_creates_new_each_time() {
-        use std::sync::atomic::{AtomicU32, Ordering};
+    fn owned_scope_resolve_arc_shares_scoped_arcs_per_scope() {
+        struct Session;
 
-        let counter = Arc::new(AtomicU32::new(0));
+        let container = Container::builder().scoped_with(|_| Ok(Arc::new(Session))).build().unwrap();
+
+        let scope = container.create_owned_scope();
+        let session = scope.resolve_arc::<Session>().unwrap();
+        assert!(Arc::ptr_eq(&session, &scope.resolve_arc::<Session>().unwrap()));
+        assert!(Arc::ptr_eq(&session, &scope.clone().resolve::<Arc<Session>>().unwrap()));
+        assert!(!Arc::ptr_eq(&session, &container.create_owned_scope().resolve_arc::<Session>().unwrap()));
+    }
+
+    #[test]
+    fn owned_scope_resolves_collections_maps_and_child_scopes_in_the_scope() {
+        #[derive(Clone)]
+        struct Session(Arc<()>);
 
         let container = Container::builder()
-            .transient_with::<u32>({
-                let counter = counter.clone();
-                move |_| {
-                    Ok(counter.fetch_add(1, Ordering::SeqCst))
-                }
-            })
+            .scoped_with(|_| Ok(Session(Arc::new(()))))
+            .add_element(Scope::Scoped, vec![DependencyKey::of::<Session>()], |r| Ok(resolve::<Session>(r)?.0))
+            .add_element(Scope::Transient, vec![], |_| Ok(Arc::new(())))
+            .add_keyed("primary", |_| Ok(Arc::new(())))
             .build()
             .unwrap();
 
-        let a: u32 = container.resolve().unwrap();
-        let b: u32 = container.resolve().unwrap();
-        let c: u32 = container.resolve().unwrap();
+        let scope = container.create_owned_scope();
+        let session = scope.resolve::<Session>().unwrap().0;
+        let all = scope.resolve_all::<Arc<()>>().unwrap();
+        assert_eq!(all.len(), 2);
+        assert!(Arc::ptr_eq(&all[0], &session));
+        let first = scope.resolve_iter::<Arc<()>>().next().unwrap().unwrap();
+        assert!(Arc::ptr_eq(&first, &session));
+        let primary = scope.resolve_keyed::<Arc<()>>("primary").unwrap();
+        assert!(Arc::ptr_eq(&scope.resolve_map::<Arc<()>>().unwrap()["primary"], &primary));
+        assert!(matches!(scope.resolve_keyed::<Arc<()>>("replica"), Err(MakhzanError::UnknownMapKey(_))));
+        let boxed = scope.resolve_boxed(&DependencyKey::of::<Session>()).unwrap();
+        assert!(Arc::ptr_eq(&boxed.downcast::<Session>().unwrap().0, &session));
 
-        assert_eq!(a, 0);
-        assert_eq!(b, 1);
-        assert_eq!(c, 2);
+        let child = scope.create_child_scope();
+        assert!(Arc::ptr_eq(&child.resolve::<Session>().unwrap().0, &session));
+        assert!(child.parent().is_none());
+        let other = container.create_owned_scope().resolve_all::<Arc<()>>().unwrap();
+        assert!(!Arc::ptr_eq(&other[0], &session));
     }
 
     #[test]
-    fn singleton_factory_called_once() {
-        use std::sync::atomic::{AtomicU32, Ordering};
+    fn on_resolve_observers_tell_cache_hits_from_constructions() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
 
-        let counter = Arc::new(AtomicU32::new(0));
+        #[derive(Clone)]
+        struct Database;
 
+        let constructed = Arc::new(AtomicUsize::new(0));
+        let hits = Arc::new(AtomicUsize::new(0));
+        let outcomes = Arc::new(Mutex::new(Vec::new()));
         let container = Container::builder()
-            .singleton_with::<i32>({
-                let counter = counter.clone();
-                move |_| {
-                    counter.fetch_add(1, Ordering::SeqCst);
-                    Ok(42)
+            .singleton_with(|_| Ok(Database))
+            .transient_copy_with(|_| Ok(2u16))
+            .on_resolve({
+                let (constructed, hits) = (constructed.clone(), hits.clone());
+                move |key, outcome| {
+                    if key.matches_type::<Database>() {
+                        match outcome {
+                            ResolveOutcome::Constructed => constructed.fetch_add(1, Ordering::SeqCst),
+                            ResolveOutcome::CacheHit => hits.fetch_add(1, Ordering::SeqCst),
+                            ResolveOutcome::Failed => 0,
+                        };
+                    }
                 }
             })
+            .on_resolve({
+                let outcomes = outcomes.clone();
+                move |key, outcome| outcomes.lock().unwrap().push((key.clone(), outcome))
+            })
             .build()
             .unwrap();
 
-        let _a: i32 = container.resolve().unwrap();
-        let _b: i32 = container.resolve().unwrap();
-        let _c: i32 = container.resolve().unwrap();
+        for _ in 0..3 {
+            container.resolve::<Database>().unwrap();
+        }
+        container.create_scope().resolve::<Database>().unwrap();
+        assert!(container.resolve::<u32>().is_err());
+        assert_eq!(container.resolve_copy::<u16>().unwrap(), 2);
+        assert_eq!(container.create_scope().resolve_copy::<u16>().unwrap(), 2);
 
-        // Factory called only once
-        assert_eq!(counter.load(Ordering::SeqCst), 1);
+        assert_eq!(constructed.load(Ordering::SeqCst), 1);
+        assert_eq!(hits.load(Ordering::SeqCst), 3);
+        let outcomes = outcomes.lock().unwrap();
+        assert_eq!(outcomes.len(), 7);
+        assert_eq!(outcomes[0], (DependencyKey::of::<Database>(), ResolveOutcome::Constructed));
+        assert_eq!(outcomes[4], (DependencyKey::of::<u32>(), ResolveOutcome::Failed));
+        assert_eq!(outcomes[5], (DependencyKey::of::<u16>(), ResolveOutcome::Constructed));
+        assert_eq!(outcomes[6], (DependencyKey::of::<u16>(), ResolveOutcome::Constructed));
     }
 
     #[test]
-    fn resolve_with_dependency() {
+    fn singleton_first_resolved_in_a_scope_is_shared_with_the_root() {
+        struct Database;
+        let builds = Arc::new(Mutex::new(0));
         let container = Container::builder()
-            .singleton_value(String::from("postgres://localhost"))
-            .transient_with::<Vec<u8>>(|r| {
-                let url: String = resolve(r)?;
-                Ok(url.into_bytes())
+            .singleton_with({
+                let builds = builds.clone();
+                move |_| {
+                    *builds.lock().unwrap() += 1;
+                    Ok(Arc::new(Database))
+                }
             })
             .build()
             .unwrap();
 
-        let bytes: Vec<u8> = container.resolve().unwrap();
-        assert_eq!(bytes, b"postgres://localhost");
+        let from_scope = {
+            let scope = container.create_scope();
+            let child = scope.create_child_scope();
+            let from_child: Arc<Database> = child.resolve().unwrap();
+            let from_scope: Arc<Database> = scope.resolve().unwrap();
+            assert!(Arc::ptr_eq(&from_child, &from_scope));
+            from_scope
+        };
+
+        let from_root: Arc<Database> = container.resolve().unwrap();
+        assert!(Arc::ptr_eq(&from_scope, &from_root));
+        let from_sibling: Arc<Database> = container.create_scope().resolve().unwrap();
+        assert!(Arc::ptr_eq(&from_sibling, &from_root));
+        assert_eq!(*builds.lock().unwrap(), 1);
     }
 
     #[test]
-    fn resolve_not_registered() {
-        let container = Container::builder().build().unwrap();
-
-        let result = container.resolve::<i32>();
-        assert!(result.is_err());
+    fn singleton_built_for_a_scoped_factory_is_shared_across_scopes() {
+        struct Pool;
+        #[derive(Clone)]
+        struct Session(Arc<Pool>);
+        let container = Container::builder()
+            .singleton_with(|_| Ok(Arc::new(Pool)))
+            .scoped_with(|r| Ok(Session(resolve(r)?)))
+            .build()
+            .unwrap();
 
-        match result.unwrap_err() {
-            MakhzanError::NotRegistered(e) => {
-                assert!(e.requested.type_name().contains("i32"));
-            }
-            other => panic!("Expected NotRegistered, got: {other:?}"),
-        }
+        let first = container.create_owned_scope().resolve::<Session>().unwrap();
+        let second = container.create_owned_scope().resolve::<Session>().unwrap();
+        assert!(Arc::ptr_eq(&first.0, &second.0));
+        assert!(Arc::ptr_eq(&first.0, &container.resolve::<Arc<Pool>>().unwrap()));
     }
 
     #[test]
-    fn scoped_container_resolves() {
+    fn singleton_factories_do_not_capture_scope_seeds() {
+        #[derive(Clone, Debug, PartialEq)]
+        struct RequestId(u32);
+        struct Audit(#[allow(dead_code)] RequestId);
+
         let container = Container::builder()
-            .singleton_value(42i32)
+            .singleton_with(|r| Ok(Arc::new(Audit(resolve(r)?))))
             .build()
             .unwrap();
 
         let scope = container.create_scope();
-        let value: i32 = scope.resolve().unwrap();
-        assert_eq!(value, 42);
+        scope.insert(RequestId(7));
+        assert_eq!(scope.resolve::<RequestId>().unwrap(), RequestId(7));
+        assert!(matches!(
+            scope.resolve::<Arc<Audit>>(),
+            Err(MakhzanError::NotRegistered(e)) if e.requested == DependencyKey::of::<RequestId>()
+        ));
+        assert!(matches!(container.resolve::<Arc<Audit>>(), Err(MakhzanError::NotRegistered(_))));
+
+        let scope = container.create_scope();
+        scope.insert(Arc::new(Audit(RequestId(8))));
+        assert!(scope.resolve::<Arc<Audit>>().is_ok());
+        assert!(container.resolve::<Arc<Audit>>().is_err());
     }
 
     #[test]
-    fn arc_singleton_pattern() {
-        // The idiomatic way: wrap services in Arc
+    fn singleton_factories_do_not_capture_scoped_instances() {
         #[derive(Clone)]
-        struct Database {
-            url: String,
-        }
-
-        struct UserService {
-            db: Arc<Database>,
-        }
+        struct Session(Arc<()>);
+        struct Sessions(Session);
 
         let container = Container::builder()
-            .singleton_with::<Arc<Database>>(|_| {
-                Ok(Arc::new(Database {
-                    url: "postgres://localhost".into(),
-                }))
-            })
-            .transient_with::<UserService>(|r| {
-                let db: Arc<Database> = resolve(r)?;
-                Ok(UserService { db })
-            })
+            .scoped_with(|_| Ok(Session(Arc::new(()))))
+            .singleton_with(|r| Ok(Arc::new(Sessions(resolve(r)?))))
             .build()
             .unwrap();
 
-        let svc: UserService = container.resolve().unwrap();
-        assert_eq!(svc.db.url, "postgres://localhost");
+        let scope = container.create_scope();
+        let session: Session = scope.resolve().unwrap();
+        let sessions: Arc<Sessions> = scope.resolve().unwrap();
+        assert!(!Arc::ptr_eq(&sessions.0.0, &session.0));
+        assert!(Arc::ptr_eq(&sessions.0.0, &container.resolve::<Arc<Sessions>>().unwrap().0.0));
+    }
+
+    #[test]
+    fn validate_reports_like_build_without_consuming_the_builder() {
+        struct Database;
+        struct Service;
+        let builder = Container::builder()
+            .transient_with_recorded(|r| {
+                let _: Arc<Database> = resolve(r)?;
+                Ok(Service)
+            })
+            .register_dynamic("service", DependencyKey::of::<Service>());
+
+        let validate_err = builder.validate().unwrap_err();
+        let build_err = builder.clone().build().map(|_| ()).unwrap_err();
+        assert!(matches!(validate_err, MakhzanError::NotRegistered(_)));
+        assert_eq!(validate_err.to_string(), build_err.to_string());
+
+        let builder = builder.singleton_with(|_| Ok(Arc::new(Database)));
+        let report = builder.validate().unwrap();
+        assert_eq!(builder.validate().unwrap(), report);
+        assert_eq!(report.validated, 2);
+        let container = builder.build().unwrap();
+        assert_eq!(*container.validation_report(), report);
+        assert!(container.resolve::<Service>().is_ok());
+
+        let err = Container::builder()
+            .singleton_value(1u8)
+            .register_dynamic("port", DependencyKey::of::<u16>())
+            .validate()
+            .unwrap_err();
+        match err {
+            MakhzanError::NotRegistered(e) => assert_eq!(e.requested, DependencyKey::of::<u16>()),
+            other => panic!("expected NotRegistered, got {other:?}"),
+        }
     }
 
     #[test]
@@ -0,0 +1,71 @@
+//! Per-key introspection.
+//!
+//! [`KeyDescription`] is returned by
+//! [`Container::describe`](crate::container::Container::describe) and
+//! collects everything the container knows about a single dependency.
+
+use std::fmt;
+
+use crate::key::DependencyKey;
+use crate::scope::Scope;
+
+/// Everything known about a single registered (or aliased) key.
+///
+/// # Examples
+/// ```rust
+/// # use makhzan_container::prelude::*;
+/// # use std::sync::Arc;
+/// # struct Database;
+/// # let container = Container::builder().singleton_arc(|_| Ok(Database)).build()?;
+/// let description = container.describe(&DependencyKey::of::<Arc<Database>>()).unwrap();
+/// println!("{description}");
+/// // DependencyKey: alloc::sync::Arc<my_app::Database>
+/// //   Scope: Singleton
+/// //   Dependencies: (none)
+/// //   Instantiated: no
+/// # Ok::<(), MakhzanError>(())
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyDescription {
+    /// The key that was described
+    pub key: DependencyKey,
+    /// The registration `key` points to, if it is an alias
+    pub alias_for: Option<DependencyKey>,
+    /// Scope of the underlying registration
+    pub scope: Scope,
+    /// Declared dependencies of the underlying registration
+    pub dependencies: Vec<DependencyKey>,
+    /// Whether the singleton instance exists yet.
+    ///
+    /// `None` for non-singletons and for singletons registered through a
+    /// [`Provider`](crate::provider::Provider), whose state is opaque.
+    pub instantiated: Option<bool>,
+}
+
+impl fmt::Display for KeyDescription {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "DependencyKey: {}", self.key)?;
+
+        if let Some(ref target) = self.alias_for {
+            write!(f, "\n  Alias for: {target}")?;
+        }
+
+        write!(f, "\n  Scope: {}", self.scope)?;
+
+        if self.dependencies.is_empty() {
+            write!(f, "\n  Dependencies: (none)")?;
+        } else {
+            write!(f, "\n  Dependencies:")?;
+            for dependency in &self.dependencies {
+                write!(f, "\n    - {dependency}")?;
+            }
+        }
+
+        let instantiated = match self.instantiated {
+            Some(true) => "yes",
+            Some(false) => "no",
+            None => "n/a",
+        };
+        write!(f, "\n  Instantiated: {instantiated}")
+    }
+}
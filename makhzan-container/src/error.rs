@@ -3,16 +3,24 @@
 //! Makhzan provides detailed, actionable error messages.
 //! No more `TypeNotFound: 0x7f3a2b1c`.
 
-use crate::key::DependencyKey;
+use crate::key::{pointee, DependencyKey};
 use crate::scope::Scope;
+use makhzan_support::rendering::{
+    render_chain, shorten_type_name, similarity_score, suggest_similar,
+};
 use std::fmt;
 
 /// Main error type for all Makhzan operations.
+///
+/// Large payloads are boxed so `Result<T, MakhzanError>` stays at most
+/// 128 bytes, which keeps the resolve hot path cheap. New variants may be
+/// added in minor releases, so matches need a wildcard arm.
 #[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
 pub enum MakhzanError {
     /// Requested dependency was never registered.
     #[error("{}", .0)]
-    NotRegistered(NotRegisteredError),
+    NotRegistered(Box<NotRegisteredError>),
 
     /// Circular dependency detected during resolve.
     #[error("{}", .0)]
@@ -21,25 +29,274 @@ pub enum MakhzanError {
     /// Scope mismatch: tried to inject a shorter-lived dependency
     /// into a longer-lived one.
     #[error("{}", .0)]
-    ScopeMismatch(ScopeMismatchError),
+    ScopeMismatch(Box<ScopeMismatchError>),
+
+    /// Scope mismatch hidden behind an alias: the consumer depends on an
+    /// alias (typically a trait object) whose target lives shorter.
+    #[error("{}", .0)]
+    AliasScopeMismatch(Box<AliasScopeMismatchError>),
 
     /// Factory returned an error during construction.
-    #[error("Failed to construct {key}: {source}")]
+    #[error("Failed to construct {key}: {source}{}", render_required_by(.required_by, .key))]
     ConstructionFailed {
         key: DependencyKey,
         #[source]
         source: Box<dyn std::error::Error + Send + Sync>,
+        /// Keys whose factories were resolving `key` when it failed,
+        /// outermost first; empty when it was resolved directly.
+        required_by: Vec<DependencyKey>,
+    },
+
+    /// The value registered under `key` is not of the requested type.
+    #[error("Type mismatch for {key}: expected {expected}{}\n  Hint: The usual cause is registering T but resolving Arc<T>, or the other way round; resolve exactly the type that was registered", render_actual(*.actual))]
+    TypeMismatch {
+        key: DependencyKey,
+        /// Full type name of the requested type
+        expected: &'static str,
+        /// Full type name of the registered value, if known
+        actual: Option<&'static str>,
     },
 
     /// Dependency was already registered (when override is disabled).
     #[error("{}", .0)]
     AlreadyRegistered(AlreadyRegisteredError),
 
+    /// A `Scoped` dependency was resolved from the root container while
+    /// [`scoped_from_root`](crate::container::ContainerBuilder::scoped_from_root)
+    /// is [`ScopedFromRoot::Deny`](crate::scope::ScopedFromRoot::Deny).
+    #[error("Scoped dependency {key} resolved from the root container\n  Hint: Resolve it through container.create_scope()")]
+    ScopedFromRoot { key: DependencyKey },
+
+    /// A mutable singleton's lock was poisoned by a panic in an earlier
+    /// [`with_mut`](crate::container::Container::with_mut) call.
+    #[error("Lock for {key} is poisoned: a previous with_mut call panicked while holding it\n  Hint: The value may be half-updated; rebuild the container or resolve the Arc<Mutex<_>> and recover it explicitly")]
+    LockPoisoned { key: DependencyKey },
+
+    /// [`Container::warm_up`](crate::container::Container::warm_up) or
+    /// [`Container::reset_singleton`](crate::container::Container::reset_singleton)
+    /// was given a key that is not a singleton.
+    #[error("{key} is {scope}, not Singleton\n  Hint: Only singletons are cached, so only they can be warmed up or reset")]
+    NotSingleton { key: DependencyKey, scope: Scope },
+
+    /// Constructing a singleton during
+    /// [`Container::warm_up`](crate::container::Container::warm_up) failed.
+    #[error("Failed to warm up {key}: {source}")]
+    WarmUpFailed {
+        key: DependencyKey,
+        #[source]
+        source: Box<MakhzanError>,
+    },
+
+    /// An element of a tuple passed to
+    /// [`Container::resolve_tuple`](crate::container::Container::resolve_tuple),
+    /// or a parameter of a function passed to
+    /// [`Container::invoke`](crate::container::Container::invoke), could
+    /// not be resolved.
+    #[error("Failed to resolve tuple element {index} ({key}): {source}")]
+    TupleElementFailed {
+        /// Zero-based position of the element in the tuple
+        index: usize,
+        key: DependencyKey,
+        #[source]
+        source: Box<MakhzanError>,
+    },
+
+    /// Factories nested deeper than the configured
+    /// [`max_resolve_depth`](crate::container::ContainerBuilder::max_resolve_depth).
+    #[error("Maximum resolution depth of {depth} exceeded while resolving {key}\n  Path: {}\n  Hint: Look for a runaway chain or a cycle between transient dependencies", render_path(.path, .key))]
+    MaxDepthExceeded {
+        key: DependencyKey,
+        depth: usize,
+        /// Keys whose factories were running, outermost first
+        path: Vec<DependencyKey>,
+    },
+
+    /// No key was registered under this name with
+    /// [`register_dynamic`](crate::container::ContainerBuilder::register_dynamic).
+    #[error("{}", .0)]
+    UnknownDynamicName(UnknownDynamicNameError),
+
+    /// No entry was added under this name with
+    /// [`add_keyed`](crate::container::ContainerBuilder::add_keyed).
+    #[error("{}", .0)]
+    UnknownMapKey(Box<UnknownMapKeyError>),
+
+    /// [`resolve_current`](crate::scope::resolve_current) was called
+    /// outside any `with_current` block.
+    #[error("No current scope to resolve from\n  Hint: Run the caller inside makhzan::scope::with_current(scope, ...) or pass the scope explicitly")]
+    NoCurrentScope,
+
+    /// An async factory did not finish within
+    /// [`resolve_timeout`](crate::container::ContainerBuilder::resolve_timeout).
+    #[error("Constructing {key} timed out after {elapsed:?}\n  Hint: Check that the service it connects to is reachable, or raise resolve_timeout")]
+    ConstructionTimeout {
+        key: DependencyKey,
+        elapsed: std::time::Duration,
+    },
+
     /// Container is already built and cannot be modified.
     #[error("Container is already built. Register dependencies before calling .build()")]
     ContainerFrozen,
 }
 
+impl MakhzanError {
+    /// A [`ConstructionFailed`](MakhzanError::ConstructionFailed) for `key`
+    /// with an empty `required_by`; the container fills that in as the
+    /// error passes back through the factories that were resolving `key`.
+    ///
+    /// ```rust
+    /// use makhzan_container::prelude::*;
+    ///
+    /// let container = Container::builder()
+    ///     .transient_with::<u16>(|_| {
+    ///         "http".parse().map_err(|e| MakhzanError::construction_failed(DependencyKey::of::<u16>(), e))
+    ///     })
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// let err = container.resolve::<u16>().unwrap_err();
+    /// assert!(matches!(err, MakhzanError::ConstructionFailed { .. }));
+    /// ```
+    pub fn construction_failed(
+        key: DependencyKey,
+        source: impl Into<Box<dyn std::error::Error + Send + Sync>>,
+    ) -> Self {
+        Self::ConstructionFailed { key, source: source.into(), required_by: Vec::new() }
+    }
+
+    /// The innermost error of the [`source`](std::error::Error::source)
+    /// chain, or `self` if there is none.
+    ///
+    /// Factory errors stay boxed in
+    /// [`ConstructionFailed`](MakhzanError::ConstructionFailed) rather than
+    /// being turned into strings, so however deeply the failure is wrapped,
+    /// e.g. by a cached failure or [`WarmUpFailed`](MakhzanError::WarmUpFailed),
+    /// the original error can be downcast:
+    ///
+    /// ```rust
+    /// # use makhzan_container::prelude::*;
+    /// # use std::io;
+    /// # let container = Container::builder()
+    /// #     .singleton_with_memo_errors::<u8>(|_| {
+    /// #         let refused = io::Error::new(io::ErrorKind::ConnectionRefused, "connection refused");
+    /// #         Err(MakhzanError::construction_failed(DependencyKey::of::<u8>(), refused))
+    /// #     })
+    /// #     .build()?;
+    /// # container.resolve::<u8>().unwrap_err();
+    /// # let error = container.resolve::<u8>().unwrap_err();
+    /// if let Some(e) = error.root_cause().downcast_ref::<std::io::Error>() {
+    ///     eprintln!("database unreachable: {e}");
+    /// }
+    /// # assert!(error.root_cause().is::<io::Error>());
+    /// # Ok::<(), MakhzanError>(())
+    /// ```
+    pub fn root_cause(&self) -> &(dyn std::error::Error + 'static) {
+        let mut cause: &(dyn std::error::Error + 'static) = self;
+        while let Some(source) = cause.source() {
+            cause = source;
+        }
+        cause
+    }
+}
+
+/// Renders a resolution path ending in `last`, eliding the middle of
+/// long paths.
+fn render_path(path: &[DependencyKey], last: &DependencyKey) -> String {
+    const HEAD: usize = 3;
+    const TAIL: usize = 6;
+
+    let names: Vec<String> = path.iter().chain([last]).map(short_name).collect();
+    if names.len() <= HEAD + TAIL + 1 {
+        return render_chain(&names);
+    }
+
+    format!(
+        "{} → … ({} more) … → {}",
+        render_chain(&names[..HEAD]),
+        names.len() - HEAD - TAIL,
+        render_chain(&names[names.len() - TAIL..])
+    )
+}
+
+/// The `Required by` line of a nested construction failure.
+fn render_required_by(required_by: &[DependencyKey], key: &DependencyKey) -> String {
+    if required_by.is_empty() {
+        return String::new();
+    }
+    format!("\n  Required by: {}", render_path(required_by, key))
+}
+
+/// The `found` part of a type mismatch, empty when the registered type
+/// is unknown.
+fn render_actual(actual: Option<&str>) -> String {
+    actual.map(|actual| format!(", found {actual}")).unwrap_or_default()
+}
+
+/// `key` without module paths, for chains that would otherwise be
+/// unreadable.
+fn short_name(key: &DependencyKey) -> String {
+    key.short_display().to_string()
+}
+
+/// How many "did you mean?" suggestions errors carry, and how close a
+/// name has to be to make the list.
+///
+/// Set with [`ContainerBuilder::suggestions`](crate::container::ContainerBuilder::suggestions);
+/// `max: 0` turns suggestions off. Scores follow
+/// [`similarity_score`](makhzan_support::rendering::similarity_score): 100
+/// when one name contains the other, 80 for short names, 10 per shared
+/// leading character, 30–50 for likely typos.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SuggestionConfig {
+    /// Most suggestions listed, best first
+    pub max: usize,
+    /// Lowest score that still counts as similar
+    pub min_score: u32,
+}
+
+impl SuggestionConfig {
+    /// No suggestions at all.
+    pub const DISABLED: Self = Self { max: 0, min_score: 0 };
+
+    /// The `max` best-scoring `candidates` for `requested`, skipping
+    /// those below `min_score`.
+    ///
+    /// A candidate that is `requested` behind an `Arc`, `Box` or `Rc`, or
+    /// the other way round, always ranks first.
+    pub(crate) fn pick<T>(
+        &self,
+        requested: &str,
+        candidates: impl IntoIterator<Item = T>,
+        name: impl Fn(&T) -> &str,
+    ) -> Vec<T> {
+        if self.max == 0 {
+            return Vec::new();
+        }
+        let mut scored: Vec<(T, u32)> = candidates
+            .into_iter()
+            .filter_map(|candidate| {
+                if is_pointer_pair(requested, name(&candidate)) {
+                    return Some((candidate, u32::MAX));
+                }
+                let score = similarity_score(requested, name(&candidate))?;
+                (score >= self.min_score).then_some((candidate, score))
+            })
+            .collect();
+        scored.sort_by(|(a, a_score), (b, b_score)| {
+            b_score.cmp(a_score).then_with(|| name(a).cmp(name(b)))
+        });
+        scored.truncate(self.max);
+        scored.into_iter().map(|(candidate, _)| candidate).collect()
+    }
+}
+
+impl Default for SuggestionConfig {
+    /// The three closest names scoring at least 30.
+    fn default() -> Self {
+        Self { max: 3, min_score: 30 }
+    }
+}
+
 /// Error when a dependency was not registered.
 ///
 /// Includes helpful hints about what went wrong.
@@ -53,14 +310,98 @@ pub struct NotRegisteredError {
     pub suggestions: Vec<DependencyKey>,
 }
 
+impl NotRegisteredError {
+    /// The suggestion that is the requested type behind an `Arc`, `Box`
+    /// or `Rc`, or the requested type's pointee.
+    fn pointer_suggestion(&self) -> Option<&DependencyKey> {
+        self.suggestions
+            .iter()
+            .find(|suggestion| is_pointer_pair(self.requested.type_name(), suggestion.type_name()))
+    }
+
+    /// [`suggestions`](Self::suggestions), best match first.
+    fn ranked_suggestions(&self) -> Vec<&DependencyKey> {
+        let names: Vec<&str> = self.suggestions.iter().map(DependencyKey::type_name).collect();
+        let ranked = suggest_similar(self.requested.type_name(), &names, names.len());
+
+        // Unscored suggestions keep their original order at the end.
+        let by_rank = ranked.iter().map(|name| Some(name.as_str())).chain([None]);
+        let mut ordered: Vec<&DependencyKey> = Vec::with_capacity(self.suggestions.len());
+        ordered.extend(self.pointer_suggestion());
+        for name in by_rank {
+            for suggestion in &self.suggestions {
+                let matches = name.is_none_or(|name| suggestion.type_name() == name);
+                if matches && !ordered.contains(&suggestion) {
+                    ordered.push(suggestion);
+                }
+            }
+        }
+        ordered
+    }
+}
+
 impl fmt::Display for NotRegisteredError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "Dependency not registered: {}", self.requested)?;
 
         if let Some(ref parent) = self.required_by {
-            write!(f, "\n  Required by: {parent}")?;
+            write!(f, "\n  Required while constructing {parent}")?;
+        }
+
+        if !self.suggestions.is_empty() {
+            write!(f, "\n  Did you mean one of:")?;
+            for suggestion in self.ranked_suggestions() {
+                write!(f, "\n    - {suggestion}")?;
+            }
+        }
+
+        if let Some(pointer) = self.pointer_suggestion() {
+            let registered = shorten_type_name(pointer.type_name());
+            return write!(f, "\n  Hint: {registered} is registered — did you mean resolve::<{registered}>()?");
         }
 
+        write!(f, "\n  Hint: {}", registration_hint(&self.requested))
+    }
+}
+
+/// How to register `key` with the builder, naming the real methods.
+fn registration_hint(key: &DependencyKey) -> String {
+    let ty = shorten_type_name(key.type_name());
+    if let Some(name) = key.name() {
+        return format!("Register it with .add_keyed::<{ty}>({name:?}, ..)");
+    }
+    if let Some(tag) = key.tag_name() {
+        let tag = shorten_type_name(tag);
+        return format!("Register it with .singleton_tagged::<{ty}, {tag}>(..)");
+    }
+    match key.trait_object() {
+        Some(object) if key.type_name().starts_with("alloc::sync::Arc<") => {
+            format!("Bind an implementation with .bind::<{}, Impl>()", shorten_type_name(object))
+        }
+        _ => format!("Register it with .singleton_with::<{ty}>(..) or .transient_with::<{ty}>(..)"),
+    }
+}
+
+/// Whether one type name is the other behind an `Arc`, `Box` or `Rc`.
+fn is_pointer_pair(a: &str, b: &str) -> bool {
+    pointee(a) == Some(b) || pointee(b) == Some(a)
+}
+
+/// Error when a dynamic name was never registered.
+#[derive(Debug)]
+pub struct UnknownDynamicNameError {
+    /// The name that was looked up
+    pub name: String,
+    /// Registered names that look similar
+    pub suggestions: Vec<String>,
+    /// Every registered dynamic name, sorted
+    pub available: Vec<String>,
+}
+
+impl fmt::Display for UnknownDynamicNameError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "No dependency registered under the dynamic name {:?}", self.name)?;
+
         if !self.suggestions.is_empty() {
             write!(f, "\n  Did you mean one of:")?;
             for suggestion in &self.suggestions {
@@ -68,11 +409,43 @@ impl fmt::Display for NotRegisteredError {
             }
         }
 
-        write!(
-            f,
-            "\n  Hint: Did you forget to call .register::<{}>()?",
-            self.requested.type_name()
-        )
+        if self.available.is_empty() {
+            write!(f, "\n  Hint: No dynamic names are registered; add them with .register_dynamic()")
+        } else {
+            write!(f, "\n  Available: {}", self.available.join(", "))
+        }
+    }
+}
+
+/// Error when a keyed map has no entry under a name.
+#[derive(Debug)]
+pub struct UnknownMapKeyError {
+    /// Type of the map's entries
+    pub key: DependencyKey,
+    /// The name that was looked up
+    pub name: String,
+    /// Entry names that look similar
+    pub suggestions: Vec<String>,
+    /// Every entry name of the map, sorted
+    pub available: Vec<String>,
+}
+
+impl fmt::Display for UnknownMapKeyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "No {} entry named {:?} in the keyed map", self.key.short_display(), self.name)?;
+
+        if !self.suggestions.is_empty() {
+            write!(f, "\n  Did you mean one of:")?;
+            for suggestion in &self.suggestions {
+                write!(f, "\n    - {suggestion}")?;
+            }
+        }
+
+        if self.available.is_empty() {
+            write!(f, "\n  Hint: The map has no entries; add them with .add_keyed()")
+        } else {
+            write!(f, "\n  Available: {}", self.available.join(", "))
+        }
     }
 }
 
@@ -90,8 +463,8 @@ impl fmt::Display for CircularDependencyError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "Circular dependency detected:\n  ")?;
 
-        let chain_str: Vec<String> = self.chain.iter().map(|k| k.type_name().to_string()).collect();
-        write!(f, "{}", chain_str.join(" → "))?;
+        let names: Vec<String> = self.chain.iter().map(short_name).collect();
+        write!(f, "{}", render_chain(&names))?;
 
         write!(
             f,
@@ -104,7 +477,7 @@ impl fmt::Display for CircularDependencyError {
 ///
 /// You cannot inject a Transient into a Singleton —
 /// the Singleton would hold a stale reference.
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ScopeMismatchError {
     /// The dependency being injected
     pub dependency: DependencyKey,
@@ -134,6 +507,47 @@ impl fmt::Display for ScopeMismatchError {
     }
 }
 
+/// Error when a dependency reaches a shorter-lived registration through
+/// an alias.
+///
+/// Reported separately from [`ScopeMismatchError`] because the alias,
+/// usually a trait object, hides the scope of what it points at.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AliasScopeMismatchError {
+    /// The alias the consumer depends on
+    pub alias_from: DependencyKey,
+    /// The registration the alias resolves to
+    pub alias_to: DependencyKey,
+    /// The dependent registration
+    pub consumer: DependencyKey,
+    pub consumer_scope: Scope,
+    /// Scope of `alias_to`
+    pub target_scope: Scope,
+}
+
+impl fmt::Display for AliasScopeMismatchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Scope mismatch through alias: {} ({}) depends on {}, which resolves to {} ({})",
+            self.consumer, self.consumer_scope, self.alias_from, self.alias_to, self.target_scope,
+        )?;
+        write!(
+            f,
+            "\n  A {} cannot resolve a {} binding via alias `{}`",
+            self.consumer_scope,
+            self.target_scope,
+            short_name(&self.alias_from),
+        )?;
+        write!(
+            f,
+            "\n  Hint: Register {} as {} or wider, or point the alias at a longer-lived implementation",
+            short_name(&self.alias_to),
+            self.consumer_scope,
+        )
+    }
+}
+
 /// Error when trying to register a dependency that already exists.
 #[derive(Debug)]
 pub struct AlreadyRegisteredError {
@@ -161,13 +575,18 @@ pub type Result<T> = std::result::Result<T, MakhzanError>;
 mod tests {
     use super::*;
 
+    #[test]
+    fn error_stays_small() {
+        assert!(std::mem::size_of::<MakhzanError>() <= 128);
+    }
+
     #[test]
     fn not_registered_error_display() {
-        let err = MakhzanError::NotRegistered(NotRegisteredError {
+        let err = MakhzanError::NotRegistered(Box::new(NotRegisteredError {
             requested: DependencyKey::of::<String>(),
             required_by: Some(DependencyKey::of::<Vec<u8>>()),
             suggestions: vec![],
-        });
+        }));
 
         let msg = format!("{err}");
         assert!(msg.contains("not registered"));
@@ -189,18 +608,148 @@ mod tests {
         assert!(msg.contains("→"));
     }
 
+    #[test]
+    fn alias_scope_mismatch_names_the_alias() {
+        trait Cache {}
+        struct RedisCache;
+
+        let err = MakhzanError::AliasScopeMismatch(Box::new(AliasScopeMismatchError {
+            alias_from: DependencyKey::of::<dyn Cache>(),
+            alias_to: DependencyKey::of::<RedisCache>(),
+            consumer: DependencyKey::of::<String>(),
+            consumer_scope: Scope::Singleton,
+            target_scope: Scope::Scoped,
+        }));
+
+        let msg = err.to_string();
+        assert!(msg.contains("A Singleton cannot resolve a Scoped binding via alias `dyn Cache`"), "{msg}");
+    }
+
     #[test]
     fn scope_mismatch_error_display() {
-        let err = MakhzanError::ScopeMismatch(ScopeMismatchError {
+        let err = MakhzanError::ScopeMismatch(Box::new(ScopeMismatchError {
             dependency: DependencyKey::of::<String>(),
             dependency_scope: Scope::Transient,
             consumer: DependencyKey::of::<Vec<u8>>(),
             consumer_scope: Scope::Singleton,
-        });
+        }));
 
         let msg = format!("{err}");
         assert!(msg.contains("Scope mismatch"));
         assert!(msg.contains("Singleton"));
         assert!(msg.contains("Transient"));
     }
+
+    #[test]
+    fn max_depth_error_elides_long_paths() {
+        let path: Vec<DependencyKey> = (0..20).map(|_| DependencyKey::of::<u8>()).collect();
+        let err = MakhzanError::MaxDepthExceeded {
+            key: DependencyKey::of::<String>(),
+            depth: 20,
+            path,
+        };
+
+        let msg = format!("{err}");
+        assert!(msg.contains("depth of 20"));
+        assert!(msg.contains("(12 more)"));
+        assert!(msg.contains("u8 → String"));
+    }
+
+    #[test]
+    fn cycle_chain_uses_short_names() {
+        mod my_app {
+            pub mod services {
+                pub struct UserService;
+                pub struct UserRepo;
+            }
+        }
+        use my_app::services::{UserRepo, UserService};
+
+        let err = MakhzanError::CircularDependency(CircularDependencyError {
+            chain: vec![
+                DependencyKey::of::<UserService>(),
+                DependencyKey::of::<std::sync::Arc<UserRepo>>(),
+                DependencyKey::of::<UserService>(),
+            ],
+        });
+
+        let msg = err.to_string();
+        assert!(msg.contains("UserService → Arc<UserRepo> → UserService"), "{msg}");
+        assert!(!msg.contains("my_app::"));
+    }
+
+    #[test]
+    fn suggestions_are_listed_best_match_first() {
+        let err = NotRegisteredError {
+            requested: DependencyKey::of::<std::sync::Arc<String>>(),
+            required_by: None,
+            suggestions: vec![DependencyKey::of::<u32>(), DependencyKey::of::<String>()],
+        };
+
+        let msg = err.to_string();
+        let string_at = msg.find("- alloc::string::String").unwrap();
+        let u32_at = msg.find("- u32").unwrap();
+        assert!(string_at < u32_at, "{msg}");
+    }
+
+    fn hint_for(requested: DependencyKey, required_by: Option<DependencyKey>) -> String {
+        NotRegisteredError { requested, required_by, suggestions: vec![] }.to_string()
+    }
+
+    #[test]
+    fn hint_names_the_builder_methods_for_a_concrete_type() {
+        struct Database;
+
+        assert_eq!(
+            hint_for(DependencyKey::of::<std::sync::Arc<Database>>(), None),
+            "Dependency not registered: alloc::sync::Arc<makhzan_container::error::tests::\
+             hint_names_the_builder_methods_for_a_concrete_type::Database>\n  \
+             Hint: Register it with .singleton_with::<Arc<Database>>(..) or .transient_with::<Arc<Database>>(..)"
+        );
+    }
+
+    #[test]
+    fn hint_suggests_bind_for_a_trait_object() {
+        trait Logger {}
+
+        assert_eq!(
+            hint_for(DependencyKey::of::<std::sync::Arc<dyn Logger + Send + Sync>>(), None),
+            "Dependency not registered: alloc::sync::Arc<dyn makhzan_container::error::tests::\
+             hint_suggests_bind_for_a_trait_object::Logger + core::marker::Send + core::marker::Sync>\n  \
+             Hint: Bind an implementation with .bind::<dyn Logger + Send + Sync, Impl>()"
+        );
+    }
+
+    #[test]
+    fn hint_for_a_named_key_uses_add_keyed() {
+        let hint = hint_for(DependencyKey::named::<u16>("port"), None);
+        assert!(hint.ends_with("\n  Hint: Register it with .add_keyed::<u16>(\"port\", ..)"), "{hint}");
+    }
+
+    #[test]
+    fn hint_for_a_tagged_key_uses_singleton_tagged() {
+        struct Primary;
+
+        let hint = hint_for(DependencyKey::tagged::<String, Primary>(), None);
+        assert!(hint.ends_with("\n  Hint: Register it with .singleton_tagged::<String, Primary>(..)"), "{hint}");
+    }
+
+    #[test]
+    fn hint_names_the_dependent_being_constructed() {
+        assert_eq!(
+            hint_for(DependencyKey::of::<u16>(), Some(DependencyKey::of::<String>())),
+            "Dependency not registered: u16\n  \
+             Required while constructing alloc::string::String\n  \
+             Hint: Register it with .singleton_with::<u16>(..) or .transient_with::<u16>(..)"
+        );
+    }
+
+    #[test]
+    fn pointer_pairs_need_the_same_pointee() {
+        assert!(is_pointer_pair("alloc::sync::Arc<app::Database>", "app::Database"));
+        assert!(is_pointer_pair("app::Database", "alloc::boxed::Box<app::Database>"));
+        assert!(is_pointer_pair("alloc::rc::Rc<dyn app::Logger>", "dyn app::Logger"));
+        assert!(!is_pointer_pair("alloc::sync::Arc<app::DatabaseConfig>", "app::Database"));
+        assert!(!is_pointer_pair("alloc::vec::Vec<app::Database>", "app::Database"));
+    }
 }
@@ -0,0 +1,289 @@
+//! Machine-readable dependency graph for
+//! [`Container::to_dot`](crate::container::Container::to_dot) and
+//! [`Container::graph_json`](crate::container::Container::graph_json).
+//!
+//! Both are rendered from the same [`DependencyGraph`], so a node or edge
+//! shown by one is shown by the other.
+//!
+//! # JSON schema
+//!
+//! ```json
+//! {
+//!   "nodes": [
+//!     {
+//!       "id": "alloc::sync::Arc<app::Database>",
+//!       "label": "Arc<Database>",
+//!       "type_name": "alloc::sync::Arc<app::Database>",
+//!       "name": null,
+//!       "tag": null,
+//!       "scope": "Singleton",
+//!       "instantiated": true
+//!     }
+//!   ],
+//!   "edges": [
+//!     { "from": "app::UserService", "to": "alloc::sync::Arc<app::Database>", "kind": "dependency" },
+//!     { "from": "alloc::sync::Arc<dyn app::Logger>", "to": "alloc::sync::Arc<app::ConsoleLogger>", "kind": "alias" }
+//!   ]
+//! }
+//! ```
+//!
+//! - `id` is the key as [`DependencyKey`] displays it and is unique;
+//!   edges refer to nodes by it.
+//! - `name` and `tag` are set for [named](DependencyKey::named) and
+//!   [tagged](DependencyKey::tagged) keys. Several bindings of one type
+//!   share `type_name` and differ in these, so a viewer can group them.
+//! - `scope` is `"Singleton"`, `"Scoped"` or `"Transient"`, and `null`
+//!   for an alias, whose only outgoing edge is its `"alias"` edge.
+//! - `instantiated` tells whether a singleton has been constructed; it is
+//!   `null` for the other scopes and aliases.
+//! - `"dependency"` edges are the declared dependencies, pointing at the
+//!   key as declared, which may be an alias.
+//!
+//! Registrations come first, in the order they were registered, then
+//! aliases sorted by `id`. Edges follow the order of their `from` node,
+//! then declaration order. The JSON deserializes back into
+//! [`DependencyGraph`].
+
+use std::collections::HashMap;
+use std::fmt::Write;
+
+use crate::graph::DependencyInfo;
+use crate::key::{BuildKeyHasher, DependencyKey};
+use crate::scope::Scope;
+
+/// Every registration and alias of a container, and the edges between
+/// them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DependencyGraph {
+    /// One node per key: registrations in registration order, then
+    /// aliases sorted by id
+    pub nodes: Vec<GraphNode>,
+    /// Dependency and alias edges
+    pub edges: Vec<GraphEdge>,
+}
+
+/// A registered key or an alias.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GraphNode {
+    /// The key as displayed; unique within the graph
+    pub id: String,
+    /// The key without module paths
+    pub label: String,
+    /// Full type name of the key
+    pub type_name: String,
+    /// Binding name of a named key
+    pub name: Option<String>,
+    /// Full type name of the tag of a tagged key
+    pub tag: Option<String>,
+    /// Scope of the registration; `None` for aliases
+    pub scope: Option<Scope>,
+    /// Whether a singleton has been constructed; `None` for other scopes
+    pub instantiated: Option<bool>,
+}
+
+/// An edge from a key to one it needs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GraphEdge {
+    /// Id of the dependent node
+    pub from: String,
+    /// Id of the node it points at
+    pub to: String,
+    /// What the edge stands for
+    pub kind: EdgeKind,
+}
+
+/// What a [`GraphEdge`] stands for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum EdgeKind {
+    /// A declared dependency of `from`
+    Dependency,
+    /// `from` is an alias resolving to `to`
+    Alias,
+}
+
+impl DependencyGraph {
+    /// Builds the graph with the registrations of `infos` in `order`;
+    /// `instantiated` reports whether the singleton registered under a key
+    /// has been constructed.
+    pub(crate) fn new(
+        order: &[DependencyKey],
+        infos: &HashMap<DependencyKey, DependencyInfo>,
+        aliases: &HashMap<DependencyKey, DependencyKey, BuildKeyHasher>,
+        instantiated: impl Fn(&DependencyKey) -> bool,
+    ) -> Self {
+        let mut nodes: Vec<(GraphNode, Vec<GraphEdge>)> = Vec::new();
+        for info in order.iter().filter_map(|key| infos.get(key)) {
+            let node = GraphNode {
+                scope: Some(info.scope),
+                instantiated: (info.scope == Scope::Singleton).then(|| instantiated(&info.key)),
+                ..GraphNode::new(&info.key)
+            };
+            let edges = info
+                .dependencies
+                .iter()
+                .map(|dep| GraphEdge { from: node.id.clone(), to: dep.to_string(), kind: EdgeKind::Dependency })
+                .collect();
+            nodes.push((node, edges));
+        }
+        let mut alias_nodes: Vec<(GraphNode, Vec<GraphEdge>)> = aliases
+            .iter()
+            .map(|(from, to)| {
+                let node = GraphNode::new(from);
+                let edge = GraphEdge { from: node.id.clone(), to: to.to_string(), kind: EdgeKind::Alias };
+                (node, vec![edge])
+            })
+            .collect();
+        alias_nodes.sort_by(|(a, _), (b, _)| a.id.cmp(&b.id));
+        nodes.extend(alias_nodes);
+
+        let (nodes, edges): (Vec<_>, Vec<_>) = nodes.into_iter().unzip();
+        Self { nodes, edges: edges.into_iter().flatten().collect() }
+    }
+
+    /// Renders the graph in Graphviz DOT.
+    ///
+    /// Registrations are boxes labelled with their scope, constructed
+    /// singletons are filled, and aliases are dashed ellipses with a
+    /// dashed edge to their target.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph makhzan {\n    rankdir=LR;\n");
+        for node in &self.nodes {
+            let id = escape(&node.id);
+            let label = escape(&node.label);
+            let _ = match node.scope {
+                Some(scope) => writeln!(
+                    dot,
+                    "    \"{id}\" [shape=box, label=\"{label}\\n{scope}\"{}];",
+                    if node.instantiated == Some(true) { ", style=filled" } else { "" },
+                ),
+                None => writeln!(dot, "    \"{id}\" [shape=ellipse, style=dashed, label=\"{label}\"];"),
+            };
+        }
+        for edge in &self.edges {
+            let _ = writeln!(
+                dot,
+                "    \"{}\" -> \"{}\"{};",
+                escape(&edge.from),
+                escape(&edge.to),
+                match edge.kind {
+                    EdgeKind::Dependency => "",
+                    EdgeKind::Alias => " [style=dashed, label=\"alias\"]",
+                },
+            );
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+impl GraphNode {
+    fn new(key: &DependencyKey) -> Self {
+        Self {
+            id: key.to_string(),
+            label: key.short_display().to_string(),
+            type_name: key.type_name().to_string(),
+            name: key.name().map(str::to_string),
+            tag: key.tag_name().map(str::to_string),
+            scope: None,
+            instantiated: None,
+        }
+    }
+}
+
+/// Escapes `text` for a double-quoted DOT string.
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::container::Container;
+    use crate::provider::ProviderRegistry;
+
+    struct Logger;
+    struct Service;
+
+    fn container() -> Container {
+        let port = DependencyKey::named::<u16>("port");
+        let mut builder = Container::builder().singleton_value(Arc::new(Logger));
+        builder.register_singleton(DependencyKey::of::<u16>(), Arc::new(|_| Ok(Box::new(8080u16))), vec![]);
+        builder.register_alias(port.clone(), DependencyKey::of::<u16>());
+        builder.register_transient(DependencyKey::of::<Service>(), Arc::new(|_| Ok(Box::new(Service))), vec![port]);
+        builder.build().unwrap()
+    }
+
+    #[test]
+    fn dot_and_json_show_the_same_graph() {
+        let container = container();
+        let graph = container.dependency_graph();
+
+        let ids: Vec<&str> = graph.nodes.iter().map(|node| node.id.as_str()).collect();
+        assert_eq!(
+            ids,
+            [
+                DependencyKey::of::<Arc<Logger>>().to_string(),
+                DependencyKey::of::<u16>().to_string(),
+                DependencyKey::of::<Service>().to_string(),
+                DependencyKey::named::<u16>("port").to_string(),
+            ]
+        );
+        assert_eq!(
+            graph.edges,
+            [
+                GraphEdge {
+                    from: DependencyKey::of::<Service>().to_string(),
+                    to: DependencyKey::named::<u16>("port").to_string(),
+                    kind: EdgeKind::Dependency,
+                },
+                GraphEdge {
+                    from: DependencyKey::named::<u16>("port").to_string(),
+                    to: DependencyKey::of::<u16>().to_string(),
+                    kind: EdgeKind::Alias,
+                },
+            ]
+        );
+        let by_label = |label: &str| graph.nodes.iter().find(|node| node.label == label).unwrap();
+        assert_eq!(by_label("Arc<Logger>").instantiated, Some(true));
+        assert_eq!(by_label("u16").instantiated, Some(false));
+        assert_eq!(by_label("Service").instantiated, None);
+        let alias = by_label("u16(\"port\")");
+        assert_eq!((alias.scope, alias.name.as_deref()), (None, Some("port")));
+
+        let dot = container.to_dot();
+        assert_eq!(dot, graph.to_dot());
+        for node in &graph.nodes {
+            assert!(dot.contains(&format!("    \"{}\" [", escape(&node.id))), "{dot}");
+        }
+        assert!(dot.contains("    \"u16 (name=\\\"port\\\")\" -> \"u16\" [style=dashed, label=\"alias\"];"), "{dot}");
+        assert!(dot.contains("label=\"Arc<Logger>\\nSingleton\", style=filled"), "{dot}");
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn json_round_trips_and_keeps_named_bindings_apart() {
+        let mut builder = Container::builder().singleton_value(0u8);
+        builder.register_singleton(DependencyKey::named::<u8>("replica"), Arc::new(|_| Ok(Box::new(2u8))), vec![]);
+        builder.register_singleton(DependencyKey::named::<u8>("primary"), Arc::new(|_| Ok(Box::new(1u8))), vec![]);
+        let container = builder.build().unwrap();
+
+        let json = container.graph_json();
+        let nodes = json["nodes"].as_array().unwrap();
+        assert_eq!(nodes.len(), 3);
+        assert!(nodes.iter().all(|node| node["type_name"] == "u8" && node["scope"] == "Singleton"));
+        let names: Vec<&serde_json::Value> = nodes.iter().map(|node| &node["name"]).collect();
+        assert_eq!(names, [&serde_json::Value::Null, &"replica".into(), &"primary".into()]);
+        assert_eq!(nodes[0]["instantiated"], true);
+        assert_eq!(nodes[1]["instantiated"], false);
+
+        let parsed: DependencyGraph = serde_json::from_value(json).unwrap();
+        assert_eq!(parsed, container.dependency_graph());
+    }
+}
@@ -0,0 +1,211 @@
+//! Generic types registered once for many parameters, such as a
+//! `Repository<T>` built from an `Arc<Database>` whatever `T` is.
+//!
+//! `TypeId`s are per closed type, so `Repository<User>` and
+//! `Repository<Order>` are unrelated keys. A family covers several of
+//! them with one factory: see
+//! [`ContainerBuilder::register_family`](crate::container::ContainerBuilder::register_family)
+//! and its typed form
+//! [`ContainerBuilder::register_generic`](crate::container::ContainerBuilder::register_generic).
+
+use std::any::Any;
+use std::sync::Arc;
+
+use crate::error::Result;
+use crate::key::DependencyKey;
+use crate::registry::{FactoryFn, Resolver};
+
+/// Builds every member of a generic type the same way.
+///
+/// Implemented on a marker type that names the generic type through
+/// [`Member`](Self::Member):
+///
+/// ```rust
+/// use makhzan_container::generic::GenericFactory;
+/// use makhzan_container::prelude::*;
+/// use makhzan_container::registry::Resolver;
+/// use std::marker::PhantomData;
+/// use std::sync::Arc;
+///
+/// struct Database;
+///
+/// struct Repository<T> {
+///     database: Arc<Database>,
+///     entity: PhantomData<T>,
+/// }
+///
+/// struct Repositories;
+///
+/// impl GenericFactory for Repositories {
+///     type Member<T: Send + Sync + 'static> = Repository<T>;
+///
+///     fn dependencies() -> Vec<DependencyKey> {
+///         vec![DependencyKey::of::<Arc<Database>>()]
+///     }
+///
+///     fn create<T: Send + Sync + 'static>(resolver: &dyn Resolver) -> Result<Repository<T>> {
+///         Ok(Repository { database: resolve(resolver)?, entity: PhantomData })
+///     }
+/// }
+///
+/// struct User;
+/// struct Order;
+///
+/// let container = Container::builder()
+///     .singleton_value(Arc::new(Database))
+///     .register_generic::<Repositories, (User, Order)>()
+///     .build()
+///     .unwrap();
+/// let users: Repository<User> = container.resolve().unwrap();
+/// let orders: Repository<Order> = container.resolve().unwrap();
+/// assert!(Arc::ptr_eq(&users.database, &orders.database));
+/// ```
+pub trait GenericFactory: 'static {
+    /// The member for parameter `T`, e.g. `Repository<T>`.
+    type Member<T: Send + Sync + 'static>: Send + Sync + 'static;
+
+    /// Keys [`create`](Self::create) resolves; the same for every member.
+    fn dependencies() -> Vec<DependencyKey> {
+        Vec::new()
+    }
+
+    /// Builds the member for parameter `T`.
+    fn create<T: Send + Sync + 'static>(resolver: &dyn Resolver) -> Result<Self::Member<T>>;
+}
+
+/// The parameters a [`GenericFactory`] is registered for: a tuple of up
+/// to eight types, each giving one member.
+pub trait GenericParams {
+    /// The key and factory of the member for each parameter.
+    fn members<F: GenericFactory>() -> Vec<(DependencyKey, FactoryFn)>;
+}
+
+fn member<F: GenericFactory, T: Send + Sync + 'static>() -> (DependencyKey, FactoryFn) {
+    (
+        DependencyKey::of::<F::Member<T>>(),
+        Arc::new(|resolver: &dyn Resolver| Ok(Box::new(F::create::<T>(resolver)?) as Box<dyn Any + Send + Sync>)),
+    )
+}
+
+macro_rules! impl_generic_params {
+    ($($param:ident),+) => {
+        impl<$($param: Send + Sync + 'static),+> GenericParams for ($($param,)+) {
+            fn members<F: GenericFactory>() -> Vec<(DependencyKey, FactoryFn)> {
+                vec![$(member::<F, $param>()),+]
+            }
+        }
+    };
+}
+
+impl_generic_params!(A);
+impl_generic_params!(A, B);
+impl_generic_params!(A, B, C);
+impl_generic_params!(A, B, C, D);
+impl_generic_params!(A, B, C, D, E);
+impl_generic_params!(A, B, C, D, E, G);
+impl_generic_params!(A, B, C, D, E, G, H);
+impl_generic_params!(A, B, C, D, E, G, H, I);
+
+#[cfg(test)]
+mod tests {
+    use std::marker::PhantomData;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+    use crate::container::{resolve, Container};
+    use crate::error::MakhzanError;
+
+    struct Database;
+
+    struct Repository<T> {
+        database: Arc<Database>,
+        #[allow(dead_code)]
+        entity: PhantomData<T>,
+    }
+
+    struct Repositories;
+
+    impl GenericFactory for Repositories {
+        type Member<T: Send + Sync + 'static> = Repository<T>;
+
+        fn dependencies() -> Vec<DependencyKey> {
+            vec![DependencyKey::of::<Arc<Database>>()]
+        }
+
+        fn create<T: Send + Sync + 'static>(resolver: &dyn Resolver) -> Result<Repository<T>> {
+            Ok(Repository { database: resolve(resolver)?, entity: PhantomData })
+        }
+    }
+
+    struct User;
+    struct Order;
+    struct Invoice;
+
+    struct Checkout(Repository<Order>);
+
+    #[test]
+    fn one_family_builds_every_member_from_the_shared_database() {
+        let container = Container::builder()
+            .singleton_with(|_| Ok(Arc::new(Database)))
+            .register_generic::<Repositories, (User, Order)>()
+            .transient_with_recorded(|r| Ok(Checkout(resolve(r)?)))
+            .build()
+            .unwrap();
+
+        let users: Repository<User> = container.resolve().unwrap();
+        let orders: Repository<Order> = container.resolve().unwrap();
+        let database: Arc<Database> = container.resolve().unwrap();
+        assert!(Arc::ptr_eq(&users.database, &database));
+        assert!(Arc::ptr_eq(&orders.database, &database));
+        assert!(Arc::ptr_eq(&container.resolve::<Checkout>().unwrap().0.database, &database));
+
+        let described = container.describe(&DependencyKey::of::<Repository<Order>>()).unwrap();
+        assert_eq!(described.dependencies, [DependencyKey::of::<Arc<Database>>()]);
+        assert!(matches!(
+            container.resolve::<Repository<Invoice>>().map(|_| ()),
+            Err(MakhzanError::NotRegistered(_))
+        ));
+    }
+
+    #[test]
+    fn family_members_are_validated_like_registrations() {
+        let err = Container::builder()
+            .register_generic::<Repositories, (Order,)>()
+            .transient_with_recorded(|r| Ok(Checkout(resolve(r)?)))
+            .build()
+            .map(|_| ())
+            .unwrap_err();
+
+        match err {
+            MakhzanError::NotRegistered(e) => {
+                assert_eq!(e.requested, DependencyKey::of::<Arc<Database>>());
+                assert_eq!(e.required_by, Some(DependencyKey::of::<Repository<Order>>()));
+            }
+            other => panic!("expected NotRegistered, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn untyped_family_is_consulted_on_a_miss() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let container = Container::builder()
+            .singleton_value(7u8)
+            .register_family({
+                let calls = calls.clone();
+                move |key| {
+                    calls.fetch_add(1, Ordering::SeqCst);
+                    key.matches_type::<u32>().then(|| -> FactoryFn {
+                        Arc::new(|r| Ok(Box::new(u32::from(resolve::<u8>(r)?) * 6)))
+                    })
+                }
+            })
+            .build()
+            .unwrap();
+
+        assert_eq!(calls.load(Ordering::SeqCst), 0);
+        assert_eq!(container.resolve::<u8>().unwrap(), 7);
+        assert_eq!(calls.load(Ordering::SeqCst), 0);
+        assert_eq!(container.resolve::<u32>().unwrap(), 42);
+        assert!(container.resolve::<u64>().is_err());
+    }
+}
@@ -6,18 +6,21 @@
 //! - Validates scope compatibility
 //!
 //! All validation happens during [`ContainerBuilder::build()`],
-//! BEFORE the first `resolve()` call.
+//! BEFORE the first `resolve()` call. The same pass measures the graph
+//! for [`GraphStats`].
 
 use std::collections::{HashMap, HashSet};
+use std::fmt;
 
-use tracing::{debug, warn, instrument};
+use makhzan_support::rendering::render_chain;
 
 use crate::error::{
-    CircularDependencyError, MakhzanError, NotRegisteredError,
-    ScopeMismatchError,
+    AliasScopeMismatchError, CircularDependencyError, MakhzanError, NotRegisteredError,
+    ScopeMismatchError, SuggestionConfig,
 };
 use crate::key::DependencyKey;
-use crate::scope::Scope;
+use crate::logging::{debug, warn};
+use crate::scope::{Scope, ScopeValidation};
 
 /// Information about a registered dependency needed for validation.
 #[derive(Debug, Clone)]
@@ -30,6 +33,146 @@ pub(crate) struct DependencyInfo {
     pub scope: Scope,
 }
 
+/// Every key reachable from `roots`, each after its dependencies.
+///
+/// Aliases are followed to their targets. Roots are visited in the given
+/// order, which breaks ties between independent keys. Assumes the graph
+/// was validated; a cycle is cut where it is found.
+pub(crate) fn dependency_order(
+    dependencies: &HashMap<DependencyKey, DependencyInfo>,
+    aliases: &HashMap<DependencyKey, DependencyKey, impl std::hash::BuildHasher>,
+    roots: &[DependencyKey],
+) -> Vec<DependencyKey> {
+    fn target<'a>(
+        aliases: &'a HashMap<DependencyKey, DependencyKey, impl std::hash::BuildHasher>,
+        key: &'a DependencyKey,
+    ) -> &'a DependencyKey {
+        let mut key = key;
+        let mut hops = 0;
+        while let Some(next) = aliases.get(key) {
+            key = next;
+            hops += 1;
+            if hops > aliases.len() {
+                break;
+            }
+        }
+        key
+    }
+
+    fn visit(
+        key: &DependencyKey,
+        dependencies: &HashMap<DependencyKey, DependencyInfo>,
+        aliases: &HashMap<DependencyKey, DependencyKey, impl std::hash::BuildHasher>,
+        seen: &mut HashSet<DependencyKey>,
+        order: &mut Vec<DependencyKey>,
+    ) {
+        let key = target(aliases, key);
+        if !seen.insert(key.clone()) {
+            return;
+        }
+        if let Some(info) = dependencies.get(key) {
+            for dep in &info.dependencies {
+                visit(dep, dependencies, aliases, seen, order);
+            }
+        }
+        order.push(key.clone());
+    }
+
+    let mut seen = HashSet::new();
+    let mut order = Vec::new();
+    for root in roots {
+        visit(root, dependencies, aliases, &mut seen, &mut order);
+    }
+    order
+}
+
+/// Size and shape of a validated dependency graph.
+///
+/// Computed by [`ContainerBuilder::build`](crate::container::ContainerBuilder::build),
+/// logged at `info` level and returned by
+/// [`Container::graph_stats`](crate::container::Container::graph_stats).
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GraphStats {
+    /// Registrations, aliases excluded
+    pub registrations: usize,
+    /// Registrations per scope; scopes without any are absent
+    pub by_scope: HashMap<Scope, usize>,
+    /// Keys on the longest dependency chain, from the key that roots it
+    /// down to a key without dependencies. Aliases are replaced by their
+    /// targets. Only validated keys are measured, so a
+    /// [partial](crate::container::ContainerBuilder::build_partial) build
+    /// reports the longest chain among its roots and an
+    /// [unchecked](crate::container::ContainerBuilder::build_unchecked)
+    /// one an empty chain.
+    pub longest_chain: Vec<DependencyKey>,
+    /// Declared dependencies per registration
+    pub average_fan_out: f64,
+    /// Registered aliases
+    pub aliases: usize,
+}
+
+impl GraphStats {
+    /// Keys on [`longest_chain`](Self::longest_chain), i.e. the deepest a
+    /// resolve of this container can nest.
+    pub fn max_depth(&self) -> usize {
+        self.longest_chain.len()
+    }
+}
+
+/// Chains with more keys than this get a [`ValidationWarning::DeepChain`].
+pub const DEEP_CHAIN_WARNING: usize = 8;
+
+/// What a validation pass saw besides pass/fail.
+///
+/// A container keeps the one from its build, see
+/// [`Container::validation_report`](crate::container::Container::validation_report).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ValidationReport {
+    /// Registrations that were validated
+    pub validated: usize,
+    /// Suspicious but valid shapes, sorted by key
+    pub warnings: Vec<ValidationWarning>,
+    /// The validated keys, each after everything it depends on
+    pub topological_order: Vec<DependencyKey>,
+}
+
+/// A valid graph shape worth a second look.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ValidationWarning {
+    /// Nothing depends on `key` and it has no dependencies. Fine for a
+    /// value the application resolves directly, but often a leftover.
+    Unused {
+        /// The isolated registration
+        key: DependencyKey,
+    },
+    /// A scope mismatch let through by [`ScopeValidation::Warn`]
+    ScopeMismatch(ScopeMismatchError),
+    /// An alias scope mismatch let through by [`ScopeValidation::Warn`]
+    AliasScopeMismatch(AliasScopeMismatchError),
+    /// The longest chain has more than [`DEEP_CHAIN_WARNING`] keys, so
+    /// its first resolve nests that deep.
+    DeepChain {
+        /// Keys on the chain, its root first
+        chain: Vec<DependencyKey>,
+    },
+}
+
+impl fmt::Display for ValidationWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unused { key } => {
+                write!(f, "{key} has no dependencies and nothing depends on it")
+            }
+            Self::ScopeMismatch(error) => error.fmt(f),
+            Self::AliasScopeMismatch(error) => error.fmt(f),
+            Self::DeepChain { chain } => {
+                let names: Vec<String> = chain.iter().map(|key| key.short_display().to_string()).collect();
+                write!(f, "Dependency chain of {} keys: {}", chain.len(), render_chain(&names))
+            }
+        }
+    }
+}
+
 /// Validates the dependency graph for correctness.
 ///
 /// Checks performed:
@@ -43,12 +186,23 @@ pub(crate) struct DependencyInfo {
 pub(crate) struct GraphValidator {
     /// All registered dependencies
     dependencies: HashMap<DependencyKey, DependencyInfo>,
+    /// Alias → the registration it finally resolves to
+    aliases: HashMap<DependencyKey, DependencyKey>,
     /// Currently being visited (for cycle detection)
     visiting: HashSet<DependencyKey>,
     /// Already validated (cache)
     validated: HashSet<DependencyKey>,
     /// Current DFS path (for error reporting)
     path: Vec<DependencyKey>,
+    /// Limits for "did you mean?" suggestions
+    suggestions: SuggestionConfig,
+    /// Validated key → keys on its longest chain, and the next key on it
+    depths: HashMap<DependencyKey, (usize, Option<DependencyKey>)>,
+    /// Validated keys in the order they finished, dependencies first
+    order: Vec<DependencyKey>,
+    scope_validation: ScopeValidation,
+    /// Mismatches let through by [`ScopeValidation::Warn`]
+    scope_warnings: Vec<ValidationWarning>,
 }
 
 impl GraphValidator {
@@ -56,12 +210,54 @@ impl GraphValidator {
     pub fn new(dependencies: HashMap<DependencyKey, DependencyInfo>) -> Self {
         Self {
             dependencies,
+            aliases: HashMap::new(),
             visiting: HashSet::new(),
             validated: HashSet::new(),
             path: Vec::new(),
+            suggestions: SuggestionConfig::default(),
+            depths: HashMap::new(),
+            order: Vec::new(),
+            scope_validation: ScopeValidation::default(),
+            scope_warnings: Vec::new(),
         }
     }
 
+    /// Limit the suggestions attached to [`MakhzanError::NotRegistered`].
+    pub fn with_suggestions(mut self, suggestions: SuggestionConfig) -> Self {
+        self.suggestions = suggestions;
+        self
+    }
+
+    /// What to do about a dependency that lives shorter than its consumer.
+    pub fn with_scope_validation(mut self, scope_validation: ScopeValidation) -> Self {
+        self.scope_validation = scope_validation;
+        self
+    }
+
+    /// Follows `aliases` when checking dependencies, so a dependency on an
+    /// alias is validated against the registration behind it.
+    ///
+    /// Chains are flattened; a cyclic chain is left pointing into itself
+    /// and reported as not registered.
+    pub fn with_aliases<'a>(
+        mut self,
+        aliases: impl IntoIterator<Item = (&'a DependencyKey, &'a DependencyKey)> + Clone,
+    ) -> Self {
+        let direct: HashMap<&DependencyKey, &DependencyKey> = aliases.clone().into_iter().collect();
+        for (from, to) in aliases {
+            let mut target = to;
+            let mut seen = HashSet::from([from]);
+            while let Some(next) = direct.get(target) {
+                if !seen.insert(target) {
+                    break;
+                }
+                target = next;
+            }
+            self.aliases.insert(from.clone(), target.clone());
+        }
+        self
+    }
+
     /// Validates the entire dependency graph.
     ///
     /// Returns `Ok(())` if the graph is valid, or an error describing
@@ -71,9 +267,11 @@ impl GraphValidator {
     /// - [`MakhzanError::CircularDependency`] — cycle detected
     /// - [`MakhzanError::NotRegistered`] — missing dependency
     /// - [`MakhzanError::ScopeMismatch`] — scope incompatibility
-    #[instrument(skip(self), name = "graph_validation")]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self), name = "graph_validation"))]
     pub fn validate(&mut self) -> Result<(), MakhzanError> {
-        let keys: Vec<DependencyKey> = self.dependencies.keys().cloned().collect();
+        // Sorted so that the topological order is the same on every run.
+        let mut keys: Vec<DependencyKey> = self.dependencies.keys().cloned().collect();
+        keys.sort_by_cached_key(DependencyKey::to_string);
 
         debug!(
             dependency_count = keys.len(),
@@ -90,6 +288,58 @@ impl GraphValidator {
         Ok(())
     }
 
+    /// [`validate`](Self::validate), keeping what the pass found.
+    ///
+    /// # Errors
+    /// The same as [`validate`](Self::validate); warnings never fail it.
+    pub fn validate_report(&mut self) -> Result<ValidationReport, MakhzanError> {
+        self.validate()?;
+        Ok(self.report())
+    }
+
+    /// What the validation so far found; after
+    /// [`validate_from`](Self::validate_from) it covers the reachable keys
+    /// only.
+    pub fn report(&self) -> ValidationReport {
+        let used: HashSet<&DependencyKey> = self
+            .dependencies
+            .values()
+            .flat_map(|info| &info.dependencies)
+            .map(|dep| self.aliases.get(dep).unwrap_or(dep))
+            .chain(self.aliases.values())
+            .collect();
+        let mut warnings: Vec<ValidationWarning> = self
+            .order
+            .iter()
+            .filter(|key| !used.contains(key))
+            .filter(|key| self.dependencies.get(*key).is_some_and(|info| info.dependencies.is_empty()))
+            .map(|key| ValidationWarning::Unused { key: key.clone() })
+            .collect();
+        warnings.sort_by_cached_key(|warning| warning.to_string());
+        warnings.extend(self.scope_warnings.iter().cloned());
+
+        let chain = self.stats().longest_chain;
+        if chain.len() > DEEP_CHAIN_WARNING {
+            warnings.push(ValidationWarning::DeepChain { chain });
+        }
+
+        ValidationReport {
+            validated: self.order.len(),
+            warnings,
+            topological_order: self.order.clone(),
+        }
+    }
+
+    /// Validates `key` and everything it transitively depends on, and
+    /// nothing else.
+    ///
+    /// `key` may be an alias. Returns the same errors as
+    /// [`validate`](Self::validate).
+    pub fn validate_from(&mut self, key: &DependencyKey) -> Result<(), MakhzanError> {
+        let target = self.aliases.get(key).unwrap_or(key).clone();
+        self.validate_key(&target)
+    }
+
     /// Validates a single dependency key (recursive DFS).
     fn validate_key(&mut self, key: &DependencyKey) -> Result<(), MakhzanError> {
         // Already validated — skip
@@ -121,35 +371,90 @@ impl GraphValidator {
         let info = self.dependencies.get(key).cloned().ok_or_else(|| {
             let suggestions = self.find_similar_keys(key);
 
-            MakhzanError::NotRegistered(NotRegisteredError {
+            MakhzanError::NotRegistered(Box::new(NotRegisteredError {
                 requested: key.clone(),
                 required_by: self.path.last().cloned(),
                 suggestions,
-            })
+            }))
         })?;
 
         // Mark as "currently visiting" and add to path
         self.visiting.insert(key.clone());
         self.path.push(key.clone());
 
-        // Recursively validate all dependencies
+        // Recursively validate all dependencies. Each one is finished
+        // before its dependents, so the longest chain below `key` is known
+        // from its dependencies' alone.
+        let mut deepest: Option<(usize, DependencyKey)> = None;
         for dep_key in &info.dependencies {
+            let target = self.aliases.get(dep_key).unwrap_or(dep_key).clone();
+
             // Check scope compatibility BEFORE recursing
-            if let Some(dep_info) = self.dependencies.get(dep_key) {
-                self.check_scope_compatibility(&info, dep_info)?;
+            if self.scope_validation != ScopeValidation::Off
+                && let Some(dep_info) = self.dependencies.get(&target)
+            {
+                let checked = if &target == dep_key {
+                    self.check_scope_compatibility(&info, dep_info)
+                } else {
+                    self.check_alias_scope_compatibility(&info, dep_key, dep_info)
+                };
+                if let Err(error) = checked {
+                    self.scope_mismatch(error)?;
+                }
             }
 
-            self.validate_key(dep_key)?;
+            self.validate_key(&target)?;
+            let depth = self.depths.get(&target).map_or(0, |(depth, _)| *depth);
+            if deepest.as_ref().is_none_or(|(best, _)| depth > *best) {
+                deepest = Some((depth, target));
+            }
         }
+        let depth = 1 + deepest.as_ref().map_or(0, |(depth, _)| *depth);
+        self.depths.insert(key.clone(), (depth, deepest.map(|(_, next)| next)));
 
         // Done visiting — remove from path, mark as validated
         self.path.pop();
         self.visiting.remove(key);
         self.validated.insert(key.clone());
+        self.order.push(key.clone());
 
         Ok(())
     }
 
+    /// Measures the graph after a successful [`validate`](Self::validate).
+    ///
+    /// Ties for the longest chain go to the root with the smallest key,
+    /// then to the dependency declared first.
+    pub fn stats(&self) -> GraphStats {
+        let mut stats = GraphStats {
+            registrations: self.dependencies.len(),
+            aliases: self.aliases.len(),
+            ..GraphStats::default()
+        };
+        let mut edges = 0;
+        for info in self.dependencies.values() {
+            *stats.by_scope.entry(info.scope).or_insert(0) += 1;
+            edges += info.dependencies.len();
+        }
+        if stats.registrations > 0 {
+            stats.average_fan_out = edges as f64 / stats.registrations as f64;
+        }
+
+        let root = self
+            .depths
+            .iter()
+            .max_by(|(a, (a_depth, _)), (b, (b_depth, _))| {
+                a_depth.cmp(b_depth).then_with(|| b.to_string().cmp(&a.to_string()))
+            })
+            .map(|(key, _)| key.clone());
+        let mut next = root;
+        while let Some(key) = next {
+            next = self.depths.get(&key).and_then(|(_, next)| next.clone());
+            stats.longest_chain.push(key);
+        }
+        stats
+    }
+
     /// Checks that scope rules are not violated.
     ///
     /// Rule: A dependency cannot have a SHORTER lifetime than its consumer.
@@ -164,65 +469,65 @@ impl GraphValidator {
         // If consumer lives LONGER than dependency — problem!
         // Singleton > Scoped > Transient
         if consumer.scope > dependency.scope {
-            warn!(
-                consumer = %consumer.key,
-                consumer_scope = %consumer.scope,
-                dependency = %dependency.key,
-                dependency_scope = %dependency.scope,
-                "Scope mismatch detected"
-            );
-
-            return Err(MakhzanError::ScopeMismatch(ScopeMismatchError {
+            return Err(MakhzanError::ScopeMismatch(Box::new(ScopeMismatchError {
                 consumer: consumer.key.clone(),
                 consumer_scope: consumer.scope,
                 dependency: dependency.key.clone(),
                 dependency_scope: dependency.scope,
-            }));
+            })));
         }
 
         Ok(())
     }
 
-    /// Finds registered keys with similar type names (for "did you mean?" suggestions).
-    fn find_similar_keys(&self, target: &DependencyKey) -> Vec<DependencyKey> {
-        let target_name = target.type_name().to_lowercase();
-
-        self.dependencies
-            .keys()
-            .filter(|k| {
-                let name = k.type_name().to_lowercase();
-                // Simple substring matching for suggestions
-                name.contains(&target_name)
-                    || target_name.contains(&name)
-                    || levenshtein_close(&target_name, &name)
-            })
-            .cloned()
-            .collect()
-    }
-}
+    /// [`check_scope_compatibility`](Self::check_scope_compatibility) for a
+    /// dependency reached through the alias `alias`.
+    fn check_alias_scope_compatibility(
+        &self,
+        consumer: &DependencyInfo,
+        alias: &DependencyKey,
+        target: &DependencyInfo,
+    ) -> Result<(), MakhzanError> {
+        if consumer.scope > target.scope {
+            return Err(MakhzanError::AliasScopeMismatch(Box::new(AliasScopeMismatchError {
+                alias_from: alias.clone(),
+                alias_to: target.key.clone(),
+                consumer: consumer.key.clone(),
+                consumer_scope: consumer.scope,
+                target_scope: target.scope,
+            })));
+        }
 
-/// Simple check if two strings are "close enough" (edit distance ≤ 3).
-///
-/// Not a full Levenshtein — just a quick heuristic for suggestions.
-fn levenshtein_close(a: &str, b: &str) -> bool {
-    let len_diff = a.len().abs_diff(b.len());
-    if len_diff > 3 {
-        return false;
+        Ok(())
     }
 
-    let common: usize = a
-        .chars()
-        .zip(b.chars())
-        .filter(|(ca, cb)| ca == cb)
-        .count();
-
-    let max_len = a.len().max(b.len());
-    if max_len == 0 {
-        return true;
+    /// Fails with a scope mismatch `error`, or under
+    /// [`ScopeValidation::Warn`] logs and records it instead.
+    fn scope_mismatch(&mut self, error: MakhzanError) -> Result<(), MakhzanError> {
+        warn!("{error}");
+        if self.scope_validation == ScopeValidation::Deny {
+            return Err(error);
+        }
+        match error {
+            MakhzanError::ScopeMismatch(error) => {
+                self.scope_warnings.push(ValidationWarning::ScopeMismatch(*error));
+            }
+            MakhzanError::AliasScopeMismatch(error) => {
+                self.scope_warnings.push(ValidationWarning::AliasScopeMismatch(*error));
+            }
+            _ => {}
+        }
+        Ok(())
     }
 
-    // At least 60% of characters match
-    common * 100 / max_len >= 60
+    /// Finds registered keys with similar type names (for "did you mean?" suggestions).
+    fn find_similar_keys(&self, target: &DependencyKey) -> Vec<DependencyKey> {
+        self.suggestions
+            .pick(target.type_name(), self.dependencies.keys(), |k| k.type_name())
+            .into_iter()
+            .cloned()
+            .collect()
+    }
 }
 
 #[cfg(test)]
@@ -252,7 +557,35 @@ mod tests {
     struct Database;
     struct UserRepo;
     struct UserService;
-    struct Logger;
+
+    #[test]
+    fn validate_from_checks_only_what_is_reachable() {
+        let graph = make_graph(vec![
+            dep_info(DependencyKey::of::<Database>(), Scope::Singleton, vec![]),
+            dep_info(
+                DependencyKey::of::<UserRepo>(),
+                Scope::Singleton,
+                vec![DependencyKey::of::<Database>()],
+            ),
+            dep_info(
+                DependencyKey::of::<UserService>(),
+                Scope::Transient,
+                vec![DependencyKey::of::<String>()],
+            ),
+        ]);
+
+        let mut validator = GraphValidator::new(graph.clone());
+        assert!(validator.validate_from(&DependencyKey::of::<UserRepo>()).is_ok());
+
+        let mut validator = GraphValidator::new(graph);
+        match validator.validate_from(&DependencyKey::of::<UserService>()) {
+            Err(MakhzanError::NotRegistered(e)) => {
+                assert_eq!(e.requested, DependencyKey::of::<String>());
+                assert_eq!(e.required_by, Some(DependencyKey::of::<UserService>()));
+            }
+            other => panic!("expected NotRegistered, got {other:?}"),
+        }
+    }
 
     #[test]
     fn valid_simple_graph() {
@@ -278,6 +611,29 @@ mod tests {
         assert!(validator.validate().is_ok());
     }
 
+    #[test]
+    fn dependencies_resolve_through_aliases() {
+        struct Cache;
+        struct Redis;
+        struct Service;
+
+        let graph = make_graph(vec![
+            dep_info(DependencyKey::of::<Redis>(), Scope::Singleton, vec![]),
+            dep_info(
+                DependencyKey::of::<Service>(),
+                Scope::Singleton,
+                vec![DependencyKey::of::<Cache>()],
+            ),
+        ]);
+        let aliases = HashMap::from([(DependencyKey::of::<Cache>(), DependencyKey::of::<Redis>())]);
+
+        assert!(GraphValidator::new(graph.clone()).with_aliases(&aliases).validate().is_ok());
+        assert!(matches!(
+            GraphValidator::new(graph).validate(),
+            Err(MakhzanError::NotRegistered(_))
+        ));
+    }
+
     #[test]
     fn detect_circular_dependency() {
         // A → B → C → A  (cycle!)
@@ -465,9 +821,130 @@ mod tests {
     }
 
     #[test]
-    fn levenshtein_close_check() {
-        assert!(levenshtein_close("UserService", "UserServise")); // typo
-        assert!(levenshtein_close("Database", "Databse"));        // typo
-        assert!(!levenshtein_close("Database", "Logger"));        // different
+    fn scope_check_follows_trait_aliases() {
+        // Logger (alias) → ConsoleLogger; Service (Singleton) depends on Logger
+        struct Logger;
+        struct ConsoleLogger;
+        struct Service;
+
+        let graph = |target_scope| {
+            make_graph(vec![
+                dep_info(DependencyKey::of::<ConsoleLogger>(), target_scope, vec![]),
+                dep_info(DependencyKey::of::<Service>(), Scope::Singleton, vec![DependencyKey::of::<Logger>()]),
+            ])
+        };
+        let aliases = HashMap::from([(DependencyKey::of::<Logger>(), DependencyKey::of::<ConsoleLogger>())]);
+
+        match GraphValidator::new(graph(Scope::Transient)).with_aliases(&aliases).validate() {
+            Err(MakhzanError::AliasScopeMismatch(e)) => {
+                assert_eq!(e.alias_from, DependencyKey::of::<Logger>());
+                assert_eq!(e.alias_to, DependencyKey::of::<ConsoleLogger>());
+                assert_eq!((e.consumer_scope, e.target_scope), (Scope::Singleton, Scope::Transient));
+                assert!(e.to_string().contains("via alias `Logger`"), "{e}");
+            }
+            other => panic!("Expected AliasScopeMismatch, got: {other:?}"),
+        }
+        assert!(GraphValidator::new(graph(Scope::Singleton)).with_aliases(&aliases).validate().is_ok());
+    }
+
+    #[test]
+    fn report_lists_topological_order_and_warnings() {
+        struct Unused;
+        struct Layer<const N: usize>;
+
+        let mut infos = vec![
+            dep_info(DependencyKey::of::<Database>(), Scope::Singleton, vec![]),
+            dep_info(DependencyKey::of::<UserRepo>(), Scope::Singleton, vec![DependencyKey::of::<Database>()]),
+            dep_info(DependencyKey::of::<UserService>(), Scope::Transient, vec![DependencyKey::of::<UserRepo>()]),
+            dep_info(DependencyKey::of::<Unused>(), Scope::Singleton, vec![]),
+        ];
+        let mut validator = GraphValidator::new(make_graph(infos.clone()));
+        let report = validator.validate_report().unwrap();
+
+        assert_eq!(report.validated, 4);
+        assert_eq!(report.warnings, [ValidationWarning::Unused { key: DependencyKey::of::<Unused>() }]);
+        let position = |key: DependencyKey| report.topological_order.iter().position(|k| *k == key).unwrap();
+        assert!(position(DependencyKey::of::<Database>()) < position(DependencyKey::of::<UserRepo>()));
+        assert!(position(DependencyKey::of::<UserRepo>()) < position(DependencyKey::of::<UserService>()));
+
+        let mut partial = GraphValidator::new(make_graph(infos.clone()));
+        partial.validate_from(&DependencyKey::of::<UserRepo>()).unwrap();
+        let report = partial.report();
+        assert_eq!(
+            report.topological_order,
+            [DependencyKey::of::<Database>(), DependencyKey::of::<UserRepo>()]
+        );
+        assert!(report.warnings.is_empty());
+
+        // Database ← UserRepo ← UserService ← Layer<1> ← … ← Layer<6>
+        macro_rules! layer {
+            ($n:literal, $dep:ty) => {
+                infos.push(dep_info(DependencyKey::of::<Layer<$n>>(), Scope::Transient, vec![DependencyKey::of::<$dep>()]))
+            };
+        }
+        layer!(1, UserService);
+        layer!(2, Layer<1>);
+        layer!(3, Layer<2>);
+        layer!(4, Layer<3>);
+        layer!(5, Layer<4>);
+        layer!(6, Layer<5>);
+        let report = GraphValidator::new(make_graph(infos)).validate_report().unwrap();
+        let [_, ValidationWarning::DeepChain { chain }] = &report.warnings[..] else {
+            panic!("{:?}", report.warnings)
+        };
+        assert_eq!(chain.len(), 9);
+        assert_eq!(chain[0], DependencyKey::of::<Layer<6>>());
+        assert!(report.warnings[1].to_string().starts_with("Dependency chain of 9 keys: Layer<6> → Layer<5>"));
+    }
+
+    #[test]
+    fn stats_measure_the_longest_chain_not_the_dfs_depth() {
+        // App → Cache → Database, and App → Service → Repo → Pool, an
+        // alias of Database. Visiting Cache first reaches Database at DFS
+        // depth 3; the longest chain goes through Service.
+        struct App;
+        struct Cache;
+        struct Service;
+        struct Repo;
+        struct Pool;
+        struct Alias;
+
+        let graph = make_graph(vec![
+            dep_info(DependencyKey::of::<Database>(), Scope::Singleton, vec![]),
+            dep_info(DependencyKey::of::<Repo>(), Scope::Scoped, vec![DependencyKey::of::<Pool>()]),
+            dep_info(DependencyKey::of::<Service>(), Scope::Transient, vec![DependencyKey::of::<Repo>()]),
+            dep_info(DependencyKey::of::<Cache>(), Scope::Singleton, vec![DependencyKey::of::<Database>()]),
+            dep_info(
+                DependencyKey::of::<App>(),
+                Scope::Transient,
+                vec![DependencyKey::of::<Cache>(), DependencyKey::of::<Service>()],
+            ),
+        ]);
+        let aliases = [
+            (DependencyKey::of::<Pool>(), DependencyKey::of::<Database>()),
+            (DependencyKey::of::<Alias>(), DependencyKey::of::<App>()),
+        ];
+
+        let mut validator = GraphValidator::new(graph).with_aliases(aliases.iter().map(|(a, b)| (a, b)));
+        validator.validate().unwrap();
+        let stats = validator.stats();
+
+        assert_eq!(stats.registrations, 5);
+        assert_eq!(
+            stats.by_scope,
+            HashMap::from([(Scope::Singleton, 2), (Scope::Scoped, 1), (Scope::Transient, 2)])
+        );
+        assert_eq!(
+            stats.longest_chain,
+            vec![
+                DependencyKey::of::<App>(),
+                DependencyKey::of::<Service>(),
+                DependencyKey::of::<Repo>(),
+                DependencyKey::of::<Database>(),
+            ]
+        );
+        assert_eq!(stats.max_depth(), 4);
+        assert_eq!(stats.average_fan_out, 1.0);
+        assert_eq!(stats.aliases, 2);
     }
 }
@@ -0,0 +1,67 @@
+//! Post-construction startup steps.
+//!
+//! A type implementing [`Initialize`] and marked with
+//! [`ContainerBuilder::with_initializer`] is initialized by
+//! [`Container::initialize_all`], after everything it depends on:
+//!
+//! ```rust
+//! # use makhzan_container::init::Initialize;
+//! # use makhzan_container::prelude::{Container, MakhzanError, resolve};
+//! # use std::error::Error;
+//! # use std::sync::Arc;
+//! # #[derive(Clone)]
+//! # struct Pool;
+//! # struct Migrator;
+//! # impl Migrator {
+//! #     fn new(_: Pool) -> Self { Migrator }
+//! #     fn run_pending(&self) -> std::io::Result<()> { Ok(()) }
+//! # }
+//! impl Initialize for Migrator {
+//!     fn initialize(&self) -> Result<(), Box<dyn Error + Send + Sync>> {
+//!         self.run_pending()?;
+//!         Ok(())
+//!     }
+//! }
+//!
+//! let container = Container::builder()
+//! #   .singleton_value(Pool)
+//!     .singleton_with::<Arc<Migrator>>(|r| Ok(Arc::new(Migrator::new(resolve(r)?))))
+//!     .with_initializer::<Arc<Migrator>>()
+//!     .build()?;
+//! container.initialize_all()?;
+//! # Ok::<(), MakhzanError>(())
+//! ```
+//!
+//! [`ContainerBuilder::with_initializer`]: crate::container::ContainerBuilder::with_initializer
+//! [`Container::initialize_all`]: crate::container::Container::initialize_all
+
+use std::error::Error;
+use std::sync::Arc;
+
+use crate::container::Container;
+use crate::error::{MakhzanError, Result};
+use crate::key::DependencyKey;
+
+/// A synchronous step run once after construction, such as running
+/// migrations or priming a cache.
+pub trait Initialize: Send + Sync {
+    /// Prepare the service. An error stops
+    /// [`Container::initialize_all`](crate::container::Container::initialize_all).
+    fn initialize(&self) -> std::result::Result<(), Box<dyn Error + Send + Sync>>;
+}
+
+impl<T: Initialize + ?Sized> Initialize for Arc<T> {
+    fn initialize(&self) -> std::result::Result<(), Box<dyn Error + Send + Sync>> {
+        (**self).initialize()
+    }
+}
+
+/// Resolves one marked key and initializes it.
+pub(crate) type InitFn = Arc<dyn Fn(&Container) -> Result<()> + Send + Sync>;
+
+pub(crate) fn initializer<T: Initialize + 'static>() -> InitFn {
+    Arc::new(|container| {
+        let service: T = container.resolve()?;
+        service.initialize().map_err(|source| MakhzanError::construction_failed(DependencyKey::of::<T>(), source))
+    })
+}
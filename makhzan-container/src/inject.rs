@@ -0,0 +1,117 @@
+//! Constructor injection.
+//!
+//! [`Injectable`] types know how to build themselves from a [`Resolver`]
+//! and which keys they need to do it. It is normally implemented by the
+//! `#[injectable]` attribute on an `impl` block, which turns the
+//! constructor marked `#[injectable]` into the factory. Written out by
+//! hand, the attribute's impl amounts to:
+//!
+//! ```rust
+//! # use makhzan_container::prelude::*;
+//! # use makhzan_container::registry::Resolver;
+//! # use std::sync::Arc;
+//! # trait Logger: Send + Sync {}
+//! # struct ConsoleLogger;
+//! # impl Logger for ConsoleLogger {}
+//! # struct Database;
+//! # struct UserService { db: Arc<Database>, log: Arc<dyn Logger> }
+//! impl UserService {
+//!     fn new(db: Arc<Database>, log: Arc<dyn Logger>) -> Self {
+//!         Self { db, log }
+//!     }
+//! }
+//!
+//! impl Injectable for UserService {
+//!     fn dependencies() -> Vec<DependencyKey> {
+//!         vec![DependencyKey::of::<Arc<Database>>(), DependencyKey::of::<Arc<dyn Logger>>()]
+//!     }
+//!
+//!     fn inject(resolver: &dyn Resolver) -> Result<Self> {
+//!         Ok(Self::new(resolve(resolver)?, resolve(resolver)?))
+//!     }
+//! }
+//!
+//! let container = Container::builder()
+//! #   .singleton_value(Arc::new(Database))
+//! #   .singleton_value(Arc::new(ConsoleLogger) as Arc<dyn Logger>)
+//!     // ...
+//!     .register_injectable::<UserService>()
+//!     .build()?;
+//! # container.resolve::<UserService>()?;
+//! # Ok::<(), MakhzanError>(())
+//! ```
+
+use std::error::Error;
+
+use crate::error::{MakhzanError, Result};
+use crate::key::DependencyKey;
+use crate::registry::Resolver;
+
+/// A type that can be constructed by resolving its constructor arguments.
+pub trait Injectable: Sized + Send + Sync + 'static {
+    /// The keys [`inject`](Self::inject) resolves, declared to the
+    /// graph validator.
+    fn dependencies() -> Vec<DependencyKey>;
+
+    /// Resolves the constructor arguments and builds `Self`.
+    fn inject(resolver: &dyn Resolver) -> Result<Self>;
+}
+
+/// What an `#[injectable]` constructor may return: `Self` or
+/// `Result<Self, E>`.
+///
+/// Constructor errors become [`MakhzanError::ConstructionFailed`] for the
+/// constructed type, keeping the error itself as the source. A
+/// [`MakhzanError`], e.g. from resolving a dependency, is passed through.
+#[doc(hidden)]
+pub trait IntoInjected<T> {
+    fn into_injected(self) -> Result<T>;
+}
+
+impl<T> IntoInjected<T> for T {
+    fn into_injected(self) -> Result<T> {
+        Ok(self)
+    }
+}
+
+impl<T: 'static, E: Into<Box<dyn Error + Send + Sync>>> IntoInjected<T> for std::result::Result<T, E> {
+    fn into_injected(self) -> Result<T> {
+        self.map_err(|e| match e.into().downcast::<MakhzanError>() {
+            Ok(error) => *error,
+            Err(source) => MakhzanError::construction_failed(DependencyKey::of::<T>(), source),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plain_values_pass_through() {
+        assert_eq!(IntoInjected::<u32>::into_injected(7u32).unwrap(), 7);
+    }
+
+    #[test]
+    fn constructor_errors_name_the_constructed_type() {
+        let failed: std::result::Result<u32, String> = Err("no connection".into());
+
+        match IntoInjected::<u32>::into_injected(failed) {
+            Err(MakhzanError::ConstructionFailed { key, source, .. }) => {
+                assert_eq!(key, DependencyKey::of::<u32>());
+                assert_eq!(source.to_string(), "no connection");
+            }
+            other => panic!("expected ConstructionFailed, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn container_errors_are_not_wrapped_again() {
+        let failed: std::result::Result<u32, MakhzanError> = Err(MakhzanError::NoCurrentScope);
+
+        assert!(matches!(
+            IntoInjected::<u32>::into_injected(failed),
+            Err(MakhzanError::NoCurrentScope)
+        ));
+    }
+}
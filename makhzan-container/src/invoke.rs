@@ -0,0 +1,62 @@
+//! Function-call injection.
+//!
+//! [`Container::invoke`](crate::container::Container::invoke) calls a
+//! closure or function after resolving each of its parameters by type,
+//! in the style of minimal-API handlers:
+//!
+//! ```rust
+//! # use makhzan_container::prelude::*;
+//! # use std::sync::Arc;
+//! # struct Database;
+//! # #[derive(Clone)]
+//! # struct Config;
+//! # fn build_report(_: &Database, _: &Config) -> String { String::from("ok") }
+//! # let container = Container::builder().singleton_value(Arc::new(Database)).singleton_value(Config).build()?;
+//! let report = container.invoke(|db: Arc<Database>, cfg: Config| build_report(&db, &cfg))?;
+//! # Ok::<(), MakhzanError>(())
+//! ```
+
+use crate::error::Result;
+use crate::registry::Resolver;
+use crate::tuple::ResolveTuple;
+
+/// A function of up to eight parameters the container can resolve.
+///
+/// `Args` is the tuple of parameter types, resolved as a
+/// [`ResolveTuple`]; it only exists to tell the impls for different
+/// arities apart and is always inferred.
+pub trait Invoke<Args, R> {
+    /// Resolves every parameter in order, then calls the function.
+    ///
+    /// # Errors
+    /// [`MakhzanError::TupleElementFailed`](crate::error::MakhzanError::TupleElementFailed)
+    /// for the first parameter that can't be resolved; the function is
+    /// not called.
+    fn invoke(self, resolver: &dyn Resolver) -> Result<R>;
+}
+
+macro_rules! impl_invoke {
+    ($($name:ident),*) => {
+        impl<Func, R, $($name),*> Invoke<($($name,)*), R> for Func
+        where
+            Func: FnOnce($($name),*) -> R,
+            ($($name,)*): ResolveTuple,
+        {
+            #[allow(non_snake_case)]
+            fn invoke(self, resolver: &dyn Resolver) -> Result<R> {
+                let ($($name,)*) = <($($name,)*)>::resolve_tuple(resolver)?;
+                Ok(self($($name),*))
+            }
+        }
+    };
+}
+
+impl_invoke!();
+impl_invoke!(A);
+impl_invoke!(A, B);
+impl_invoke!(A, B, C);
+impl_invoke!(A, B, C, D);
+impl_invoke!(A, B, C, D, E);
+impl_invoke!(A, B, C, D, E, F);
+impl_invoke!(A, B, C, D, E, F, G);
+impl_invoke!(A, B, C, D, E, F, G, H);
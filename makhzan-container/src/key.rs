@@ -1,11 +1,18 @@
 //! Dependency identification keys.
 //!
 //! [`DependencyKey`] uniquely identifies a dependency within the container.
-//! It combines a [`TypeId`] with an optional name for named bindings.
+//! It combines a [`TypeId`] with an optional name or tag type for named
+//! bindings, or an index for the elements of a collection.
 
 use std::any::{TypeId, type_name};
+use std::cmp::Ordering;
+use std::collections::HashSet;
 use std::fmt;
-use std::hash::{Hash, Hasher};
+use std::hash::{BuildHasherDefault, Hash, Hasher};
+use std::marker::PhantomData;
+use std::sync::{Mutex, OnceLock};
+
+use makhzan_support::rendering::shorten_type_name;
 
 /// Uniquely identifies a dependency in the container.
 ///
@@ -25,12 +32,51 @@ use std::hash::{Hash, Hasher};
 /// // Named key — type + name
 /// let key = DependencyKey::named::<String>("database_url");
 /// assert_eq!(key.name(), Some("database_url"));
+///
+/// // Tagged key — type + tag type
+/// struct Primary;
+/// let key = DependencyKey::tagged::<String, Primary>();
+/// assert_eq!(key.to_string(), "alloc::string::String (tag=Primary)");
 /// ```
+///
+/// # Ordering
+///
+/// Keys order by type name, then name, tag name and element index, so a
+/// `BTreeMap` of keys iterates in the same order on every build. Distinct
+/// types sharing a type name, which `type_name` allows, are told apart
+/// by `TypeId` last; only that tiebreak can differ between builds.
+///
+/// Keys that are `==` compare `Equal`, with one exception: two
+/// [`from_raw`](Self::from_raw) keys with the same `TypeId` but different
+/// type names are `==` yet order by their names. Ordering them `Equal`
+/// would mean comparing `TypeId` before the names, giving up both the
+/// stable order and transitivity with it. A `BTreeMap` may therefore hold
+/// both where a `HashMap` holds one.
 #[derive(Clone)]
 pub struct DependencyKey {
     type_id: TypeId,
     type_name: &'static str,
     name: Option<&'static str>,
+    tag: Option<&'static KeyTag>,
+    element: Option<u32>,
+}
+
+/// The tag type of a [`DependencyKey::tagged`] key.
+///
+/// Kept behind a `'static` reference so tagging adds one pointer to every
+/// key rather than a second `TypeId` and name.
+struct KeyTag {
+    type_id: TypeId,
+    type_name: fn() -> &'static str,
+}
+
+struct TagOf<Tag: ?Sized>(PhantomData<Tag>);
+
+impl<Tag: ?Sized + 'static> TagOf<Tag> {
+    const KEY_TAG: &'static KeyTag = &KeyTag {
+        type_id: TypeId::of::<Tag>(),
+        type_name: type_name::<Tag>,
+    };
 }
 
 impl DependencyKey {
@@ -48,6 +94,8 @@ impl DependencyKey {
             type_id: TypeId::of::<T>(),
             type_name: type_name::<T>(),
             name: None,
+            tag: None,
+            element: None,
         }
     }
 
@@ -69,6 +117,54 @@ impl DependencyKey {
             type_id: TypeId::of::<T>(),
             type_name: type_name::<T>(),
             name: Some(name),
+            tag: None,
+            element: None,
+        }
+    }
+
+    /// Creates a named key for type `T` from a name built at runtime.
+    ///
+    /// The name is interned: the first use of each distinct name stores it
+    /// for the life of the process, later uses share that copy. The key is
+    /// equal to [`named`](Self::named) with the same text.
+    ///
+    /// # Examples
+    /// ```
+    /// use makhzan_container::key::DependencyKey;
+    ///
+    /// let tenant = 42;
+    /// let key = DependencyKey::named_owned::<String>(format!("tenant-{tenant}-db"));
+    /// assert_eq!(key, DependencyKey::named::<String>("tenant-42-db"));
+    /// ```
+    pub fn named_owned<T: ?Sized + 'static>(name: String) -> Self {
+        Self::named::<T>(intern(name))
+    }
+
+    /// Creates a key for type `T` tagged with the type `Tag`.
+    ///
+    /// The compile-checked alternative to [`named`](Self::named): `Tag` is
+    /// usually a unit struct, so a misspelled tag is a compile error and
+    /// two tags never collide.
+    ///
+    /// # Examples
+    /// ```
+    /// use makhzan_container::key::DependencyKey;
+    ///
+    /// struct Primary;
+    /// struct Replica;
+    ///
+    /// let primary = DependencyKey::tagged::<String, Primary>();
+    /// assert_ne!(primary, DependencyKey::tagged::<String, Replica>());
+    /// assert_ne!(primary, DependencyKey::of::<String>());
+    /// ```
+    #[inline]
+    pub fn tagged<T: ?Sized + 'static, Tag: ?Sized + 'static>() -> Self {
+        Self {
+            type_id: TypeId::of::<T>(),
+            type_name: type_name::<T>(),
+            name: None,
+            tag: Some(TagOf::<Tag>::KEY_TAG),
+            element: None,
         }
     }
 
@@ -78,7 +174,25 @@ impl DependencyKey {
     /// advanced use cases (e.g., inside proc-macros).
     #[inline]
     pub fn from_raw(type_id: TypeId, type_name: &'static str) -> Self {
-        Self { type_id, type_name, name: None }
+        Self { type_id, type_name, name: None, tag: None, element: None }
+    }
+
+    /// Creates the key of element `index` of the collection of `T`s built
+    /// by [`add_element`](crate::container::ContainerBuilder::add_element).
+    ///
+    /// # Examples
+    /// ```
+    /// use makhzan_container::key::DependencyKey;
+    ///
+    /// let key = DependencyKey::element::<u32>(2);
+    /// assert_eq!(key.element_index(), Some(2));
+    /// assert_eq!(key.to_string(), "element #2 of collection alloc::vec::Vec<u32>");
+    /// assert_ne!(key, DependencyKey::of::<u32>());
+    /// ```
+    #[inline]
+    pub fn element<T: ?Sized + 'static>(index: usize) -> Self {
+        let index = u32::try_from(index).expect("collection index fits in u32");
+        Self { element: Some(index), ..Self::of::<T>() }
     }
 
     /// Returns the [`TypeId`] of this dependency.
@@ -100,43 +214,288 @@ impl DependencyKey {
     pub fn name(&self) -> Option<&'static str> { 
         self.name 
     }
+
+    /// Returns the position of a collection element's key.
+    #[inline]
+    pub fn element_index(&self) -> Option<usize> {
+        self.element.map(|index| index as usize)
+    }
+
+    /// Returns the full type name of the tag for tagged bindings.
+    #[inline]
+    pub fn tag_name(&self) -> Option<&'static str> {
+        self.tag.map(|tag| (tag.type_name)())
+    }
+
+    /// Whether this key is for type `T`, whatever its name, tag or
+    /// collection position.
+    ///
+    /// Unlike `==`, which tells `u16` and `u16 (name="port")` apart.
+    #[inline]
+    pub fn matches_type<T: ?Sized + 'static>(&self) -> bool {
+        self.type_id == TypeId::of::<T>()
+    }
+
+    /// Whether both keys are for the same type, whatever their names,
+    /// tags or collection positions.
+    #[inline]
+    pub fn same_type(&self, other: &DependencyKey) -> bool {
+        self.type_id == other.type_id
+    }
+
+    /// The trait object this key stands for, if its type is `dyn Trait`
+    /// or an `Arc`, `Box` or `Rc` of one.
+    ///
+    /// ```
+    /// use makhzan_container::key::DependencyKey;
+    /// use std::fmt::Debug;
+    /// use std::sync::Arc;
+    ///
+    /// let key = DependencyKey::of::<Arc<dyn Debug + Send + Sync>>();
+    /// assert_eq!(key.trait_object(), Some("dyn core::fmt::Debug + core::marker::Send + core::marker::Sync"));
+    /// assert_eq!(DependencyKey::of::<Arc<String>>().trait_object(), None);
+    /// ```
+    pub fn trait_object(&self) -> Option<&'static str> {
+        let name = pointee(self.type_name).unwrap_or(self.type_name);
+        name.starts_with("dyn ").then_some(name)
+    }
+
+    /// Displays the key without module paths, for logs and error chains.
+    ///
+    /// [`Display`](fmt::Display) keeps the full type path and
+    /// [`Debug`](fmt::Debug) adds the name and tag verbatim, so use those
+    /// when two types share a short name.
+    ///
+    /// # Examples
+    /// ```
+    /// use makhzan_container::key::DependencyKey;
+    ///
+    /// let key = DependencyKey::named::<String>("primary");
+    /// assert_eq!(key.short_display().to_string(), r#"String("primary")"#);
+    ///
+    /// let key = DependencyKey::of::<std::sync::Arc<Vec<String>>>();
+    /// assert_eq!(key.short_display().to_string(), "Arc<Vec<String>>");
+    /// ```
+    #[inline]
+    pub fn short_display(&self) -> ShortKey<'_> {
+        ShortKey(self)
+    }
+}
+
+/// `T` for the type name of an `Arc<T>`, `Box<T>` or `Rc<T>`.
+pub(crate) fn pointee(type_name: &str) -> Option<&str> {
+    ["alloc::sync::Arc<", "alloc::boxed::Box<", "alloc::rc::Rc<"]
+        .iter()
+        .find_map(|pointer| type_name.strip_prefix(pointer))
+        .and_then(|rest| rest.strip_suffix('>'))
 }
 
-// PartialEq: два ключа равны если совпадает TypeId И name
+/// A [`DependencyKey`] rendered without module paths; see
+/// [`DependencyKey::short_display`].
+#[derive(Clone, Copy)]
+pub struct ShortKey<'a>(&'a DependencyKey);
+
+impl fmt::Display for ShortKey<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(index) = self.0.element {
+            write!(f, "element #{index} of collection ")?;
+            return write!(f, "{}", shorten_type_name(&collection_name(self.0.type_name)));
+        }
+        write!(f, "{}", shorten_type_name(self.0.type_name))?;
+        if let Some(name) = self.0.name {
+            write!(f, "({:?})", name)?;
+        }
+        if let Some(tag) = self.0.tag {
+            write!(f, " (tag={})", shorten_type_name((tag.type_name)()))?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Debug for ShortKey<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+/// The type name of the `Vec` collecting `element_type`s.
+pub(crate) fn collection_name(element_type: &str) -> String {
+    format!("alloc::vec::Vec<{element_type}>")
+}
+
+/// Returns the one `'static` copy of `name`, leaking it on first use.
+fn intern(name: String) -> &'static str {
+    static NAMES: OnceLock<Mutex<HashSet<&'static str>>> = OnceLock::new();
+
+    let mut names = NAMES
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    if let Some(interned) = names.get(name.as_str()) {
+        return interned;
+    }
+    let interned: &'static str = Box::leak(name.into_boxed_str());
+    names.insert(interned);
+    interned
+}
+
+// PartialEq: два ключа равны если совпадает TypeId, name, tag И element
 impl PartialEq for DependencyKey {
     fn eq(&self, other: &Self) -> bool {
-        self.type_id == other.type_id && self.name == other.name
+        self.type_id == other.type_id
+            && self.name == other.name
+            && self.tag.map(|tag| tag.type_id) == other.tag.map(|tag| tag.type_id)
+            && self.element == other.element
     }
 }
 
 impl Eq for DependencyKey {}
 
-// Hash: хешируем по TypeId + name
+// Hash: хешируем по TypeId + name + tag + element
 impl Hash for DependencyKey {
     fn hash<H: Hasher>(&self, state: &mut H) {
         self.type_id.hash(state);
         self.name.hash(state);
+        self.tag.map(|tag| tag.type_id).hash(state);
+        self.element.hash(state);
+    }
+}
+
+// Ord: по отображаемым частям, TypeId только для одноимённых типов
+impl Ord for DependencyKey {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.type_name, self.name, self.tag_name(), self.element)
+            .cmp(&(other.type_name, other.name, other.tag_name(), other.element))
+            .then_with(|| self.type_id.cmp(&other.type_id))
+            .then_with(|| self.tag.map(|tag| tag.type_id).cmp(&other.tag.map(|tag| tag.type_id)))
+    }
+}
+
+impl PartialOrd for DependencyKey {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
     }
 }
 
 impl fmt::Debug for DependencyKey {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self.name {
-            Some(name) => write!(f, "DependencyKey({}, name={:?})", self.type_name, name),
-            None => write!(f, "DependencyKey({})", self.type_name),
+        write!(f, "DependencyKey({}", self.type_name)?;
+        if let Some(name) = self.name {
+            write!(f, ", name={:?}", name)?;
+        }
+        if let Some(tag) = self.tag {
+            write!(f, ", tag={}", (tag.type_name)())?;
+        }
+        if let Some(index) = self.element {
+            write!(f, ", element={index}")?;
         }
+        write!(f, ")")
     }
 }
 
 impl fmt::Display for DependencyKey {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self.name {
-            Some(name) => write!(f, "{} (name={:?})", self.type_name, name),
-            None => write!(f, "{}", self.type_name),
+        if let Some(index) = self.element {
+            return write!(f, "element #{index} of collection {}", collection_name(self.type_name));
+        }
+        write!(f, "{}", self.type_name)?;
+        if let Some(name) = self.name {
+            write!(f, " (name={:?})", name)?;
+        }
+        if let Some(tag) = self.tag {
+            write!(f, " (tag={})", shorten_type_name((tag.type_name)()))?;
+        }
+        Ok(())
+    }
+}
+
+/// A fast [`Hasher`] specialised for [`DependencyKey`].
+///
+/// [`TypeId`] is already a well-distributed value, so running it through
+/// SipHash is wasted work on the resolve hot path. This hasher folds each
+/// written word into the state with a single rotate-xor-multiply step
+/// (the FxHash scheme), which is enough to mix the `TypeId` bits with the
+/// optional name.
+///
+/// It is **not** DoS-resistant — only use it for maps keyed by types, never
+/// by untrusted input.
+///
+/// # Examples
+/// ```
+/// use std::collections::HashMap;
+/// use makhzan_container::key::{BuildKeyHasher, DependencyKey};
+///
+/// let mut map: HashMap<DependencyKey, &str, BuildKeyHasher> = HashMap::default();
+/// map.insert(DependencyKey::of::<String>(), "string");
+/// assert_eq!(map.get(&DependencyKey::of::<String>()), Some(&"string"));
+/// ```
+#[derive(Debug, Default, Clone, Copy)]
+pub struct KeyHasher {
+    hash: u64,
+}
+
+impl KeyHasher {
+    const SEED: u64 = 0x51_7c_c1_b7_27_22_0a_95;
+
+    #[inline]
+    fn add(&mut self, word: u64) {
+        self.hash = (self.hash.rotate_left(5) ^ word).wrapping_mul(Self::SEED);
+    }
+}
+
+impl Hasher for KeyHasher {
+    #[inline]
+    fn finish(&self) -> u64 {
+        self.hash
+    }
+
+    #[inline]
+    fn write(&mut self, bytes: &[u8]) {
+        let mut chunks = bytes.chunks_exact(8);
+        for chunk in &mut chunks {
+            let mut word = [0u8; 8];
+            word.copy_from_slice(chunk);
+            self.add(u64::from_le_bytes(word));
+        }
+
+        let rest = chunks.remainder();
+        if !rest.is_empty() {
+            let mut word = [0u8; 8];
+            word[..rest.len()].copy_from_slice(rest);
+            self.add(u64::from_le_bytes(word));
         }
     }
+
+    #[inline]
+    fn write_u8(&mut self, i: u8) {
+        self.add(i as u64);
+    }
+
+    #[inline]
+    fn write_u32(&mut self, i: u32) {
+        self.add(i as u64);
+    }
+
+    #[inline]
+    fn write_u64(&mut self, i: u64) {
+        self.add(i);
+    }
+
+    #[inline]
+    fn write_u128(&mut self, i: u128) {
+        self.add(i as u64);
+        self.add((i >> 64) as u64);
+    }
+
+    #[inline]
+    fn write_usize(&mut self, i: usize) {
+        self.add(i as u64);
+    }
 }
 
+/// [`BuildHasher`](std::hash::BuildHasher) for [`KeyHasher`].
+pub type BuildKeyHasher = BuildHasherDefault<KeyHasher>;
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -175,6 +534,77 @@ mod tests {
         );
     }
 
+    #[test]
+    fn type_predicates_ignore_name_tag_and_element() {
+        struct Primary;
+        let plain = DependencyKey::of::<String>();
+        let named = DependencyKey::named::<String>("replica");
+        let tagged = DependencyKey::tagged::<String, Primary>();
+
+        assert_ne!(plain, named);
+        assert!(named.matches_type::<String>());
+        assert!(tagged.matches_type::<String>());
+        assert!(DependencyKey::element::<String>(0).matches_type::<String>());
+        assert!(plain.same_type(&named) && named.same_type(&tagged));
+
+        let other = DependencyKey::named::<&str>("replica");
+        assert!(!other.matches_type::<String>());
+        assert!(!other.same_type(&named));
+        assert!(!DependencyKey::of::<std::sync::Arc<String>>().matches_type::<String>());
+    }
+
+    #[test]
+    fn keys_order_by_display_parts() {
+        use std::collections::BTreeSet;
+        struct Primary;
+
+        let keys: BTreeSet<DependencyKey> = [
+            DependencyKey::named::<u8>("b"),
+            DependencyKey::of::<String>(),
+            DependencyKey::element::<u8>(1),
+            DependencyKey::tagged::<u8, Primary>(),
+            DependencyKey::named::<u8>("a"),
+            DependencyKey::of::<u8>(),
+            DependencyKey::element::<u8>(0),
+            DependencyKey::of::<String>(),
+        ]
+        .into_iter()
+        .collect();
+        let ordered: Vec<String> = keys.iter().map(|key| format!("{key:?}")).collect();
+        assert_eq!(
+            ordered,
+            [
+                "DependencyKey(alloc::string::String)",
+                "DependencyKey(u8)",
+                "DependencyKey(u8, element=0)",
+                "DependencyKey(u8, element=1)",
+                format!("DependencyKey(u8, tag={})", type_name::<Primary>()).as_str(),
+                "DependencyKey(u8, name=\"a\")",
+                "DependencyKey(u8, name=\"b\")",
+            ]
+        );
+    }
+
+    #[test]
+    fn same_type_name_orders_consistently_with_eq() {
+        let a = DependencyKey::from_raw(TypeId::of::<u8>(), "plugin::Config");
+        let b = DependencyKey::from_raw(TypeId::of::<u16>(), "plugin::Config");
+
+        assert_ne!(a, b);
+        assert_ne!(a.cmp(&b), Ordering::Equal);
+        assert_eq!(a.cmp(&b), b.cmp(&a).reverse());
+        assert_eq!(a.cmp(&DependencyKey::from_raw(TypeId::of::<u8>(), "plugin::Config")), Ordering::Equal);
+    }
+
+    #[test]
+    fn same_type_id_under_another_name_is_eq_but_not_equal() {
+        let a = DependencyKey::from_raw(TypeId::of::<u8>(), "plugin::Config");
+        let b = DependencyKey::from_raw(TypeId::of::<u8>(), "plugin::Settings");
+
+        assert_eq!(a, b);
+        assert_eq!(a.cmp(&b), Ordering::Less);
+    }
+
     #[test]
     fn key_in_hashmap() {
         use std::collections::HashMap;
@@ -185,10 +615,104 @@ mod tests {
         assert_eq!(map.get(&DependencyKey::of::<bool>()), None);
     }
 
+    fn key_hash(key: &DependencyKey) -> u64 {
+        let mut hasher = KeyHasher::default();
+        key.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    #[test]
+    fn key_hasher_named_vs_unnamed_differ() {
+        let plain = DependencyKey::of::<String>();
+        let named = DependencyKey::named::<String>("a");
+        let other = DependencyKey::named::<String>("b");
+
+        assert_ne!(key_hash(&plain), key_hash(&named));
+        assert_ne!(key_hash(&named), key_hash(&other));
+        assert_eq!(key_hash(&named), key_hash(&DependencyKey::named::<String>("a")));
+    }
+
+    #[test]
+    fn key_hasher_map_keeps_named_and_unnamed_apart() {
+        use std::collections::HashMap;
+
+        let mut map: HashMap<DependencyKey, &str, BuildKeyHasher> = HashMap::default();
+        map.insert(DependencyKey::of::<String>(), "plain");
+        map.insert(DependencyKey::named::<String>("a"), "a");
+        map.insert(DependencyKey::named::<String>("b"), "b");
+        map.insert(DependencyKey::of::<i32>(), "i32");
+
+        assert_eq!(map.len(), 4);
+        assert_eq!(map[&DependencyKey::of::<String>()], "plain");
+        assert_eq!(map[&DependencyKey::named::<String>("a")], "a");
+        assert_eq!(map[&DependencyKey::named::<String>("b")], "b");
+        assert_eq!(map[&DependencyKey::of::<i32>()], "i32");
+    }
+
+    #[test]
+    fn owned_names_match_static_names() {
+        use std::collections::HashMap;
+
+        let owned = DependencyKey::named_owned::<String>(format!("tenant-{}-db", 42));
+        assert_eq!(owned, DependencyKey::named::<String>("tenant-42-db"));
+        assert_eq!(key_hash(&owned), key_hash(&DependencyKey::named::<String>("tenant-42-db")));
+
+        let mut map: HashMap<DependencyKey, u32, BuildKeyHasher> = HashMap::default();
+        map.insert(DependencyKey::named::<String>("tenant-42-db"), 42);
+        map.insert(DependencyKey::named_owned::<String>("tenant-7-db".to_string()), 7);
+        assert_eq!(map[&owned], 42);
+        assert_eq!(map[&DependencyKey::named::<String>("tenant-7-db")], 7);
+    }
+
+    #[test]
+    fn owned_names_are_interned() {
+        let a = DependencyKey::named_owned::<u8>("interned".to_string());
+        let b = DependencyKey::named_owned::<u16>("interned".to_string());
+        assert!(std::ptr::eq(a.name().unwrap(), b.name().unwrap()));
+    }
+
+    #[test]
+    fn tagged_keys_compare_by_tag_type() {
+        struct Primary;
+        struct Replica;
+
+        let primary = DependencyKey::tagged::<String, Primary>();
+        assert_eq!(primary, DependencyKey::tagged::<String, Primary>());
+        assert_ne!(primary, DependencyKey::tagged::<String, Replica>());
+        assert_ne!(primary, DependencyKey::of::<String>());
+        assert_ne!(key_hash(&primary), key_hash(&DependencyKey::tagged::<String, Replica>()));
+
+        assert_eq!(primary.to_string(), "alloc::string::String (tag=Primary)");
+        assert!(primary.tag_name().unwrap().ends_with("Primary"));
+    }
+
     #[test]
     fn unsized_type_key() {
         // dyn traits work as keys
         trait MyTrait {}
         let _key = DependencyKey::of::<dyn MyTrait>();
     }
+
+    #[test]
+    fn short_display_drops_module_paths() {
+        use std::collections::HashMap;
+        use std::sync::Arc;
+
+        trait Logger {}
+        struct Primary;
+
+        let generic = DependencyKey::of::<HashMap<String, Arc<MyStruct>>>();
+        assert_eq!(generic.short_display().to_string(), "HashMap<String, Arc<MyStruct>>");
+        assert!(format!("{generic:?}").contains("std::collections::hash::map::HashMap"));
+
+        let object = DependencyKey::of::<Arc<dyn Logger + Send + Sync>>();
+        assert_eq!(object.short_display().to_string(), "Arc<dyn Logger + Send + Sync>");
+        assert!(object.to_string().contains("alloc::sync::Arc"));
+
+        let named = DependencyKey::named::<Arc<dyn Logger>>("audit");
+        assert_eq!(named.short_display().to_string(), r#"Arc<dyn Logger>("audit")"#);
+
+        let tagged = DependencyKey::tagged::<String, Primary>();
+        assert_eq!(tagged.short_display().to_string(), "String (tag=Primary)");
+    }
 }
\ No newline at end of file
@@ -1,14 +1,50 @@
 //! Core container implementation for Makhzan DI.
 
+#[cfg(feature = "ambient")]
+mod ambient;
+mod cell;
+#[cfg(feature = "clap")]
+mod cli;
+#[cfg(feature = "config")]
+pub mod config;
 pub mod container;
+pub mod describe;
 pub mod error;
+pub mod export;
+pub mod generic;
 pub mod graph;
+pub mod init;
+pub mod inject;
+pub mod invoke;
 pub mod key;
+pub mod local;
+mod logging;
+#[cfg(feature = "serde")]
+pub mod manifest;
+pub mod plan;
 pub mod provider;
+pub mod recording;
 pub mod registry;
 pub mod scope;
+mod stack;
+#[cfg(any(test, feature = "test-util"))]
+pub mod testing;
+pub mod trace;
+mod tree;
+pub mod tuple;
+pub mod upcast;
 
 pub use container::prelude;
 pub use error::{MakhzanError, Result};
 pub use key::DependencyKey;
 pub use scope::Scope;
+
+// Containers are shared across worker threads and errors cross thread
+// boundaries; keep that a compile-time guarantee.
+const _: () = {
+    const fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<container::Container>();
+    assert_send_sync::<container::ScopedContainer<'static>>();
+    assert_send_sync::<container::OwnedScope>();
+    assert_send_sync::<MakhzanError>();
+};
@@ -0,0 +1,585 @@
+//! Single-threaded container for `!Send` / `!Sync` services.
+//!
+//! [`LocalContainer`] mirrors [`Container`](crate::container::Container)
+//! for code that never crosses threads, such as GUI state held in
+//! `Rc<RefCell<_>>`. Registrations need only `'static`: factories are
+//! `Rc`-shared and each container keeps its singletons in
+//! `once_cell::unsync::OnceCell`s. Scoped values are cached per
+//! [`LocalScopedContainer`]. Keys, scopes, errors and build-time graph
+//! validation are the same as for `Container`; the container itself is
+//! neither `Send` nor `Sync`.
+//!
+//! # Examples
+//! ```rust
+//! use std::cell::Cell;
+//! use std::rc::Rc;
+//! use makhzan_container::local::{self, LocalContainer};
+//!
+//! struct Counter(Rc<Cell<u32>>);
+//!
+//! let container = LocalContainer::builder()
+//!     .singleton_value(Rc::new(Cell::new(0u32)))
+//!     .transient_with(|r| Ok(Counter(local::resolve(r)?)))
+//!     .build()
+//!     .unwrap();
+//!
+//! container.resolve::<Counter>().unwrap().0.set(3);
+//! assert_eq!(container.resolve::<Rc<Cell<u32>>>().unwrap().get(), 3);
+//! ```
+
+use std::any::{Any, type_name};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt;
+use std::rc::Rc;
+
+use once_cell::unsync::OnceCell;
+
+use crate::container::DEFAULT_MAX_RESOLVE_DEPTH;
+use crate::error::{
+    AlreadyRegisteredError, CircularDependencyError, MakhzanError, NotRegisteredError, Result,
+};
+use crate::graph::{DependencyInfo, GraphValidator};
+use crate::key::{BuildKeyHasher, DependencyKey};
+use crate::logging::{debug, factory_span, info, trace};
+use crate::scope::Scope;
+
+/// What [`LocalContainer`] factories receive to resolve their own
+/// dependencies. Use [`resolve`] for typed access.
+pub trait LocalResolver {
+    fn resolve_key(&self, key: &DependencyKey) -> Result<Box<dyn Any>>;
+}
+
+/// Type-erased local factory.
+pub type LocalFactoryFn = Rc<dyn Fn(&dyn LocalResolver) -> Result<Box<dyn Any>>>;
+
+#[derive(Clone)]
+struct LocalRegistration {
+    factory: LocalFactoryFn,
+    scope: Scope,
+    dependencies: Vec<DependencyKey>,
+    /// Set for singletons and scoped values whose instance is cached and
+    /// cloned out on every resolve.
+    cloner: Option<LocalCloner>,
+}
+
+/// Clones the `T` behind a cached instance into a fresh box.
+type LocalCloner = fn(&dyn Any) -> Box<dyn Any>;
+
+fn cloner<T: Clone + 'static>() -> LocalCloner {
+    |value| Box::new(value.downcast_ref::<T>().expect("cloner matches the cached type").clone())
+}
+
+/// Scoped instances built in one [`LocalScopedContainer`].
+type ScopeCache = RefCell<HashMap<DependencyKey, Rc<dyn Any>, BuildKeyHasher>>;
+
+// ═══════════════════════════════════════════
+// LocalContainerBuilder
+// ═══════════════════════════════════════════
+
+/// Builder for [`LocalContainer`].
+///
+/// Same registration API as
+/// [`ContainerBuilder`](crate::container::ContainerBuilder), without the
+/// `Send + Sync` bounds.
+#[derive(Clone, Default)]
+pub struct LocalContainerBuilder {
+    registrations: HashMap<DependencyKey, LocalRegistration, BuildKeyHasher>,
+    allow_override: bool,
+    /// Keys registered twice through the chained methods, reported by
+    /// `build`.
+    duplicates: Vec<DependencyKey>,
+}
+
+impl LocalContainerBuilder {
+    /// Allow overriding previously registered dependencies.
+    pub fn allow_override(mut self, allow: bool) -> Self {
+        self.allow_override = allow;
+        self
+    }
+
+    /// Register a pre-built value as a singleton.
+    ///
+    /// Cloned on every resolve (use `Rc<T>` for cheap sharing).
+    pub fn singleton_value<T: Clone + 'static>(self, value: T) -> Self {
+        self.register::<T>(
+            Scope::Singleton,
+            Rc::new(move |_: &dyn LocalResolver| Ok(Box::new(value.clone()) as Box<dyn Any>)),
+        )
+    }
+
+    /// Register a singleton factory, called once on first resolve.
+    ///
+    /// Each container built from this builder, or from a clone of it,
+    /// constructs its own instance. A factory that resolves its own key
+    /// fails with [`MakhzanError::CircularDependency`].
+    pub fn singleton_with<T: Clone + 'static>(
+        self,
+        factory: impl Fn(&dyn LocalResolver) -> Result<T> + 'static,
+    ) -> Self {
+        self.register_cached::<T>(
+            Scope::Singleton,
+            Rc::new(move |resolver: &dyn LocalResolver| {
+                Ok(Box::new(factory(resolver)?) as Box<dyn Any>)
+            }),
+        )
+    }
+
+    /// Register a scoped factory.
+    ///
+    /// Like [`ContainerBuilder::scoped_with`](crate::container::ContainerBuilder::scoped_with):
+    /// resolves within one [`LocalScopedContainer`] share an instance and
+    /// each scope builds its own. Resolved from the container itself,
+    /// outside any scope, it is built on every resolve.
+    ///
+    /// The scope hands out clones of its instance; wrap a value that
+    /// isn't `Clone` in an `Rc`.
+    pub fn scoped_with<T: Clone + 'static>(
+        self,
+        factory: impl Fn(&dyn LocalResolver) -> Result<T> + 'static,
+    ) -> Self {
+        self.register_cached::<T>(
+            Scope::Scoped,
+            Rc::new(move |resolver: &dyn LocalResolver| {
+                Ok(Box::new(factory(resolver)?) as Box<dyn Any>)
+            }),
+        )
+    }
+
+    /// Register a transient factory, called on every resolve.
+    pub fn transient_with<T: 'static>(
+        self,
+        factory: impl Fn(&dyn LocalResolver) -> Result<T> + 'static,
+    ) -> Self {
+        self.register::<T>(
+            Scope::Transient,
+            Rc::new(move |resolver: &dyn LocalResolver| {
+                Ok(Box::new(factory(resolver)?) as Box<dyn Any>)
+            }),
+        )
+    }
+
+    /// Register a type-erased factory with declared dependencies.
+    ///
+    /// Nothing is cached: the factory runs on every resolve whatever
+    /// `scope` says, so a singleton or scoped factory registered here has
+    /// to keep its own instance. `scope` is still used to validate the
+    /// graph.
+    ///
+    /// # Errors
+    /// Returns [`MakhzanError::AlreadyRegistered`] if `key` is taken and
+    /// overriding is disabled.
+    pub fn register_factory(
+        &mut self,
+        key: DependencyKey,
+        scope: Scope,
+        factory: LocalFactoryFn,
+        dependencies: Vec<DependencyKey>,
+    ) -> Result<()> {
+        if !self.allow_override && self.registrations.contains_key(&key) {
+            return Err(MakhzanError::AlreadyRegistered(AlreadyRegisteredError { key }));
+        }
+
+        debug!(key = %key, scope = %scope, "Registered local dependency");
+        self.registrations.insert(key, LocalRegistration { factory, scope, dependencies, cloner: None });
+        Ok(())
+    }
+
+    /// Build the container, validating the dependency graph.
+    ///
+    /// # Errors
+    /// [`MakhzanError::AlreadyRegistered`] for the first key registered
+    /// twice through the chained methods while overriding was disabled,
+    /// and the errors of graph validation.
+    pub fn build(self) -> Result<LocalContainer> {
+        info!(registered = self.registrations.len(), "Building local container");
+        if let Some(key) = self.duplicates.first() {
+            return Err(MakhzanError::AlreadyRegistered(AlreadyRegisteredError { key: key.clone() }));
+        }
+
+        let infos: HashMap<DependencyKey, DependencyInfo> = self
+            .registrations
+            .iter()
+            .map(|(key, reg)| {
+                (
+                    key.clone(),
+                    DependencyInfo {
+                        key: key.clone(),
+                        dependencies: reg.dependencies.clone(),
+                        scope: reg.scope,
+                    },
+                )
+            })
+            .collect();
+        GraphValidator::new(infos).validate()?;
+
+        let singletons = self
+            .registrations
+            .iter()
+            .filter(|(_, reg)| reg.scope == Scope::Singleton && reg.cloner.is_some())
+            .map(|(key, _)| (key.clone(), OnceCell::new()))
+            .collect();
+        Ok(LocalContainer {
+            registrations: self.registrations,
+            singletons,
+            resolving: RefCell::new(Vec::new()),
+        })
+    }
+
+    fn register<T: 'static>(mut self, scope: Scope, factory: LocalFactoryFn) -> Self {
+        let key = DependencyKey::of::<T>();
+        if self.register_factory(key.clone(), scope, factory, vec![]).is_err() {
+            self.duplicates.push(key);
+        }
+        self
+    }
+
+    /// [`register`](Self::register) with the instance cached according to
+    /// `scope` and cloned out on every resolve.
+    fn register_cached<T: Clone + 'static>(self, scope: Scope, factory: LocalFactoryFn) -> Self {
+        let key = DependencyKey::of::<T>();
+        let mut builder = self.register::<T>(scope, factory);
+        if let Some(registration) = builder.registrations.get_mut(&key)
+            && !builder.duplicates.contains(&key)
+        {
+            registration.cloner = Some(cloner::<T>());
+        }
+        builder
+    }
+}
+
+// ═══════════════════════════════════════════
+// LocalContainer
+// ═══════════════════════════════════════════
+
+/// Single-threaded dependency injection container.
+///
+/// Created by [`LocalContainerBuilder::build`].
+pub struct LocalContainer {
+    registrations: HashMap<DependencyKey, LocalRegistration, BuildKeyHasher>,
+    /// This container's singleton instances, one cell per cached
+    /// singleton registration.
+    singletons: HashMap<DependencyKey, OnceCell<Rc<dyn Any>>, BuildKeyHasher>,
+    /// Keys whose factories are running, outermost first.
+    resolving: RefCell<Vec<DependencyKey>>,
+}
+
+impl LocalContainer {
+    /// Create a new builder.
+    pub fn builder() -> LocalContainerBuilder {
+        LocalContainerBuilder::default()
+    }
+
+    /// Resolve a dependency by type.
+    pub fn resolve<T: 'static>(&self) -> Result<T> {
+        let key = DependencyKey::of::<T>();
+        trace!(key = %key, "Resolving locally");
+        let value = self.resolve_internal(&key, None)?;
+        downcast(key, value)
+    }
+
+    /// Create a scoped child container.
+    pub fn create_scope(&self) -> LocalScopedContainer<'_> {
+        LocalScopedContainer { parent: self, cache: ScopeCache::default() }
+    }
+
+    /// Resolves `key`; `scope` is the cache of the
+    /// [`LocalScopedContainer`] the request comes from, `None` for the
+    /// container itself.
+    fn resolve_internal(&self, key: &DependencyKey, scope: Option<&ScopeCache>) -> Result<Box<dyn Any>> {
+        let registration = self.registrations.get(key).ok_or_else(|| {
+            MakhzanError::NotRegistered(Box::new(NotRegisteredError {
+                requested: key.clone(),
+                required_by: self.resolving.borrow().last().cloned(),
+                suggestions: vec![],
+            }))
+        })?;
+
+        if let Some(cell) = self.singletons.get(key)
+            && let Some(cloner) = registration.cloner
+        {
+            if let Some(value) = cell.get() {
+                return Ok(cloner(&**value));
+            }
+            let _frame = self.enter(key, registration.scope)?;
+            let _span = factory_span(key);
+            let value = cell.get_or_try_init(|| (registration.factory)(self).map(Rc::from))?;
+            return Ok(cloner(&**value));
+        }
+
+        let Some(cache) = scope.filter(|_| registration.scope != Scope::Singleton) else {
+            let _frame = self.enter(key, registration.scope)?;
+            let _span = factory_span(key);
+            return (registration.factory)(self);
+        };
+        let cloner = registration.cloner.filter(|_| registration.scope == Scope::Scoped);
+        if let Some(cloner) = cloner
+            && let Some(value) = cache.borrow().get(key)
+        {
+            return Ok(cloner(&**value));
+        }
+
+        let _frame = self.enter(key, registration.scope)?;
+        let _span = factory_span(key);
+        let value = (registration.factory)(&InScope { container: self, cache })?;
+        match cloner {
+            Some(cloner) => {
+                let value: Rc<dyn Any> = Rc::from(value);
+                cache.borrow_mut().insert(key.clone(), value.clone());
+                Ok(cloner(&*value))
+            }
+            None => Ok(value),
+        }
+    }
+
+    /// Pushes `key` onto the resolution path, rejecting a singleton that
+    /// is already being constructed and runaway nesting.
+    fn enter(&self, key: &DependencyKey, scope: Scope) -> Result<Frame<'_>> {
+        let mut resolving = self.resolving.borrow_mut();
+
+        if resolving.len() >= DEFAULT_MAX_RESOLVE_DEPTH {
+            return Err(MakhzanError::MaxDepthExceeded {
+                key: key.clone(),
+                depth: DEFAULT_MAX_RESOLVE_DEPTH,
+                path: resolving.clone(),
+            });
+        }
+        if scope == Scope::Singleton
+            && let Some(start) = resolving.iter().position(|k| k == key)
+        {
+            let mut chain = resolving[start..].to_vec();
+            chain.push(key.clone());
+            return Err(MakhzanError::CircularDependency(CircularDependencyError { chain }));
+        }
+
+        resolving.push(key.clone());
+        Ok(Frame(&self.resolving))
+    }
+}
+
+impl LocalResolver for LocalContainer {
+    fn resolve_key(&self, key: &DependencyKey) -> Result<Box<dyn Any>> {
+        self.resolve_internal(key, None)
+    }
+}
+
+/// The resolver factories get inside a [`LocalScopedContainer`], so their
+/// own scoped dependencies come from the same scope.
+struct InScope<'a> {
+    container: &'a LocalContainer,
+    cache: &'a ScopeCache,
+}
+
+impl LocalResolver for InScope<'_> {
+    fn resolve_key(&self, key: &DependencyKey) -> Result<Box<dyn Any>> {
+        self.container.resolve_internal(key, Some(self.cache))
+    }
+}
+
+impl fmt::Debug for LocalContainer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LocalContainer")
+            .field("registered", &self.registrations.len())
+            .finish()
+    }
+}
+
+/// Pops the resolution path when a factory returns.
+struct Frame<'a>(&'a RefCell<Vec<DependencyKey>>);
+
+impl Drop for Frame<'_> {
+    fn drop(&mut self) {
+        self.0.borrow_mut().pop();
+    }
+}
+
+/// A scoped child of a [`LocalContainer`].
+///
+/// Like [`ScopedContainer`](crate::container::ScopedContainer), it caches
+/// the `Scoped` instances built in it and takes singletons from the
+/// parent; its instances are dropped with it.
+pub struct LocalScopedContainer<'a> {
+    parent: &'a LocalContainer,
+    cache: ScopeCache,
+}
+
+impl LocalScopedContainer<'_> {
+    /// Resolve a dependency within this scope.
+    pub fn resolve<T: 'static>(&self) -> Result<T> {
+        let key = DependencyKey::of::<T>();
+        trace!(key = %key, "Resolving locally in scope");
+        let value = self.parent.resolve_internal(&key, Some(&self.cache))?;
+        downcast(key, value)
+    }
+}
+
+impl fmt::Debug for LocalScopedContainer<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LocalScopedContainer").finish()
+    }
+}
+
+/// Resolve a typed dependency from a [`LocalResolver`].
+///
+/// The local counterpart of [`container::resolve`](crate::container::resolve),
+/// for use inside [`LocalContainer`] factories.
+pub fn resolve<T: 'static>(resolver: &dyn LocalResolver) -> Result<T> {
+    let key = DependencyKey::of::<T>();
+    let value = resolver.resolve_key(&key)?;
+    downcast(key, value)
+}
+
+fn downcast<T: 'static>(key: DependencyKey, value: Box<dyn Any>) -> Result<T> {
+    value.downcast::<T>().map(|boxed| *boxed).map_err(|_| MakhzanError::TypeMismatch {
+        key,
+        expected: type_name::<T>(),
+        actual: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    #[test]
+    fn rc_singleton_keeps_identity() {
+        let container = LocalContainer::builder()
+            .singleton_with(|_| Ok(Rc::new(Cell::new(0u32))))
+            .build()
+            .unwrap();
+
+        let a: Rc<Cell<u32>> = container.resolve().unwrap();
+        let b: Rc<Cell<u32>> = container.create_scope().resolve().unwrap();
+        a.set(5);
+        assert!(Rc::ptr_eq(&a, &b));
+        assert_eq!(b.get(), 5);
+    }
+
+    #[test]
+    fn scoped_values_are_shared_within_a_scope_only() {
+        struct View(Rc<Cell<u32>>);
+
+        let container = LocalContainer::builder()
+            .scoped_with(|_| Ok(Rc::new(Cell::new(0u32))))
+            .transient_with(|r| Ok(View(resolve(r)?)))
+            .build()
+            .unwrap();
+
+        let scope = container.create_scope();
+        let state: Rc<Cell<u32>> = scope.resolve().unwrap();
+        let view: View = scope.resolve().unwrap();
+        assert!(Rc::ptr_eq(&state, &scope.resolve().unwrap()));
+        assert!(Rc::ptr_eq(&state, &view.0));
+
+        let other = container.create_scope();
+        assert!(!Rc::ptr_eq(&state, &other.resolve().unwrap()));
+    }
+
+    #[test]
+    fn cloned_builders_build_their_own_singletons() {
+        let builder = LocalContainer::builder().singleton_with(|_| Ok(Rc::new(Cell::new(0u32))));
+
+        let first = builder.clone().build().unwrap();
+        let second = builder.build().unwrap();
+        let a: Rc<Cell<u32>> = first.resolve().unwrap();
+        assert!(Rc::ptr_eq(&a, &first.resolve().unwrap()));
+        assert!(!Rc::ptr_eq(&a, &second.resolve().unwrap()));
+    }
+
+    #[test]
+    fn transients_resolve_dependencies() {
+        struct View {
+            state: Rc<RefCell<Vec<u32>>>,
+        }
+
+        let calls = Rc::new(Cell::new(0));
+        let container = LocalContainer::builder()
+            .singleton_value(Rc::new(RefCell::new(vec![1u32])))
+            .transient_with({
+                let calls = calls.clone();
+                move |r| {
+                    calls.set(calls.get() + 1);
+                    Ok(View { state: resolve(r)? })
+                }
+            })
+            .build()
+            .unwrap();
+
+        let first: View = container.resolve().unwrap();
+        first.state.borrow_mut().push(2);
+        let second: View = container.resolve().unwrap();
+        assert_eq!(*second.state.borrow(), vec![1, 2]);
+        assert_eq!(calls.get(), 2);
+    }
+
+    #[test]
+    fn wrong_factory_output_is_a_type_mismatch() {
+        let mut builder = LocalContainer::builder();
+        builder
+            .register_factory(
+                DependencyKey::of::<u8>(),
+                Scope::Transient,
+                Rc::new(|_| Ok(Box::new(7u32))),
+                vec![],
+            )
+            .unwrap();
+        let container = builder.build().unwrap();
+
+        match container.resolve::<u8>() {
+            Err(MakhzanError::TypeMismatch { key, expected, actual: None }) => {
+                assert_eq!(key, DependencyKey::of::<u8>());
+                assert_eq!(expected, "u8");
+            }
+            other => panic!("expected TypeMismatch, got {:?}", other.err()),
+        }
+    }
+
+    #[test]
+    fn build_validates_declared_dependencies() {
+        let mut builder = LocalContainer::builder();
+        builder
+            .register_factory(
+                DependencyKey::of::<u8>(),
+                Scope::Transient,
+                Rc::new(|r| Ok(Box::new(resolve::<u32>(r)? as u8))),
+                vec![DependencyKey::of::<u32>()],
+            )
+            .unwrap();
+
+        assert!(matches!(builder.build(), Err(MakhzanError::NotRegistered(_))));
+    }
+
+    #[test]
+    fn reentrant_singleton_is_circular_dependency() {
+        let container = LocalContainer::builder()
+            .singleton_with::<Rc<u32>>(|r| resolve::<Rc<u32>>(r))
+            .build()
+            .unwrap();
+
+        assert!(matches!(
+            container.resolve::<Rc<u32>>(),
+            Err(MakhzanError::CircularDependency(_))
+        ));
+    }
+
+    #[test]
+    fn duplicates_are_rejected_unless_overriding() {
+        let builder = LocalContainer::builder().singleton_value(1u32);
+
+        let mut strict = builder.clone();
+        let duplicate = strict.register_factory(
+            DependencyKey::of::<u32>(),
+            Scope::Transient,
+            Rc::new(|_| Ok(Box::new(2u32))),
+            vec![],
+        );
+        assert!(matches!(duplicate, Err(MakhzanError::AlreadyRegistered(_))));
+
+        let chained = builder.clone().singleton_value(2u32).build();
+        assert!(matches!(chained, Err(MakhzanError::AlreadyRegistered(_))));
+
+        let container = builder.allow_override(true).singleton_value(2u32).build().unwrap();
+        assert_eq!(container.resolve::<u32>().unwrap(), 2);
+    }
+}
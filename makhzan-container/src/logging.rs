@@ -0,0 +1,130 @@
+//! Internal logging shims.
+//!
+//! With the `tracing` feature (default) these are re-exports of the
+//! `tracing` macros. Without it they expand to nothing, so call sites
+//! stay identical in both configurations.
+
+use crate::key::DependencyKey;
+
+#[cfg(feature = "tracing")]
+pub(crate) use tracing::{debug, info, trace, warn};
+
+#[cfg(not(feature = "tracing"))]
+macro_rules! noop {
+    ($($arg:tt)*) => {};
+}
+
+#[cfg(not(feature = "tracing"))]
+pub(crate) use {noop as debug, noop as info, noop as trace, noop as warn};
+
+/// Enters a `resolve` span for the factory run of `key`. Keys its factory
+/// resolves open their spans inside it, so subscribers such as
+/// `tracing-tree` show the resolution chain as nested spans.
+#[cfg(feature = "tracing")]
+pub(crate) fn factory_span(key: &DependencyKey) -> tracing::span::EnteredSpan {
+    tracing::debug_span!("resolve", key = %key).entered()
+}
+
+/// Stand-in for the entered span when `tracing` is off.
+#[cfg(not(feature = "tracing"))]
+pub(crate) struct FactorySpan;
+
+#[cfg(not(feature = "tracing"))]
+pub(crate) fn factory_span(_key: &DependencyKey) -> FactorySpan {
+    FactorySpan
+}
+
+#[cfg(all(test, feature = "tracing"))]
+mod tests {
+    use std::fmt;
+    use std::sync::{Arc, Mutex};
+
+    use tracing::field::{Field, Visit};
+    use tracing::span::{Attributes, Id, Record};
+    use tracing::{Event, Metadata, Subscriber};
+
+    use crate::container::{resolve, Container};
+    use crate::key::DependencyKey;
+
+    /// Records every span as its `key` field and the index of the span it
+    /// was opened in.
+    #[derive(Default)]
+    struct SpanTree {
+        spans: Mutex<Vec<(String, Option<usize>)>>,
+        entered: Mutex<Vec<usize>>,
+    }
+
+    struct KeyField(String);
+
+    impl Visit for KeyField {
+        fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+            if field.name() == "key" {
+                self.0 = format!("{value:?}");
+            }
+        }
+    }
+
+    impl Subscriber for SpanTree {
+        fn enabled(&self, _: &Metadata<'_>) -> bool {
+            true
+        }
+
+        fn new_span(&self, attributes: &Attributes<'_>) -> Id {
+            let mut key = KeyField(String::new());
+            attributes.record(&mut key);
+            let parent = self.entered.lock().unwrap().last().copied();
+            let mut spans = self.spans.lock().unwrap();
+            spans.push((key.0, parent));
+            Id::from_u64(spans.len() as u64)
+        }
+
+        fn record(&self, _: &Id, _: &Record<'_>) {}
+
+        fn record_follows_from(&self, _: &Id, _: &Id) {}
+
+        fn event(&self, _: &Event<'_>) {}
+
+        fn enter(&self, span: &Id) {
+            self.entered.lock().unwrap().push(span.into_u64() as usize - 1);
+        }
+
+        fn exit(&self, _: &Id) {
+            self.entered.lock().unwrap().pop();
+        }
+    }
+
+    struct Database;
+    struct Repository(#[allow(dead_code)] Arc<Database>);
+    struct Service(#[allow(dead_code)] Arc<Repository>);
+
+    #[test]
+    fn factory_spans_nest_along_the_resolution_chain() {
+        let container = Container::builder()
+            .singleton_with(|_| Ok(Arc::new(Database)))
+            .transient_with_recorded(|r| Ok(Arc::new(Repository(resolve(r)?))))
+            .transient_with_recorded(|r| Ok(Service(resolve(r)?)))
+            .build()
+            .unwrap();
+        let tree = Arc::new(SpanTree::default());
+
+        tracing::subscriber::with_default(tree.clone(), || {
+            container.resolve::<Service>().unwrap();
+            container.resolve::<Service>().unwrap();
+        });
+
+        let spans = tree.spans.lock().unwrap();
+        let key = |key: DependencyKey| key.to_string();
+        assert_eq!(
+            *spans,
+            [
+                (key(DependencyKey::of::<Service>()), None),
+                (key(DependencyKey::of::<Arc<Repository>>()), Some(0)),
+                (key(DependencyKey::of::<Arc<Database>>()), Some(1)),
+                // The cached singleton runs no factory, so gets no span.
+                (key(DependencyKey::of::<Service>()), None),
+                (key(DependencyKey::of::<Arc<Repository>>()), Some(3)),
+            ]
+        );
+        assert!(tree.entered.lock().unwrap().is_empty());
+    }
+}
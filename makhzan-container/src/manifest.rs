@@ -0,0 +1,250 @@
+//! Serializable snapshot of a container's wiring.
+//!
+//! [`ContainerManifest`] is returned by
+//! [`Container::manifest`](crate::container::Container::manifest). It
+//! holds type names rather than `TypeId`s, so a manifest written by one
+//! build can be compared with the next: commit an approved manifest and
+//! fail CI when [`ContainerManifest::diff`] against the current one is
+//! not empty.
+//!
+//! ```rust,no_run
+//! # use makhzan_container::manifest::ContainerManifest;
+//! # use makhzan_container::prelude::*;
+//! # use std::fs;
+//! # let container = Container::builder().build()?;
+//! let approved: ContainerManifest = serde_json::from_str(&fs::read_to_string("wiring.json")?)?;
+//! let diff = approved.diff(&container.manifest());
+//! assert!(diff.is_empty(), "DI wiring changed:\n{diff}");
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::key::{collection_name, DependencyKey};
+use crate::scope::Scope;
+
+/// Every registration and alias of a container, sorted by key.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ContainerManifest {
+    /// One entry per key
+    pub entries: Vec<ManifestEntry>,
+}
+
+/// How a single key is wired.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ManifestEntry {
+    /// Full type name of the key
+    pub type_name: String,
+    /// Binding name of a [named](DependencyKey::named) key
+    pub name: Option<String>,
+    /// Full type name of the tag of a [tagged](DependencyKey::tagged) key
+    pub tag: Option<String>,
+    /// Position of a collection [element](DependencyKey::element) key
+    pub element: Option<usize>,
+    /// Scope of the registration; `None` for aliases
+    pub scope: Option<Scope>,
+    /// Declared dependencies, as displayed keys
+    pub dependencies: Vec<String>,
+    /// [`Provider::name`](crate::provider::Provider::name) of the
+    /// provider that registered it, if one did
+    pub provider: Option<String>,
+    /// The key an alias resolves to
+    pub alias_for: Option<String>,
+}
+
+impl ManifestEntry {
+    pub(crate) fn new(key: &DependencyKey) -> Self {
+        Self {
+            type_name: key.type_name().to_string(),
+            name: key.name().map(str::to_string),
+            tag: key.tag_name().map(str::to_string),
+            element: key.element_index(),
+            scope: None,
+            dependencies: Vec::new(),
+            provider: None,
+            alias_for: None,
+        }
+    }
+
+    /// The key this entry describes, as [`DependencyKey`] displays it.
+    pub fn key(&self) -> String {
+        if let Some(index) = self.element {
+            return format!("element #{index} of collection {}", collection_name(&self.type_name));
+        }
+        let mut key = self.type_name.clone();
+        if let Some(name) = &self.name {
+            key.push_str(&format!(" (name={name:?})"));
+        }
+        if let Some(tag) = &self.tag {
+            key.push_str(&format!(" (tag={tag})"));
+        }
+        key
+    }
+}
+
+impl ContainerManifest {
+    /// Builds a manifest from `entries`, sorted by key.
+    pub(crate) fn new(mut entries: Vec<ManifestEntry>) -> Self {
+        entries.sort_by_cached_key(ManifestEntry::key);
+        Self { entries }
+    }
+
+    /// What changed going from `self` to `other`.
+    ///
+    /// Entries are matched by [`key`](ManifestEntry::key); a key present
+    /// in both with any field different is reported as changed.
+    pub fn diff(&self, other: &ContainerManifest) -> ManifestDiff {
+        let before: BTreeMap<String, &ManifestEntry> =
+            self.entries.iter().map(|entry| (entry.key(), entry)).collect();
+        let after: BTreeMap<String, &ManifestEntry> =
+            other.entries.iter().map(|entry| (entry.key(), entry)).collect();
+
+        let mut diff = ManifestDiff::default();
+        for (key, entry) in &after {
+            match before.get(key) {
+                None => diff.added.push((*entry).clone()),
+                Some(old) if old != entry => diff.changed.push(ManifestChange {
+                    before: (*old).clone(),
+                    after: (*entry).clone(),
+                }),
+                Some(_) => {}
+            }
+        }
+        for (key, entry) in &before {
+            if !after.contains_key(key) {
+                diff.removed.push((*entry).clone());
+            }
+        }
+        diff
+    }
+}
+
+/// Differences between two manifests, each list sorted by key.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ManifestDiff {
+    /// Keys only in the newer manifest
+    pub added: Vec<ManifestEntry>,
+    /// Keys only in the older manifest
+    pub removed: Vec<ManifestEntry>,
+    /// Keys in both whose wiring differs
+    pub changed: Vec<ManifestChange>,
+}
+
+/// A key whose entry differs between two manifests.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ManifestChange {
+    /// The entry in the older manifest
+    pub before: ManifestEntry,
+    /// The entry in the newer manifest
+    pub after: ManifestEntry,
+}
+
+impl ManifestDiff {
+    /// Whether the two manifests describe the same wiring.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+impl fmt::Display for ManifestDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for entry in &self.added {
+            writeln!(f, "+ {}", entry.key())?;
+        }
+        for entry in &self.removed {
+            writeln!(f, "- {}", entry.key())?;
+        }
+        for change in &self.changed {
+            writeln!(f, "~ {}", change.after.key())?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::container::{resolve, Container, ContainerBuilder};
+    use crate::provider::{Provider, ProviderRegistry};
+
+    #[derive(Clone)]
+    struct Config;
+    struct Database;
+    #[derive(Clone)]
+    struct Mailer;
+
+    struct Storage;
+    impl Provider for Storage {
+        fn register(&self, builder: &mut dyn ProviderRegistry) {
+            builder.register_singleton(
+                DependencyKey::of::<Arc<Database>>(),
+                Arc::new(|_| Ok(Box::new(Arc::new(Database)))),
+                vec![DependencyKey::of::<Config>()],
+            );
+        }
+
+        fn name(&self) -> &str {
+            "storage"
+        }
+    }
+
+    fn base() -> ContainerBuilder {
+        Container::builder()
+            .singleton_value(Config)
+            .add_provider(&Storage)
+            .transient_with_recorded(|r| {
+                let _: Arc<Database> = resolve(r)?;
+                Ok(Mailer)
+            })
+    }
+
+    #[test]
+    fn manifest_round_trips_through_json() {
+        let manifest = base().build().unwrap().manifest();
+        let json = serde_json::to_string_pretty(&manifest).unwrap();
+        let parsed: ContainerManifest = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(parsed, manifest);
+        assert!(parsed.diff(&manifest).is_empty());
+
+        let database = manifest
+            .entries
+            .iter()
+            .find(|entry| entry.type_name.ends_with("Arc<makhzan_container::manifest::tests::Database>"))
+            .unwrap();
+        assert_eq!(database.scope, Some(Scope::Singleton));
+        assert_eq!(database.provider.as_deref(), Some("storage"));
+        assert_eq!(database.dependencies, vec![DependencyKey::of::<Config>().to_string()]);
+    }
+
+    #[test]
+    fn diff_reports_exactly_the_changed_wiring() {
+        let approved = base().build().unwrap().manifest();
+        let current = base()
+            .allow_override(true)
+            .singleton_with(|_| Ok(Mailer))
+            .singleton_value(String::from("smtp://localhost"))
+            .build()
+            .unwrap()
+            .manifest();
+
+        let diff = approved.diff(&current);
+        assert_eq!(diff.added.len(), 1);
+        assert_eq!(diff.added[0].key(), DependencyKey::of::<String>().to_string());
+        assert!(diff.removed.is_empty());
+        assert_eq!(diff.changed.len(), 1);
+        let change = &diff.changed[0];
+        assert_eq!(change.before.key(), DependencyKey::of::<Mailer>().to_string());
+        assert_eq!(change.before.scope, Some(Scope::Transient));
+        assert_eq!(change.after.scope, Some(Scope::Singleton));
+
+        let reverse = current.diff(&approved);
+        assert_eq!(reverse.removed.len(), 1);
+        assert!(reverse.added.is_empty());
+    }
+}
@@ -0,0 +1,310 @@
+//! Precompiled resolution plans.
+//!
+//! The dependency graph is fixed once [`ContainerBuilder::build()`] runs,
+//! so alias chains can be flattened up front. Every resolvable key maps
+//! directly to the registration that serves it, and a resolve costs a
+//! single map lookup with no alias hops.
+//!
+//! Each entry also carries the singleton instance cache, so a cached
+//! singleton is served by that same lookup plus a clone.
+//!
+//! [`ContainerBuilder::build()`]: crate::container::ContainerBuilder::build
+
+use std::any::Any;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use crate::cell::ResetCell;
+use crate::error::MakhzanError;
+use crate::key::{BuildKeyHasher, DependencyKey};
+use crate::registry::{Registration, Registry};
+use crate::scope::Scope;
+
+/// Flattened key → registration table used on the resolve hot path.
+#[derive(Debug)]
+pub(crate) struct Plans {
+    entries: Vec<PlanEntry>,
+    slots: HashMap<DependencyKey, Slot, BuildKeyHasher>,
+}
+
+/// A registration plus the container-owned state attached to it.
+pub(crate) struct PlanEntry {
+    pub registration: Registration,
+    /// Cached singleton outcome, filled on first resolve of a
+    /// registration that has a [`Cloner`](crate::registry::Cloner). A
+    /// failure is only kept under
+    /// [`SingletonFailurePolicy::CacheError`](crate::scope::SingletonFailurePolicy::CacheError).
+    pub instance: ResetCell<Instance>,
+    /// Entries [derived from](Registration::derived_from) this one,
+    /// whose cached values go stale when this one is reset.
+    derived: Vec<usize>,
+}
+
+/// What a singleton's first construction produced.
+pub(crate) type Instance = std::result::Result<Arc<dyn Any + Send + Sync>, CachedFailure>;
+
+impl PlanEntry {
+    /// The cached instance, if one was constructed.
+    #[inline]
+    pub fn cached(&self) -> Option<Arc<dyn Any + Send + Sync>> {
+        self.instance.get_cloned().and_then(Result::ok)
+    }
+
+    /// Whether an instance was constructed; a cached failure doesn't count.
+    pub fn is_instantiated(&self) -> bool {
+        matches!(self.instance.generation().get(), Some(Ok(_)))
+    }
+}
+
+impl std::fmt::Debug for PlanEntry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PlanEntry")
+            .field("registration", &self.registration)
+            .field("instantiated", &self.is_instantiated())
+            .finish()
+    }
+}
+
+/// A singleton factory error kept by
+/// [`SingletonFailurePolicy::CacheError`](crate::scope::SingletonFailurePolicy::CacheError).
+#[derive(Debug, Clone)]
+pub(crate) struct CachedFailure(pub Arc<MakhzanError>);
+
+impl std::fmt::Display for CachedFailure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl std::error::Error for CachedFailure {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&*self.0)
+    }
+}
+
+/// Where a key's registration lives, and how it was reached.
+#[derive(Debug)]
+struct Slot {
+    index: usize,
+    /// Alias hops from the requested key to the registration, inclusive.
+    path: Vec<DependencyKey>,
+}
+
+impl Plans {
+    /// Compiles plans for every registration and every alias in `registry`.
+    ///
+    /// Aliases that form a cycle or end at an unregistered key get no plan;
+    /// resolving them reports [`NotRegistered`](crate::error::MakhzanError::NotRegistered).
+    pub fn compile(registry: &Registry) -> Self {
+        let mut entries = Vec::with_capacity(registry.len());
+        let mut indices: HashMap<DependencyKey, usize, BuildKeyHasher> = HashMap::default();
+
+        for (key, registration) in registry.all_registrations() {
+            indices.insert(key.clone(), entries.len());
+            entries.push(PlanEntry {
+                registration: registration.clone(),
+                instance: ResetCell::new(),
+                derived: Vec::new(),
+            });
+        }
+
+        let mut slots: HashMap<DependencyKey, Slot, BuildKeyHasher> = HashMap::default();
+
+        for (key, &index) in &indices {
+            // An alias with the same key shadows the registration.
+            if registry.alias_target(key).is_none() {
+                slots.insert(key.clone(), Slot { index, path: vec![key.clone()] });
+            }
+        }
+
+        for key in registry.all_aliases().keys() {
+            let mut path = vec![key.clone()];
+            let mut seen = HashSet::from([key.clone()]);
+            let mut current = key;
+
+            while let Some(target) = registry.alias_target(current) {
+                if !seen.insert(target.clone()) {
+                    break;
+                }
+                path.push(target.clone());
+                current = target;
+            }
+
+            if registry.alias_target(current).is_none()
+                && let Some(&index) = indices.get(current)
+            {
+                slots.insert(key.clone(), Slot { index, path });
+            }
+        }
+
+        for index in 0..entries.len() {
+            if let Some(source) = &entries[index].registration.derived_from
+                && let Some(slot) = slots.get(source)
+            {
+                entries[slot.index].derived.push(index);
+            }
+        }
+
+        Self { entries, slots }
+    }
+
+    /// The entries derived from `entry`, directly or through other derived
+    /// entries, e.g. an [`also_as`](crate::container::ContainerBuilder::also_as)
+    /// view of a [`bind`](crate::container::ContainerBuilder::bind) target.
+    pub fn derived(&self, entry: &PlanEntry) -> Vec<&PlanEntry> {
+        let mut seen = vec![false; self.entries.len()];
+        let mut pending = entry.derived.clone();
+        let mut derived = Vec::new();
+        while let Some(index) = pending.pop() {
+            if !std::mem::replace(&mut seen[index], true) {
+                let entry = &self.entries[index];
+                pending.extend(&entry.derived);
+                derived.push(entry);
+            }
+        }
+        derived
+    }
+
+    /// Every registration's entry, once each.
+    pub fn entries(&self) -> impl Iterator<Item = &PlanEntry> {
+        self.entries.iter()
+    }
+
+    /// Returns the entry serving `key`.
+    #[inline]
+    pub fn get(&self, key: &DependencyKey) -> Option<&PlanEntry> {
+        self.slots.get(key).map(|slot| &self.entries[slot.index])
+    }
+
+    /// Number of singleton instances currently cached.
+    #[cfg(test)]
+    pub fn cached_instances(&self) -> usize {
+        self.entries.iter().filter(|entry| entry.is_instantiated()).count()
+    }
+
+    /// Returns a debug view of the plan for `key`.
+    pub fn describe(&self, key: &DependencyKey) -> Option<ResolutionPlan> {
+        let slot = self.slots.get(key)?;
+        let registration = &self.entries[slot.index].registration;
+
+        Some(ResolutionPlan {
+            key: key.clone(),
+            target: registration.key.clone(),
+            path: slot.path.clone(),
+            scope: registration.scope,
+            dependencies: registration.dependencies.clone(),
+        })
+    }
+}
+
+/// Debug view of how a key is resolved, from
+/// [`Container::plan_for`](crate::container::Container::plan_for).
+///
+/// # Examples
+/// ```rust
+/// # use makhzan_container::prelude::*;
+/// # use makhzan_container::provider::ProviderRegistry;
+/// # use std::sync::Arc;
+/// # trait Logger: Send + Sync {}
+/// # struct ConsoleLogger;
+/// # impl Logger for ConsoleLogger {}
+/// # struct Aliases;
+/// # impl Provider for Aliases {
+/// #     fn register(&self, builder: &mut dyn ProviderRegistry) {
+/// #         builder.register_alias(DependencyKey::of::<Arc<dyn Logger>>(), DependencyKey::of::<Arc<ConsoleLogger>>());
+/// #     }
+/// # }
+/// # let container = Container::builder().singleton_arc(|_| Ok(ConsoleLogger)).add_provider(&Aliases).build()?;
+/// let plan = container.plan_for::<Arc<dyn Logger>>().unwrap();
+/// assert_eq!(plan.target, DependencyKey::of::<Arc<ConsoleLogger>>());
+/// # Ok::<(), MakhzanError>(())
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResolutionPlan {
+    /// The requested key
+    pub key: DependencyKey,
+    /// The registration that actually serves `key`
+    pub target: DependencyKey,
+    /// Alias hops from `key` to `target`, inclusive.
+    /// A direct registration has a single-element path.
+    pub path: Vec<DependencyKey>,
+    /// Scope of the target registration
+    pub scope: Scope,
+    /// Declared dependencies of the target registration
+    pub dependencies: Vec<DependencyKey>,
+}
+
+impl ResolutionPlan {
+    /// Returns `true` if `key` reaches its registration through aliases.
+    pub fn is_alias(&self) -> bool {
+        self.path.len() > 1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::registry::FactoryFn;
+    use std::sync::Arc;
+
+    fn dummy_factory() -> FactoryFn {
+        Arc::new(|_| Ok(Box::new(42i32)))
+    }
+
+    fn registry_with(keys: &[DependencyKey]) -> Registry {
+        let mut registry = Registry::new();
+        for key in keys {
+            registry
+                .register(Registration::new(key.clone(), Scope::Singleton, dummy_factory(), vec![]), false)
+                .unwrap();
+        }
+        registry
+    }
+
+    #[test]
+    fn direct_registration_plan() {
+        let key = DependencyKey::of::<String>();
+        let plans = Plans::compile(&registry_with(std::slice::from_ref(&key)));
+
+        let plan = plans.describe(&key).unwrap();
+        assert_eq!(plan.target, key);
+        assert!(!plan.is_alias());
+        assert!(plans.get(&key).is_some());
+        assert_eq!(plans.cached_instances(), 0);
+    }
+
+    #[test]
+    fn alias_chain_is_flattened() {
+        let concrete = DependencyKey::of::<String>();
+        let middle = DependencyKey::of::<i64>();
+        let outer = DependencyKey::of::<u64>();
+
+        let mut registry = registry_with(std::slice::from_ref(&concrete));
+        registry.register_alias(outer.clone(), middle.clone());
+        registry.register_alias(middle.clone(), concrete.clone());
+
+        let plans = Plans::compile(&registry);
+        let plan = plans.describe(&outer).unwrap();
+
+        assert_eq!(plan.target, concrete);
+        assert_eq!(plan.path, vec![outer.clone(), middle, concrete.clone()]);
+        assert_eq!(plans.get(&outer).unwrap().registration.key, concrete);
+    }
+
+    #[test]
+    fn alias_cycle_and_dangling_alias_have_no_plan() {
+        let a = DependencyKey::of::<u8>();
+        let b = DependencyKey::of::<u16>();
+        let dangling = DependencyKey::of::<u32>();
+
+        let mut registry = Registry::new();
+        registry.register_alias(a.clone(), b.clone());
+        registry.register_alias(b.clone(), a.clone());
+        registry.register_alias(dangling.clone(), DependencyKey::of::<u64>());
+
+        let plans = Plans::compile(&registry);
+        assert!(plans.get(&a).is_none());
+        assert!(plans.get(&b).is_none());
+        assert!(plans.get(&dangling).is_none());
+    }
+}
@@ -4,19 +4,62 @@
 //! Laravel's ServiceProvider or DIshka's Provider class.
 //!
 //! # Examples
-//! ```rust,ignore
+//! ```rust
+//! use makhzan_container::prelude::*;
+//! use makhzan_container::provider::{Cacheable, ProviderRegistry};
+//! use std::sync::Arc;
+//!
+//! # #[derive(Clone)]
+//! # struct Config { database_url: String }
+//! # struct Database;
+//! # impl Database { fn connect(_: &str) -> Self { Database } }
+//! # trait Repository: Send + Sync {}
+//! # struct PostgresRepository(Arc<Database>);
+//! # impl Repository for PostgresRepository {}
 //! struct DatabaseProvider;
 //!
 //! impl Provider for DatabaseProvider {
-//!     fn register(&self, builder: &mut ContainerBuilder) {
-//!         builder.singleton::<Database>(|_| {
-//!             Ok(Database::connect("postgres://localhost"))
-//!         });
-//!         builder.bind::<dyn Repository, PostgresRepository>();
+//!     fn register(&self, builder: &mut dyn ProviderRegistry) {
+//!         builder.register_cacheable(
+//!             DependencyKey::of::<Arc<Database>>(),
+//!             Scope::Singleton,
+//!             Arc::new(|r| {
+//!                 let config: Config = resolve(r)?;
+//!                 Ok(Box::new(Arc::new(Database::connect(&config.database_url))))
+//!             }),
+//!             vec![DependencyKey::of::<Config>()],
+//!             Cacheable::of::<Arc<Database>>(),
+//!         );
+//!         builder.register_transient(
+//!             DependencyKey::of::<Arc<dyn Repository>>(),
+//!             Arc::new(|r| Ok(Box::new(Arc::new(PostgresRepository(resolve(r)?)) as Arc<dyn Repository>))),
+//!             vec![DependencyKey::of::<Arc<Database>>()],
+//!         );
+//!     }
+//!
+//!     fn provides(&self) -> Vec<DependencyKey> {
+//!         vec![DependencyKey::of::<Arc<Database>>(), DependencyKey::of::<Arc<dyn Repository>>()]
+//!     }
+//!
+//!     fn requires(&self) -> Vec<DependencyKey> {
+//!         vec![DependencyKey::of::<Config>()]
 //!     }
 //! }
+//!
+//! let container = Container::builder()
+//!     .singleton_value(Config { database_url: "postgres://localhost".into() })
+//!     .add_provider(&DatabaseProvider)
+//!     .build()?;
+//! let repository: Arc<dyn Repository> = container.resolve()?;
+//! # Ok::<(), MakhzanError>(())
 //! ```
 
+use std::fmt;
+
+use crate::key::DependencyKey;
+use crate::registry::Cloner;
+use crate::scope::Scope;
+
 /// A module that registers related dependencies into a container.
 ///
 /// Implement this trait to group related services together.
@@ -29,17 +72,29 @@
 /// Providers encourage modular architecture. Instead of one giant
 /// registration block, split your dependencies by domain:
 ///
-/// ```rust,ignore
+/// ```rust
+/// # use makhzan_container::prelude::*;
+/// # use makhzan_container::provider::ProviderRegistry;
+/// # struct DatabaseProvider;
+/// # struct AuthProvider;
+/// # struct EmailProvider;
+/// # impl Provider for DatabaseProvider { fn register(&self, _: &mut dyn ProviderRegistry) {} }
+/// # impl Provider for AuthProvider { fn register(&self, _: &mut dyn ProviderRegistry) {} }
+/// # impl Provider for EmailProvider { fn register(&self, _: &mut dyn ProviderRegistry) {} }
 /// // Good: separated by concern
-/// container.add_provider(DatabaseProvider);
-/// container.add_provider(AuthProvider);
-/// container.add_provider(EmailProvider);
+/// let container = Container::builder()
+///     .add_provider(&DatabaseProvider)
+///     .add_provider(&AuthProvider)
+///     .add_provider(&EmailProvider)
+///     .build()?;
 ///
 /// // Bad: everything in one place
-/// container.register::<Database>(...);
-/// container.register::<AuthService>(...);
-/// container.register::<EmailService>(...);
-/// // ... 200 more lines
+/// // Container::builder()
+/// //     .singleton_with::<Database>(...)
+/// //     .singleton_with::<AuthService>(...)
+/// //     .singleton_with::<EmailService>(...)
+/// //     // ... 200 more lines
+/// # Ok::<(), MakhzanError>(())
 /// ```
 pub trait Provider: Send + Sync {
     /// Register dependencies into the container builder.
@@ -51,6 +106,48 @@ pub trait Provider: Send + Sync {
     fn name(&self) -> &str {
         std::any::type_name::<Self>()
     }
+
+    /// Keys this provider registers, for tooling that lists what a
+    /// provider offers before adding it. Empty unless overridden.
+    fn provides(&self) -> Vec<DependencyKey> {
+        Vec::new()
+    }
+
+    /// Keys this provider needs from elsewhere. Empty unless overridden.
+    ///
+    /// [`ContainerBuilder::unmet_requirements`](crate::container::ContainerBuilder::unmet_requirements)
+    /// checks them against everything registered so far.
+    fn requires(&self) -> Vec<DependencyKey> {
+        Vec::new()
+    }
+
+    /// Release what this provider set up, e.g. close a pool it registered.
+    ///
+    /// Called for providers added with
+    /// [`add_provider_owned`](crate::container::ContainerBuilder::add_provider_owned),
+    /// once, when the last [`Container`](crate::container::Container)
+    /// built with it is dropped. Within a container, providers are torn
+    /// down in reverse add order, so a provider goes before the ones
+    /// added ahead of it. Singletons can still be resolved from
+    /// `container`. No-op unless overridden.
+    fn teardown(&self, container: &crate::container::Container) {
+        let _ = container;
+    }
+}
+
+/// A key a provider [`requires`](Provider::requires) that nothing
+/// registered.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnmetRequirement {
+    /// [`Provider::name`] of the provider declaring the requirement
+    pub provider: String,
+    pub key: DependencyKey,
+}
+
+impl fmt::Display for UnmetRequirement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} requires {}, which is not registered", self.provider, self.key)
+    }
 }
 
 /// Interface that providers use to register dependencies.
@@ -59,7 +156,12 @@ pub trait Provider: Send + Sync {
 /// implementations. This decoupling allows providers to be tested
 /// independently.
 pub trait ProviderRegistry {
-    /// Register a singleton factory.
+    /// Register a factory under [`Scope::Singleton`].
+    ///
+    /// **Nothing is cached**: without a way to clone the value back out,
+    /// `factory` runs on every resolve, so only the scope rules apply.
+    /// Use [`register_cacheable`](Self::register_cacheable) with a
+    /// [`Cacheable`] for one shared instance.
     fn register_singleton(
         &mut self,
         key: crate::key::DependencyKey,
@@ -67,7 +169,13 @@ pub trait ProviderRegistry {
         dependencies: Vec<crate::key::DependencyKey>,
     );
 
-    /// Register a scoped factory.
+    /// Register a factory under [`Scope::Scoped`].
+    ///
+    /// **Nothing is cached**, as with
+    /// [`register_singleton`](Self::register_singleton): `factory` runs on
+    /// every resolve, scope or not. Use
+    /// [`register_cacheable`](Self::register_cacheable) with a
+    /// [`Cacheable`] for one instance per scope.
     fn register_scoped(
         &mut self,
         key: crate::key::DependencyKey,
@@ -89,70 +197,75 @@ pub trait ProviderRegistry {
         from: crate::key::DependencyKey,
         to: crate::key::DependencyKey,
     );
+
+    /// Register a factory whose value can be cached.
+    ///
+    /// The untyped methods above can't clone a value back out of a cache,
+    /// so their singletons and scoped values are rebuilt on every resolve
+    /// unless the factory caches them itself. With `cacheable` the
+    /// container caches them like
+    /// [`ContainerBuilder::singleton_with`](crate::container::ContainerBuilder::singleton_with)
+    /// and [`scoped_with`](crate::container::ContainerBuilder::scoped_with)
+    /// do. The default ignores `cacheable`.
+    fn register_cacheable(
+        &mut self,
+        key: DependencyKey,
+        scope: Scope,
+        factory: crate::registry::FactoryFn,
+        dependencies: Vec<DependencyKey>,
+        cacheable: Cacheable,
+    ) {
+        let _ = cacheable;
+        match scope {
+            Scope::Singleton => self.register_singleton(key, factory, dependencies),
+            Scope::Scoped => self.register_scoped(key, factory, dependencies),
+            Scope::Transient => self.register_transient(key, factory, dependencies),
+        }
+    }
+}
+
+/// Tells [`ProviderRegistry::register_cacheable`] how to clone the
+/// factory's value out of a cache.
+#[derive(Clone, Copy)]
+pub struct Cacheable(pub(crate) Cloner);
+
+impl Cacheable {
+    /// For a factory producing `T`.
+    pub fn of<T: Clone + Send + Sync + 'static>() -> Self {
+        Self(Cloner::of::<T>())
+    }
+}
+
+impl fmt::Debug for Cacheable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Cacheable").finish_non_exhaustive()
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::key::DependencyKey;
-    use crate::registry::FactoryFn;
+    use crate::scope::Scope;
+    use crate::testing::{RecordingRegistry, RecordingResolver};
     use std::sync::Arc;
 
-    // Mock registry for testing providers
-    struct MockRegistry {
-        registered_count: usize,
-        alias_count: usize,
-    }
-
-    impl MockRegistry {
-        fn new() -> Self {
-            Self {
-                registered_count: 0,
-                alias_count: 0,
-            }
-        }
-    }
+    // Test provider
+    struct TestProvider;
 
-    impl ProviderRegistry for MockRegistry {
-        fn register_singleton(
-            &mut self,
-            _key: DependencyKey,
-            _factory: FactoryFn,
-            _deps: Vec<DependencyKey>,
-        ) {
-            self.registered_count += 1;
-        }
+    struct DescribedProvider;
 
-        fn register_scoped(
-            &mut self,
-            _key: DependencyKey,
-            _factory: FactoryFn,
-            _deps: Vec<DependencyKey>,
-        ) {
-            self.registered_count += 1;
-        }
+    impl Provider for DescribedProvider {
+        fn register(&self, _builder: &mut dyn ProviderRegistry) {}
 
-        fn register_transient(
-            &mut self,
-            _key: DependencyKey,
-            _factory: FactoryFn,
-            _deps: Vec<DependencyKey>,
-        ) {
-            self.registered_count += 1;
+        fn provides(&self) -> Vec<DependencyKey> {
+            vec![DependencyKey::of::<String>()]
         }
 
-        fn register_alias(
-            &mut self,
-            _from: DependencyKey,
-            _to: DependencyKey,
-        ) {
-            self.alias_count += 1;
+        fn requires(&self) -> Vec<DependencyKey> {
+            vec![DependencyKey::of::<u64>()]
         }
     }
 
-    // Test provider
-    struct TestProvider;
-
     impl Provider for TestProvider {
         fn register(&self, builder: &mut dyn ProviderRegistry) {
             builder.register_singleton(
@@ -171,12 +284,30 @@ mod tests {
 
     #[test]
     fn provider_registers_dependencies() {
-        let mut registry = MockRegistry::new();
+        let mut registry = RecordingRegistry::new();
         let provider = TestProvider;
 
         provider.register(&mut registry);
 
-        assert_eq!(registry.registered_count, 2);
+        let registered: Vec<_> = registry.registrations().iter().map(|r| (r.key.clone(), r.scope)).collect();
+        assert_eq!(
+            registered,
+            [(DependencyKey::of::<String>(), Scope::Singleton), (DependencyKey::of::<i32>(), Scope::Transient)]
+        );
+        assert!(registry.aliases().is_empty());
+
+        let resolver = RecordingResolver::new();
+        let number = registry.get(&DependencyKey::of::<i32>()).unwrap();
+        assert_eq!(number.create::<i32>(&resolver).unwrap(), 42);
+        assert!(resolver.requested().is_empty());
+    }
+
+    #[test]
+    fn metadata_is_empty_by_default() {
+        assert!(TestProvider.provides().is_empty());
+        assert!(TestProvider.requires().is_empty());
+        assert_eq!(DescribedProvider.provides(), vec![DependencyKey::of::<String>()]);
+        assert_eq!(DescribedProvider.requires(), vec![DependencyKey::of::<u64>()]);
     }
 
     #[test]
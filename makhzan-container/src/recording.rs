@@ -0,0 +1,127 @@
+//! Dependency inference by dry run.
+//!
+//! Registrations made with
+//! [`transient_with_recorded`](crate::container::ContainerBuilder::transient_with_recorded)
+//! don't declare their dependencies. Instead, `build()` runs their factory
+//! once against a [`RecordingResolver`], which notes every key the factory
+//! asks for. The recorded keys become the registration's dependency edges
+//! and go through the usual graph validation.
+//!
+//! # Caveat
+//!
+//! The factory really runs, but never sees real dependencies: keys with a
+//! sentinel (see [`ContainerBuilder::sentinel`](crate::container::ContainerBuilder::sentinel))
+//! resolve to that value, every other key resolves to an error. A factory
+//! that stops at the first error (`resolve(r)?`) is only recorded up to the
+//! first key without a sentinel, so register sentinels for the dependencies
+//! you need to get past. Factories with side effects should not be
+//! recorded.
+
+use std::any::Any;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use crate::error::MakhzanError;
+use crate::key::{BuildKeyHasher, DependencyKey};
+use crate::logging::trace;
+use crate::registry::{FactoryFn, Resolver};
+
+/// Stand-in values handed to factories during a dry run.
+pub(crate) type Sentinels = HashMap<DependencyKey, FactoryFn, BuildKeyHasher>;
+
+/// A [`Resolver`] that records every key it is asked for.
+///
+/// Resolves keys to their sentinel, if one is registered, and to an error
+/// otherwise.
+pub struct RecordingResolver<'a> {
+    sentinels: &'a Sentinels,
+    recorded: Mutex<Vec<DependencyKey>>,
+}
+
+impl<'a> RecordingResolver<'a> {
+    pub(crate) fn new(sentinels: &'a Sentinels) -> Self {
+        Self {
+            sentinels,
+            recorded: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Runs `factory` and returns the keys it resolved, in first-use order.
+    ///
+    /// Whatever the factory returns is discarded.
+    pub(crate) fn record(sentinels: &'a Sentinels, factory: &FactoryFn) -> Vec<DependencyKey> {
+        let resolver = Self::new(sentinels);
+        let _ = factory(&resolver);
+        resolver.recorded()
+    }
+
+    /// The keys resolved so far, without duplicates, in first-use order.
+    pub fn recorded(&self) -> Vec<DependencyKey> {
+        self.recorded.lock().map(|keys| keys.clone()).unwrap_or_default()
+    }
+}
+
+impl Resolver for RecordingResolver<'_> {
+    fn resolve_key(&self, key: &DependencyKey) -> Result<Box<dyn Any + Send + Sync>, MakhzanError> {
+        trace!(key = %key, "Recorded dependency");
+        if let Ok(mut recorded) = self.recorded.lock()
+            && !recorded.contains(key)
+        {
+            recorded.push(key.clone());
+        }
+
+        match self.sentinels.get(key) {
+            Some(sentinel) => sentinel(self),
+            None => Err(MakhzanError::construction_failed(
+                key.clone(),
+                "no sentinel registered for the dry run; \
+                 add one with ContainerBuilder::sentinel",
+            )),
+        }
+    }
+}
+
+/// Wraps a sentinel value into a factory that clones it.
+pub(crate) fn sentinel_factory<T: Clone + Send + Sync + 'static>(value: T) -> FactoryFn {
+    Arc::new(move |_: &dyn Resolver| Ok(Box::new(value.clone()) as Box<dyn Any + Send + Sync>))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::container::resolve;
+
+    #[test]
+    fn records_in_first_use_order_without_duplicates() {
+        let mut sentinels = Sentinels::default();
+        sentinels.insert(DependencyKey::of::<u32>(), sentinel_factory(1u32));
+        sentinels.insert(DependencyKey::of::<String>(), sentinel_factory(String::new()));
+
+        let factory: FactoryFn = Arc::new(|r| {
+            let n: u32 = resolve(r)?;
+            let s: String = resolve(r)?;
+            let again: u32 = resolve(r)?;
+            Ok(Box::new(format!("{n}{s}{again}")))
+        });
+
+        assert_eq!(
+            RecordingResolver::record(&sentinels, &factory),
+            vec![DependencyKey::of::<u32>(), DependencyKey::of::<String>()]
+        );
+    }
+
+    #[test]
+    fn recording_stops_at_first_key_without_sentinel() {
+        let sentinels = Sentinels::default();
+        let factory: FactoryFn = Arc::new(|r| {
+            let n: u32 = resolve(r)?;
+            let s: String = resolve(r)?;
+            Ok(Box::new(format!("{n}{s}")))
+        });
+
+        assert_eq!(
+            RecordingResolver::record(&sentinels, &factory),
+            vec![DependencyKey::of::<u32>()]
+        );
+    }
+}
@@ -7,11 +7,11 @@ use std::any::Any;
 use std::collections::HashMap;
 use std::sync::Arc;
 
-use tracing::{debug, trace};
-
 use crate::error::{MakhzanError, AlreadyRegisteredError};
-use crate::key::DependencyKey;
-use crate::scope::Scope;
+use crate::graph::DependencyInfo;
+use crate::key::{BuildKeyHasher, DependencyKey};
+use crate::logging::debug;
+use crate::scope::{Scope, SingletonFailurePolicy};
 
 /// Type alias for factory functions.
 ///
@@ -29,7 +29,156 @@ pub type FactoryFn = Arc<dyn Fn(&dyn Resolver) -> Result<Box<dyn Any + Send + Sy
 /// Separated from Container to avoid circular references.
 pub trait Resolver: Send + Sync {
     fn resolve_key(&self,key: &DependencyKey) -> Result<Box<dyn Any + Send + Sync>, MakhzanError>;
+
+    /// Resolves `key` without forcing a `Box` for cached singletons.
+    ///
+    /// The default implementation wraps [`resolve_key`](Self::resolve_key).
+    fn resolve_erased(&self, key: &DependencyKey) -> Result<Resolved, MakhzanError> {
+        self.resolve_key(key).map(Resolved::owned)
+    }
+}
+
+/// A type-erased resolved value.
+///
+/// Cached singletons are shared and cloned straight into the requested
+/// type, so resolving e.g. an `Arc<Database>` singleton costs a reference
+/// count bump instead of a `Box` allocation. Everything else is boxed.
+pub struct Resolved(Erased);
+
+enum Erased {
+    Shared(Arc<dyn Any + Send + Sync>, Cloner),
+    Owned(Box<dyn Any + Send + Sync>),
+}
+
+impl Resolved {
+    /// Wraps a freshly constructed, boxed value.
+    pub fn owned(value: Box<dyn Any + Send + Sync>) -> Self {
+        Self(Erased::Owned(value))
+    }
+
+    /// Wraps a shared singleton that `cloner` knows how to copy out.
+    pub(crate) fn shared(value: Arc<dyn Any + Send + Sync>, cloner: Cloner) -> Self {
+        Self(Erased::Shared(value, cloner))
+    }
+
+    /// Returns `true` if the value is a `T`.
+    pub fn is<T: 'static>(&self) -> bool {
+        match &self.0 {
+            Erased::Shared(value, _) => value.is::<T>(),
+            Erased::Owned(value) => value.is::<T>(),
+        }
+    }
+
+    /// Borrows the value as `T`, without cloning a shared singleton.
+    pub fn downcast_ref<T: 'static>(&self) -> Option<&T> {
+        match &self.0 {
+            Erased::Shared(value, _) => value.downcast_ref::<T>(),
+            Erased::Owned(value) => value.downcast_ref::<T>(),
+        }
+    }
+
+    /// Extracts the value as `T`, handing `self` back on a type mismatch.
+    pub fn downcast<T: 'static>(self) -> Result<T, Self> {
+        match self.0 {
+            Erased::Shared(value, cloner) => match cloner.get::<T>(&*value) {
+                Some(value) => Ok(value),
+                None => Err(Self(Erased::Shared(value, cloner))),
+            },
+            Erased::Owned(value) => value
+                .downcast::<T>()
+                .map(|value| *value)
+                .map_err(|value| Self(Erased::Owned(value))),
+        }
+    }
+
+    /// Full type name of the value, if known; only shared singletons
+    /// record it.
+    pub(crate) fn type_name(&self) -> Option<&'static str> {
+        match &self.0 {
+            Erased::Shared(_, cloner) => Some((cloner.type_name)()),
+            Erased::Owned(_) => None,
+        }
+    }
+
+    /// Converts into a boxed value, cloning a shared singleton if needed.
+    pub fn into_box(self) -> Box<dyn Any + Send + Sync> {
+        match self.0 {
+            Erased::Shared(value, cloner) => (cloner.boxed)(&*value),
+            Erased::Owned(value) => value,
+        }
+    }
+}
+
+impl From<Box<dyn Any + Send + Sync>> for Resolved {
+    fn from(value: Box<dyn Any + Send + Sync>) -> Self {
+        Self::owned(value)
+    }
+}
+
+impl std::fmt::Debug for Resolved {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.0 {
+            Erased::Shared(..) => f.write_str("Resolved::Shared"),
+            Erased::Owned(_) => f.write_str("Resolved::Owned"),
+        }
+    }
+}
+
+/// Type-erased `Clone` for singleton values.
+///
+/// Lets the container's singleton cache hand out values without boxing.
+#[derive(Clone, Copy)]
+pub(crate) struct Cloner {
+    /// Clones the `T` behind the value into an `Option<T>` slot.
+    into_slot: fn(&(dyn Any + Send + Sync), &mut dyn Any),
+    /// Clones the `T` behind the value into a fresh box.
+    boxed: fn(&(dyn Any + Send + Sync)) -> Box<dyn Any + Send + Sync>,
+    /// Full type name of `T`.
+    type_name: fn() -> &'static str,
+}
+
+impl Cloner {
+    /// Cloner for values of type `T`.
+    pub fn of<T: Clone + Send + Sync + 'static>() -> Self {
+        Self {
+            into_slot: |value, slot| {
+                if let (Some(value), Some(slot)) =
+                    (value.downcast_ref::<T>(), slot.downcast_mut::<Option<T>>())
+                {
+                    *slot = Some(value.clone());
+                }
+            },
+            boxed: |value| {
+                let value = value.downcast_ref::<T>().expect("cloner matches the cached type");
+                Box::new(value.clone())
+            },
+            type_name: std::any::type_name::<T>,
+        }
+    }
+
+    /// Clones `value` out as `T`, or `None` on a type mismatch.
+    pub fn get<T: 'static>(&self, value: &(dyn Any + Send + Sync)) -> Option<T> {
+        let mut slot: Option<T> = None;
+        (self.into_slot)(value, &mut slot);
+        slot
+    }
 }
+
+/// A key family: returns the registration for the keys it covers.
+pub(crate) type FamilyFn = Arc<dyn Fn(&DependencyKey) -> Option<Registration> + Send + Sync>;
+
+/// Reports whether a singleton's instance has been created yet.
+pub(crate) type InstanceProbe = Arc<dyn Fn() -> bool + Send + Sync>;
+
+/// Drops a singleton's cached instance so the next resolve rebuilds it.
+pub(crate) type SingletonReset = Arc<dyn Fn() + Send + Sync>;
+
+/// Builds a value straight into a caller-provided `Option<T>` slot, so
+/// small `Copy` transients skip the `Box`. Leaves the slot untouched if
+/// it is not an `Option` of the registered type.
+pub(crate) type InlineFactory =
+    Arc<dyn Fn(&dyn Resolver, &mut dyn Any) -> Result<(), MakhzanError> + Send + Sync>;
+
 /// Registration entry for a single dependency.
 #[derive(Clone)]
 pub(crate) struct Registration {
@@ -37,6 +186,50 @@ pub(crate) struct Registration {
     pub factory: FactoryFn,
     pub scope: Scope,
     pub dependencies: Vec<DependencyKey>,
+    /// Set for singletons whose value can be cached and cloned out.
+    pub cloner: Option<Cloner>,
+    /// Set for singletons that can report whether they were created yet.
+    pub instantiated: Option<InstanceProbe>,
+    /// Set for singletons whose factory caches its own instance.
+    pub reset: Option<SingletonReset>,
+    /// Set for [`bind`](crate::container::ContainerBuilder::bind) and
+    /// [`also_as`](crate::container::ContainerBuilder::also_as) keys: the
+    /// registration their value is computed from.
+    pub derived_from: Option<DependencyKey>,
+    /// Whether a failed singleton construction is cached like a value.
+    pub failure_policy: SingletonFailurePolicy,
+    /// Dependencies are inferred by a dry run at build time.
+    pub recorded: bool,
+    /// Set for transients that can be built without boxing.
+    pub inline: Option<InlineFactory>,
+    /// [`Provider::name`](crate::provider::Provider::name) of the
+    /// provider that registered it.
+    pub provider: Option<Arc<str>>,
+}
+
+impl Registration {
+    /// Creates a registration with no singleton bookkeeping attached.
+    pub fn new(
+        key: DependencyKey,
+        scope: Scope,
+        factory: FactoryFn,
+        dependencies: Vec<DependencyKey>,
+    ) -> Self {
+        Self {
+            key,
+            factory,
+            scope,
+            dependencies,
+            cloner: None,
+            instantiated: None,
+            reset: None,
+            derived_from: None,
+            failure_policy: SingletonFailurePolicy::RetryOnNextResolve,
+            recorded: false,
+            inline: None,
+            provider: None,
+        }
+    }
 }
 
 
@@ -54,18 +247,39 @@ impl std::fmt::Debug for Registration {
 ///
 /// The registry is populated during the build phase and becomes
 /// immutable once the container is constructed.
-#[derive(Debug)]
+///
+/// Cloning is cheap: factories are `Arc`-shared, only the maps are copied.
+#[derive(Debug, Clone)]
 pub(crate) struct Registry {
-    registrations: HashMap<DependencyKey, Registration>,
-    aliases: HashMap<DependencyKey, DependencyKey>,
+    registrations: HashMap<DependencyKey, Registration, BuildKeyHasher>,
+    /// Keys of `registrations` in the order they were first registered.
+    order: Vec<DependencyKey>,
+    aliases: HashMap<DependencyKey, DependencyKey, BuildKeyHasher>,
+    /// Bumped on every `register`, so replaced registrations can be told
+    /// apart from the ones an alias saw.
+    revisions: HashMap<DependencyKey, u64, BuildKeyHasher>,
+    next_revision: u64,
+    /// What each alias target looked like when the alias was recorded.
+    alias_snapshots: HashMap<DependencyKey, AliasSnapshot, BuildKeyHasher>,
+}
+
+/// An alias target's registration at the time the alias was recorded.
+#[derive(Debug, Clone, Copy)]
+struct AliasSnapshot {
+    revision: u64,
+    scope: Scope,
 }
 
 impl Registry {
     /// Creates an empty registry.
     pub fn new() -> Self {
         Self {
-            registrations: HashMap::new(),
-            aliases: HashMap::new(),
+            registrations: HashMap::default(),
+            order: Vec::new(),
+            aliases: HashMap::default(),
+            revisions: HashMap::default(),
+            next_revision: 0,
+            alias_snapshots: HashMap::default(),
         }
     }
 
@@ -89,50 +303,227 @@ impl Registry {
         }
 
         debug!(key = %key, scope = %registration.scope, "Registered dependency");
+        self.revisions.insert(key.clone(), self.next_revision);
+        self.next_revision += 1;
+        if !self.registrations.contains_key(&key) {
+            self.order.push(key.clone());
+        }
         self.registrations.insert(key, registration);
         Ok(())
     }
 
+    /// The revision the next `register` will get.
+    pub fn next_revision(&self) -> u64 {
+        self.next_revision
+    }
+
+    /// Records `provider` as the source of every registration made at or
+    /// after `revision`.
+    pub fn attribute_since(&mut self, revision: u64, provider: &Arc<str>) {
+        for (key, registration) in &mut self.registrations {
+            if self.revisions.get(key).is_some_and(|&r| r >= revision) {
+                registration.provider = Some(provider.clone());
+            }
+        }
+    }
+
     /// Registers an alias: resolving `from` will resolve `to` instead.
     ///
     /// Used for trait bindings: `bind::<dyn Logger, ConsoleLogger>()`
     /// creates an alias from `dyn Logger` to `ConsoleLogger`.
     pub fn register_alias(&mut self, from: DependencyKey, to: DependencyKey) {
         debug!(from = %from, to = %to, "Registered alias");
+        match (self.revisions.get(&to), self.registrations.get(&to)) {
+            (Some(&revision), Some(target)) => {
+                let snapshot = AliasSnapshot { revision, scope: target.scope };
+                self.alias_snapshots.insert(from.clone(), snapshot);
+            }
+            _ => {
+                self.alias_snapshots.remove(&from);
+            }
+        }
         self.aliases.insert(from, to);
     }
 
-    /// Looks up a registration by key, following aliases.
+    /// Describes aliases that probably don't bind what their author meant.
+    ///
+    /// Flags aliases whose target was re-registered after the alias was
+    /// recorded (noting a scope change), aliases to keys that were never
+    /// registered, and registrations made unreachable by a same-key alias.
+    /// These usually come from providers being added in the wrong order.
+    pub fn alias_warnings(&self) -> Vec<String> {
+        let mut aliases: Vec<_> = self.aliases.iter().collect();
+        aliases.sort_by_key(|(from, _)| from.to_string());
+
+        let mut warnings = Vec::new();
+        for (from, to) in aliases {
+            if self.registrations.contains_key(from) {
+                warnings.push(format!(
+                    "{from} is registered but also aliased to {to}; the registration is unreachable"
+                ));
+            }
+
+            match (self.registrations.get(to), self.alias_snapshots.get(from)) {
+                (None, _) if !self.aliases.contains_key(to) => warnings.push(format!(
+                    "Alias {from} → {to} points at a key that is never registered"
+                )),
+                (Some(target), Some(snapshot))
+                    if self.revisions.get(to) != Some(&snapshot.revision) =>
+                {
+                    let mut warning = format!(
+                        "Alias {from} → {to} was recorded before {to} was registered again"
+                    );
+                    if target.scope != snapshot.scope {
+                        warning.push_str(&format!(
+                            " (scope changed from {} to {})",
+                            snapshot.scope, target.scope
+                        ));
+                    }
+                    warnings.push(warning);
+                }
+                _ => {}
+            }
+        }
+        warnings
+    }
+
+    /// Looks up a registration by key, following a single alias hop.
+    ///
+    /// The container resolves through [`Plans`](crate::plan::Plans)
+    /// instead, which flattens whole alias chains at build time.
+    #[cfg(test)]
     pub fn get(&self, key: &DependencyKey) -> Option<&Registration> {
         if let Some(aliased_key) = self.aliases.get(key) {
-            trace!(from = %key, to = %aliased_key, "Following alias");
             return self.registrations.get(aliased_key);
         }
         self.registrations.get(key)
     }
 
+    /// Returns `true` if `key` is registered or aliased.
+    pub fn contains(&self, key: &DependencyKey) -> bool {
+        self.registrations.contains_key(key) || self.aliases.contains_key(key)
+    }
+
+    /// Replaces the dependencies of a registration.
+    ///
+    /// Used to store dependencies inferred by a dry run.
+    pub fn set_dependencies(&mut self, key: &DependencyKey, dependencies: Vec<DependencyKey>) {
+        if let Some(registration) = self.registrations.get_mut(key) {
+            registration.dependencies = dependencies;
+        }
+    }
+
+    /// Moves the registration of `key` to `scope`, caching it with `cloner`
+    /// unless it becomes transient.
+    ///
+    /// Returns `false` if `key` is not registered.
+    pub fn set_scope(&mut self, key: &DependencyKey, scope: Scope, cloner: Cloner) -> bool {
+        let Some(registration) = self.registrations.get_mut(key) else {
+            return false;
+        };
+        if scope != Scope::Transient {
+            registration.inline = None;
+        }
+        registration.cloner = (scope != Scope::Transient).then_some(cloner);
+        registration.scope = scope;
+        true
+    }
+
+    /// Gives the registration of `key` the scope of its first dependency,
+    /// following aliases, and `cloner` if that dependency is cached too.
+    /// Records that dependency as the one `key` is
+    /// [derived from](Registration::derived_from).
+    ///
+    /// Used for [`bind`](crate::container::ContainerBuilder::bind) and
+    /// [`also_as`](crate::container::ContainerBuilder::also_as)
+    /// registrations, whose source may be registered after them.
+    /// Does nothing if `key` was re-registered with a factory other than
+    /// `factory`.
+    pub fn inherit_scope(&mut self, key: &DependencyKey, factory: &FactoryFn, cloner: Cloner) {
+        let Some(mut from) = self
+            .registrations
+            .get(key)
+            .filter(|r| Arc::ptr_eq(&r.factory, factory))
+            .and_then(|r| r.dependencies.first())
+        else {
+            return;
+        };
+        for _ in 0..self.aliases.len() {
+            match self.aliases.get(from) {
+                Some(next) => from = next,
+                None => break,
+            }
+        }
+        let Some((scope, cached)) = self.registrations.get(from).map(|r| (r.scope, r.cloner.is_some())) else {
+            return;
+        };
+        let from = from.clone();
+        if let Some(registration) = self.registrations.get_mut(key) {
+            registration.scope = scope;
+            registration.cloner = cached.then_some(cloner);
+            registration.derived_from = Some(from);
+        }
+    }
+
+    /// Returns the target of `key` if it is an alias.
+    pub fn alias_target(&self, key: &DependencyKey) -> Option<&DependencyKey> {
+        self.aliases.get(key)
+    }
+
+    /// Returns all aliases.
+    pub fn all_aliases(&self) -> &HashMap<DependencyKey, DependencyKey, BuildKeyHasher> {
+        &self.aliases
+    }
+
     /// Returns all registrations (for validation).
-    pub fn all_registrations(&self) -> &HashMap<DependencyKey, Registration> {
+    pub fn all_registrations(&self) -> &HashMap<DependencyKey, Registration, BuildKeyHasher> {
         &self.registrations
     }
 
 
-    /// Returns all aliases (for validation).
+    /// Returns the declared graph, in the form the validator works on.
+    pub fn dependency_infos(&self) -> HashMap<DependencyKey, DependencyInfo> {
+        self.registrations
+            .iter()
+            .map(|(key, reg)| {
+                (
+                    key.clone(),
+                    DependencyInfo {
+                        key: key.clone(),
+                        dependencies: reg.dependencies.clone(),
+                        scope: reg.scope,
+                    },
+                )
+            })
+            .collect()
+    }
+
+    /// Returns the number of registered dependencies.
     pub fn len(&self) -> usize {
         self.registrations.len()
     }
 
-    /// Returns the number of registered dependencies.
+    /// Returns true if no dependencies are registered.
+    #[cfg_attr(not(test), allow(dead_code))]
     pub fn is_empty(&self) -> bool {
         self.registrations.is_empty()
     }
 
-    /// Returns true if no dependencies are registered.
-     pub fn registered_keys(&self) -> Vec<DependencyKey> {
-        let mut keys: Vec<_> = self.registrations.keys().cloned().collect();
+    /// Returns all registered keys, including aliases.
+    ///
+    /// Registrations come first, in [registration order](Self::ordered_keys).
+    pub fn registered_keys(&self) -> Vec<DependencyKey> {
+        let mut keys = self.order.clone();
         keys.extend(self.aliases.keys().cloned());
         keys
     }
+
+    /// Registered keys in the order they were first registered.
+    ///
+    /// Overriding a registration keeps its original position.
+    pub fn ordered_keys(&self) -> &[DependencyKey] {
+        &self.order
+    }
 }
 
 #[cfg(test)]
@@ -146,15 +537,17 @@ mod tests {
     }
 
     fn make_reg(key: DependencyKey, scope: Scope) -> Registration {
-        Registration { key, factory: dummy_factory(), scope, dependencies: vec![] }
+        Registration::new(key, scope, dummy_factory(), vec![])
     }
 
     #[test]
     fn register_and_get() {
         let mut reg = Registry::new();
         let key = DependencyKey::of::<Database>();
+        assert!(reg.is_empty());
         reg.register(make_reg(key.clone(), Scope::Singleton), false).unwrap();
         assert!(reg.get(&key).is_some());
+        assert!(!reg.is_empty());
     }
 
     #[test]
@@ -173,6 +566,25 @@ mod tests {
         assert!(reg.register(make_reg(key, Scope::Singleton), true).is_ok());
     }
 
+    #[test]
+    fn keys_keep_registration_order() {
+        struct Cache;
+        struct Mailer;
+
+        let mut registry = Registry::new();
+        let keys = [
+            DependencyKey::of::<Mailer>(),
+            DependencyKey::of::<Database>(),
+            DependencyKey::of::<Cache>(),
+        ];
+        for key in &keys {
+            registry.register(make_reg(key.clone(), Scope::Singleton), false).unwrap();
+        }
+        registry.register(make_reg(keys[0].clone(), Scope::Transient), true).unwrap();
+
+        assert_eq!(registry.ordered_keys(), keys);
+    }
+
     #[test]
     fn alias_resolves() {
         let mut reg = Registry::new();
@@ -183,4 +595,44 @@ mod tests {
         reg.register_alias(alias_key.clone(), concrete);
         assert!(reg.get(&alias_key).is_some());
     }
+
+    #[test]
+    fn alias_to_replaced_target_is_flagged() {
+        let mut reg = Registry::new();
+        let concrete = DependencyKey::of::<String>();
+        let alias_key = DependencyKey::of::<i64>();
+
+        reg.register(make_reg(concrete.clone(), Scope::Singleton), false).unwrap();
+        reg.register_alias(alias_key.clone(), concrete.clone());
+        assert!(reg.alias_warnings().is_empty());
+
+        reg.register(make_reg(concrete, Scope::Transient), true).unwrap();
+        let warnings = reg.alias_warnings();
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].contains("registered again"));
+        assert!(warnings[0].contains("scope changed from Singleton to Transient"));
+    }
+
+    #[test]
+    fn alias_recorded_before_target_is_not_flagged() {
+        let mut reg = Registry::new();
+        let concrete = DependencyKey::of::<String>();
+
+        reg.register_alias(DependencyKey::of::<i64>(), concrete.clone());
+        reg.register(make_reg(concrete, Scope::Singleton), false).unwrap();
+        assert!(reg.alias_warnings().is_empty());
+    }
+
+    #[test]
+    fn dangling_and_shadowing_aliases_are_flagged() {
+        let mut reg = Registry::new();
+        let shadowed = DependencyKey::of::<Database>();
+        reg.register(make_reg(shadowed.clone(), Scope::Singleton), false).unwrap();
+        reg.register_alias(shadowed, DependencyKey::of::<String>());
+
+        let warnings = reg.alias_warnings();
+        assert_eq!(warnings.len(), 2);
+        assert!(warnings[0].contains("unreachable"));
+        assert!(warnings[1].contains("never registered"));
+    }
 }
@@ -8,7 +8,16 @@
 //! # Ordering
 //! Scopes have a natural ordering: `Singleton > Scoped > Transient`.
 //! A Singleton "outlives" a Scoped, which "outlives" a Transient.
+//!
+//! # Ambient scope
+//! With the `ambient` feature, [`with_current`] and [`with_current_sync`]
+//! make an [`OwnedScope`](crate::container::OwnedScope) available to code
+//! that can't take it as a parameter, through [`current`] and
+//! [`resolve_current`].
 use std::fmt;
+
+#[cfg(feature = "ambient")]
+pub use crate::ambient::{current, resolve_current, with_current, with_current_sync};
 /// Defines the lifetime of a dependency within the container.
 ///
 /// # Examples
@@ -19,6 +28,7 @@ use std::fmt;
 /// assert!(Scope::Singleton > Scope::Scoped);
 /// assert!(Scope::Scoped > Scope::Transient);
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Scope {
     /// One instance shared across the entire application.
     ///
@@ -102,6 +112,82 @@ impl fmt::Display for Scope {
     }
 }
 
+/// What a root [`Container`](crate::container::Container) does when a
+/// [`Scope::Scoped`] dependency is resolved from it instead of from a
+/// scope, where it silently behaves like a transient.
+///
+/// Set with [`ContainerBuilder::scoped_from_root`](crate::container::ContainerBuilder::scoped_from_root).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum ScopedFromRoot {
+    /// Build a fresh instance, as for a transient.
+    #[default]
+    Allow,
+
+    /// Build a fresh instance and log a warning through `tracing`; a
+    /// no-op when the `tracing` feature is disabled.
+    Warn,
+
+    /// Fail with [`MakhzanError::ScopedFromRoot`](crate::error::MakhzanError::ScopedFromRoot).
+    Deny,
+}
+
+/// How [`ContainerBuilder::build`](crate::container::ContainerBuilder::build)
+/// treats a dependency that lives shorter than its consumer, e.g. a
+/// transient captured by a singleton.
+///
+/// Set with [`ContainerBuilder::scope_validation`](crate::container::ContainerBuilder::scope_validation).
+/// Relaxing it is for intentional captives, such as a singleton holding a
+/// snapshot taken from a transient at startup.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum ScopeValidation {
+    /// Fail with [`MakhzanError::ScopeMismatch`](crate::error::MakhzanError::ScopeMismatch)
+    /// or [`AliasScopeMismatch`](crate::error::MakhzanError::AliasScopeMismatch).
+    #[default]
+    Deny,
+
+    /// Build anyway, logging the mismatch as a warning and listing it in
+    /// the [validation report](crate::container::Container::validation_report).
+    Warn,
+
+    /// Don't check scopes at all.
+    Off,
+}
+
+/// What a singleton does after its factory returns an error.
+///
+/// Set per registration with
+/// [`ContainerBuilder::singleton_with_policy`](crate::container::ContainerBuilder::singleton_with_policy).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum SingletonFailurePolicy {
+    /// Leave the singleton unset; the next resolve runs the factory again.
+    ///
+    /// Suits transient startup failures such as a database that is not
+    /// reachable yet. This is what `singleton_with` does.
+    #[default]
+    RetryOnNextResolve,
+
+    /// Remember the failure; every later resolve returns the same
+    /// `ConstructionFailed` error without running the factory again.
+    CacheError,
+}
+
+/// What a [TTL singleton](crate::container::ContainerBuilder::singleton_with_ttl)
+/// does when refreshing an expired instance fails.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum RefreshFailurePolicy {
+    /// Keep serving the expired instance; the next resolve tries to
+    /// refresh it again.
+    ///
+    /// Suits data that is better stale than missing, such as a list of
+    /// service endpoints. The first build has no instance to fall back
+    /// on, so its failure is always returned.
+    #[default]
+    KeepStale,
+
+    /// Return the refresh error, as if nothing had been cached.
+    Fail,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
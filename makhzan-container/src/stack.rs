@@ -0,0 +1,171 @@
+//! Per-thread stack of keys whose factories are currently running.
+//!
+//! A singleton or scoped factory that (directly or transitively) resolves
+//! its own key would re-enter the `OnceCell` guarding it, which deadlocks. The
+//! stack lets [`Container`](crate::container::Container) notice the
+//! reentry first and report the chain as a circular dependency. It also
+//! bounds how deep factories may nest, so a runaway chain fails with an
+//! error instead of overflowing the thread's stack.
+
+use std::cell::{Cell, RefCell};
+
+use crate::error::{CircularDependencyError, MakhzanError, Result};
+use crate::key::DependencyKey;
+use crate::scope::Scope;
+
+thread_local! {
+    static STACK: RefCell<Vec<Frame>> = const { RefCell::new(Vec::new()) };
+    static ENTERED: Cell<u64> = const { Cell::new(0) };
+}
+
+/// A factory invocation in progress on this thread.
+struct Frame {
+    /// Address of the owning container, so nested containers don't
+    /// mistake each other's keys for a cycle.
+    owner: usize,
+    key: DependencyKey,
+}
+
+/// Pops its frame off the thread's stack when dropped, including on
+/// error and unwind.
+pub(crate) struct StackGuard(());
+
+impl StackGuard {
+    /// Pushes `key` for the container at `owner`.
+    ///
+    /// Fails with [`MakhzanError::CircularDependency`] when a singleton
+    /// or scoped key is already being constructed by the same container on this thread,
+    /// and with [`MakhzanError::MaxDepthExceeded`] when that container
+    /// already has `max_depth` factories running.
+    pub(crate) fn enter(
+        owner: usize,
+        key: &DependencyKey,
+        scope: Scope,
+        max_depth: usize,
+    ) -> Result<Self> {
+        STACK.with(|stack| {
+            let mut stack = stack.borrow_mut();
+            if stack.len() >= max_depth {
+                let path: Vec<DependencyKey> = stack
+                    .iter()
+                    .filter(|f| f.owner == owner)
+                    .map(|f| f.key.clone())
+                    .collect();
+                if path.len() >= max_depth {
+                    return Err(MakhzanError::MaxDepthExceeded {
+                        key: key.clone(),
+                        depth: max_depth,
+                        path,
+                    });
+                }
+            }
+            if matches!(scope, Scope::Singleton | Scope::Scoped)
+                && let Some(start) = stack.iter().position(|f| f.owner == owner && &f.key == key)
+            {
+                let mut chain: Vec<DependencyKey> = stack[start..]
+                    .iter()
+                    .filter(|f| f.owner == owner)
+                    .map(|f| f.key.clone())
+                    .collect();
+                chain.push(key.clone());
+                return Err(MakhzanError::CircularDependency(CircularDependencyError { chain }));
+            }
+
+            stack.push(Frame {
+                owner,
+                key: key.clone(),
+            });
+            ENTERED.with(|entered| entered.set(entered.get() + 1));
+            Ok(StackGuard(()))
+        })
+    }
+}
+
+/// Keys of the factories the container at `owner` is running on this
+/// thread, outermost first.
+pub(crate) fn current_path(owner: usize) -> Vec<DependencyKey> {
+    STACK.with(|stack| {
+        stack
+            .borrow()
+            .iter()
+            .filter(|f| f.owner == owner)
+            .map(|f| f.key.clone())
+            .collect()
+    })
+}
+
+/// How many factories have been entered on this thread so far; a resolve
+/// that leaves it unchanged ran none.
+pub(crate) fn entered() -> u64 {
+    ENTERED.with(Cell::get)
+}
+
+impl Drop for StackGuard {
+    fn drop(&mut self) {
+        STACK.with(|stack| {
+            stack.borrow_mut().pop();
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reentered_singleton_reports_chain() {
+        let a = DependencyKey::of::<String>();
+        let b = DependencyKey::of::<u32>();
+
+        let _a = StackGuard::enter(1, &a, Scope::Singleton, 8).unwrap();
+        let _b = StackGuard::enter(1, &b, Scope::Transient, 8).unwrap();
+        let err = StackGuard::enter(1, &a, Scope::Singleton, 8).err().unwrap();
+
+        match err {
+            MakhzanError::CircularDependency(e) => assert_eq!(e.chain, vec![a.clone(), b, a]),
+            other => panic!("expected CircularDependency, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn reentered_scoped_key_is_a_cycle() {
+        let a = DependencyKey::of::<String>();
+
+        let _a = StackGuard::enter(1, &a, Scope::Scoped, 8).unwrap();
+        assert!(matches!(
+            StackGuard::enter(1, &a, Scope::Scoped, 8),
+            Err(MakhzanError::CircularDependency(_))
+        ));
+    }
+
+    #[test]
+    fn other_owner_and_popped_frames_are_ignored() {
+        let a = DependencyKey::of::<String>();
+
+        {
+            let _outer = StackGuard::enter(1, &a, Scope::Singleton, 8).unwrap();
+            assert!(StackGuard::enter(2, &a, Scope::Singleton, 8).is_ok());
+        }
+
+        assert!(StackGuard::enter(1, &a, Scope::Singleton, 8).is_ok());
+    }
+
+    #[test]
+    fn depth_is_counted_per_owner() {
+        let a = DependencyKey::of::<String>();
+        let b = DependencyKey::of::<u32>();
+
+        let _a = StackGuard::enter(1, &a, Scope::Transient, 2).unwrap();
+        let _other = StackGuard::enter(2, &a, Scope::Transient, 2).unwrap();
+        let _b = StackGuard::enter(1, &b, Scope::Transient, 2).unwrap();
+
+        match StackGuard::enter(1, &a, Scope::Transient, 2).err().unwrap() {
+            MakhzanError::MaxDepthExceeded { key, depth, path } => {
+                assert_eq!(key, a);
+                assert_eq!(depth, 2);
+                assert_eq!(path, vec![a.clone(), b]);
+            }
+            other => panic!("expected MaxDepthExceeded, got {other:?}"),
+        }
+    }
+}
@@ -0,0 +1,232 @@
+//! Test doubles for unit-testing factories and providers without building
+//! a container. Enabled by the `test-util` feature.
+//!
+//! A factory only sees a [`Resolver`], so it can be run against a
+//! [`RecordingResolver`] serving canned values; a [`Provider`] only sees a
+//! [`ProviderRegistry`], so it can register into a [`RecordingRegistry`]
+//! that keeps what it was given:
+//!
+//! ```rust
+//! use makhzan_container::prelude::*;
+//! use makhzan_container::provider::ProviderRegistry;
+//! use makhzan_container::testing::{RecordingRegistry, RecordingResolver};
+//!
+//! struct Greeter;
+//! impl Provider for Greeter {
+//!     fn register(&self, registry: &mut dyn ProviderRegistry) {
+//!         registry.register_transient(
+//!             DependencyKey::of::<String>(),
+//!             std::sync::Arc::new(|r| Ok(Box::new(format!("hello {}", resolve::<&str>(r)?)))),
+//!             vec![DependencyKey::of::<&str>()],
+//!         );
+//!     }
+//! }
+//!
+//! let mut registry = RecordingRegistry::new();
+//! Greeter.register(&mut registry);
+//! let greeting = registry.get(&DependencyKey::of::<String>()).unwrap();
+//! assert_eq!(greeting.scope, Scope::Transient);
+//!
+//! let resolver = RecordingResolver::new().with("world");
+//! assert_eq!(greeting.create::<String>(&resolver).unwrap(), "hello world");
+//! assert_eq!(resolver.requested(), greeting.dependencies);
+//! ```
+//!
+//! [`Provider`]: crate::provider::Provider
+
+use std::any::Any;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, Mutex, PoisonError};
+
+use crate::error::{MakhzanError, NotRegisteredError, Result};
+use crate::key::{BuildKeyHasher, DependencyKey};
+use crate::provider::{Cacheable, ProviderRegistry};
+use crate::registry::{Cloner, FactoryFn, Resolved, Resolver};
+use crate::scope::Scope;
+
+/// A [`Resolver`] serving canned values and recording every key asked of
+/// it.
+///
+/// Keys without a value fail with [`MakhzanError::NotRegistered`], or
+/// panic after [`panic_on_unexpected`](Self::panic_on_unexpected).
+#[derive(Default)]
+pub struct RecordingResolver {
+    values: HashMap<DependencyKey, (Arc<dyn Any + Send + Sync>, Cloner), BuildKeyHasher>,
+    requested: Mutex<Vec<DependencyKey>>,
+    panic_on_unexpected: bool,
+}
+
+impl RecordingResolver {
+    /// A resolver with no values.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Serve a clone of `value` for `T`.
+    pub fn with<T: Clone + Send + Sync + 'static>(self, value: T) -> Self {
+        self.with_key(DependencyKey::of::<T>(), value)
+    }
+
+    /// Serve a clone of `value` for `key`, e.g. a
+    /// [named](DependencyKey::named) or [tagged](DependencyKey::tagged)
+    /// key of `T`.
+    pub fn with_key<T: Clone + Send + Sync + 'static>(mut self, key: DependencyKey, value: T) -> Self {
+        self.values.insert(key, (Arc::new(value), Cloner::of::<T>()));
+        self
+    }
+
+    /// Panic on a key without a value instead of returning an error, so a
+    /// factory that swallows the error still fails the test.
+    pub fn panic_on_unexpected(mut self, panic: bool) -> Self {
+        self.panic_on_unexpected = panic;
+        self
+    }
+
+    /// Every key requested so far, in order, repeats included.
+    pub fn requested(&self) -> Vec<DependencyKey> {
+        self.requested.lock().unwrap_or_else(PoisonError::into_inner).clone()
+    }
+}
+
+impl Resolver for RecordingResolver {
+    fn resolve_key(&self, key: &DependencyKey) -> Result<Box<dyn Any + Send + Sync>> {
+        self.resolve_erased(key).map(Resolved::into_box)
+    }
+
+    fn resolve_erased(&self, key: &DependencyKey) -> Result<Resolved> {
+        self.requested.lock().unwrap_or_else(PoisonError::into_inner).push(key.clone());
+        match self.values.get(key) {
+            Some((value, cloner)) => Ok(Resolved::shared(value.clone(), *cloner)),
+            None if self.panic_on_unexpected => panic!("unexpected resolve of {key}"),
+            None => Err(MakhzanError::NotRegistered(Box::new(NotRegisteredError {
+                requested: key.clone(),
+                required_by: None,
+                suggestions: self.values.keys().filter(|k| k.same_type(key)).cloned().collect(),
+            }))),
+        }
+    }
+}
+
+impl fmt::Debug for RecordingResolver {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RecordingResolver")
+            .field("values", &self.values.keys().collect::<Vec<_>>())
+            .field("requested", &self.requested())
+            .finish()
+    }
+}
+
+/// A registration captured by a [`RecordingRegistry`].
+#[derive(Clone)]
+pub struct RegistrationInfo {
+    /// The registered key
+    pub key: DependencyKey,
+    /// Its scope
+    pub scope: Scope,
+    /// Declared dependencies, in declaration order
+    pub dependencies: Vec<DependencyKey>,
+    /// Whether it was registered with
+    /// [`register_cacheable`](ProviderRegistry::register_cacheable)
+    pub cacheable: bool,
+    /// The factory, to run against a [`RecordingResolver`]
+    pub factory: FactoryFn,
+}
+
+impl RegistrationInfo {
+    /// Runs the factory against `resolver` and downcasts the value.
+    ///
+    /// # Errors
+    /// Whatever the factory returns, or [`MakhzanError::TypeMismatch`] if
+    /// it produced something other than `T`.
+    pub fn create<T: 'static>(&self, resolver: &dyn Resolver) -> Result<T> {
+        let value = (self.factory)(resolver)?;
+        value.downcast::<T>().map(|value| *value).map_err(|_| MakhzanError::TypeMismatch {
+            key: self.key.clone(),
+            expected: std::any::type_name::<T>(),
+            actual: None,
+        })
+    }
+}
+
+impl fmt::Debug for RegistrationInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RegistrationInfo")
+            .field("key", &self.key)
+            .field("scope", &self.scope)
+            .field("dependencies", &self.dependencies)
+            .field("cacheable", &self.cacheable)
+            .finish_non_exhaustive()
+    }
+}
+
+/// A [`ProviderRegistry`] that keeps every registration and alias, in
+/// order, for a test to inspect.
+#[derive(Debug, Default)]
+pub struct RecordingRegistry {
+    registrations: Vec<RegistrationInfo>,
+    aliases: Vec<(DependencyKey, DependencyKey)>,
+}
+
+impl RecordingRegistry {
+    /// An empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The registrations made, in order.
+    pub fn registrations(&self) -> &[RegistrationInfo] {
+        &self.registrations
+    }
+
+    /// The aliases registered, as `(from, to)` pairs in order.
+    pub fn aliases(&self) -> &[(DependencyKey, DependencyKey)] {
+        &self.aliases
+    }
+
+    /// The last registration of `key`, which is the one a container
+    /// allowing overrides would keep.
+    pub fn get(&self, key: &DependencyKey) -> Option<&RegistrationInfo> {
+        self.registrations.iter().rev().find(|info| info.key == *key)
+    }
+
+    fn push(
+        &mut self,
+        key: DependencyKey,
+        scope: Scope,
+        factory: FactoryFn,
+        dependencies: Vec<DependencyKey>,
+        cacheable: bool,
+    ) {
+        self.registrations.push(RegistrationInfo { key, scope, dependencies, cacheable, factory });
+    }
+}
+
+impl ProviderRegistry for RecordingRegistry {
+    fn register_singleton(&mut self, key: DependencyKey, factory: FactoryFn, dependencies: Vec<DependencyKey>) {
+        self.push(key, Scope::Singleton, factory, dependencies, false);
+    }
+
+    fn register_scoped(&mut self, key: DependencyKey, factory: FactoryFn, dependencies: Vec<DependencyKey>) {
+        self.push(key, Scope::Scoped, factory, dependencies, false);
+    }
+
+    fn register_transient(&mut self, key: DependencyKey, factory: FactoryFn, dependencies: Vec<DependencyKey>) {
+        self.push(key, Scope::Transient, factory, dependencies, false);
+    }
+
+    fn register_alias(&mut self, from: DependencyKey, to: DependencyKey) {
+        self.aliases.push((from, to));
+    }
+
+    fn register_cacheable(
+        &mut self,
+        key: DependencyKey,
+        scope: Scope,
+        factory: FactoryFn,
+        dependencies: Vec<DependencyKey>,
+        _cacheable: Cacheable,
+    ) {
+        self.push(key, scope, factory, dependencies, true);
+    }
+}
@@ -0,0 +1,201 @@
+//! Per-resolve timing tree for
+//! [`Container::resolve_traced`](crate::container::Container::resolve_traced).
+//!
+//! Plain [`resolve`](crate::container::Container::resolve) never creates a
+//! [`TraceRecorder`], so it skips the clock reads and bookkeeping below.
+
+use std::sync::{Mutex, PoisonError};
+use std::time::{Duration, Instant};
+
+use makhzan_support::rendering::{render_chain_vertical, ChainEntry};
+
+use crate::key::DependencyKey;
+use crate::scope::Scope;
+
+/// One resolved key and the keys its factory resolved in turn.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResolutionTrace {
+    /// The requested key
+    pub key: DependencyKey,
+    /// Scope of its registration
+    pub scope: Scope,
+    /// Whether the value came from a singleton or scope cache instead of
+    /// its factory
+    pub cache_hit: bool,
+    /// Wall time of the resolve, children included
+    pub duration: Duration,
+    /// Keys resolved while producing this one, in resolve order
+    pub children: Vec<ResolutionTrace>,
+}
+
+impl ResolutionTrace {
+    /// Renders the tree in the order values were finished, dependencies
+    /// above their dependents and indented by depth.
+    ///
+    /// ```text
+    /// [Singleton]     Arc<Database>  26.5µs (cached)
+    ///             ↓
+    /// [Transient]   Arc<UserRepo>  42.1µs
+    ///             ↓
+    /// [Transient] UserService  125.7µs
+    /// ```
+    pub fn render(&self) -> String {
+        let mut entries = Vec::new();
+        self.flatten(0, &mut entries);
+        render_chain_vertical(&entries)
+    }
+
+    fn flatten(&self, depth: usize, entries: &mut Vec<ChainEntry>) {
+        for child in &self.children {
+            child.flatten(depth + 1, entries);
+        }
+        entries.push(ChainEntry {
+            type_name: format!(
+                "{}{}  {:?}{}",
+                "  ".repeat(depth),
+                self.key.short_display(),
+                self.duration,
+                if self.cache_hit { " (cached)" } else { "" },
+            ),
+            scope: self.scope.to_string(),
+            source_name: None,
+        });
+    }
+}
+
+/// How a resolve reported to an
+/// [`on_resolve`](crate::container::ContainerBuilder::on_resolve) observer
+/// ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResolveOutcome {
+    /// A factory ran to produce the value
+    Constructed,
+    /// The value came from a singleton or scope cache, or was seeded
+    CacheHit,
+    /// The resolve returned an error
+    Failed,
+}
+
+/// Collects a [`ResolutionTrace`] while a traced resolve runs.
+#[derive(Default)]
+pub(crate) struct TraceRecorder {
+    frames: Mutex<Vec<Frame>>,
+    root: Mutex<Option<ResolutionTrace>>,
+}
+
+/// A key whose resolve is still running.
+#[derive(Default)]
+struct Frame {
+    constructed: bool,
+    children: Vec<ResolutionTrace>,
+}
+
+impl TraceRecorder {
+    /// Times `resolve` as the resolve of `key` and adds it below the key
+    /// currently being resolved, or as the root.
+    ///
+    /// `cached` reports a cache hit the recorder can't observe itself,
+    /// e.g. a weak singleton whose factory hands out its live instance.
+    pub(crate) fn record<R>(
+        &self,
+        key: &DependencyKey,
+        scope: Scope,
+        cached: bool,
+        resolve: impl FnOnce() -> R,
+    ) -> R {
+        self.frames().push(Frame::default());
+        let started = Instant::now();
+        let result = resolve();
+        let duration = started.elapsed();
+
+        let mut frames = self.frames();
+        let frame = frames.pop().unwrap_or_default();
+        let node = ResolutionTrace {
+            key: key.clone(),
+            scope,
+            cache_hit: cached || !frame.constructed,
+            duration,
+            children: frame.children,
+        };
+        match frames.last_mut() {
+            Some(parent) => parent.children.push(node),
+            None => *self.root.lock().unwrap_or_else(PoisonError::into_inner) = Some(node),
+        }
+        result
+    }
+
+    /// Marks the innermost key as built by its factory.
+    pub(crate) fn constructed(&self) {
+        if let Some(frame) = self.frames().last_mut() {
+            frame.constructed = true;
+        }
+    }
+
+    pub(crate) fn finish(self) -> Option<ResolutionTrace> {
+        self.root.into_inner().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn frames(&self) -> std::sync::MutexGuard<'_, Vec<Frame>> {
+        self.frames.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use crate::container::{resolve, Container};
+    use crate::key::DependencyKey;
+    use crate::scope::Scope;
+
+    struct Database;
+    struct Repository(#[allow(dead_code)] Arc<Database>);
+    struct Service(#[allow(dead_code)] Arc<Repository>);
+
+    fn container() -> Container {
+        Container::builder()
+            .singleton_with(|_| Ok(Arc::new(Database)))
+            .transient_with_recorded(|r| Ok(Arc::new(Repository(resolve(r)?))))
+            .transient_with_recorded(|r| Ok(Service(resolve(r)?)))
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn traces_a_three_level_graph() {
+        let container = container();
+        let (_, trace) = container.resolve_traced::<Service>().unwrap();
+
+        assert_eq!(trace.key, DependencyKey::of::<Service>());
+        assert_eq!(trace.scope, Scope::Transient);
+        assert!(!trace.cache_hit);
+        let [repository] = &trace.children[..] else { panic!("{trace:?}") };
+        assert_eq!(repository.key, DependencyKey::of::<Arc<Repository>>());
+        assert!(!repository.cache_hit);
+        let [database] = &repository.children[..] else { panic!("{trace:?}") };
+        assert_eq!(database.key, DependencyKey::of::<Arc<Database>>());
+        assert_eq!(database.scope, Scope::Singleton);
+        assert!(!database.cache_hit);
+        assert!(database.children.is_empty());
+        assert!(trace.duration >= repository.duration && repository.duration >= database.duration);
+
+        let rendered = trace.render();
+        let lines: Vec<&str> = rendered.lines().filter(|line| line.contains('[')).collect();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].starts_with("[Singleton]     Arc<Database>  "), "{rendered}");
+        assert!(lines[2].starts_with("[Transient] Service  "), "{rendered}");
+    }
+
+    #[test]
+    fn cached_singleton_is_a_cache_hit_on_the_next_trace() {
+        let container = container();
+        container.resolve_traced::<Service>().unwrap();
+        let (_, trace) = container.resolve_traced::<Service>().unwrap();
+
+        let database = &trace.children[0].children[0];
+        assert_eq!(database.key, DependencyKey::of::<Arc<Database>>());
+        assert!(database.cache_hit);
+        assert!(trace.render().contains("Arc<Database>  "));
+        assert!(!trace.children[0].cache_hit);
+    }
+}
@@ -0,0 +1,260 @@
+//! Dependency forest for [`Container::tree`](crate::container::Container::tree)
+//! and the per-scope listing of
+//! [`Container::debug_tree`](crate::container::Container::debug_tree).
+//!
+//! Turns the declared edges into [`TreeNode`]s for
+//! [`render_tree`](makhzan_support::rendering::render_tree). Roots are the
+//! registrations nothing else depends on. A subtree shared by several
+//! dependents (a diamond) is expanded once and referenced afterwards, and
+//! an edge back into the current path is marked as a cycle instead of
+//! being followed.
+
+use std::collections::{HashMap, HashSet};
+
+use makhzan_support::rendering::TreeNode;
+
+use crate::graph::DependencyInfo;
+use crate::key::{BuildKeyHasher, DependencyKey};
+use crate::scope::Scope;
+
+/// Builds the dependency forest, roots sorted by label.
+pub(crate) fn forest(
+    infos: &HashMap<DependencyKey, DependencyInfo>,
+    aliases: &HashMap<DependencyKey, DependencyKey, BuildKeyHasher>,
+) -> Vec<TreeNode> {
+    let mut builder = ForestBuilder {
+        infos,
+        aliases,
+        expanded: HashSet::new(),
+        ancestors: Vec::new(),
+    };
+
+    let depended_on: HashSet<DependencyKey> = infos
+        .values()
+        .flat_map(|info| &info.dependencies)
+        .map(|dep| builder.target(dep))
+        .collect();
+
+    let mut keys: Vec<&DependencyKey> = infos.keys().collect();
+    keys.sort_by_key(|key| builder.name(key));
+
+    let mut roots: Vec<TreeNode> = keys
+        .iter()
+        .filter(|key| !depended_on.contains(key))
+        .map(|key| builder.node(key))
+        .collect();
+
+    // Whatever is left is only reachable from inside a cycle.
+    for key in keys {
+        if !builder.expanded.contains(key) {
+            roots.push(builder.node(key));
+        }
+    }
+    roots
+}
+
+/// Lists every registration under its scope, followed by the aliases.
+///
+/// Each registration shows its declared dependencies one level deep;
+/// `instantiated` marks cached singletons. Entries are sorted by short
+/// name, then full type name, so the output is stable.
+pub(crate) fn by_scope(
+    infos: &HashMap<DependencyKey, DependencyInfo>,
+    aliases: &HashMap<DependencyKey, DependencyKey, BuildKeyHasher>,
+    instantiated: impl Fn(&DependencyKey) -> bool,
+) -> Vec<TreeNode> {
+    let builder = ForestBuilder {
+        infos,
+        aliases,
+        expanded: HashSet::new(),
+        ancestors: Vec::new(),
+    };
+    let sorted = |keys: &mut Vec<&DependencyKey>| {
+        keys.sort_by_cached_key(|key| (builder.name(key), key.to_string()));
+    };
+
+    let mut groups = Vec::new();
+    for scope in [Scope::Singleton, Scope::Scoped, Scope::Transient] {
+        let mut keys: Vec<&DependencyKey> =
+            infos.values().filter(|info| info.scope == scope).map(|info| &info.key).collect();
+        if keys.is_empty() {
+            continue;
+        }
+        sorted(&mut keys);
+        let children = keys
+            .iter()
+            .map(|key| {
+                let mut label = builder.name(key);
+                if instantiated(key) {
+                    label.push_str("  (initialized)");
+                }
+                let children = infos[*key]
+                    .dependencies
+                    .iter()
+                    .map(|dep| TreeNode::leaf(builder.edge(dep)))
+                    .collect();
+                TreeNode { label, children }
+            })
+            .collect();
+        groups.push(TreeNode { label: format!("{scope} ({})", keys.len()), children });
+    }
+
+    if !aliases.is_empty() {
+        let mut keys: Vec<&DependencyKey> = aliases.keys().collect();
+        sorted(&mut keys);
+        groups.push(TreeNode {
+            label: format!("Aliases ({})", keys.len()),
+            children: keys.iter().map(|key| TreeNode::leaf(builder.edge(key))).collect(),
+        });
+    }
+    groups
+}
+
+struct ForestBuilder<'a> {
+    infos: &'a HashMap<DependencyKey, DependencyInfo>,
+    aliases: &'a HashMap<DependencyKey, DependencyKey, BuildKeyHasher>,
+    /// Registrations whose children were already drawn
+    expanded: HashSet<DependencyKey>,
+    /// Registrations on the path from the current root
+    ancestors: Vec<DependencyKey>,
+}
+
+impl ForestBuilder<'_> {
+    fn node(&mut self, key: &DependencyKey) -> TreeNode {
+        let target = self.target(key);
+        let mut label = self.label(key, &target);
+
+        let Some(info) = self.infos.get(&target) else {
+            label.push_str("  (not registered)");
+            return TreeNode::leaf(label);
+        };
+
+        if self.ancestors.contains(&target) {
+            label.push_str("  ⟲ cycle");
+            return TreeNode::leaf(label);
+        }
+        if !self.expanded.insert(target.clone()) {
+            if !info.dependencies.is_empty() {
+                label.push_str("  (see above)");
+            }
+            return TreeNode::leaf(label);
+        }
+
+        self.ancestors.push(target);
+        let children = info.dependencies.iter().map(|dep| self.node(dep)).collect();
+        self.ancestors.pop();
+
+        TreeNode { label, children }
+    }
+
+    fn label(&self, key: &DependencyKey, target: &DependencyKey) -> String {
+        let scope = self
+            .infos
+            .get(target)
+            .map(|info| info.scope.to_string())
+            .unwrap_or_else(|| "?".to_string());
+
+        if key == target {
+            format!("[{scope}] {}", self.name(key))
+        } else {
+            format!("[{scope}] {} → {}", self.name(key), self.name(target))
+        }
+    }
+
+    /// `key` as a dependency: where it resolves to and whether it exists.
+    fn edge(&self, key: &DependencyKey) -> String {
+        let target = self.target(key);
+        let mut label = self.name(key);
+        if &target != key {
+            label.push_str(&format!(" → {}", self.name(&target)));
+        }
+        if !self.infos.contains_key(&target) {
+            label.push_str("  (not registered)");
+        }
+        label
+    }
+
+    fn name(&self, key: &DependencyKey) -> String {
+        key.short_display().to_string()
+    }
+
+    /// Follows alias hops to the key that is actually registered.
+    fn target(&self, key: &DependencyKey) -> DependencyKey {
+        let mut current = key;
+        let mut seen = HashSet::new();
+        while let Some(next) = self.aliases.get(current) {
+            if !seen.insert(current) {
+                break;
+            }
+            current = next;
+        }
+        current.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scope::Scope;
+    use makhzan_support::rendering::render_tree;
+
+    struct Config;
+    struct Pool;
+    struct Repo;
+    struct Cache;
+    struct Service;
+
+    fn info(key: DependencyKey, scope: Scope, dependencies: Vec<DependencyKey>) -> (DependencyKey, DependencyInfo) {
+        (key.clone(), DependencyInfo { key, dependencies, scope })
+    }
+
+    #[test]
+    fn diamonds_are_expanded_once() {
+        let config = DependencyKey::of::<Config>();
+        let pool = DependencyKey::of::<Pool>();
+        let repo = DependencyKey::of::<Repo>();
+        let cache = DependencyKey::of::<Cache>();
+        let service = DependencyKey::of::<Service>();
+
+        let infos: HashMap<_, _> = [
+            info(config.clone(), Scope::Singleton, vec![]),
+            info(pool.clone(), Scope::Singleton, vec![config.clone()]),
+            info(repo.clone(), Scope::Transient, vec![pool.clone()]),
+            info(cache.clone(), Scope::Singleton, vec![pool.clone()]),
+            info(service, Scope::Transient, vec![repo, cache]),
+        ]
+        .into_iter()
+        .collect();
+
+        let rendered = render_tree(&forest(&infos, &HashMap::default()));
+        assert_eq!(
+            rendered,
+            "[Transient] Service\n\
+             ├── [Transient] Repo\n\
+             │   └── [Singleton] Pool\n\
+             │       └── [Singleton] Config\n\
+             └── [Singleton] Cache\n\
+             \u{20}   └── [Singleton] Pool  (see above)\n"
+        );
+    }
+
+    #[test]
+    fn cycles_and_aliases_are_marked() {
+        let a = DependencyKey::of::<Repo>();
+        let b = DependencyKey::of::<Cache>();
+        let alias = DependencyKey::of::<Config>();
+
+        let infos: HashMap<_, _> = [
+            info(a.clone(), Scope::Transient, vec![alias.clone()]),
+            info(b.clone(), Scope::Transient, vec![a.clone()]),
+        ]
+        .into_iter()
+        .collect();
+        let mut aliases = HashMap::default();
+        aliases.insert(alias, b);
+
+        let rendered = render_tree(&forest(&infos, &aliases));
+        assert!(rendered.contains("[Transient] Config → Cache"));
+        assert!(rendered.contains("⟲ cycle"));
+    }
+}
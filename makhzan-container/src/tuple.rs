@@ -0,0 +1,167 @@
+//! Resolving several dependencies at once, and the other [`ResolverExt`]
+//! helpers for factory bodies.
+//!
+//! [`ResolveTuple`] is implemented for tuples of up to eight resolvable
+//! types, so a handler can ask for everything it needs in one call:
+//!
+//! ```rust
+//! # use makhzan_container::prelude::*;
+//! # use std::sync::Arc;
+//! # trait Logger: Send + Sync {}
+//! # struct ConsoleLogger;
+//! # impl Logger for ConsoleLogger {}
+//! # struct Database;
+//! # #[derive(Clone)]
+//! # struct Config;
+//! # struct Handler { db: Arc<Database>, logger: Arc<dyn Logger> }
+//! # let builder = Container::builder()
+//! #     .singleton_value(Arc::new(Database))
+//! #     .singleton_value(Arc::new(ConsoleLogger) as Arc<dyn Logger>)
+//! #     .singleton_value(Config);
+//! # let container = builder.clone().build()?;
+//! let (db, logger, cfg): (Arc<Database>, Arc<dyn Logger>, Config) =
+//!     container.resolve_tuple()?;
+//!
+//! // Inside a factory:
+//! let builder = builder.transient_with(|r| {
+//!     let (db, logger) = r.resolve_tuple::<(Arc<Database>, Arc<dyn Logger>)>()?;
+//!     Ok(Handler { db, logger })
+//! });
+//! # builder.build()?.resolve::<Handler>()?;
+//! # Ok::<(), MakhzanError>(())
+//! ```
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::container::{resolve, resolve_keyed};
+use crate::error::{MakhzanError, Result};
+use crate::key::DependencyKey;
+use crate::registry::Resolver;
+
+/// A tuple whose elements are resolved one by one, in order.
+///
+/// The first element that fails stops resolution with
+/// [`MakhzanError::TupleElementFailed`] naming its position. Also resolves
+/// the parameters of [`Container::invoke`](crate::container::Container::invoke).
+pub trait ResolveTuple: Sized {
+    /// Resolves every element from `resolver`.
+    fn resolve_tuple(resolver: &dyn Resolver) -> Result<Self>;
+}
+
+/// Convenience methods on the [`Resolver`] handed to factories.
+///
+/// ```rust
+/// # use makhzan_container::prelude::*;
+/// # use std::sync::Arc;
+/// # struct Pool;
+/// # struct Metrics;
+/// # struct Repository { primary: Arc<Pool>, metrics: Option<Arc<Metrics>> }
+/// # let builder = Container::builder().add_keyed("primary", |_| Ok(Arc::new(Pool)));
+/// let builder = builder.transient_with(|r| {
+///     let primary: Arc<Pool> = r.resolve_named("primary")?;
+///     let metrics: Option<Arc<Metrics>> = r.resolve_opt()?;
+///     Ok(Repository { primary, metrics })
+/// });
+/// # assert!(builder.build()?.resolve::<Repository>()?.metrics.is_none());
+/// # Ok::<(), MakhzanError>(())
+/// ```
+pub trait ResolverExt {
+    /// Resolves a tuple of dependencies; see [`ResolveTuple`].
+    fn resolve_tuple<T: ResolveTuple>(&self) -> Result<T>;
+
+    /// Resolves a dependency registered under a
+    /// [named](DependencyKey::named) key; see
+    /// [`Container::resolve_named`](crate::container::Container::resolve_named).
+    fn resolve_named<T: Send + Sync + 'static>(&self, name: &str) -> Result<T>;
+
+    /// Resolves `T`, or `None` if `T` was never registered.
+    ///
+    /// Only a missing registration for `T` itself gives `None`; a failing
+    /// factory or a missing dependency of `T` is still an error. Don't list
+    /// an optional dependency in the factory's declared dependencies, or
+    /// `build` requires it after all.
+    fn resolve_opt<T: Send + Sync + 'static>(&self) -> Result<Option<T>>;
+
+    /// Resolves `Arc<T>` if it is registered, otherwise `T` wrapped in a
+    /// new `Arc`.
+    ///
+    /// A registered singleton `Arc<T>` gives the same `Arc` every time;
+    /// a wrapped transient `T` gives a fresh one.
+    fn resolve_arc<T: Send + Sync + 'static>(&self) -> Result<Arc<T>>;
+
+    /// Resolves every element added with
+    /// [`add_element`](crate::container::ContainerBuilder::add_element),
+    /// in the order they were added; empty if there are none.
+    fn resolve_all<T: Send + Sync + 'static>(&self) -> Result<Vec<T>>;
+
+    /// Resolves every entry added with
+    /// [`add_keyed`](crate::container::ContainerBuilder::add_keyed), by
+    /// name; empty if there are none.
+    fn resolve_map<T: Send + Sync + 'static>(&self) -> Result<HashMap<&'static str, T>>;
+}
+
+impl ResolverExt for dyn Resolver + '_ {
+    fn resolve_tuple<T: ResolveTuple>(&self) -> Result<T> {
+        T::resolve_tuple(self)
+    }
+
+    fn resolve_named<T: Send + Sync + 'static>(&self, name: &str) -> Result<T> {
+        resolve_keyed(self, DependencyKey::named_owned::<T>(name.to_string()))
+    }
+
+    fn resolve_opt<T: Send + Sync + 'static>(&self) -> Result<Option<T>> {
+        match resolve::<T>(self) {
+            Err(MakhzanError::NotRegistered(e)) if e.requested == DependencyKey::of::<T>() => Ok(None),
+            other => other.map(Some),
+        }
+    }
+
+    fn resolve_arc<T: Send + Sync + 'static>(&self) -> Result<Arc<T>> {
+        match self.resolve_opt::<Arc<T>>()? {
+            Some(shared) => Ok(shared),
+            None => resolve::<T>(self).map(Arc::new),
+        }
+    }
+
+    fn resolve_all<T: Send + Sync + 'static>(&self) -> Result<Vec<T>> {
+        Ok(self.resolve_opt::<Vec<T>>()?.unwrap_or_default())
+    }
+
+    fn resolve_map<T: Send + Sync + 'static>(&self) -> Result<HashMap<&'static str, T>> {
+        Ok(self.resolve_opt::<HashMap<&'static str, T>>()?.unwrap_or_default())
+    }
+}
+
+fn element<T: Send + Sync + 'static>(resolver: &dyn Resolver, index: usize) -> Result<T> {
+    resolve::<T>(resolver).map_err(|e| MakhzanError::TupleElementFailed {
+        index,
+        key: DependencyKey::of::<T>(),
+        source: Box::new(e),
+    })
+}
+
+macro_rules! impl_resolve_tuple {
+    ($($index:tt $name:ident),+) => {
+        impl<$($name: Send + Sync + 'static),+> ResolveTuple for ($($name,)+) {
+            fn resolve_tuple(resolver: &dyn Resolver) -> Result<Self> {
+                Ok(($(element::<$name>(resolver, $index)?,)+))
+            }
+        }
+    };
+}
+
+impl ResolveTuple for () {
+    fn resolve_tuple(_: &dyn Resolver) -> Result<Self> {
+        Ok(())
+    }
+}
+
+impl_resolve_tuple!(0 A);
+impl_resolve_tuple!(0 A, 1 B);
+impl_resolve_tuple!(0 A, 1 B, 2 C);
+impl_resolve_tuple!(0 A, 1 B, 2 C, 3 D);
+impl_resolve_tuple!(0 A, 1 B, 2 C, 3 D, 4 E);
+impl_resolve_tuple!(0 A, 1 B, 2 C, 3 D, 4 E, 5 F);
+impl_resolve_tuple!(0 A, 1 B, 2 C, 3 D, 4 E, 5 F, 6 G);
+impl_resolve_tuple!(0 A, 1 B, 2 C, 3 D, 4 E, 5 F, 6 G, 7 H);
@@ -0,0 +1,65 @@
+//! Generic `Arc<C>` → `Arc<dyn Trait>` conversion for
+//! [`ContainerBuilder::bind`](crate::container::ContainerBuilder::bind).
+//!
+//! Stable Rust can't write "any `C` that coerces to `I`" as a bound, so
+//! each trait object opts in once with [`upcast!`](crate::upcast!), which
+//! implements [`Upcast`] for every implementor of the trait:
+//!
+//! ```rust
+//! use makhzan_container::prelude::*;
+//! use std::sync::Arc;
+//!
+//! trait Logger: Send + Sync {
+//!     fn log(&self, msg: &str) -> String;
+//! }
+//! makhzan_container::upcast!(Logger);
+//!
+//! struct ConsoleLogger;
+//! impl Logger for ConsoleLogger {
+//!     fn log(&self, msg: &str) -> String { format!("[console] {msg}") }
+//! }
+//!
+//! let container = Container::builder()
+//!     .singleton_arc(|_| Ok(ConsoleLogger))
+//!     .bind::<dyn Logger, ConsoleLogger>()
+//!     .build()
+//!     .unwrap();
+//!
+//! let logger: Arc<dyn Logger> = container.resolve().unwrap();
+//! assert_eq!(logger.log("hi"), "[console] hi");
+//! ```
+
+use std::sync::Arc;
+
+/// A trait object `Self` that an `Arc<C>` can be converted into.
+///
+/// Implement it with [`upcast!`](crate::upcast!) rather than by hand.
+pub trait Upcast<C>: Send + Sync + 'static {
+    /// Performs the unsizing coercion.
+    fn upcast(concrete: Arc<C>) -> Arc<Self>;
+}
+
+/// Implements [`Upcast`] from every implementor of a trait to its trait
+/// object, so [`bind`](crate::container::ContainerBuilder::bind) works
+/// for it.
+///
+/// Takes the trait object's bounds without `dyn`. The object must be
+/// `Send + Sync`, through supertraits or spelled out:
+///
+/// ```rust
+/// trait Logger: Send + Sync {}
+/// trait Cache {}
+///
+/// makhzan_container::upcast!(Logger);               // trait Logger: Send + Sync
+/// makhzan_container::upcast!(Cache + Send + Sync);  // trait Cache
+/// ```
+#[macro_export]
+macro_rules! upcast {
+    ($($bounds:tt)+) => {
+        impl<C: $($bounds)+ + 'static> $crate::upcast::Upcast<C> for dyn $($bounds)+ {
+            fn upcast(concrete: ::std::sync::Arc<C>) -> ::std::sync::Arc<Self> {
+                concrete
+            }
+        }
+    };
+}
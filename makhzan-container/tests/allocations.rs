@@ -0,0 +1,79 @@
+//! Allocation-counting checks for the resolve hot path.
+//!
+//! Lives in its own test binary because it installs a global allocator.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use std::sync::Arc;
+
+use makhzan_container::container::resolve;
+use makhzan_container::prelude::*;
+
+struct CountingAllocator;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.with(|count| count.set(count.get() + 1));
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+/// Number of allocations `f` performs on the current thread.
+fn allocations<R>(f: impl FnOnce() -> R) -> usize {
+    let before = ALLOCATIONS.with(Cell::get);
+    let result = f();
+    let after = ALLOCATIONS.with(Cell::get);
+    drop(result);
+    after - before
+}
+
+struct Database;
+
+struct UserService {
+    _db: Arc<Database>,
+}
+
+#[test]
+fn arc_singleton_resolve_does_not_allocate() {
+    let container = Container::builder()
+        .singleton_with::<Arc<Database>>(|_| Ok(Arc::new(Database)))
+        .transient_with::<UserService>(|r| Ok(UserService { _db: resolve(r)? }))
+        .build()
+        .unwrap();
+
+    // First resolve runs the factory and fills the singleton cache.
+    let first: Arc<Database> = container.resolve().unwrap();
+
+    assert_eq!(allocations(|| container.resolve::<Arc<Database>>().unwrap()), 0);
+
+    // Only the transient's own box; the nested singleton is shared.
+    assert_eq!(allocations(|| container.resolve::<UserService>().unwrap()), 1);
+
+    assert!(Arc::ptr_eq(&first, &container.resolve::<Arc<Database>>().unwrap()));
+}
+
+#[test]
+fn copy_transient_resolve_does_not_allocate() {
+    let container = Container::builder()
+        .singleton_value(7u32)
+        .transient_copy_with::<u64>(|r| Ok(u64::from(resolve::<u32>(r)?) * 2))
+        .build()
+        .unwrap();
+
+    assert_eq!(container.resolve_copy::<u64>().unwrap(), 14);
+    assert_eq!(allocations(|| container.resolve_copy::<u64>().unwrap()), 0);
+
+    // The boxed path is still there for plain resolve.
+    assert_eq!(allocations(|| container.resolve::<u64>().unwrap()), 1);
+}
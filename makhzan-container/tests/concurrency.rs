@@ -0,0 +1,258 @@
+//! Concurrent resolution stress tests.
+//!
+//! Many threads start resolving at the same moment (behind a barrier) so
+//! first-resolve races on singletons actually happen. Iteration counts
+//! drop under Miri, where these also run: `cargo +nightly miri test --test concurrency`.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Barrier};
+use std::thread;
+
+use makhzan_container::container::resolve;
+use makhzan_container::prelude::*;
+
+const THREADS: usize = if cfg!(miri) { 4 } else { 32 };
+const ITERATIONS: usize = if cfg!(miri) { 10 } else { 1_000 };
+
+struct Config {
+    name: &'static str,
+}
+
+struct Pool {
+    config: Arc<Config>,
+}
+
+struct Request {
+    pool: Arc<Pool>,
+    id: usize,
+}
+
+#[derive(Default)]
+struct Counters {
+    config: AtomicUsize,
+    pool: AtomicUsize,
+    request: AtomicUsize,
+}
+
+fn container(counters: &Arc<Counters>) -> Container {
+    Container::builder()
+        .singleton_with::<Arc<Config>>({
+            let counters = counters.clone();
+            move |_| {
+                counters.config.fetch_add(1, Ordering::SeqCst);
+                Ok(Arc::new(Config { name: "app" }))
+            }
+        })
+        .singleton_with::<Arc<Pool>>({
+            let counters = counters.clone();
+            move |r| {
+                counters.pool.fetch_add(1, Ordering::SeqCst);
+                // Widen the window in which other threads see an empty cell.
+                thread::yield_now();
+                Ok(Arc::new(Pool { config: resolve(r)? }))
+            }
+        })
+        .transient_with::<Request>({
+            let counters = counters.clone();
+            move |r| {
+                let id = counters.request.fetch_add(1, Ordering::SeqCst);
+                Ok(Request { pool: resolve(r)?, id })
+            }
+        })
+        .build()
+        .unwrap()
+}
+
+#[test]
+fn singletons_are_built_once_under_contention() {
+    let counters = Arc::new(Counters::default());
+    let container = container(&counters);
+    let barrier = Barrier::new(THREADS);
+
+    let pools: Vec<Arc<Pool>> = thread::scope(|s| {
+        let handles: Vec<_> = (0..THREADS)
+            .map(|_| {
+                s.spawn(|| {
+                    barrier.wait();
+                    container.resolve::<Arc<Pool>>().unwrap()
+                })
+            })
+            .collect();
+        handles.into_iter().map(|h| h.join().unwrap()).collect()
+    });
+
+    assert_eq!(counters.pool.load(Ordering::SeqCst), 1);
+    assert_eq!(counters.config.load(Ordering::SeqCst), 1);
+    assert!(pools.iter().all(|pool| Arc::ptr_eq(pool, &pools[0])));
+}
+
+#[test]
+fn mixed_resolutions_from_many_threads() {
+    let counters = Arc::new(Counters::default());
+    let container = container(&counters);
+    let barrier = Barrier::new(THREADS);
+
+    let ids: Vec<usize> = thread::scope(|s| {
+        let handles: Vec<_> = (0..THREADS)
+            .map(|t| {
+                let container = &container;
+                let barrier = &barrier;
+                s.spawn(move || {
+                    barrier.wait();
+                    let mut ids = Vec::with_capacity(ITERATIONS);
+                    for i in 0..ITERATIONS {
+                        if (t + i) % 3 == 0 {
+                            let config: Arc<Config> = container.resolve().unwrap();
+                            assert_eq!(config.name, "app");
+                        } else {
+                            let request: Request = container.resolve().unwrap();
+                            assert_eq!(request.pool.config.name, "app");
+                            ids.push(request.id);
+                        }
+                    }
+                    ids
+                })
+            })
+            .collect();
+        handles.into_iter().flat_map(|h| h.join().unwrap()).collect()
+    });
+
+    assert_eq!(counters.config.load(Ordering::SeqCst), 1);
+    assert_eq!(counters.pool.load(Ordering::SeqCst), 1);
+
+    // Every transient resolve ran its own factory exactly once.
+    let mut sorted = ids.clone();
+    sorted.sort_unstable();
+    sorted.dedup();
+    assert_eq!(sorted.len(), ids.len());
+    assert_eq!(counters.request.load(Ordering::SeqCst), ids.len());
+}
+
+#[test]
+fn scopes_resolve_concurrently() {
+    let counters = Arc::new(Counters::default());
+    let container = container(&counters);
+
+    thread::scope(|s| {
+        for _ in 0..THREADS {
+            s.spawn(|| {
+                let scope = container.create_scope();
+                for _ in 0..ITERATIONS / 10 {
+                    let request: Request = scope.resolve().unwrap();
+                    assert_eq!(request.pool.config.name, "app");
+                }
+            });
+        }
+    });
+
+    assert_eq!(counters.pool.load(Ordering::SeqCst), 1);
+}
+
+#[derive(Clone)]
+struct Session {
+    id: usize,
+}
+
+#[derive(Clone)]
+struct Cart {
+    session: Session,
+}
+
+#[derive(Clone)]
+struct Checkout {
+    cart: Cart,
+    session: Session,
+}
+
+#[test]
+fn shared_owned_scope_builds_each_scoped_key_once() {
+    let sessions = Arc::new(AtomicUsize::new(0));
+    let carts = Arc::new(AtomicUsize::new(0));
+    let checkouts = Arc::new(AtomicUsize::new(0));
+    let container = Container::builder()
+        .scoped_with::<Session>({
+            let sessions = sessions.clone();
+            move |_| {
+                thread::yield_now();
+                Ok(Session { id: sessions.fetch_add(1, Ordering::SeqCst) })
+            }
+        })
+        .scoped_with::<Cart>({
+            let carts = carts.clone();
+            move |r| {
+                carts.fetch_add(1, Ordering::SeqCst);
+                thread::yield_now();
+                Ok(Cart { session: resolve(r)? })
+            }
+        })
+        .scoped_with::<Checkout>({
+            let checkouts = checkouts.clone();
+            move |r| {
+                checkouts.fetch_add(1, Ordering::SeqCst);
+                // Resolves a different scoped key while this one is being
+                // initialised.
+                Ok(Checkout { cart: resolve(r)?, session: resolve(r)? })
+            }
+        })
+        .build()
+        .unwrap();
+    let scope = container.create_owned_scope();
+    let barrier = Barrier::new(THREADS);
+
+    let ids: Vec<usize> = thread::scope(|s| {
+        let handles: Vec<_> = (0..THREADS)
+            .map(|t| {
+                let scope = scope.clone();
+                let barrier = &barrier;
+                s.spawn(move || {
+                    barrier.wait();
+                    let mut ids = Vec::new();
+                    for i in 0..ITERATIONS / 10 {
+                        match (t + i) % 3 {
+                            0 => ids.push(scope.resolve::<Session>().unwrap().id),
+                            1 => ids.push(scope.resolve::<Cart>().unwrap().session.id),
+                            _ => {
+                                let checkout: Checkout = scope.resolve().unwrap();
+                                assert_eq!(checkout.cart.session.id, checkout.session.id);
+                                ids.push(checkout.session.id);
+                            }
+                        }
+                    }
+                    ids
+                })
+            })
+            .collect();
+        handles.into_iter().flat_map(|h| h.join().unwrap()).collect()
+    });
+
+    assert_eq!(sessions.load(Ordering::SeqCst), 1);
+    assert_eq!(carts.load(Ordering::SeqCst), 1);
+    assert_eq!(checkouts.load(Ordering::SeqCst), 1);
+    assert!(ids.iter().all(|&id| id == 0));
+}
+
+#[test]
+fn clones_moved_into_threads_share_singletons() {
+    let counters = Arc::new(Counters::default());
+    let container = container(&counters);
+    let barrier = Arc::new(Barrier::new(THREADS));
+
+    let handles: Vec<thread::JoinHandle<Arc<Pool>>> = (0..THREADS)
+        .map(|_| {
+            let container = container.clone();
+            let barrier = barrier.clone();
+            thread::spawn(move || {
+                barrier.wait();
+                container.resolve().unwrap()
+            })
+        })
+        .collect();
+    let pools: Vec<Arc<Pool>> = handles.into_iter().map(|h| h.join().unwrap()).collect();
+
+    assert_eq!(counters.pool.load(Ordering::SeqCst), 1);
+    assert!(pools.iter().all(|pool| Arc::ptr_eq(pool, &pools[0])));
+
+    // The original handle sees the instance built on a worker thread.
+    let pool: Arc<Pool> = container.resolve().unwrap();
+    assert!(Arc::ptr_eq(&pool, &pools[0]));
+}
@@ -0,0 +1,78 @@
+//! A registration helper written the way a downstream crate would: an
+//! extension trait on `ContainerBuilder` using only the public API.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use makhzan_container::container::resolve;
+use makhzan_container::prelude::*;
+
+struct KafkaConfig {
+    brokers: String,
+}
+
+struct KafkaClient {
+    brokers: String,
+}
+
+#[derive(Clone)]
+struct Producer {
+    client: Arc<KafkaClient>,
+    topic: String,
+}
+
+trait KafkaExt {
+    fn add_kafka(self, config: KafkaConfig, connections: Arc<AtomicUsize>) -> Self;
+}
+
+impl KafkaExt for ContainerBuilder {
+    fn add_kafka(self, config: KafkaConfig, connections: Arc<AtomicUsize>) -> Self {
+        self.register_factory(Scope::Singleton, vec![], move |_| {
+            connections.fetch_add(1, Ordering::SeqCst);
+            Ok(Arc::new(KafkaClient { brokers: config.brokers.clone() }))
+        })
+        .register_factory(
+            Scope::Scoped,
+            vec![DependencyKey::of::<Arc<KafkaClient>>(), DependencyKey::of::<String>()],
+            |r| Ok(Producer { client: resolve(r)?, topic: resolve(r)? }),
+        )
+    }
+}
+
+#[test]
+fn extension_trait_registers_related_services() {
+    let connections = Arc::new(AtomicUsize::new(0));
+    let container = Container::builder()
+        .singleton_value(String::from("orders"))
+        .add_kafka(KafkaConfig { brokers: "localhost:9092".into() }, connections.clone())
+        .build()
+        .unwrap();
+
+    let scope = container.create_scope();
+    let producer: Producer = scope.resolve().unwrap();
+    assert_eq!((producer.client.brokers.as_str(), producer.topic.as_str()), ("localhost:9092", "orders"));
+    assert!(Arc::ptr_eq(&producer.client, &scope.resolve::<Producer>().unwrap().client));
+    assert!(Arc::ptr_eq(&producer.client, &container.create_scope().resolve::<Producer>().unwrap().client));
+    assert_eq!(connections.load(Ordering::SeqCst), 1);
+
+    let described = container.describe(&DependencyKey::of::<Producer>()).unwrap();
+    assert_eq!(described.scope, Scope::Scoped);
+    assert_eq!(described.dependencies.len(), 2);
+}
+
+#[test]
+fn extension_dependencies_are_validated_at_build() {
+    let err = Container::builder()
+        .add_kafka(KafkaConfig { brokers: "localhost:9092".into() }, Arc::default())
+        .build()
+        .map(|_| ())
+        .unwrap_err();
+
+    match err {
+        MakhzanError::NotRegistered(e) => {
+            assert_eq!(e.requested, DependencyKey::of::<String>());
+            assert_eq!(e.required_by, Some(DependencyKey::of::<Producer>()));
+        }
+        other => panic!("expected NotRegistered, got {other:?}"),
+    }
+}
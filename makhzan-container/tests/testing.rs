@@ -0,0 +1,101 @@
+//! The `test-util` doubles used the way an application's own tests would:
+//! a `UserService` factory and the provider registering it, tested without
+//! building a container.
+
+use std::sync::Arc;
+
+use makhzan_container::container::resolve;
+use makhzan_container::prelude::*;
+use makhzan_container::provider::{Cacheable, ProviderRegistry};
+use makhzan_container::registry::Resolver;
+use makhzan_container::testing::{RecordingRegistry, RecordingResolver};
+
+struct Database {
+    url: String,
+}
+
+#[derive(Clone)]
+struct UserService {
+    database: Arc<Database>,
+    page_size: u32,
+}
+
+fn user_service(r: &dyn Resolver) -> Result<UserService> {
+    Ok(UserService { database: resolve(r)?, page_size: r.resolve_named("page_size")? })
+}
+
+struct Users;
+
+impl Provider for Users {
+    fn register(&self, registry: &mut dyn ProviderRegistry) {
+        registry.register_cacheable(
+            DependencyKey::of::<UserService>(),
+            Scope::Scoped,
+            Arc::new(|r| Ok(Box::new(user_service(r)?))),
+            vec![DependencyKey::of::<Arc<Database>>(), DependencyKey::named::<u32>("page_size")],
+            Cacheable::of::<UserService>(),
+        );
+        registry.register_alias(DependencyKey::named::<UserService>("users"), DependencyKey::of::<UserService>());
+    }
+}
+
+fn database() -> Arc<Database> {
+    Arc::new(Database { url: "postgres://localhost/test".into() })
+}
+
+#[test]
+fn factory_runs_against_canned_values_and_records_requests() {
+    let database = database();
+    let resolver = RecordingResolver::new()
+        .with(database.clone())
+        .with_key(DependencyKey::named::<u32>("page_size"), 50u32);
+
+    let service = user_service(&resolver).unwrap();
+    assert!(Arc::ptr_eq(&service.database, &database));
+    assert_eq!((service.database.url.as_str(), service.page_size), ("postgres://localhost/test", 50));
+
+    user_service(&resolver).unwrap();
+    let requested = resolver.requested();
+    assert_eq!(requested.len(), 4);
+    assert_eq!(requested[..2], [DependencyKey::of::<Arc<Database>>(), DependencyKey::named::<u32>("page_size")]);
+    assert_eq!(requested[2..], requested[..2]);
+}
+
+#[test]
+fn unexpected_key_errors_or_panics() {
+    let resolver = RecordingResolver::new().with(database()).with(8u32);
+    match user_service(&resolver).map(|_| ()).unwrap_err() {
+        MakhzanError::NotRegistered(e) => {
+            assert_eq!(e.requested, DependencyKey::named::<u32>("page_size"));
+            assert_eq!(e.suggestions, [DependencyKey::of::<u32>()]);
+        }
+        other => panic!("expected NotRegistered, got {other:?}"),
+    }
+
+    let strict = RecordingResolver::new().with(database()).panic_on_unexpected(true);
+    let resolve = std::panic::AssertUnwindSafe(|| user_service(&strict).map(|_| ()));
+    let panic = std::panic::catch_unwind(resolve).unwrap_err();
+    let message = panic.downcast_ref::<String>().unwrap();
+    assert_eq!(*message, format!("unexpected resolve of {}", DependencyKey::named::<u32>("page_size")));
+}
+
+#[test]
+fn registry_captures_what_a_provider_registers() {
+    let mut registry = RecordingRegistry::new();
+    Users.register(&mut registry);
+
+    let [info] = registry.registrations() else { panic!("{registry:?}") };
+    assert_eq!(info.key, DependencyKey::of::<UserService>());
+    assert_eq!(info.scope, Scope::Scoped);
+    assert!(info.cacheable);
+    assert_eq!(registry.aliases(), [(DependencyKey::named::<UserService>("users"), DependencyKey::of::<UserService>())]);
+
+    let resolver = RecordingResolver::new()
+        .with(database())
+        .with_key(DependencyKey::named::<u32>("page_size"), 20u32);
+    let registered = registry.get(&DependencyKey::of::<UserService>()).unwrap();
+    let service: UserService = registered.create(&resolver).unwrap();
+    assert_eq!(service.page_size, 20);
+    assert_eq!(resolver.requested(), info.dependencies);
+    assert!(matches!(info.create::<u32>(&resolver), Err(MakhzanError::TypeMismatch { .. })));
+}
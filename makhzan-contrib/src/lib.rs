@@ -0,0 +1,9 @@
+//! Ready-made providers for Makhzan DI.
+//!
+//! Each integration sits behind its own feature:
+//!
+//! - `sqlx-postgres`: [`postgres::SqlxProvider`], a Postgres pool
+//!   singleton and a transaction per scope.
+
+#[cfg(feature = "sqlx-postgres")]
+pub mod postgres;
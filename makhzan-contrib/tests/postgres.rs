@@ -0,0 +1,59 @@
+//! `SqlxProvider` against a real database.
+//!
+//! Ignored by default. Run with
+//! `DATABASE_URL=postgres://... cargo test -p makhzan-contrib --features sqlx-postgres -- --ignored`.
+
+use std::sync::Arc;
+
+use makhzan_container::container::Container;
+use makhzan_contrib::postgres::{DatabaseConfig, PgTransaction, SqlxProvider};
+use sqlx::postgres::PgPool;
+
+fn container() -> Container {
+    let url = std::env::var("DATABASE_URL").expect("DATABASE_URL must be set");
+    Container::builder()
+        .singleton_value(DatabaseConfig::new(url))
+        .add_provider(&SqlxProvider)
+        .build()
+        .unwrap()
+}
+
+async fn count(pool: &PgPool, table: &str) -> i64 {
+    sqlx::query_scalar(&format!("SELECT count(*) FROM {table}"))
+        .fetch_one(pool)
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+#[ignore = "needs DATABASE_URL"]
+async fn commit_keeps_and_disposal_rolls_back() {
+    let container = container();
+    let pool: Arc<PgPool> = container.resolve().unwrap();
+    sqlx::query("CREATE TABLE IF NOT EXISTS makhzan_contrib_events (id serial PRIMARY KEY)")
+        .execute(&*pool)
+        .await
+        .unwrap();
+    let before = count(&pool, "makhzan_contrib_events").await;
+
+    {
+        let scope = container.create_owned_scope();
+        let tx: PgTransaction = scope.resolve().unwrap();
+        sqlx::query("INSERT INTO makhzan_contrib_events DEFAULT VALUES")
+            .execute(&mut **tx.get().await.unwrap())
+            .await
+            .unwrap();
+        tx.commit().await.unwrap();
+    }
+    assert_eq!(count(&pool, "makhzan_contrib_events").await, before + 1);
+
+    {
+        let scope = container.create_owned_scope();
+        let tx: PgTransaction = scope.resolve().unwrap();
+        sqlx::query("INSERT INTO makhzan_contrib_events DEFAULT VALUES")
+            .execute(&mut **tx.get().await.unwrap())
+            .await
+            .unwrap();
+    }
+    assert_eq!(count(&pool, "makhzan_contrib_events").await, before + 1);
+}
@@ -0,0 +1,230 @@
+//! `#[injectable]`: constructor injection.
+
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{
+    Attribute, FnArg, GenericArgument, ImplItem, ImplItemFn, Item, ItemImpl, PathArguments,
+    ReturnType, Type,
+};
+
+/// Expands `#[injectable]` on an `impl` block.
+pub fn expand(attr: TokenStream, item: TokenStream) -> syn::Result<TokenStream> {
+    if !attr.is_empty() {
+        return Err(syn::Error::new_spanned(attr, "#[injectable] takes no arguments"));
+    }
+
+    let mut item_impl = match syn::parse2::<Item>(item)? {
+        Item::Impl(item_impl) => item_impl,
+        Item::Fn(item_fn) => {
+            return Err(syn::Error::new_spanned(
+                item_fn.sig.ident,
+                "#[injectable] on a constructor also needs #[injectable] on its impl block",
+            ));
+        }
+        other => {
+            return Err(syn::Error::new_spanned(
+                other,
+                "#[injectable] goes on an impl block and on its constructor",
+            ));
+        }
+    };
+
+    if let Some((_, path, _)) = &item_impl.trait_ {
+        return Err(syn::Error::new_spanned(
+            path,
+            "#[injectable] needs an inherent impl block, not a trait impl",
+        ));
+    }
+
+    let constructor = take_constructor(&mut item_impl)?;
+    let params = constructor_params(&constructor)?;
+    check_return_type(&constructor, &item_impl.self_ty)?;
+
+    let self_ty = &item_impl.self_ty;
+    let (impl_generics, _, where_clause) = item_impl.generics.split_for_impl();
+    let name = &constructor.sig.ident;
+    let args: Vec<_> = (0..params.len()).map(|i| format_ident!("__arg{i}")).collect();
+
+    Ok(quote! {
+        #item_impl
+
+        #[automatically_derived]
+        impl #impl_generics ::makhzan::inject::Injectable for #self_ty #where_clause {
+            fn dependencies() -> ::std::vec::Vec<::makhzan::DependencyKey> {
+                ::std::vec![#(::makhzan::DependencyKey::of::<#params>()),*]
+            }
+
+            fn inject(
+                resolver: &dyn ::makhzan::registry::Resolver,
+            ) -> ::makhzan::Result<Self> {
+                #(let #args = ::makhzan::container::resolve::<#params>(resolver)?;)*
+                ::makhzan::inject::IntoInjected::<Self>::into_injected(Self::#name(#(#args),*))
+            }
+        }
+    })
+}
+
+/// Finds the one method marked `#[injectable]`, strips the marker and
+/// returns a copy of it.
+fn take_constructor(item_impl: &mut ItemImpl) -> syn::Result<ImplItemFn> {
+    let mut found: Option<ImplItemFn> = None;
+
+    for item in &mut item_impl.items {
+        let ImplItem::Fn(method) = item else { continue };
+        let before = method.attrs.len();
+        method.attrs.retain(|attr| !is_injectable(attr));
+        if method.attrs.len() == before {
+            continue;
+        }
+
+        if found.is_some() {
+            return Err(syn::Error::new_spanned(
+                &method.sig.ident,
+                "only one constructor per impl block can be #[injectable]",
+            ));
+        }
+        found = Some(method.clone());
+    }
+
+    found.ok_or_else(|| {
+        syn::Error::new_spanned(
+            &item_impl.self_ty,
+            "mark the constructor to inject with #[injectable]",
+        )
+    })
+}
+
+fn is_injectable(attr: &Attribute) -> bool {
+    attr.path().is_ident("injectable")
+}
+
+/// Returns the parameter types, rejecting signatures that can't be
+/// resolved from the container.
+fn constructor_params(constructor: &ImplItemFn) -> syn::Result<Vec<Type>> {
+    let sig = &constructor.sig;
+
+    if let Some(asyncness) = &sig.asyncness {
+        return Err(syn::Error::new_spanned(
+            asyncness,
+            "an #[injectable] constructor can't be async",
+        ));
+    }
+    if !sig.generics.params.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &sig.generics,
+            "an #[injectable] constructor can't be generic",
+        ));
+    }
+
+    sig.inputs
+        .iter()
+        .map(|input| match input {
+            FnArg::Receiver(receiver) => Err(syn::Error::new_spanned(
+                receiver,
+                "an #[injectable] constructor can't take `self`",
+            )),
+            FnArg::Typed(typed) => match &*typed.ty {
+                Type::Reference(reference) => Err(syn::Error::new_spanned(
+                    reference,
+                    "can't inject a reference; take an owned value such as `Arc<T>`",
+                )),
+                Type::ImplTrait(impl_trait) => Err(syn::Error::new_spanned(
+                    impl_trait,
+                    "can't inject `impl Trait`; take a concrete type such as `Arc<dyn Trait>`",
+                )),
+                ty => Ok(ty.clone()),
+            },
+        })
+        .collect()
+}
+
+fn check_return_type(constructor: &ImplItemFn, self_ty: &Type) -> syn::Result<()> {
+    let ok = match &constructor.sig.output {
+        ReturnType::Type(_, ty) => is_self(ty, self_ty) || is_result_of_self(ty, self_ty),
+        ReturnType::Default => false,
+    };
+
+    if ok {
+        Ok(())
+    } else {
+        Err(syn::Error::new_spanned(
+            &constructor.sig.output,
+            "an #[injectable] constructor must return `Self` or `Result<Self, E>`",
+        ))
+    }
+}
+
+fn is_self(ty: &Type, self_ty: &Type) -> bool {
+    match ty {
+        Type::Path(path) if path.qself.is_none() && path.path.is_ident("Self") => true,
+        _ => ty == self_ty,
+    }
+}
+
+fn is_result_of_self(ty: &Type, self_ty: &Type) -> bool {
+    let Type::Path(path) = ty else { return false };
+    let Some(last) = path.path.segments.last() else { return false };
+    if last.ident != "Result" {
+        return false;
+    }
+
+    match &last.arguments {
+        PathArguments::AngleBracketed(args) => matches!(
+            args.args.first(),
+            Some(GenericArgument::Type(ok)) if is_self(ok, self_ty)
+        ),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn expand_err(item: TokenStream) -> String {
+        expand(TokenStream::new(), item).unwrap_err().to_string()
+    }
+
+    #[test]
+    fn generates_impl_and_strips_marker() {
+        let out = expand(
+            TokenStream::new(),
+            quote! {
+                impl Service {
+                    #[injectable]
+                    fn new(db: Arc<Database>) -> Result<Self, String> { todo!() }
+                }
+            },
+        )
+        .unwrap()
+        .to_string();
+
+        assert!(out.contains("Injectable for Service"));
+        assert!(out.contains("DependencyKey :: of :: < Arc < Database > >"));
+        assert!(!out.contains("# [injectable]"));
+    }
+
+    #[test]
+    fn rejects_unsupported_constructors() {
+        assert!(expand_err(quote! { impl S { fn new() -> Self { S } } }).contains("mark the constructor"));
+        assert!(expand_err(quote! { impl S { #[injectable] fn new(&self) -> Self { S } } })
+            .contains("`self`"));
+        assert!(expand_err(quote! { impl S { #[injectable] fn new(a: &A) -> Self { S } } })
+            .contains("reference"));
+        assert!(expand_err(quote! { impl S { #[injectable] fn new() -> u32 { 0 } } })
+            .contains("must return"));
+        assert!(expand_err(quote! { fn new() -> Self { S } }).contains("impl block"));
+    }
+
+    #[test]
+    fn accepts_named_self_type_and_result() {
+        assert!(expand(TokenStream::new(), quote! {
+            impl S { #[injectable] fn new() -> S { S } }
+        })
+        .is_ok());
+        assert!(expand(TokenStream::new(), quote! {
+            impl S { #[injectable] fn new() -> std::result::Result<S, E> { Ok(S) } }
+        })
+        .is_ok());
+    }
+}
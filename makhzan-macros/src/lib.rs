@@ -2,3 +2,29 @@
 
 extern crate proc_macro;
 
+mod injectable;
+
+use proc_macro::TokenStream;
+
+/// Generates an `Injectable` impl from a constructor.
+///
+/// Put `#[injectable]` on an inherent `impl` block and on the one
+/// associated function in it that builds the type. Every parameter is
+/// resolved by its type and declared as a dependency; the function must
+/// not take `self` and must return `Self` or `Result<Self, E>`.
+///
+/// ```rust,ignore
+/// #[injectable]
+/// impl UserService {
+///     #[injectable]
+///     fn new(db: Arc<Database>, log: Arc<dyn Logger>) -> Self {
+///         Self { db, log }
+///     }
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn injectable(attr: TokenStream, item: TokenStream) -> TokenStream {
+    injectable::expand(attr.into(), item.into())
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
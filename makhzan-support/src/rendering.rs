@@ -3,8 +3,6 @@
 //! Provides helpers to format dependency chains, type names,
 //! and helpful suggestions in error output.
 
-use std::fmt;
-
 /// Renders a dependency chain as a readable string.
 ///
 /// # Examples
@@ -75,6 +73,62 @@ pub struct ChainEntry {
     pub source_name: Option<String>,
 }
 
+/// A node of a tree drawn by [`render_tree`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TreeNode {
+    /// Text shown for this node
+    pub label: String,
+    /// Nodes drawn indented below this one
+    pub children: Vec<TreeNode>,
+}
+
+impl TreeNode {
+    /// Creates a node without children.
+    pub fn leaf(label: impl Into<String>) -> Self {
+        Self {
+            label: label.into(),
+            children: Vec::new(),
+        }
+    }
+}
+
+/// Renders a forest as an indented ASCII tree.
+///
+/// ```
+/// use makhzan_support::rendering::{TreeNode, render_tree};
+///
+/// let root = TreeNode {
+///     label: "UserService".to_string(),
+///     children: vec![TreeNode::leaf("UserRepo"), TreeNode::leaf("Logger")],
+/// };
+/// assert_eq!(
+///     render_tree(&[root]),
+///     "UserService\n├── UserRepo\n└── Logger\n"
+/// );
+/// ```
+pub fn render_tree(roots: &[TreeNode]) -> String {
+    fn render_children(children: &[TreeNode], prefix: &str, out: &mut String) {
+        for (i, child) in children.iter().enumerate() {
+            let last = i + 1 == children.len();
+            out.push_str(prefix);
+            out.push_str(if last { "└── " } else { "├── " });
+            out.push_str(&child.label);
+            out.push('\n');
+
+            let nested = format!("{prefix}{}", if last { "    " } else { "│   " });
+            render_children(&child.children, &nested, out);
+        }
+    }
+
+    let mut out = String::new();
+    for root in roots {
+        out.push_str(&root.label);
+        out.push('\n');
+        render_children(&root.children, "", &mut out);
+    }
+    out
+}
+
 /// Shortens a fully qualified type name for display.
 ///
 /// ```
@@ -125,45 +179,12 @@ pub fn suggest_similar(
     available: &[&str],
     max_suggestions: usize,
 ) -> Vec<String> {
-    let requested_lower = requested.to_lowercase();
-    let requested_short = shorten_type_name(requested).to_lowercase();
-
-    let mut scored: Vec<(&str, usize)> = available
+    let mut scored: Vec<(&str, u32)> = available
         .iter()
-        .filter_map(|&name| {
-            let name_lower = name.to_lowercase();
-            let name_short = shorten_type_name(name).to_lowercase();
-
-            // Exact substring match (highest priority)
-            if name_lower.contains(&requested_lower)
-                || requested_lower.contains(&name_lower)
-            {
-                return Some((name, 100));
-            }
-
-            // Short name match
-            if name_short.contains(&requested_short)
-                || requested_short.contains(&name_short)
-            {
-                return Some((name, 80));
-            }
-
-            // Common prefix
-            let common = name_short
-                .chars()
-                .zip(requested_short.chars())
-                .take_while(|(a, b)| a == b)
-                .count();
-
-            if common >= 3 {
-                return Some((name, common * 10));
-            }
-
-            None
-        })
+        .filter_map(|&name| Some((name, similarity_score(requested, name)?)))
         .collect();
 
-    scored.sort_by(|a, b| b.1.cmp(&a.1));
+    scored.sort_by_key(|&(_, score)| std::cmp::Reverse(score));
     scored
         .into_iter()
         .take(max_suggestions)
@@ -171,6 +192,57 @@ pub fn suggest_similar(
         .collect()
 }
 
+/// How close `candidate` is to `requested`, or `None` if unrelated.
+///
+/// - 100: one full name contains the other
+/// - 80: one short name (see [`shorten_type_name`]) contains the other
+/// - 10 per leading character the short names share, from 3 on
+/// - 30–50: a likely typo, at least 60% of positions matching
+pub fn similarity_score(requested: &str, candidate: &str) -> Option<u32> {
+    let requested_lower = requested.to_lowercase();
+    let requested_short = shorten_type_name(requested).to_lowercase();
+    let name_lower = candidate.to_lowercase();
+    let name_short = shorten_type_name(candidate).to_lowercase();
+
+    // Exact substring match (highest priority)
+    if name_lower.contains(&requested_lower) || requested_lower.contains(&name_lower) {
+        return Some(100);
+    }
+
+    // Short name match
+    if name_short.contains(&requested_short) || requested_short.contains(&name_short) {
+        return Some(80);
+    }
+
+    // Common prefix
+    let common = name_short
+        .chars()
+        .zip(requested_short.chars())
+        .take_while(|(a, b)| a == b)
+        .count() as u32;
+    if common >= 3 {
+        return Some(common * 10);
+    }
+
+    // Typo: same length give or take a few, mostly the same characters
+    let percent = matching_percent(&requested_short, &name_short)?;
+    (percent >= 60).then_some(percent / 2)
+}
+
+/// Share of positions holding the same character, if the lengths differ
+/// by at most 3.
+fn matching_percent(a: &str, b: &str) -> Option<u32> {
+    if a.len().abs_diff(b.len()) > 3 {
+        return None;
+    }
+    let max_len = a.len().max(b.len());
+    if max_len == 0 {
+        return Some(100);
+    }
+    let common = a.chars().zip(b.chars()).filter(|(ca, cb)| ca == cb).count();
+    Some((common * 100 / max_len) as u32)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -228,6 +300,13 @@ mod tests {
         assert!(suggestions[0].contains("UserService"));
     }
 
+    #[test]
+    fn typos_score_below_prefix_matches() {
+        assert_eq!(similarity_score("Database", "Databse"), Some(50));
+        assert!(similarity_score("Logger", "Lgoger").is_some());
+        assert_eq!(similarity_score("Database", "Logger"), None);
+    }
+
     #[test]
     fn suggest_no_match() {
         let available = vec!["my_app::Database"];
@@ -261,4 +340,26 @@ mod tests {
         assert!(rendered.contains("↓"));
         assert!(rendered.contains("UserService"));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn tree_rendering_indents_nested_children() {
+        let roots = vec![
+            TreeNode {
+                label: "A".to_string(),
+                children: vec![
+                    TreeNode {
+                        label: "B".to_string(),
+                        children: vec![TreeNode::leaf("D")],
+                    },
+                    TreeNode::leaf("C"),
+                ],
+            },
+            TreeNode::leaf("E"),
+        ];
+
+        assert_eq!(
+            render_tree(&roots),
+            "A\n├── B\n│   └── D\n└── C\nE\n"
+        );
+    }
+}
@@ -0,0 +1,62 @@
+//! The per-call scope interceptor.
+
+use std::net::SocketAddr;
+
+use makhzan_container::container::Container;
+use tonic::metadata::{AsciiMetadataKey, MetadataMap};
+use tonic::service::Interceptor;
+use tonic::{Request, Status};
+
+/// Creates an [`OwnedScope`](makhzan_container::container::OwnedScope)
+/// for every call, seeds it with the call's [`CallMetadata`] and inserts
+/// it into the request extensions.
+///
+/// The scope ends when the service impl drops the request. Clone it out
+/// with [`RequestScopeExt::scope`](crate::RequestScopeExt::scope) before
+/// calling `into_inner` if it must outlive the request.
+#[derive(Debug, Clone)]
+pub struct ScopeInterceptor {
+    container: Container,
+    headers: Vec<AsciiMetadataKey>,
+}
+
+impl ScopeInterceptor {
+    /// Scope calls from `container`.
+    pub fn new(container: Container) -> Self {
+        Self { container, headers: Vec::new() }
+    }
+
+    /// Copy the `name` header into [`CallMetadata::headers`] when present.
+    ///
+    /// # Panics
+    /// If `name` is not a valid lowercase ASCII metadata key.
+    pub fn with_header(mut self, name: &'static str) -> Self {
+        self.headers.push(AsciiMetadataKey::from_static(name));
+        self
+    }
+}
+
+impl Interceptor for ScopeInterceptor {
+    fn call(&mut self, mut request: Request<()>) -> Result<Request<()>, Status> {
+        let mut headers = MetadataMap::new();
+        for name in &self.headers {
+            if let Some(value) = request.metadata().get(name) {
+                headers.insert(name.clone(), value.clone());
+            }
+        }
+
+        let scope = self.container.create_owned_scope();
+        scope.insert(CallMetadata { remote_addr: request.remote_addr(), headers });
+        request.extensions_mut().insert(scope);
+        Ok(request)
+    }
+}
+
+/// Seeded into every call's scope by [`ScopeInterceptor`].
+#[derive(Debug, Clone)]
+pub struct CallMetadata {
+    /// The peer's address, when the transport knows it
+    pub remote_addr: Option<SocketAddr>,
+    /// The headers selected with [`ScopeInterceptor::with_header`]
+    pub headers: MetadataMap,
+}
@@ -0,0 +1,28 @@
+//! Tonic integration for Makhzan DI.
+//!
+//! [`ScopeInterceptor`] gives every RPC its own
+//! [`OwnedScope`](makhzan_container::container::OwnedScope), stored in the
+//! request extensions and seeded with the call's [`CallMetadata`]. Inside
+//! the service impl, [`RequestScopeExt::inject`] resolves from it and maps
+//! failures to `Status::internal`.
+//!
+//! ```rust,ignore
+//! let greeter = GreeterServer::with_interceptor(
+//!     MyGreeter,
+//!     ScopeInterceptor::new(container).with_header("x-request-id"),
+//! );
+//!
+//! #[tonic::async_trait]
+//! impl Greeter for MyGreeter {
+//!     async fn say_hello(&self, request: Request<HelloRequest>) -> Result<Response<HelloReply>, Status> {
+//!         let users: Arc<UserService> = request.inject()?;
+//!         // ...
+//!     }
+//! }
+//! ```
+
+mod interceptor;
+mod request;
+
+pub use interceptor::{CallMetadata, ScopeInterceptor};
+pub use request::{RequestScopeExt, into_status};
@@ -0,0 +1,68 @@
+//! Resolving from a call's scope inside a service impl.
+
+use makhzan_container::container::OwnedScope;
+use makhzan_container::error::MakhzanError;
+use tonic::{Request, Status};
+
+/// Access to the scope [`ScopeInterceptor`](crate::ScopeInterceptor)
+/// attached to a request.
+pub trait RequestScopeExt {
+    /// The call's scope.
+    ///
+    /// # Errors
+    /// `Status::internal` if the service isn't wrapped in
+    /// [`ScopeInterceptor`](crate::ScopeInterceptor).
+    fn scope(&self) -> Result<&OwnedScope, Status>;
+
+    /// Resolve `T` from the call's scope.
+    ///
+    /// # Errors
+    /// `Status::internal` without a scope, or with the short message of
+    /// the [`MakhzanError`] if resolving fails.
+    fn inject<T: Send + Sync + 'static>(&self) -> Result<T, Status> {
+        self.scope()?.resolve().map_err(into_status)
+    }
+}
+
+impl<M> RequestScopeExt for Request<M> {
+    fn scope(&self) -> Result<&OwnedScope, Status> {
+        self.extensions().get::<OwnedScope>().ok_or_else(|| {
+            Status::internal("No Makhzan scope on this call; wrap the service in ScopeInterceptor")
+        })
+    }
+}
+
+/// Maps a [`MakhzanError`] to `Status::internal`.
+///
+/// Only the first line of the message is kept; hints and resolution
+/// paths stay in the server logs rather than going to the client.
+pub fn into_status(error: MakhzanError) -> Status {
+    let message = error.to_string();
+    let short = message.lines().next().unwrap_or_default();
+    Status::internal(short)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use makhzan_container::container::Container;
+    use tonic::Code;
+
+    #[test]
+    fn errors_become_internal_with_the_first_line() {
+        let error = Container::builder().build().unwrap().resolve::<u32>().unwrap_err();
+        assert!(error.to_string().contains('\n'));
+
+        let status = into_status(error);
+        assert_eq!(status.code(), Code::Internal);
+        assert!(!status.message().contains('\n'));
+        assert!(status.message().contains("u32"));
+    }
+
+    #[test]
+    fn missing_scope_is_internal() {
+        let request = Request::new(());
+        assert_eq!(request.scope().unwrap_err().code(), Code::Internal);
+        assert_eq!(request.inject::<u32>().unwrap_err().code(), Code::Internal);
+    }
+}
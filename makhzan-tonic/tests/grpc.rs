@@ -0,0 +1,146 @@
+//! A hand-rolled unary gRPC service behind `ScopeInterceptor`.
+
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use bytes::{Buf, BufMut, Bytes};
+use http_body_util::Full;
+use makhzan_container::container::Container;
+use makhzan_tonic::{CallMetadata, RequestScopeExt, ScopeInterceptor};
+use tonic::body::Body;
+use tonic::codec::{Codec, DecodeBuf, Decoder, EncodeBuf, Encoder};
+use tonic::server::Grpc;
+use tonic::service::interceptor::{InterceptedService, ResponseBody};
+use tonic::transport::server::TcpConnectInfo;
+use tonic::{Request, Response, Status};
+use tower::{ServiceExt, service_fn};
+
+/// Messages are plain UTF-8 strings.
+#[derive(Default)]
+struct StringCodec;
+
+impl Codec for StringCodec {
+    type Encode = String;
+    type Decode = String;
+    type Encoder = Self;
+    type Decoder = Self;
+
+    fn encoder(&mut self) -> Self {
+        Self
+    }
+
+    fn decoder(&mut self) -> Self {
+        Self
+    }
+}
+
+impl Encoder for StringCodec {
+    type Item = String;
+    type Error = Status;
+
+    fn encode(&mut self, item: String, dst: &mut EncodeBuf<'_>) -> Result<(), Status> {
+        dst.put_slice(item.as_bytes());
+        Ok(())
+    }
+}
+
+impl Decoder for StringCodec {
+    type Item = String;
+    type Error = Status;
+
+    fn decode(&mut self, src: &mut DecodeBuf<'_>) -> Result<Option<String>, Status> {
+        let bytes = src.copy_to_bytes(src.remaining());
+        String::from_utf8(bytes.to_vec()).map(Some).map_err(|e| Status::invalid_argument(e.to_string()))
+    }
+}
+
+/// Greets the caller using the scoped `Greeting` and the seeded metadata.
+async fn greet(request: Request<String>) -> Result<Response<String>, Status> {
+    let greeting: Arc<Greeting> = request.inject()?;
+    let again: Arc<Greeting> = request.inject()?;
+    assert!(Arc::ptr_eq(&greeting, &again));
+
+    let metadata: CallMetadata = request.inject()?;
+    let request_id = metadata
+        .headers
+        .get("x-request-id")
+        .map(|value| value.to_str().unwrap().to_string())
+        .unwrap_or_default();
+    assert!(metadata.headers.get("authorization").is_none());
+
+    Ok(Response::new(format!(
+        "{} {} [{request_id}] from {}",
+        greeting.0,
+        request.get_ref(),
+        metadata.remote_addr.map(|addr| addr.to_string()).unwrap_or_default()
+    )))
+}
+
+struct Greeting(&'static str);
+
+/// A unary RPC as generated servers dispatch it.
+fn server(
+    container: Container,
+) -> InterceptedService<
+    impl tower::Service<
+        http::Request<Body>,
+        Response = http::Response<Body>,
+        Error = Infallible,
+        Future = impl Send,
+    > + Clone,
+    ScopeInterceptor,
+> {
+    let rpc = service_fn(|request: http::Request<Body>| async move {
+        Ok::<_, Infallible>(Grpc::new(StringCodec).unary(service_fn(greet), request).await)
+    });
+    InterceptedService::new(rpc, ScopeInterceptor::new(container).with_header("x-request-id"))
+}
+
+fn call(message: &str) -> http::Request<Body> {
+    let mut frame = vec![0u8];
+    frame.extend_from_slice(&(message.len() as u32).to_be_bytes());
+    frame.extend_from_slice(message.as_bytes());
+
+    let mut request = http::Request::post("/greeter.Greeter/SayHello")
+        .header("content-type", "application/grpc")
+        .header("te", "trailers")
+        .header("x-request-id", "req-7")
+        .header("authorization", "secret")
+        .body(Body::new(Full::new(Bytes::from(frame))))
+        .unwrap();
+    let peer: SocketAddr = "10.0.0.1:5000".parse().unwrap();
+    request
+        .extensions_mut()
+        .insert(TcpConnectInfo { local_addr: None, remote_addr: Some(peer) });
+    request
+}
+
+async fn reply(response: http::Response<ResponseBody<Body>>) -> String {
+    use http_body_util::BodyExt;
+
+    let bytes = response.into_body().collect().await.unwrap().to_bytes();
+    String::from_utf8(bytes[5..].to_vec()).unwrap()
+}
+
+#[tokio::test]
+async fn each_call_gets_a_seeded_scope() {
+    let container = Container::builder()
+        .scoped_with(|_| Ok(Arc::new(Greeting("hello"))))
+        .build()
+        .unwrap();
+
+    let response = server(container).oneshot(call("world")).await.unwrap();
+    assert_eq!(response.headers().get("grpc-status"), None);
+    assert_eq!(reply(response).await, "hello world [req-7] from 10.0.0.1:5000");
+}
+
+#[tokio::test]
+async fn resolve_errors_become_internal_status() {
+    let container = Container::builder().build().unwrap();
+
+    let response = server(container).oneshot(call("world")).await.unwrap();
+    assert_eq!(response.headers()["grpc-status"], "13");
+    let message = response.headers()["grpc-message"].to_str().unwrap();
+    assert!(message.contains("Greeting"), "{message}");
+}
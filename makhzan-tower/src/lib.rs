@@ -0,0 +1,105 @@
+//! Tower middleware giving each request its own Makhzan scope.
+//!
+//! [`ScopeLayer`] works with any `Service<http::Request<B>>`, whatever the
+//! framework or body type. For every request it creates an
+//! [`OwnedScope`], inserts it into the request extensions and keeps it
+//! alive until the response future completes, fails or is dropped; then
+//! the scope and everything it cached are dropped.
+//!
+//! ```rust,ignore
+//! let service = ServiceBuilder::new()
+//!     .layer(ScopeLayer::new(container))
+//!     .service(handler);
+//!
+//! // Inside the service:
+//! let scope = request.extensions().get::<OwnedScope>().unwrap();
+//! let session: Session = scope.resolve()?;
+//! ```
+
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use http::Request;
+use makhzan_container::container::{Container, OwnedScope};
+use pin_project_lite::pin_project;
+use tower_layer::Layer;
+use tower_service::Service;
+
+/// Creates an [`OwnedScope`] for every request and inserts it into the
+/// request extensions.
+///
+/// Middleware that seeds per-request values must run inside this layer:
+///
+/// ```rust,ignore
+/// if let Some(scope) = request.extensions().get::<OwnedScope>() {
+///     scope.insert(RequestId::new());
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct ScopeLayer {
+    container: Container,
+}
+
+impl ScopeLayer {
+    /// Scope requests from `container`.
+    pub fn new(container: Container) -> Self {
+        Self { container }
+    }
+}
+
+impl<S> Layer<S> for ScopeLayer {
+    type Service = ScopeService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        ScopeService { inner, container: self.container.clone() }
+    }
+}
+
+/// The service produced by [`ScopeLayer`].
+#[derive(Debug, Clone)]
+pub struct ScopeService<S> {
+    inner: S,
+    container: Container,
+}
+
+impl<S, B> Service<Request<B>> for ScopeService<S>
+where
+    S: Service<Request<B>>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = ResponseFuture<S::Future>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut request: Request<B>) -> Self::Future {
+        let scope = self.container.create_owned_scope();
+        request.extensions_mut().insert(scope.clone());
+        ResponseFuture { inner: self.inner.call(request), scope: Some(scope) }
+    }
+}
+
+pin_project! {
+    /// Response future of [`ScopeService`]; holds the request's scope
+    /// until the inner future finishes.
+    pub struct ResponseFuture<F> {
+        #[pin]
+        inner: F,
+        scope: Option<OwnedScope>,
+    }
+}
+
+impl<F: Future> Future for ResponseFuture<F> {
+    type Output = F::Output;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+        let output = std::task::ready!(this.inner.poll(cx));
+        // The response is ready; end the scope before handing it out.
+        this.scope.take();
+        Poll::Ready(output)
+    }
+}
@@ -0,0 +1,91 @@
+//! `ScopeLayer` around a mock tower service.
+
+use std::convert::Infallible;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use http::Request;
+use makhzan_container::prelude::*;
+use makhzan_tower::ScopeLayer;
+use tower::{Layer, ServiceExt, service_fn};
+
+/// Scoped; decrements `alive` when its last clone drops.
+#[derive(Clone)]
+struct Session(Arc<Alive>);
+
+struct Alive(Arc<AtomicUsize>);
+
+impl Drop for Alive {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+fn container(alive: &Arc<AtomicUsize>) -> Container {
+    let alive = alive.clone();
+    Container::builder()
+        .scoped_with(move |_| {
+            alive.fetch_add(1, Ordering::SeqCst);
+            Ok(Session(Arc::new(Alive(alive.clone()))))
+        })
+        .build()
+        .unwrap()
+}
+
+#[tokio::test]
+async fn scope_lives_for_the_call_and_ends_after_it() {
+    let alive = Arc::new(AtomicUsize::new(0));
+    let counter = alive.clone();
+
+    let service = ScopeLayer::new(container(&alive)).layer(service_fn(move |request: Request<()>| {
+        let counter = counter.clone();
+        async move {
+            let scope = request.extensions().get::<OwnedScope>().expect("scope inserted");
+            let first: Session = scope.resolve().unwrap();
+            let second: Session = scope.resolve().unwrap();
+            assert!(Arc::ptr_eq(&first.0, &second.0));
+            drop(request);
+            tokio::task::yield_now().await;
+            Ok::<_, Infallible>(counter.load(Ordering::SeqCst))
+        }
+    }));
+
+    let alive_during_call = service.oneshot(Request::new(())).await.unwrap();
+    assert_eq!(alive_during_call, 1);
+    assert_eq!(alive.load(Ordering::SeqCst), 0);
+}
+
+#[tokio::test]
+async fn scope_ends_on_error() {
+    let alive = Arc::new(AtomicUsize::new(0));
+
+    let service = ScopeLayer::new(container(&alive)).layer(service_fn(|request: Request<String>| async move {
+        let scope = request.extensions().get::<OwnedScope>().unwrap().clone();
+        drop(request);
+        let _: Session = scope.resolve().unwrap();
+        Err::<(), _>("handler failed")
+    }));
+
+    assert_eq!(service.oneshot(Request::new(String::new())).await, Err("handler failed"));
+    assert_eq!(alive.load(Ordering::SeqCst), 0);
+}
+
+#[tokio::test]
+async fn scope_ends_when_the_call_is_cancelled() {
+    let alive = Arc::new(AtomicUsize::new(0));
+
+    let service = ScopeLayer::new(container(&alive)).layer(service_fn(|request: Request<()>| async move {
+        let scope = request.extensions().get::<OwnedScope>().unwrap().clone();
+        drop(request);
+        let _: Session = scope.resolve().unwrap();
+        std::future::pending::<std::result::Result<(), Infallible>>().await
+    }));
+
+    let call = tokio::spawn(service.oneshot(Request::new(())));
+    while alive.load(Ordering::SeqCst) == 0 {
+        tokio::task::yield_now().await;
+    }
+    call.abort();
+    assert!(call.await.unwrap_err().is_cancelled());
+    assert_eq!(alive.load(Ordering::SeqCst), 0);
+}
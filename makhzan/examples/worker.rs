@@ -0,0 +1,100 @@
+//! A queue worker with one Makhzan scope per job.
+//!
+//! Run with `cargo run -p makhzan --example worker`.
+//!
+//! Each job gets its own `OwnedScope`: the job's `JobContext` is seeded
+//! into it, the handler and its `UnitOfWork` are resolved from it, and
+//! `OwnedScope::run` disposes of the scope once the job has finished,
+//! failed or panicked.
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU32, Ordering};
+
+use makhzan::container::resolve;
+use makhzan::prelude::*;
+
+/// What the queue delivered.
+struct Job {
+    id: u32,
+    payload: &'static str,
+}
+
+/// Seeded into each job's scope.
+#[derive(Clone)]
+struct JobContext {
+    id: u32,
+}
+
+/// Shared by everything in one job; committed or rolled back at the end.
+struct UnitOfWork {
+    job: u32,
+    transaction: u32,
+}
+
+impl Drop for UnitOfWork {
+    fn drop(&mut self) {
+        println!("  job {}: released transaction {}", self.job, self.transaction);
+    }
+}
+
+struct EmailHandler {
+    work: Arc<UnitOfWork>,
+}
+
+impl EmailHandler {
+    fn handle(&self, job: &Job) -> std::result::Result<(), String> {
+        if job.payload.is_empty() {
+            return Err(format!("job {} has no recipient", job.id));
+        }
+        println!(
+            "  job {}: emailing {} in transaction {}",
+            self.work.job, job.payload, self.work.transaction
+        );
+        Ok(())
+    }
+}
+
+fn container() -> Result<Container> {
+    let transactions = Arc::new(AtomicU32::new(1));
+
+    Container::builder()
+        .scoped_with(move |resolver| {
+            let context: JobContext = resolve(resolver)?;
+            Ok(Arc::new(UnitOfWork {
+                job: context.id,
+                transaction: transactions.fetch_add(1, Ordering::SeqCst),
+            }))
+        })
+        .transient_with(|resolver| Ok(EmailHandler { work: resolve(resolver)? }))
+        .build()
+}
+
+/// Wiring errors come back as the outer `Err`, job failures as the inner.
+fn process(container: &Container, job: Job) -> Result<std::result::Result<(), String>> {
+    let scope = container.create_owned_scope();
+    scope.insert(JobContext { id: job.id });
+
+    scope.run(|scope| {
+        let handler: EmailHandler = scope.resolve()?;
+        Ok(handler.handle(&job))
+    })
+}
+
+fn main() -> Result<()> {
+    let container = container()?;
+    let queue = [
+        Job { id: 1, payload: "ada@example.com" },
+        Job { id: 2, payload: "" },
+        Job { id: 3, payload: "grace@example.com" },
+    ];
+
+    for job in queue {
+        let id = job.id;
+        println!("job {id}: started");
+        match process(&container, job)? {
+            Ok(()) => println!("job {id}: done"),
+            Err(e) => println!("job {id}: failed: {e}"),
+        }
+    }
+    Ok(())
+}
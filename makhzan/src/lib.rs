@@ -40,4 +40,20 @@
 
 pub use makhzan_container::*;
 pub use makhzan_container::container::prelude::*;
-pub use makhzan_support::rendering;
\ No newline at end of file
+pub use makhzan_derive::injectable;
+pub use makhzan_support::rendering;
+
+/// Actix-web integration, with the `actix` feature.
+#[cfg(feature = "actix")]
+pub use makhzan_actix as actix;
+
+/// Axum integration, with the `axum` feature.
+#[cfg(feature = "axum")]
+pub use makhzan_axum as axum;
+/// Tonic integration, with the `tonic` feature.
+#[cfg(feature = "tonic")]
+pub use makhzan_tonic as tonic;
+
+/// Framework-independent tower middleware, with the `tower` feature.
+#[cfg(feature = "tower")]
+pub use makhzan_tower as tower;
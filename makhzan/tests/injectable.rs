@@ -0,0 +1,99 @@
+use std::sync::Arc;
+
+use makhzan::prelude::*;
+use makhzan::injectable;
+
+struct Database {
+    url: String,
+}
+
+trait Logger: Send + Sync {
+    fn prefix(&self) -> &'static str;
+}
+
+struct ConsoleLogger;
+
+impl Logger for ConsoleLogger {
+    fn prefix(&self) -> &'static str {
+        "[console]"
+    }
+}
+
+struct UserService {
+    db: Arc<Database>,
+    log: Arc<dyn Logger>,
+}
+
+#[injectable]
+impl UserService {
+    #[injectable]
+    fn new(db: Arc<Database>, log: Arc<dyn Logger>) -> Self {
+        Self { db, log }
+    }
+
+    fn describe(&self) -> String {
+        format!("{} {}", self.log.prefix(), self.db.url)
+    }
+}
+
+struct Mailer {
+    host: String,
+}
+
+#[injectable]
+impl Mailer {
+    #[injectable]
+    pub fn connect(host: String) -> std::result::Result<Self, String> {
+        if host.is_empty() {
+            return Err("empty host".into());
+        }
+        Ok(Self { host })
+    }
+}
+
+fn base() -> ContainerBuilder {
+    Container::builder()
+        .singleton_value(Arc::new(Database { url: "postgres://localhost".into() }))
+        .singleton_value(Arc::new(ConsoleLogger) as Arc<dyn Logger>)
+}
+
+#[test]
+fn constructor_parameters_are_resolved_by_type() {
+    let container = base().register_injectable::<UserService>().build().unwrap();
+
+    let service: UserService = container.resolve().unwrap();
+    assert_eq!(service.describe(), "[console] postgres://localhost");
+    assert_eq!(
+        UserService::dependencies(),
+        vec![DependencyKey::of::<Arc<Database>>(), DependencyKey::of::<Arc<dyn Logger>>()]
+    );
+}
+
+#[test]
+fn missing_constructor_parameter_fails_validation() {
+    let result = Container::builder().register_injectable::<UserService>().build();
+    assert!(matches!(result, Err(MakhzanError::NotRegistered(_))));
+}
+
+#[test]
+fn result_constructors_report_construction_failure() {
+    let ok = base()
+        .singleton_value(String::from("smtp.local"))
+        .register_injectable::<Mailer>()
+        .build()
+        .unwrap();
+    assert_eq!(ok.resolve::<Mailer>().unwrap().host, "smtp.local");
+
+    let failing = base()
+        .singleton_value(String::new())
+        .register_injectable::<Mailer>()
+        .build()
+        .unwrap();
+    match failing.resolve::<Mailer>() {
+        Err(MakhzanError::ConstructionFailed { key, source, .. }) => {
+            assert_eq!(key, DependencyKey::of::<Mailer>());
+            assert_eq!(source.to_string(), "empty host");
+        }
+        other => panic!("expected ConstructionFailed, got {:?}", other.err()),
+    }
+}
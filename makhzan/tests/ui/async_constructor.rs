@@ -0,0 +1,13 @@
+use makhzan::injectable;
+
+struct Service;
+
+#[injectable]
+impl Service {
+    #[injectable]
+    async fn new() -> Self {
+        Service
+    }
+}
+
+fn main() {}
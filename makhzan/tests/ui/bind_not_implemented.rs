@@ -0,0 +1,10 @@
+use makhzan::prelude::*;
+
+trait Logger: Send + Sync {}
+makhzan::upcast!(Logger);
+
+struct Mailer;
+
+fn main() {
+    let _ = Container::builder().bind::<dyn Logger, Mailer>();
+}
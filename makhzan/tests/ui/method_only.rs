@@ -0,0 +1,12 @@
+use makhzan::injectable;
+
+struct Service;
+
+impl Service {
+    #[injectable]
+    fn new() -> Self {
+        Service
+    }
+}
+
+fn main() {}
@@ -0,0 +1,7 @@
+use std::rc::Rc;
+
+use makhzan::prelude::*;
+
+fn main() {
+    let _ = Container::builder().transient_with::<Rc<u32>>(|_| Ok(Rc::new(1)));
+}
@@ -0,0 +1,29 @@
+use std::sync::Arc;
+
+use makhzan::prelude::*;
+
+trait Logger: Send + Sync {
+    fn log(&self, msg: &str) -> String;
+}
+makhzan::upcast!(Logger);
+
+struct ConsoleLogger;
+
+impl Logger for ConsoleLogger {
+    fn log(&self, msg: &str) -> String {
+        format!("[console] {msg}")
+    }
+}
+
+fn main() {
+    let container = Container::builder()
+        .singleton_arc(|_| Ok(ConsoleLogger))
+        .bind::<dyn Logger, ConsoleLogger>()
+        .singleton_with::<Arc<dyn Logger + Send + Sync>>(|_| Ok(Arc::new(ConsoleLogger)))
+        .build()
+        .unwrap();
+
+    let logger: Arc<dyn Logger> = container.resolve().unwrap();
+    assert_eq!(logger.log("hi"), "[console] hi");
+    let _: Arc<dyn Logger + Send + Sync> = container.resolve().unwrap();
+}
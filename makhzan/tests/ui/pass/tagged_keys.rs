@@ -0,0 +1,16 @@
+use makhzan::prelude::*;
+
+struct Primary;
+trait Replica {}
+
+fn main() {
+    let container = Container::builder()
+        .singleton_tagged::<String, Primary>(|_| Ok("postgres://primary".into()))
+        .singleton_tagged::<String, dyn Replica>(|_| Ok("postgres://replica".into()))
+        .build()
+        .unwrap();
+
+    assert_eq!(container.resolve_tagged::<String, Primary>().unwrap(), "postgres://primary");
+    assert_eq!(container.resolve_tagged::<String, dyn Replica>().unwrap(), "postgres://replica");
+    assert_eq!(DependencyKey::tagged::<String, Primary>(), DependencyKey::tagged::<String, Primary>());
+}
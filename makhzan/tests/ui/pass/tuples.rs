@@ -0,0 +1,18 @@
+use std::sync::Arc;
+
+use makhzan::prelude::*;
+
+struct Database;
+
+fn main() {
+    let container = Container::builder()
+        .singleton_value(Arc::new(Database))
+        .singleton_value(8080u16)
+        .transient_with(|_| Ok(String::from("orders")))
+        .build()
+        .unwrap();
+
+    let (_database, port, topic): (Arc<Database>, u16, String) = container.resolve_tuple().unwrap();
+    assert_eq!((port, topic.as_str()), (8080, "orders"));
+    let _: (u16,) = container.create_scope().resolve_tuple().unwrap();
+}
@@ -0,0 +1,14 @@
+use makhzan::injectable;
+
+struct Config;
+struct Service;
+
+#[injectable]
+impl Service {
+    #[injectable]
+    fn new(_config: &Config) -> Self {
+        Service
+    }
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use makhzan::prelude::*;
+
+fn lookup<'a>(container: &Container) -> &'a u32 {
+    container.resolve::<&'a u32>().unwrap()
+}
+
+fn main() {
+    let container = Container::builder().build().unwrap();
+    let _ = lookup(&container);
+}
@@ -0,0 +1,8 @@
+use std::cell::Cell;
+
+use makhzan::prelude::*;
+
+fn main() {
+    let container = Container::builder().build().unwrap();
+    let _ = container.resolve::<Cell<u32>>();
+}
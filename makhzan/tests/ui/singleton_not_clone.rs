@@ -0,0 +1,7 @@
+use std::sync::Mutex;
+
+use makhzan::prelude::*;
+
+fn main() {
+    let _ = Container::builder().singleton_with::<Mutex<u32>>(|_| Ok(Mutex::new(0)));
+}
@@ -0,0 +1,13 @@
+use makhzan::injectable;
+
+struct Service;
+
+#[injectable]
+impl Service {
+    #[injectable]
+    fn new(&self) -> Self {
+        Service
+    }
+}
+
+fn main() {}
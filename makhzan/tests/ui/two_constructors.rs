@@ -0,0 +1,18 @@
+use makhzan::injectable;
+
+struct Service;
+
+#[injectable]
+impl Service {
+    #[injectable]
+    fn new() -> Self {
+        Service
+    }
+
+    #[injectable]
+    fn other() -> Self {
+        Service
+    }
+}
+
+fn main() {}
@@ -0,0 +1,12 @@
+use makhzan::injectable;
+
+struct Service;
+
+#[injectable]
+impl Service {
+    fn new() -> Self {
+        Service
+    }
+}
+
+fn main() {}